@@ -0,0 +1,128 @@
+/// Iterations after which the estimation stops even if it has not converged.
+pub const DEFAULT_MAX_ITERATIONS: u32 = 50;
+
+/// Largest change of any posterior between iterations at which the
+/// estimation counts as converged.
+pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+/// A labeler's answer to whether an item has the property being estimated,
+/// e.g. whether an image shows a given tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinaryLabel {
+    pub item: usize,
+    pub labeler: usize,
+    pub positive: bool,
+}
+
+/// How a labeler answers depending on the true answer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfusionMatrix {
+    /// Probability of answering yes when the truth is yes.
+    pub sensitivity: f64,
+    /// Probability of answering no when the truth is no.
+    pub specificity: f64,
+}
+
+impl ConfusionMatrix {
+    /// The matrix as rows of the true answer (no, yes) and columns of the
+    /// given answer (no, yes).
+    pub fn rows(&self) -> [[f64; 2]; 2] {
+        [
+            [self.specificity, 1.0 - self.specificity],
+            [1.0 - self.sensitivity, self.sensitivity],
+        ]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DawidSkene {
+    /// Estimated share of items whose true answer is yes.
+    pub prior: f64,
+    /// Probability that each item's true answer is yes, by item index.
+    pub posteriors: Vec<f64>,
+    /// Confusion matrix of each labeler, by labeler index.
+    pub confusion: Vec<ConfusionMatrix>,
+    pub iterations: u32,
+    pub converged: bool,
+}
+
+/// Estimates the true yes/no answer of `items` items from the `labels` of
+/// `labelers` labelers with Dawid–Skene expectation-maximization. Starts
+/// from the share of yes answers per item, then alternates between
+/// estimating each labeler's confusion matrix from the posteriors and the
+/// posteriors from the confusion matrices. Counts are smoothed with one
+/// pseudo-answer each way, so a labeler with few answers is not taken as
+/// perfectly reliable.
+pub fn dawid_skene(
+    labels: &[BinaryLabel],
+    items: usize,
+    labelers: usize,
+    max_iterations: u32,
+    tolerance: f64,
+) -> DawidSkene {
+    let mut yes = vec![0.0; items];
+    let mut answers = vec![0.0; items];
+    for label in labels {
+        answers[label.item] += 1.0;
+        if label.positive {
+            yes[label.item] += 1.0;
+        }
+    }
+    let mut posteriors: Vec<f64> = (0..items)
+        .map(|i| if answers[i] > 0.0 { yes[i] / answers[i] } else { 0.5 })
+        .collect();
+
+    let mut prior = 0.5;
+    let mut confusion = vec![ConfusionMatrix { sensitivity: 0.5, specificity: 0.5 }; labelers];
+    let mut iterations = 0;
+    let mut converged = false;
+
+    while iterations < max_iterations {
+        iterations += 1;
+
+        // M-step: class prior and confusion matrices from the posteriors
+        prior = (posteriors.iter().sum::<f64>() + 1.0) / (items as f64 + 2.0);
+        let mut positive_weight = vec![0.0; labelers];
+        let mut positive_hits = vec![0.0; labelers];
+        let mut negative_weight = vec![0.0; labelers];
+        let mut negative_hits = vec![0.0; labelers];
+        for label in labels {
+            let p = posteriors[label.item];
+            positive_weight[label.labeler] += p;
+            negative_weight[label.labeler] += 1.0 - p;
+            if label.positive {
+                positive_hits[label.labeler] += p;
+            } else {
+                negative_hits[label.labeler] += 1.0 - p;
+            }
+        }
+        for (j, matrix) in confusion.iter_mut().enumerate() {
+            matrix.sensitivity = (positive_hits[j] + 1.0) / (positive_weight[j] + 2.0);
+            matrix.specificity = (negative_hits[j] + 1.0) / (negative_weight[j] + 2.0);
+        }
+
+        // E-step: posteriors from the prior and every answer, as log-odds
+        let mut log_odds = vec![(prior / (1.0 - prior)).ln(); items];
+        for label in labels {
+            let ConfusionMatrix { sensitivity, specificity } = confusion[label.labeler];
+            log_odds[label.item] += if label.positive {
+                (sensitivity / (1.0 - specificity)).ln()
+            } else {
+                ((1.0 - sensitivity) / specificity).ln()
+            };
+        }
+
+        let mut change: f64 = 0.0;
+        for (posterior, log_odds) in posteriors.iter_mut().zip(log_odds) {
+            let updated = 1.0 / (1.0 + (-log_odds).exp());
+            change = change.max((updated - *posterior).abs());
+            *posterior = updated;
+        }
+        if change < tolerance {
+            converged = true;
+            break;
+        }
+    }
+
+    DawidSkene { prior, posteriors, confusion, iterations, converged }
+}
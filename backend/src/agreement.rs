@@ -0,0 +1,80 @@
+/// Cohen's kappa of two raters' yes/no answers on the same items. `None`
+/// without items, or when chance agreement is certain (both raters gave a
+/// single identical answer throughout) and kappa is undefined.
+pub fn cohen_kappa(answers: &[(bool, bool)]) -> Option<f64> {
+    if answers.is_empty() {
+        return None;
+    }
+    let n = answers.len() as f64;
+    let observed = answers.iter().filter(|(a, b)| a == b).count() as f64 / n;
+    let yes_a = answers.iter().filter(|(a, _)| *a).count() as f64 / n;
+    let yes_b = answers.iter().filter(|(_, b)| *b).count() as f64 / n;
+    let expected = yes_a * yes_b + (1.0 - yes_a) * (1.0 - yes_b);
+
+    if (1.0 - expected).abs() < 1e-12 {
+        return None;
+    }
+    Some((observed - expected) / (1.0 - expected))
+}
+
+/// Fleiss' kappa over items given as the number of raters who chose each
+/// category. Items may have different numbers of raters; those with fewer
+/// than two are ignored. `None` when no item has two raters or only one
+/// category was ever chosen.
+pub fn fleiss_kappa(items: &[Vec<usize>]) -> Option<f64> {
+    let items: Vec<&Vec<usize>> = items.iter().filter(|counts| counts.iter().sum::<usize>() >= 2).collect();
+    if items.is_empty() {
+        return None;
+    }
+    let categories = items.iter().map(|counts| counts.len()).max().unwrap_or(0);
+
+    let mut agreement = 0.0;
+    let mut totals = vec![0.0; categories];
+    let mut ratings = 0.0;
+    for counts in &items {
+        let n = counts.iter().sum::<usize>() as f64;
+        let pairs: f64 = counts.iter().map(|&c| (c * c) as f64).sum::<f64>() - n;
+        agreement += pairs / (n * (n - 1.0));
+        for (total, &c) in totals.iter_mut().zip(counts.iter()) {
+            *total += c as f64;
+        }
+        ratings += n;
+    }
+
+    let observed = agreement / items.len() as f64;
+    let expected: f64 = totals.iter().map(|total| (total / ratings).powi(2)).sum();
+    if (1.0 - expected).abs() < 1e-12 {
+        return None;
+    }
+    Some((observed - expected) / (1.0 - expected))
+}
+
+/// Krippendorff's alpha for nominal data, with items given as in
+/// [`fleiss_kappa`]. Missing ratings are fine; items with fewer than two
+/// ratings carry no pairs and are ignored. `None` when no pairs remain or
+/// only one category was ever chosen.
+pub fn krippendorff_alpha(items: &[Vec<usize>]) -> Option<f64> {
+    let categories = items.iter().map(|counts| counts.len()).max().unwrap_or(0);
+
+    // Coincidences of equal values, and how often each value is paired
+    let mut matching = 0.0;
+    let mut marginals = vec![0.0; categories];
+    for counts in items {
+        let m = counts.iter().sum::<usize>() as f64;
+        if m < 2.0 {
+            continue;
+        }
+        for (marginal, &c) in marginals.iter_mut().zip(counts.iter()) {
+            let c = c as f64;
+            matching += c * (c - 1.0) / (m - 1.0);
+            *marginal += c;
+        }
+    }
+
+    let n: f64 = marginals.iter().sum();
+    let expected_disagreement = n * n - marginals.iter().map(|c| c * c).sum::<f64>();
+    if n < 2.0 || expected_disagreement <= 0.0 {
+        return None;
+    }
+    Some(1.0 - (n - 1.0) * (n - matching) / expected_disagreement)
+}
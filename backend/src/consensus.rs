@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Weight of a labeler without enough history to estimate a reliability.
+pub const DEFAULT_RELIABILITY: f64 = 0.5;
+
+/// Rule for turning the labelers' tags on an image into its final tags.
+/// Only labelers who submitted the image take part in the vote, including
+/// those who submitted it with no tags. Serialized as
+/// e.g. `{"type": "majority", "threshold": 0.5}`, which is also how the
+/// strategy is recorded on groups and on the final tags it produced.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConsensusStrategy {
+    /// Tags chosen by at least `threshold` (a fraction in (0, 1]) of the
+    /// labelers.
+    Majority { threshold: f64 },
+    /// Tags chosen by at least `min_votes` labelers.
+    MinimumVotes { min_votes: u32 },
+    /// Tags chosen by every labeler.
+    Unanimous,
+    /// Like `Majority`, but each vote counts with the labeler's reliability
+    /// instead of 1.
+    WeightedReliability { threshold: f64 },
+    /// Tags whose posterior probability, estimated by Dawid–Skene
+    /// aggregation over the whole group, is at least `threshold`.
+    DawidSkene { threshold: f64 },
+}
+
+impl Default for ConsensusStrategy {
+    fn default() -> Self {
+        Self::Majority { threshold: 0.5 }
+    }
+}
+
+impl ConsensusStrategy {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Majority { threshold } | Self::WeightedReliability { threshold } | Self::DawidSkene { threshold } => {
+                if !(*threshold > 0.0 && *threshold <= 1.0) {
+                    return Err("Consensus threshold must be greater than 0 and at most 1".to_string());
+                }
+            }
+            Self::MinimumVotes { min_votes } => {
+                if *min_votes == 0 {
+                    return Err("Consensus min_votes must be at least 1".to_string());
+                }
+            }
+            Self::Unanimous => {}
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// Picks the final tags from `(labeler_id, tag_id)` votes, in ascending
+    /// tag order. `reliability` is only read by `WeightedReliability`;
+    /// labelers missing from it weigh `DEFAULT_RELIABILITY`. `DawidSkene`
+    /// needs the rest of the group, so from one image's votes alone it is a
+    /// majority with its threshold, which is where its estimation starts.
+    pub fn select(&self, votes: &[(i32, i32)], reliability: &HashMap<i32, f64>) -> Vec<i32> {
+        let labelers: BTreeSet<i32> = votes.iter().map(|&(labeler_id, _)| labeler_id).collect();
+        self.select_among(&labelers, votes, reliability)
+    }
+
+    /// Like [`Self::select`], with `labelers` as everyone who voted, so
+    /// labelers who chose no tag still count towards thresholds.
+    pub fn select_among(
+        &self,
+        labelers: &BTreeSet<i32>,
+        votes: &[(i32, i32)],
+        reliability: &HashMap<i32, f64>,
+    ) -> Vec<i32> {
+        let mut voters: BTreeMap<i32, BTreeSet<i32>> = BTreeMap::new();
+        for &(labeler_id, tag_id) in votes {
+            voters.entry(tag_id).or_default().insert(labeler_id);
+        }
+
+        let weight = |labeler_id: &i32| reliability.get(labeler_id).copied().unwrap_or(DEFAULT_RELIABILITY);
+        let total_weight: f64 = labelers.iter().map(weight).sum();
+
+        voters
+            .into_iter()
+            .filter(|(_, tag_voters)| {
+                let count = tag_voters.len();
+                match self {
+                    Self::Majority { threshold } | Self::DawidSkene { threshold } => count as f64 >= threshold * labelers.len() as f64 - 1e-9,
+                    Self::MinimumVotes { min_votes } => count >= *min_votes as usize,
+                    Self::Unanimous => count == labelers.len(),
+                    Self::WeightedReliability { threshold } => {
+                        total_weight > 0.0
+                            && tag_voters.iter().map(weight).sum::<f64>() >= threshold * total_weight - 1e-9
+                    }
+                }
+            })
+            .map(|(tag_id, _)| tag_id)
+            .collect()
+    }
+}
@@ -1,13 +1,21 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "group")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
+    pub allowed_formats: Option<String>,
+    pub duplicate_policy: String,
+    pub consensus_strategy: Option<String>,
+    pub gold_window: i32,
+    pub gold_min_accuracy: Option<f64>,
+    pub labels_per_image: i32,
+    pub lease_seconds: i32,
+    pub task_type: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
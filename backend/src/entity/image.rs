@@ -8,9 +8,18 @@ pub struct Model {
     pub id: i32,
     pub filename: String,
     pub filetype: String,
-    pub base64_data: String,
+    pub storage_key: Option<String>,
+    pub size_bytes: Option<i64>,
     pub group_id: i32,
     pub uploaded_at: chrono::DateTime<chrono::Utc>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub color_mode: Option<String>,
+    pub orientation: Option<i32>,
+    pub average_hash: Option<String>,
+    pub difference_hash: Option<String>,
+    pub duplicate_of: Option<i32>,
+    pub is_gold: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
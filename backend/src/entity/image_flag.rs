@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "image_flag")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub labeler_id: i32,
+    pub image_id: i32,
+    /// "skip" or "flag".
+    pub kind: String,
+    pub reason: String,
+    pub note: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::labeler::Entity",
+        from = "Column::LabelerId",
+        to = "super::labeler::Column::Id"
+    )]
+    Labeler,
+    #[sea_orm(
+        belongs_to = "super::image::Entity",
+        from = "Column::ImageId",
+        to = "super::image::Column::Id"
+    )]
+    Image,
+}
+
+impl Related<super::labeler::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Labeler.def()
+    }
+}
+
+impl Related<super::image::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Image.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
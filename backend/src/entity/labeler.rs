@@ -8,6 +8,8 @@ pub struct Model {
     pub id: i32,
     pub username: String,
     pub password_hash: String,
+    pub suspended: bool,
+    pub suspended_reason: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
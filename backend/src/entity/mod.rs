@@ -6,6 +6,22 @@ pub mod group;
 pub mod tag;
 pub mod image_tags;
 pub mod labeler_groups;
+pub mod import_job;
+pub mod image_thumbnail;
+pub mod gold_tag;
+pub mod gold_result;
+pub mod assignment;
+pub mod image_flag;
+pub mod submission;
+pub mod image_view;
+pub mod region;
+pub mod final_region;
+pub mod segmentation;
+pub mod final_segmentation;
+pub mod keypoint_set;
+pub mod final_keypoint_set;
+pub mod caption;
+pub mod final_caption;
 
 pub use admin::{Entity as Admin, Model as AdminModel, Relation as AdminRelation};
 pub use final_tags::{Entity as FinalTags, Model as FinalTagsModel, Relation as FinalTagsRelation};
@@ -15,3 +31,19 @@ pub use group::{Entity as Group, Model as GroupModel, Relation as GroupRelation}
 pub use tag::{Entity as Tag, Model as TagModel, Relation as TagRelation};
 pub use image_tags::{Entity as ImageTags, Model as ImageTagsModel, Relation as ImageTagsRelation};
 pub use labeler_groups::{Entity as LabelerGroups, Model as LabelerGroupsModel, Relation as LabelerGroupsRelation};
+pub use import_job::{Entity as ImportJob, Model as ImportJobModel, Relation as ImportJobRelation};
+pub use image_thumbnail::{Entity as ImageThumbnail, Model as ImageThumbnailModel, Relation as ImageThumbnailRelation};
+pub use gold_tag::{Entity as GoldTag, Model as GoldTagModel, Relation as GoldTagRelation};
+pub use gold_result::{Entity as GoldResult, Model as GoldResultModel, Relation as GoldResultRelation};
+pub use assignment::{Entity as Assignment, Model as AssignmentModel, Relation as AssignmentRelation};
+pub use image_flag::{Entity as ImageFlag, Model as ImageFlagModel, Relation as ImageFlagRelation};
+pub use submission::{Entity as Submission, Model as SubmissionModel, Relation as SubmissionRelation};
+pub use image_view::{Entity as ImageView, Model as ImageViewModel, Relation as ImageViewRelation};
+pub use region::{Entity as Region, Model as RegionModel, Relation as RegionRelation};
+pub use final_region::{Entity as FinalRegion, Model as FinalRegionModel, Relation as FinalRegionRelation};
+pub use segmentation::{Entity as Segmentation, Model as SegmentationModel, Relation as SegmentationRelation};
+pub use final_segmentation::{Entity as FinalSegmentation, Model as FinalSegmentationModel, Relation as FinalSegmentationRelation};
+pub use keypoint_set::{Entity as KeypointSet, Model as KeypointSetModel, Relation as KeypointSetRelation};
+pub use final_keypoint_set::{Entity as FinalKeypointSet, Model as FinalKeypointSetModel, Relation as FinalKeypointSetRelation};
+pub use caption::{Entity as Caption, Model as CaptionModel, Relation as CaptionRelation};
+pub use final_caption::{Entity as FinalCaption, Model as FinalCaptionModel, Relation as FinalCaptionRelation};
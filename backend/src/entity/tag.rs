@@ -9,6 +9,8 @@ pub struct Model {
     pub name: String,
     pub description: Option<String>,
     pub group_id: i32,
+    /// JSON of a `keypoint::KeypointSchema`, for tags placed as keypoints.
+    pub keypoint_schema: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,62 @@
+use super::Raster;
+
+/// Average (aHash) and difference (dHash) hashes of an image. Visually
+/// similar images get hashes a small Hamming distance apart, whatever their
+/// size or encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PerceptualHash {
+    pub average: u64,
+    pub difference: u64,
+}
+
+impl PerceptualHash {
+    pub fn of(raster: &Raster) -> Self {
+        Self {
+            average: average_hash(raster),
+            difference: difference_hash(raster),
+        }
+    }
+}
+
+/// One bit per cell of an 8x8 grayscale thumbnail, set when the cell is
+/// brighter than the mean.
+pub fn average_hash(raster: &Raster) -> u64 {
+    let cells = luma(&raster.resized(8, 8));
+    let sum: u32 = cells.iter().map(|&v| v as u32).sum();
+    cells.iter().fold(0, |hash, &v| (hash << 1) | (v as u32 * 64 > sum) as u64)
+}
+
+/// One bit per horizontally adjacent pair of a 9x8 grayscale thumbnail, set
+/// when the left cell is brighter than the right one.
+pub fn difference_hash(raster: &Raster) -> u64 {
+    let cells = luma(&raster.resized(9, 8));
+    cells
+        .chunks(9)
+        .flat_map(|row| row.windows(2))
+        .fold(0, |hash, pair| (hash << 1) | (pair[0] > pair[1]) as u64)
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Fixed-width hex form hashes are stored in.
+pub fn to_hex(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
+pub fn from_hex(hex: &str) -> Option<u64> {
+    if hex.len() != 16 {
+        return None;
+    }
+    u64::from_str_radix(hex, 16).ok()
+}
+
+// ITU-R BT.601 luma of every pixel
+fn luma(raster: &Raster) -> Vec<u8> {
+    raster
+        .pixels
+        .chunks(3)
+        .map(|p| ((p[0] as u32 * 299 + p[1] as u32 * 587 + p[2] as u32 * 114 + 500) / 1000) as u8)
+        .collect()
+}
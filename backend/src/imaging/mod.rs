@@ -0,0 +1,246 @@
+use image::metadata::Orientation;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ExtendedColorType, ImageDecoder, ImageReader};
+use std::io::Cursor;
+use zune_jpeg::zune_core::bytestream::ZCursor;
+use zune_jpeg::zune_core::colorspace::ColorSpace;
+use zune_jpeg::zune_core::options::DecoderOptions;
+
+pub mod hash;
+pub mod raster;
+
+pub use hash::PerceptualHash;
+pub use raster::Raster;
+
+/// Largest image, in pixels, that will be decoded into memory.
+pub const MAX_PIXELS: usize = 100_000_000;
+
+/// Image formats the server can identify from their content.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    WebP,
+    Gif,
+    Bmp,
+    Tiff,
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 6] = [Self::Png, Self::Jpeg, Self::WebP, Self::Gif, Self::Bmp, Self::Tiff];
+
+    /// Identifies a format from its magic bytes.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match image::guess_format(bytes).ok()? {
+            image::ImageFormat::Png => Some(Self::Png),
+            image::ImageFormat::Jpeg => Some(Self::Jpeg),
+            image::ImageFormat::WebP => Some(Self::WebP),
+            image::ImageFormat::Gif => Some(Self::Gif),
+            image::ImageFormat::Bmp => Some(Self::Bmp),
+            image::ImageFormat::Tiff => Some(Self::Tiff),
+            _ => None,
+        }
+    }
+
+    /// Maps a client-supplied file type, extension such as `jpg` or MIME
+    /// type such as `image/jpeg`.
+    pub fn from_filetype(filetype: &str) -> Option<Self> {
+        match filetype.trim().to_lowercase().trim_start_matches("image/") {
+            "png" => Some(Self::Png),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebP),
+            "gif" => Some(Self::Gif),
+            "bmp" | "x-ms-bmp" => Some(Self::Bmp),
+            "tif" | "tiff" => Some(Self::Tiff),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::WebP => "WebP",
+            Self::Gif => "GIF",
+            Self::Bmp => "BMP",
+            Self::Tiff => "TIFF",
+        }
+    }
+
+    /// MIME type, which is also what uploads record as their `filetype`.
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::WebP => "image/webp",
+            Self::Gif => "image/gif",
+            Self::Bmp => "image/bmp",
+            Self::Tiff => "image/tiff",
+        }
+    }
+
+    fn codec(&self) -> image::ImageFormat {
+        match self {
+            Self::Png => image::ImageFormat::Png,
+            Self::Jpeg => image::ImageFormat::Jpeg,
+            Self::WebP => image::ImageFormat::WebP,
+            Self::Gif => image::ImageFormat::Gif,
+            Self::Bmp => image::ImageFormat::Bmp,
+            Self::Tiff => image::ImageFormat::Tiff,
+        }
+    }
+}
+
+/// Header metadata of a validated image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    /// PIL-style mode: `1`, `L`, `LA`, `P`, `RGB`, `RGBA`, `CMYK` or `LAB`.
+    pub color_mode: String,
+    /// EXIF orientation (1-8), when the file carries one.
+    pub orientation: Option<u16>,
+}
+
+/// Sniffs the format of `bytes` and decodes the whole image, so corrupt or
+/// truncated content fails here rather than when it is first displayed.
+pub fn inspect(bytes: &[u8]) -> Result<ImageInfo, String> {
+    open(bytes).map(|(info, _)| info)
+}
+
+/// Fully decodes `bytes` into RGB pixels, compositing any transparency onto
+/// white. Only the first frame or page of animated GIFs and multi-page
+/// TIFFs is read.
+pub fn decode(bytes: &[u8]) -> Result<Raster, String> {
+    open(bytes).map(|(_, image)| Raster::from_image(&image))
+}
+
+// Reads the metadata of `bytes` and decodes its pixels
+fn open(bytes: &[u8]) -> Result<(ImageInfo, DynamicImage), String> {
+    let format = ImageFormat::detect(bytes).ok_or("Unrecognized image format")?;
+    let invalid = |e: image::ImageError| format!("Invalid {}: {}", format.name(), e);
+
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format.codec())
+        .into_decoder()
+        .map_err(invalid)?;
+    let (width, height) = decoder.dimensions();
+    if width == 0 || height == 0 {
+        return Err(format!("{} has zero dimensions", format.name()));
+    }
+    if width as usize * height as usize > MAX_PIXELS {
+        return Err(format!("{} is too large to decode ({}x{})", format.name(), width, height));
+    }
+
+    let color_mode = color_mode(format, bytes, decoder.original_color_type());
+    let orientation = match decoder.exif_metadata().map_err(invalid)? {
+        Some(exif) => Orientation::from_exif_chunk(&exif),
+        // TIFF keeps the orientation in its own directory
+        None => Some(decoder.orientation().map_err(invalid)?).filter(|o| *o != Orientation::NoTransforms),
+    };
+    let image = match format {
+        ImageFormat::Jpeg => decode_jpeg(bytes, width, height)?,
+        _ => DynamicImage::from_decoder(decoder).map_err(invalid)?,
+    };
+
+    let info = ImageInfo {
+        format,
+        width,
+        height,
+        color_mode: color_mode.to_string(),
+        orientation: orientation.map(|o| u16::from(o.to_exif())),
+    };
+    Ok((info, image))
+}
+
+// `image` decodes JPEGs leniently, drawing gray over scan data that is cut
+// off, so they are decoded in strict mode instead
+fn decode_jpeg(bytes: &[u8], width: u32, height: u32) -> Result<DynamicImage, String> {
+    let options = DecoderOptions::default()
+        .set_strict_mode(true)
+        .set_max_width(MAX_PIXELS)
+        .set_max_height(MAX_PIXELS)
+        .jpeg_set_out_colorspace(ColorSpace::RGB);
+    let pixels = zune_jpeg::JpegDecoder::new_with_options(ZCursor::new(bytes), options)
+        .decode()
+        .map_err(|e| format!("Invalid JPEG: {:?}", e))?;
+    image::RgbImage::from_raw(width, height, pixels)
+        .map(DynamicImage::ImageRgb8)
+        .ok_or_else(|| "Invalid JPEG: decoded size does not match its header".to_string())
+}
+
+// Decoders expand palettes, so those are told apart from the header
+fn color_mode(format: ImageFormat, bytes: &[u8], color: ExtendedColorType) -> &'static str {
+    let paletted = match format {
+        ImageFormat::Gif => true,
+        // IHDR color type 3
+        ImageFormat::Png => bytes.get(25) == Some(&3),
+        _ => false,
+    };
+
+    match color {
+        _ if paletted => "P",
+        ExtendedColorType::L1 => "1",
+        ExtendedColorType::Cmyk8 | ExtendedColorType::Cmyk16 => "CMYK",
+        _ => match color.channel_count() {
+            1 => "L",
+            2 => "LA",
+            3 => "RGB",
+            _ => "RGBA",
+        },
+    }
+}
+
+/// Decodes `bytes`, shrinks the result to fit in a `max_side` square,
+/// rotates it upright according to its EXIF orientation and encodes it as
+/// `format`, JPEG or WebP.
+pub fn thumbnail(bytes: &[u8], max_side: u32, format: ImageFormat, quality: u8) -> Result<Thumbnail, String> {
+    let (info, image) = open(bytes)?;
+    let raster = Raster::from_image(&image)
+        .fit_within(max_side)
+        .oriented(info.orientation.unwrap_or(1));
+
+    Ok(Thumbnail {
+        bytes: encode(&raster, format, quality)?,
+        format,
+        width: raster.width,
+        height: raster.height,
+    })
+}
+
+/// Encodes `raster` as JPEG at `quality` (1-100), or as WebP. The image
+/// crate only writes lossless WebP, so `quality` does not apply to it and
+/// WebP output is usually larger than JPEG for photographs.
+pub fn encode(raster: &Raster, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let (width, height) = (raster.width, raster.height);
+    let result = match format {
+        ImageFormat::Jpeg => JpegEncoder::new_with_quality(&mut out, quality)
+            .encode(&raster.pixels, width, height, ExtendedColorType::Rgb8),
+        ImageFormat::WebP => WebPEncoder::new_lossless(&mut out)
+            .encode(&raster.pixels, width, height, ExtendedColorType::Rgb8),
+        _ => return Err(format!("Encoding {} is not supported", format.name())),
+    };
+    result.map_err(|e| format!("Failed to encode {}: {}", format.name(), e))?;
+    Ok(out)
+}
+
+/// Decodes `bytes` and computes the perceptual hashes of the upright image.
+pub fn perceptual_hash(bytes: &[u8]) -> Result<PerceptualHash, String> {
+    let (info, image) = open(bytes)?;
+    // Hashes only look at 9x8 pixels, so shrink before rotating
+    let raster = Raster::from_image(&image)
+        .fit_within(64)
+        .oriented(info.orientation.unwrap_or(1));
+    Ok(PerceptualHash::of(&raster))
+}
+
+/// An encoded thumbnail.
+#[derive(Clone, Debug)]
+pub struct Thumbnail {
+    pub bytes: Vec<u8>,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+}
@@ -0,0 +1,143 @@
+use image::DynamicImage;
+
+/// Decoded 8-bit RGB image, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Raster {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Raster {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        debug_assert_eq!(pixels.len(), width as usize * height as usize * 3);
+        Self { width, height, pixels }
+    }
+
+    /// Converts a decoded image to 8-bit RGB, compositing any transparency
+    /// onto white.
+    pub fn from_image(image: &DynamicImage) -> Self {
+        if !image.color().has_alpha() {
+            let rgb = image.to_rgb8();
+            return Self::new(rgb.width(), rgb.height(), rgb.into_raw());
+        }
+
+        let rgba = image.to_rgba8();
+        let mut pixels = Vec::with_capacity(rgba.width() as usize * rgba.height() as usize * 3);
+        for p in rgba.pixels() {
+            let alpha = p[3] as u32;
+            pixels.extend((0..3).map(|c| ((p[c] as u32 * alpha + 255 * (255 - alpha) + 127) / 255) as u8));
+        }
+        Self::new(rgba.width(), rgba.height(), pixels)
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    /// Scales the image down, keeping its aspect ratio, so that neither side
+    /// exceeds `max_side`. Uses area averaging; never scales up.
+    pub fn fit_within(&self, max_side: u32) -> Raster {
+        let longest = self.width.max(self.height);
+        if longest <= max_side {
+            return self.clone();
+        }
+
+        let scale = max_side as f64 / longest as f64;
+        let width = ((self.width as f64 * scale).round() as u32).clamp(1, max_side);
+        let height = ((self.height as f64 * scale).round() as u32).clamp(1, max_side);
+        self.resized(width, height)
+    }
+
+    /// Scales the image to exactly `width` x `height` with area averaging,
+    /// ignoring the aspect ratio.
+    pub fn resized(&self, width: u32, height: u32) -> Raster {
+        let horizontal = resample_rows(&self.pixels, self.width as usize, self.height as usize, width as usize);
+        let pixels = resample_columns(&horizontal, width as usize, self.height as usize, height as usize);
+        Raster::new(width, height, pixels)
+    }
+
+    /// Applies an EXIF orientation (1-8) so the image displays upright.
+    pub fn oriented(self, orientation: u16) -> Raster {
+        if !(2..=8).contains(&orientation) {
+            return self;
+        }
+
+        let (w, h) = (self.width, self.height);
+        let (out_w, out_h) = if orientation >= 5 { (h, w) } else { (w, h) };
+        let mut pixels = Vec::with_capacity(self.pixels.len());
+
+        for y in 0..out_h {
+            for x in 0..out_w {
+                let (sx, sy) = match orientation {
+                    2 => (w - 1 - x, y),
+                    3 => (w - 1 - x, h - 1 - y),
+                    4 => (x, h - 1 - y),
+                    5 => (y, x),
+                    6 => (y, h - 1 - x),
+                    7 => (w - 1 - y, h - 1 - x),
+                    _ => (w - 1 - y, x),
+                };
+                pixels.extend_from_slice(&self.pixel(sx, sy));
+            }
+        }
+
+        Raster::new(out_w, out_h, pixels)
+    }
+}
+
+// Weights of the source samples covering each destination sample when
+// `src` samples are squeezed into `dst`
+fn coverage(src: usize, dst: usize) -> Vec<Vec<(usize, f32)>> {
+    let scale = src as f64 / dst as f64;
+    (0..dst)
+        .map(|i| {
+            let start = i as f64 * scale;
+            let end = (i + 1) as f64 * scale;
+            let mut weights = Vec::new();
+            let mut j = start.floor() as usize;
+            while (j as f64) < end && j < src {
+                let overlap = (end.min(j as f64 + 1.0) - start.max(j as f64)) / scale;
+                if overlap > 0.0 {
+                    weights.push((j, overlap as f32));
+                }
+                j += 1;
+            }
+            weights
+        })
+        .collect()
+}
+
+fn resample_rows(pixels: &[u8], width: usize, height: usize, new_width: usize) -> Vec<u8> {
+    let weights = coverage(width, new_width);
+    let mut out = Vec::with_capacity(new_width * height * 3);
+    for y in 0..height {
+        let row = &pixels[y * width * 3..(y + 1) * width * 3];
+        for taps in &weights {
+            let mut sum = [0f32; 3];
+            for &(x, weight) in taps {
+                for c in 0..3 {
+                    sum[c] += row[x * 3 + c] as f32 * weight;
+                }
+            }
+            out.extend(sum.iter().map(|v| v.round().clamp(0.0, 255.0) as u8));
+        }
+    }
+    out
+}
+
+fn resample_columns(pixels: &[u8], width: usize, height: usize, new_height: usize) -> Vec<u8> {
+    let weights = coverage(height, new_height);
+    let mut out = Vec::with_capacity(width * new_height * 3);
+    for taps in &weights {
+        for x in 0..width * 3 {
+            let sum: f32 = taps
+                .iter()
+                .map(|&(y, weight)| pixels[y * width * 3 + x] as f32 * weight)
+                .sum();
+            out.push(sum.round().clamp(0.0, 255.0) as u8);
+        }
+    }
+    out
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+/// Tags to apply to imported files, keyed by the file's path in the archive.
+///
+/// Accepted sidecar formats:
+/// - CSV: `filename,tags` rows, an optional header row, tags separated by
+///   `;` or `|` within a cell or spread over extra columns.
+/// - JSON: `{"a.png": ["cat", "dog"]}` or
+///   `[{"filename": "a.png", "tags": ["cat", "dog"]}]`.
+#[derive(Clone, Debug, Default)]
+pub struct TagMapping {
+    tags: HashMap<String, Vec<String>>,
+}
+
+impl TagMapping {
+    /// Parses a sidecar file, choosing the format from its extension.
+    pub fn parse(filename: &str, bytes: &[u8]) -> Result<Self, String> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| format!("Mapping file {} is not valid UTF-8", filename))?
+            .trim_start_matches('\u{feff}');
+
+        match extension(filename).as_deref() {
+            Some("csv") => Self::parse_csv(text),
+            Some("json") => Self::parse_json(text),
+            _ => Err("Mapping file must be a .csv or .json file".to_string()),
+        }
+    }
+
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut mapping = Self::default();
+
+        for (index, row) in parse_csv_rows(text)?.into_iter().enumerate() {
+            let Some((filename, cells)) = row.split_first() else { continue };
+            let filename = filename.trim();
+            if filename.is_empty() {
+                continue;
+            }
+            if index == 0 && ["filename", "file", "image"].contains(&filename.to_lowercase().as_str()) {
+                continue;
+            }
+
+            let tags = cells
+                .iter()
+                .flat_map(|cell| cell.split([';', '|']))
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty());
+            mapping.insert(filename, tags);
+        }
+
+        Ok(mapping)
+    }
+
+    pub fn parse_json(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text)
+            .map_err(|e| format!("Invalid mapping JSON: {}", e))?;
+        let mut mapping = Self::default();
+
+        let tag_list = |value: &serde_json::Value| -> Result<Vec<String>, String> {
+            match value {
+                serde_json::Value::String(tag) => Ok(vec![tag.clone()]),
+                serde_json::Value::Array(tags) => tags
+                    .iter()
+                    .map(|tag| tag.as_str().map(str::to_string).ok_or("Tags must be strings".to_string()))
+                    .collect(),
+                _ => Err("Tags must be a string or an array of strings".to_string()),
+            }
+        };
+
+        match &value {
+            serde_json::Value::Object(files) => {
+                for (filename, tags) in files {
+                    mapping.insert(filename, tag_list(tags)?);
+                }
+            }
+            serde_json::Value::Array(rows) => {
+                for row in rows {
+                    let filename = row
+                        .get("filename")
+                        .and_then(|f| f.as_str())
+                        .ok_or("Every mapping entry needs a \"filename\"")?;
+                    let tags = match row.get("tags") {
+                        Some(tags) => tag_list(tags)?,
+                        None => Vec::new(),
+                    };
+                    mapping.insert(filename, tags);
+                }
+            }
+            _ => return Err("Mapping JSON must be an object or an array".to_string()),
+        }
+
+        Ok(mapping)
+    }
+
+    fn insert(&mut self, filename: &str, tags: impl IntoIterator<Item = String>) {
+        let entry = self.tags.entry(filename.trim_start_matches("./").to_string()).or_default();
+        for tag in tags {
+            let tag = tag.trim().to_string();
+            if !tag.is_empty() && !entry.contains(&tag) {
+                entry.push(tag);
+            }
+        }
+    }
+
+    /// Looks up an archive path, falling back to its bare filename.
+    pub fn tags_for(&self, path: &str) -> Option<&[String]> {
+        self.tags
+            .get(path)
+            .or_else(|| path.rsplit('/').next().and_then(|name| self.tags.get(name)))
+            .map(Vec::as_slice)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+pub fn extension(filename: &str) -> Option<String> {
+    filename.rsplit_once('.').map(|(_, ext)| ext.to_lowercase())
+}
+
+// Minimal RFC 4180 reader: quoted fields, doubled quotes and CRLF line endings
+fn parse_csv_rows(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("Unterminated quoted field in mapping CSV".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
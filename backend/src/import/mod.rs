@@ -0,0 +1,5 @@
+pub mod mapping;
+pub mod zip;
+
+pub use mapping::TagMapping;
+pub use zip::{ZipArchive, ZipEntry};
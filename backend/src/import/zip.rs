@@ -0,0 +1,75 @@
+use ::zip::result::ZipError;
+use std::io::{Read, Seek};
+
+/// One file listed in a ZIP archive's central directory.
+#[derive(Clone, Debug)]
+pub struct ZipEntry {
+    pub name: String,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    index: usize,
+}
+
+impl ZipEntry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+/// Read-only view of a ZIP archive, usually a temporary file the upload was
+/// spooled to. Supports stored and deflated entries, including ZIP64
+/// archives; encrypted entries are listed but cannot be read.
+pub struct ZipArchive<R> {
+    archive: ::zip::ZipArchive<R>,
+    entries: Vec<ZipEntry>,
+}
+
+impl<R: Read + Seek> ZipArchive<R> {
+    pub fn new(reader: R) -> Result<Self, String> {
+        let mut archive = ::zip::ZipArchive::new(reader).map_err(|e| e.to_string())?;
+
+        let mut entries = Vec::with_capacity(archive.len());
+        for index in 0..archive.len() {
+            let file = archive.by_index_raw(index).map_err(|e| e.to_string())?;
+            entries.push(ZipEntry {
+                name: file.name().to_string(),
+                compressed_size: file.compressed_size(),
+                uncompressed_size: file.size(),
+                index,
+            });
+        }
+
+        Ok(Self { archive, entries })
+    }
+
+    pub fn entries(&self) -> &[ZipEntry] {
+        &self.entries
+    }
+
+    /// Decompresses `entry`, refusing to produce more than `max_size` bytes.
+    /// The checksum is verified once the whole entry has been read.
+    pub fn read(&mut self, entry: &ZipEntry, max_size: usize) -> Result<Vec<u8>, String> {
+        if entry.uncompressed_size > max_size as u64 {
+            return Err(format!("Entry exceeds the maximum size of {} bytes", max_size));
+        }
+
+        let file = self.archive.by_index(entry.index).map_err(|e| match e {
+            ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => {
+                "Encrypted entries are not supported".to_string()
+            }
+            e => e.to_string(),
+        })?;
+
+        // Never trust the declared size: cap the output one byte past it
+        let mut bytes = Vec::with_capacity(entry.uncompressed_size as usize);
+        file.take(entry.uncompressed_size + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to decompress entry: {}", e))?;
+
+        if bytes.len() as u64 != entry.uncompressed_size {
+            return Err("Entry size does not match the archive directory".to_string());
+        }
+
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+/// Largest number of keypoints a schema may define.
+pub const MAX_KEYPOINTS: usize = 100;
+
+/// Default distance from the median, as a fraction of the image's diagonal,
+/// beyond which a labeler's point is an outlier.
+pub const DEFAULT_OUTLIER_FRACTION: f64 = 0.05;
+
+/// Visibility flags, as in COCO: not labeled, labeled but occluded, visible.
+pub const NOT_LABELED: u8 = 0;
+pub const OCCLUDED: u8 = 1;
+pub const VISIBLE: u8 = 2;
+
+/// Named points of a tag and the edges between them. `skeleton` holds
+/// 1-based indices into `keypoints`, like COCO's.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeypointSchema {
+    pub keypoints: Vec<String>,
+    #[serde(default)]
+    pub skeleton: Vec<[usize; 2]>,
+}
+
+impl KeypointSchema {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.keypoints.is_empty() {
+            return Err("A keypoint schema needs at least one keypoint".to_string());
+        }
+        if self.keypoints.len() > MAX_KEYPOINTS {
+            return Err(format!("A keypoint schema may have at most {} keypoints", MAX_KEYPOINTS));
+        }
+        for (index, name) in self.keypoints.iter().enumerate() {
+            if name.trim().is_empty() {
+                return Err("Keypoint names must not be empty".to_string());
+            }
+            if self.keypoints[..index].contains(name) {
+                return Err(format!("Keypoint '{}' is defined twice", name));
+            }
+        }
+        for &[from, to] in &self.skeleton {
+            let valid = |index: usize| (1..=self.keypoints.len()).contains(&index);
+            if !valid(from) || !valid(to) || from == to {
+                return Err(format!(
+                    "Skeleton edge [{}, {}] must join two different keypoints between 1 and {}",
+                    from,
+                    to,
+                    self.keypoints.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+}
+
+/// One point of a keypoint set, serialized as `[x, y, visibility]` in pixels
+/// of the image. Points that are not labeled are at (0, 0).
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keypoint(pub f64, pub f64, pub u8);
+
+impl Keypoint {
+    pub const NONE: Keypoint = Keypoint(0.0, 0.0, NOT_LABELED);
+
+    pub fn is_labeled(&self) -> bool {
+        self.2 != NOT_LABELED
+    }
+
+    fn distance(&self, x: f64, y: f64) -> f64 {
+        (self.0 - x).hypot(self.1 - y)
+    }
+}
+
+/// Checks a keypoint set against a schema and an image of `width` ×
+/// `height` pixels.
+pub fn validate(keypoints: &[Keypoint], schema: &KeypointSchema, width: u32, height: u32) -> Result<(), String> {
+    if keypoints.len() != schema.keypoints.len() {
+        return Err(format!("Expected {} keypoints, got {}", schema.keypoints.len(), keypoints.len()));
+    }
+    for (keypoint, name) in keypoints.iter().zip(&schema.keypoints) {
+        let Keypoint(x, y, visibility) = *keypoint;
+        if visibility > VISIBLE {
+            return Err(format!("Visibility of '{}' must be 0, 1 or 2", name));
+        }
+        if visibility == NOT_LABELED {
+            if x != 0.0 || y != 0.0 {
+                return Err(format!("'{}' is not labeled, so it must be at (0, 0)", name));
+            }
+        } else if !x.is_finite() || !y.is_finite() || x < 0.0 || y < 0.0 || x > width as f64 || y > height as f64 {
+            return Err(format!("'{}' must lie within the image ({}x{})", name, width, height));
+        }
+    }
+    if !keypoints.iter().any(Keypoint::is_labeled) {
+        return Err("At least one keypoint must be labeled".to_string());
+    }
+    Ok(())
+}
+
+/// A labeler's point that was too far from the median to count.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct KeypointOutlier {
+    pub labeler_id: i32,
+    /// 0-based index into the schema's keypoints.
+    pub keypoint: usize,
+    /// Pixels from the median of all labelers' points.
+    pub distance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregatedKeypoints {
+    pub keypoints: Vec<Keypoint>,
+    pub outliers: Vec<KeypointOutlier>,
+}
+
+/// Aggregates labelers' keypoint sets of one tag, point by point. A point
+/// is the median of the labelers' points, after dropping those farther than
+/// `outlier_distance` from the median of all of them. It stays unlabeled
+/// unless at least `min_votes` labelers placed it within that distance, and
+/// it is visible unless most of them marked it occluded.
+pub fn aggregate(sets: &[(i32, Vec<Keypoint>)], outlier_distance: f64, min_votes: usize) -> AggregatedKeypoints {
+    let count = sets.iter().map(|(_, keypoints)| keypoints.len()).max().unwrap_or(0);
+    let mut keypoints = Vec::with_capacity(count);
+    let mut outliers = Vec::new();
+    for index in 0..count {
+        let placed: Vec<(i32, Keypoint)> = sets
+            .iter()
+            .filter_map(|(labeler_id, keypoints)| keypoints.get(index).map(|keypoint| (*labeler_id, *keypoint)))
+            .filter(|(_, keypoint)| keypoint.is_labeled())
+            .collect();
+        if placed.is_empty() {
+            keypoints.push(Keypoint::NONE);
+            continue;
+        }
+
+        let (x, y) = median_point(placed.iter().map(|(_, keypoint)| keypoint));
+        let mut inliers = Vec::new();
+        for (labeler_id, keypoint) in &placed {
+            let distance = keypoint.distance(x, y);
+            if distance > outlier_distance {
+                outliers.push(KeypointOutlier {
+                    labeler_id: *labeler_id,
+                    keypoint: index,
+                    distance,
+                });
+            } else {
+                inliers.push(*keypoint);
+            }
+        }
+        if inliers.len() < min_votes.max(1) {
+            keypoints.push(Keypoint::NONE);
+            continue;
+        }
+
+        let (x, y) = median_point(inliers.iter());
+        let visible = inliers.iter().filter(|keypoint| keypoint.2 == VISIBLE).count();
+        let visibility = if visible * 2 >= inliers.len() { VISIBLE } else { OCCLUDED };
+        keypoints.push(Keypoint(x, y, visibility));
+    }
+    AggregatedKeypoints { keypoints, outliers }
+}
+
+// Coordinate-wise median
+fn median_point<'a>(keypoints: impl Iterator<Item = &'a Keypoint>) -> (f64, f64) {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = keypoints.map(|keypoint| (keypoint.0, keypoint.1)).unzip();
+    (median(xs), median(ys))
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+/// `[x, y, width, height]` around the labeled points, if any.
+pub fn bbox(keypoints: &[Keypoint]) -> Option<[f64; 4]> {
+    let labeled: Vec<&Keypoint> = keypoints.iter().filter(|keypoint| keypoint.is_labeled()).collect();
+    if labeled.is_empty() {
+        return None;
+    }
+    let min_x = labeled.iter().map(|keypoint| keypoint.0).fold(f64::INFINITY, f64::min);
+    let min_y = labeled.iter().map(|keypoint| keypoint.1).fold(f64::INFINITY, f64::min);
+    let max_x = labeled.iter().map(|keypoint| keypoint.0).fold(f64::NEG_INFINITY, f64::max);
+    let max_y = labeled.iter().map(|keypoint| keypoint.1).fold(f64::NEG_INFINITY, f64::max);
+    Some([min_x, min_y, max_x - min_x, max_y - min_y])
+}
@@ -1,7 +1,19 @@
 pub mod entity;
 pub mod repository;
 pub mod database;
+pub mod migrations;
 pub mod schemas;
 pub mod service;
 pub mod routes;
 pub mod middleware;
+pub mod multipart;
+pub mod import;
+pub mod imaging;
+pub mod aggregation;
+pub mod agreement;
+pub mod consensus;
+pub mod keypoint;
+pub mod region;
+pub mod segmentation;
+pub mod storage;
+pub mod task;
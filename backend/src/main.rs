@@ -1,23 +1,44 @@
 use actix_web::{web, App, HttpServer};
 use actix_cors::Cors;
-use image_labeling_website::database::{establish_connection, create_tables};
+use image_labeling_website::database::{establish_connection, run_migrations};
+use image_labeling_website::migrations;
+use image_labeling_website::multipart::MultipartLimits;
+use image_labeling_website::storage::{self, ImageStorage};
 use image_labeling_website::routes::auth::login;
 use image_labeling_website::routes::admin::labeler::{
     create_labeler, get_labeler, list_labelers, update_labeler, delete_labeler
 };
-use image_labeling_website::routes::admin::groups::{list_groups, create_group, get_group_details, delete_group, add_labeler_to_group, remove_labeler_from_group};
-use image_labeling_website::routes::admin::image::{upload_image, get_image_details};
+use image_labeling_website::routes::admin::groups::{list_groups, create_group, get_group_details, update_group, delete_group, add_labeler_to_group, remove_labeler_from_group};
+use image_labeling_website::routes::admin::image::{upload_image, upload_images, get_image_details};
 use image_labeling_website::routes::admin::tag::{
     create_tag, get_tag, list_tags_by_group, update_tag, delete_tag
 };
 use image_labeling_website::routes::admin::final_tags::{
     get_final_tags, update_final_tags, auto_generate_final_tags
 };
-use image_labeling_website::routes::admin::export::bulk_export;
-use image_labeling_website::routes::labeler::groups::{get_groups, get_group_images};
-use image_labeling_website::routes::labeler::images::{get_labeler_image_details, update_image_tags, suggest_tags};
+use image_labeling_website::routes::admin::duplicates::get_duplicate_report;
+use image_labeling_website::routes::admin::aggregation::{apply_aggregation, get_aggregation};
+use image_labeling_website::routes::admin::analytics::{get_group_agreement, get_labeler_score, get_leaderboard, get_throughput};
+use image_labeling_website::routes::admin::captions::{draft_final_caption, get_image_captions, select_final_caption};
+use image_labeling_website::routes::admin::export::{bulk_export, caption_export, coco_export};
+use image_labeling_website::routes::admin::flags::get_flagged_images;
+use image_labeling_website::routes::admin::gold::{get_gold_report, set_gold};
+use image_labeling_website::routes::admin::import::{import_zip, get_import_job, list_import_jobs};
+use image_labeling_website::routes::admin::keypoints::{aggregate_keypoints, get_image_keypoints};
+use image_labeling_website::routes::admin::regions::{auto_generate_final_regions, get_image_regions};
+use image_labeling_website::routes::admin::segmentations::{get_image_segmentations, merge_segmentations};
+use image_labeling_website::routes::labeler::groups::{get_groups, get_group_images, get_next_image};
+use image_labeling_website::routes::labeler::images::{get_labeler_image_details, update_image_tags, suggest_tags, skip_image, flag_image};
+use image_labeling_website::routes::labeler::captions::{draft_caption, get_caption, save_caption};
+use image_labeling_website::routes::labeler::keypoints::{delete_keypoint_set, list_keypoint_sets, save_keypoint_set};
+use image_labeling_website::routes::labeler::regions::{create_region, delete_region, list_regions, update_region};
+use image_labeling_website::routes::labeler::segmentations::{
+    create_segmentation, delete_segmentation, list_segmentations, update_segmentation,
+};
 use image_labeling_website::repository::AdminRepository;
-use image_labeling_website::middleware::auth::{AdminAuthMiddleware, LabelerAuthMiddleware};
+use image_labeling_website::service::import::ImportService;
+use image_labeling_website::routes::images::{get_image_content, get_image_thumbnail};
+use image_labeling_website::middleware::auth::{self, AdminAuthMiddleware, LabelerAuthMiddleware, ImageAuthMiddleware};
 use dotenv::dotenv;
 use bcrypt::hash;
 
@@ -33,9 +54,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let db = establish_connection().await?;
     println!("Database connected successfully!");
     
-    // Create tables
-    create_tables(&db).await?;
-    println!("Database tables created successfully!");
+    // Set up the image storage backend
+    let image_storage = storage::from_env()?;
+    
+    // `cargo run -- migrate [status]` manages the schema without starting the server
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(command) = args.get(1) {
+        return run_command(&db, image_storage.as_ref(), command, &args[2..]).await;
+    }
+    
+    // Logins and signed image URLs cannot work without the secret
+    auth::require_jwt_secret()?;
+
+    // Apply pending migrations
+    run_migrations(&db).await?;
+    println!("Database schema is at version {}", migrations::latest_version());
+    
+    // Move any images still stored as base64 in the database into storage
+    let moved = storage::migrate_legacy_images(&db, image_storage.as_ref()).await?;
+    if moved > 0 {
+        println!("Moved {} legacy images into image storage", moved);
+    }
+    let backfilled = storage::backfill_image_metadata(&db, image_storage.as_ref()).await?;
+    if backfilled > 0 {
+        println!("Recorded metadata for {} existing images", backfilled);
+    }
+    let hashed = storage::backfill_image_hashes(&db, image_storage.as_ref()).await?;
+    if hashed > 0 {
+        println!("Computed perceptual hashes for {} existing images", hashed);
+    }
+    let interrupted = ImportService::fail_interrupted_jobs(&db).await?;
+    if interrupted > 0 {
+        println!("Marked {} interrupted import jobs as failed", interrupted);
+    }
     
     // Create default admin user if it doesn't exist
     println!("Setting up default admin user...");
@@ -55,6 +106,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
     
+    // Limits for multipart image uploads
+    let upload_limits = web::Data::new(MultipartLimits::from_env());
+
     println!("Starting HTTP server on http://127.0.0.1:8080");
     
     // Start the HTTP server
@@ -63,11 +117,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .allow_any_origin()
             .allow_any_method()
             .allow_any_header()
+            .expose_headers(["X-Total-Count"])
             .max_age(3600);
             
         App::new()
             .wrap(cors)
             .app_data(web::Data::new(db.clone()))
+            .app_data(web::Data::from(image_storage.clone()))
+            .app_data(upload_limits.clone())
             .service(
                 web::scope("/api/v1")
                     .route("/login", web::post().to(login))
@@ -77,14 +134,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             .route("/groups", web::get().to(list_groups))
                             .route("/groups", web::post().to(create_group))
                             .route("/groups/{id}", web::get().to(get_group_details))
+                            .route("/groups/{id}", web::put().to(update_group))
                             .route("/groups/{id}", web::delete().to(delete_group))
                             .route("/groups/{id}/labelers", web::post().to(add_labeler_to_group))
                             .route("/groups/{group_id}/labelers/{labeler_id}", web::delete().to(remove_labeler_from_group))
                             .route("/groups/{group_id}/image/{image_id}", web::get().to(get_image_details))
+                            .route("/groups/{group_id}/images", web::post().to(upload_images))
+                            .route("/groups/{group_id}/import", web::post().to(import_zip))
+                            .route("/groups/{group_id}/import-jobs", web::get().to(list_import_jobs))
+                            .route("/groups/{group_id}/duplicates", web::get().to(get_duplicate_report))
+                            .route("/groups/{group_id}/aggregation", web::get().to(get_aggregation))
+                            .route("/groups/{group_id}/aggregation", web::post().to(apply_aggregation))
+                            .route("/groups/{group_id}/analytics/agreement", web::get().to(get_group_agreement))
+                            .route("/groups/{group_id}/gold", web::get().to(get_gold_report))
+                            .route("/groups/{group_id}/export/coco", web::get().to(coco_export))
+                            .route("/groups/{group_id}/export/captions", web::get().to(caption_export))
+                            .route("/import-jobs/{id}", web::get().to(get_import_job))
+                            .route("/analytics/leaderboard", web::get().to(get_leaderboard))
+                            .route("/analytics/throughput", web::get().to(get_throughput))
+                            .route("/flagged-images", web::get().to(get_flagged_images))
                             .route("/image", web::post().to(upload_image))
                             .route("/image/{image_id}/final-tags", web::get().to(get_final_tags))
                             .route("/image/{image_id}/final-tags", web::put().to(update_final_tags))
                             .route("/image/{image_id}/final-tags/auto-generate", web::post().to(auto_generate_final_tags))
+                            .route("/image/{image_id}/gold", web::put().to(set_gold))
+                            .route("/image/{image_id}/regions", web::get().to(get_image_regions))
+                            .route("/image/{image_id}/regions/auto-generate", web::post().to(auto_generate_final_regions))
+                            .route("/image/{image_id}/segmentations", web::get().to(get_image_segmentations))
+                            .route("/image/{image_id}/segmentations/merge", web::post().to(merge_segmentations))
+                            .route("/image/{image_id}/keypoints", web::get().to(get_image_keypoints))
+                            .route("/image/{image_id}/keypoints/auto-generate", web::post().to(aggregate_keypoints))
+                            .route("/image/{image_id}/captions", web::get().to(get_image_captions))
+                            .route("/image/{image_id}/captions/draft", web::post().to(draft_final_caption))
+                            .route("/image/{image_id}/final-caption", web::put().to(select_final_caption))
                             .route("/export/bulk", web::get().to(bulk_export))
                             .service(
                                 web::scope("/tag")
@@ -101,15 +183,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                     .route("/{id}", web::get().to(get_labeler))
                                     .route("/{id}", web::put().to(update_labeler))
                                     .route("/{id}", web::delete().to(delete_labeler))
+                                    .route("/{id}/scores", web::get().to(get_labeler_score))
                             )
                     )
+                    .service(
+                        web::scope("/images")
+                            .wrap(ImageAuthMiddleware)
+                            .route("/{id}/content", web::get().to(get_image_content))
+                            .route("/{id}/thumbnail", web::get().to(get_image_thumbnail))
+                    )
                     .service(
                         web::scope("/labeler")
                             .wrap(LabelerAuthMiddleware)
                             .route("/groups", web::get().to(get_groups))
                             .route("/groups/{group_id}/images", web::get().to(get_group_images))
+                            .route("/groups/{group_id}/next", web::get().to(get_next_image))
                             .route("/groups/{group_id}/images/{image_id}", web::get().to(get_labeler_image_details))
                             .route("/groups/{group_id}/images/{image_id}/tags", web::put().to(update_image_tags))
+                            .route("/groups/{group_id}/images/{image_id}/skip", web::post().to(skip_image))
+                            .route("/groups/{group_id}/images/{image_id}/flag", web::post().to(flag_image))
+                            .route("/groups/{group_id}/images/{image_id}/regions", web::get().to(list_regions))
+                            .route("/groups/{group_id}/images/{image_id}/regions", web::post().to(create_region))
+                            .route("/groups/{group_id}/images/{image_id}/regions/{region_id}", web::put().to(update_region))
+                            .route("/groups/{group_id}/images/{image_id}/regions/{region_id}", web::delete().to(delete_region))
+                            .route("/groups/{group_id}/images/{image_id}/segmentations", web::get().to(list_segmentations))
+                            .route("/groups/{group_id}/images/{image_id}/segmentations", web::post().to(create_segmentation))
+                            .route("/groups/{group_id}/images/{image_id}/segmentations/{segmentation_id}", web::put().to(update_segmentation))
+                            .route("/groups/{group_id}/images/{image_id}/segmentations/{segmentation_id}", web::delete().to(delete_segmentation))
+                            .route("/groups/{group_id}/images/{image_id}/keypoints", web::get().to(list_keypoint_sets))
+                            .route("/groups/{group_id}/images/{image_id}/keypoints", web::put().to(save_keypoint_set))
+                            .route("/groups/{group_id}/images/{image_id}/keypoints/{tag_id}", web::delete().to(delete_keypoint_set))
+                            .route("/groups/{group_id}/images/{image_id}/caption", web::get().to(get_caption))
+                            .route("/groups/{group_id}/images/{image_id}/caption", web::put().to(save_caption))
+                            .route("/groups/{group_id}/images/{image_id}/caption/draft", web::post().to(draft_caption))
                             .route("/images/{image_id}/suggest_tags", web::post().to(suggest_tags))
                     )
             )
@@ -119,4 +225,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     .await?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+async fn run_command(
+    db: &sea_orm::DatabaseConnection,
+    image_storage: &dyn ImageStorage,
+    command: &str,
+    args: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    match (command, args.first().map(String::as_str)) {
+        ("migrate", None) => {
+            let applied = migrations::run(db).await?;
+            if applied.is_empty() {
+                println!("Database is already up to date.");
+            }
+            for migration in applied {
+                println!("Applied migration {:04} {}", migration.version, migration.name);
+            }
+            let moved = storage::migrate_legacy_images(db, image_storage).await?;
+            if moved > 0 {
+                println!("Moved {} legacy images into image storage", moved);
+            }
+            let backfilled = storage::backfill_image_metadata(db, image_storage).await?;
+            if backfilled > 0 {
+                println!("Recorded metadata for {} existing images", backfilled);
+            }
+            let hashed = storage::backfill_image_hashes(db, image_storage).await?;
+            if hashed > 0 {
+                println!("Computed perceptual hashes for {} existing images", hashed);
+            }
+            Ok(())
+        }
+        ("migrate", Some("status")) => {
+            println!("Current schema version: {}", migrations::current_version(db).await?);
+            println!("Latest known version: {}", migrations::latest_version());
+            for migration in migrations::pending(db).await? {
+                println!("Pending migration {:04} {}", migration.version, migration.name);
+            }
+            Ok(())
+        }
+        _ => Err(format!("Unknown command: {} (expected `migrate` or `migrate status`)", command).into()),
+    }
+}
@@ -1,6 +1,6 @@
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    Error, HttpMessage, HttpResponse,
+    web, Error, HttpMessage, HttpResponse,
 };
 use actix_web::body::EitherBody;
 use futures_util::future::LocalBoxFuture;
@@ -10,9 +10,116 @@ use std::{
     rc::Rc,
 };
 use std::env;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 
+use sea_orm::DatabaseConnection;
+use crate::repository::LabelerRepository;
 use crate::schemas::auth::Claims;
 
+// Signed URLs expire at the end of the window after the one they were
+// issued in, so URLs issued within one window are identical and stay
+// cacheable by the browser
+const SIGNED_URL_WINDOW_SECONDS: i64 = 10 * 60;
+
+/// Decodes and verifies a token issued at login.
+pub fn validate_jwt_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
+    let jwt_secret = env::var("JWT_SECRET")
+        .map_err(|_| jsonwebtoken::errors::ErrorKind::InvalidToken)?;
+
+    let validation = Validation::new(Algorithm::HS256);
+    let token_data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(jwt_secret.as_ref()),
+        &validation,
+    )?;
+
+    Ok(token_data.claims)
+}
+
+/// Marks a request let through [`ImageAuthMiddleware`] by a signed URL
+/// rather than a user's token. It grants access to that URL's path only.
+#[derive(Clone, Copy, Debug)]
+pub struct SignedUrl;
+
+/// Fails unless `JWT_SECRET` is set. Neither tokens nor signed image URLs
+/// can be issued without it, so the server refuses to start instead.
+pub fn require_jwt_secret() -> Result<(), String> {
+    match env::var("JWT_SECRET") {
+        Ok(secret) if !secret.is_empty() => Ok(()),
+        _ => Err("JWT_SECRET environment variable not set".to_string()),
+    }
+}
+
+// URLs are signed with a key derived from the JWT secret rather than the
+// secret itself, so one kind of signature never verifies as the other
+fn url_signature(secret: &str, path: &str, expires: i64) -> Hmac<Sha256> {
+    let mut key = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    key.update(b"image-url");
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key.finalize().into_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}:{}", path, expires).as_bytes());
+    mac
+}
+
+/// Appends `expires` and `signature` query parameters to `path`, so that
+/// browsers can load it from a plain `<img src>`, which cannot set headers,
+/// for the next 10 to 20 minutes. Needs `JWT_SECRET`, see
+/// [`require_jwt_secret`].
+pub fn sign_url(path: &str) -> String {
+    let secret = env::var("JWT_SECRET").expect("JWT_SECRET is checked at startup");
+    let expires = (chrono::Utc::now().timestamp() / SIGNED_URL_WINDOW_SECONDS + 2) * SIGNED_URL_WINDOW_SECONDS;
+    let signature = hex::encode(url_signature(&secret, path, expires).finalize().into_bytes());
+
+    format!("{}?expires={}&signature={}", path, expires, signature)
+}
+
+/// Whether `query` holds an unexpired signature of `path` made by
+/// [`sign_url`].
+pub fn verify_signed_url(path: &str, query: &str) -> bool {
+    let Ok(secret) = env::var("JWT_SECRET") else {
+        return false;
+    };
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    };
+    let Some(expires) = param("expires").and_then(|value| value.parse::<i64>().ok()) else {
+        return false;
+    };
+    let Some(signature) = param("signature").and_then(|value| hex::decode(value).ok()) else {
+        return false;
+    };
+
+    expires > chrono::Utc::now().timestamp()
+        && url_signature(&secret, path, expires).verify_slice(&signature).is_ok()
+}
+
+// Suspended labelers keep valid tokens but may not use them. The check fails
+// closed: a labeler is only let through once the database says they are not
+// suspended.
+async fn suspension_response(req: &ServiceRequest, claims: &Claims) -> Option<HttpResponse> {
+    if claims.role != "labeler" {
+        return None;
+    }
+    let Some(db) = req.app_data::<web::Data<DatabaseConnection>>() else {
+        return Some(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Database connection not configured"
+        })));
+    };
+    match LabelerRepository::find_by_id(db, claims.user_id).await {
+        Ok(Some(labeler)) if labeler.suspended => Some(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Account suspended"
+        }))),
+        Ok(_) => None,
+        Err(e) => Some(HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Database error: {}", e)
+        }))),
+    }
+}
+
 pub struct AdminAuthMiddleware;
 
 impl<S, B> Transform<S, ServiceRequest> for AdminAuthMiddleware
@@ -76,7 +183,7 @@ where
                     let token = &header[7..]; // Remove "Bearer " prefix
 
                     // Validate the JWT token
-                    match Self::validate_jwt_token(token) {
+                    match validate_jwt_token(token) {
                         Ok(claims) => {
                             // Check if the role is admin
                             if claims.role != "admin" {
@@ -181,7 +288,7 @@ where
                     let token = &header[7..]; // Remove "Bearer " prefix
 
                     // Validate the JWT token
-                    match Self::validate_jwt_token(token) {
+                    match validate_jwt_token(token) {
                         Ok(claims) => {
                             // Check if the role is labeler
                             if claims.role != "labeler" {
@@ -193,6 +300,10 @@ where
                                 return Ok(ServiceResponse::new(req.into_parts().0, response));
                             }
 
+                            if let Some(response) = suspension_response(&req, &claims).await {
+                                return Ok(ServiceResponse::new(req.into_parts().0, response.map_into_right_body()));
+                            }
+
                             // Add user info to request extensions for use in handlers
                             req.extensions_mut().insert(claims);
 
@@ -223,34 +334,116 @@ where
     }
 }
 
-impl<S> LabelerAuthMiddlewareService<S> {
-    fn validate_jwt_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-        let jwt_secret = env::var("JWT_SECRET")
-            .map_err(|_| jsonwebtoken::errors::ErrorKind::InvalidToken)?;
+/// Accepts any authenticated user (admin or labeler) by their
+/// `Authorization: Bearer` header, or requests for a URL signed with
+/// [`sign_url`], which are marked with [`SignedUrl`] instead of carrying
+/// claims.
+pub struct ImageAuthMiddleware;
 
-        let validation = Validation::new(Algorithm::HS256);
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(jwt_secret.as_ref()),
-            &validation,
-        )?;
+impl<S, B> Transform<S, ServiceRequest> for ImageAuthMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = ImageAuthMiddlewareService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
-        Ok(token_data.claims)
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ImageAuthMiddlewareService {
+            service: Rc::new(service),
+        }))
     }
 }
 
-impl<S> AdminAuthMiddlewareService<S> {
-    fn validate_jwt_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-        let jwt_secret = env::var("JWT_SECRET")
-            .map_err(|_| jsonwebtoken::errors::ErrorKind::InvalidToken)?;
+pub struct ImageAuthMiddlewareService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ImageAuthMiddlewareService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
 
-        let validation = Validation::new(Algorithm::HS256);
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(jwt_secret.as_ref()),
-            &validation,
-        )?;
+        Box::pin(async move {
+            let header = req
+                .headers()
+                .get("Authorization")
+                .and_then(|header| header.to_str().ok());
+            let token = match header {
+                Some(header) => match header.strip_prefix("Bearer ") {
+                    Some(token) => token.to_string(),
+                    None => {
+                        let response = HttpResponse::Unauthorized()
+                            .json(serde_json::json!({
+                                "error": "Invalid authorization header format"
+                            }))
+                            .map_into_right_body();
+                        return Ok(ServiceResponse::new(req.into_parts().0, response));
+                    }
+                },
+                None => {
+                    if verify_signed_url(req.path(), req.query_string()) {
+                        req.extensions_mut().insert(SignedUrl);
+                        let res = service.call(req).await?;
+                        return Ok(res.map_into_left_body());
+                    }
 
-        Ok(token_data.claims)
+                    let response = HttpResponse::Unauthorized()
+                        .json(serde_json::json!({
+                            "error": "Authorization header missing"
+                        }))
+                        .map_into_right_body();
+                    return Ok(ServiceResponse::new(req.into_parts().0, response));
+                }
+            };
+
+            // Validate the JWT token
+            match validate_jwt_token(&token) {
+                Ok(claims) => {
+                    // Check if the role is one we know about
+                    if claims.role != "admin" && claims.role != "labeler" {
+                        let response = HttpResponse::Forbidden()
+                            .json(serde_json::json!({
+                                "error": "Insufficient permissions."
+                            }))
+                            .map_into_right_body();
+                        return Ok(ServiceResponse::new(req.into_parts().0, response));
+                    }
+
+                    if let Some(response) = suspension_response(&req, &claims).await {
+                        return Ok(ServiceResponse::new(req.into_parts().0, response.map_into_right_body()));
+                    }
+
+                    // Add user info to request extensions for use in handlers
+                    req.extensions_mut().insert(claims);
+
+                    // Continue with the request
+                    let res = service.call(req).await?;
+                    Ok(res.map_into_left_body())
+                }
+                Err(_) => {
+                    let response = HttpResponse::Unauthorized()
+                        .json(serde_json::json!({
+                            "error": "Invalid or expired token"
+                        }))
+                        .map_into_right_body();
+                    Ok(ServiceResponse::new(req.into_parts().0, response))
+                }
+            }
+        })
     }
 }
@@ -0,0 +1,84 @@
+use super::Migration;
+
+// Baseline schema. Uses IF NOT EXISTS so databases created before the
+// migration runner existed are adopted at version 1 without changes.
+pub const MIGRATION: Migration = Migration {
+    version: 1,
+    name: "initial_schema",
+    statements: &[
+        r#"
+        CREATE TABLE IF NOT EXISTS admin (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL UNIQUE,
+            password_hash TEXT NOT NULL
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS labeler (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL UNIQUE,
+            password_hash TEXT NOT NULL
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS "group" (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS image (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            filename TEXT NOT NULL,
+            filetype TEXT NOT NULL,
+            base64_data TEXT NOT NULL,
+            group_id INTEGER NOT NULL,
+            uploaded_at DATETIME NOT NULL,
+            FOREIGN KEY (group_id) REFERENCES "group"(id)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS tag (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            description TEXT,
+            group_id INTEGER NOT NULL,
+            FOREIGN KEY (group_id) REFERENCES "group"(id)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS image_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id),
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id),
+            FOREIGN KEY (tag_id) REFERENCES tag(id),
+            UNIQUE(image_id, labeler_id, tag_id)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS labeler_groups (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            labeler_id INTEGER NOT NULL,
+            group_id INTEGER NOT NULL,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id),
+            FOREIGN KEY (group_id) REFERENCES "group"(id)
+        )
+        "#,
+        r#"
+        CREATE TABLE IF NOT EXISTS final_tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            is_admin_override BOOLEAN NOT NULL DEFAULT 0,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id),
+            FOREIGN KEY (tag_id) REFERENCES tag(id)
+        )
+        "#,
+    ],
+};
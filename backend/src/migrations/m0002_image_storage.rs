@@ -0,0 +1,23 @@
+use super::Migration;
+
+// Moves image payloads out of the `image` table. The base64 TEXT is parked in
+// `legacy_image_data` until `storage::migrate_legacy_images` copies it into
+// the configured `ImageStorage` backend and fills in `storage_key`.
+pub const MIGRATION: Migration = Migration {
+    version: 2,
+    name: "image_storage",
+    statements: &[
+        "ALTER TABLE image ADD COLUMN storage_key TEXT",
+        "ALTER TABLE image ADD COLUMN size_bytes INTEGER",
+        r#"
+        CREATE TABLE legacy_image_data (
+            image_id INTEGER PRIMARY KEY,
+            base64_data TEXT NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id)
+        )
+        "#,
+        "INSERT INTO legacy_image_data (image_id, base64_data) SELECT id, base64_data FROM image",
+        "ALTER TABLE image DROP COLUMN base64_data",
+        "CREATE INDEX IF NOT EXISTS idx_image_group_id ON image(group_id)",
+    ],
+};
@@ -0,0 +1,27 @@
+use super::Migration;
+
+// Background ZIP imports. Per-entry errors are kept as a JSON array in
+// `errors` since they are only ever read back whole.
+pub const MIGRATION: Migration = Migration {
+    version: 3,
+    name: "import_jobs",
+    statements: &[
+        r#"
+        CREATE TABLE import_job (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            group_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            total_entries INTEGER NOT NULL DEFAULT 0,
+            processed_entries INTEGER NOT NULL DEFAULT 0,
+            created_images INTEGER NOT NULL DEFAULT 0,
+            created_tags INTEGER NOT NULL DEFAULT 0,
+            errors TEXT NOT NULL DEFAULT '[]',
+            message TEXT,
+            created_at DATETIME NOT NULL,
+            finished_at DATETIME,
+            FOREIGN KEY (group_id) REFERENCES "group"(id)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_import_job_group_id ON import_job(group_id)",
+    ],
+};
@@ -0,0 +1,14 @@
+use super::Migration;
+
+// Header metadata recorded at upload time. Existing rows stay NULL until
+// `storage::backfill_image_metadata` reads them back from storage.
+pub const MIGRATION: Migration = Migration {
+    version: 4,
+    name: "image_metadata",
+    statements: &[
+        "ALTER TABLE image ADD COLUMN width INTEGER",
+        "ALTER TABLE image ADD COLUMN height INTEGER",
+        "ALTER TABLE image ADD COLUMN color_mode TEXT",
+        "ALTER TABLE image ADD COLUMN orientation INTEGER",
+    ],
+};
@@ -0,0 +1,23 @@
+use super::Migration;
+
+// Cache of generated thumbnails. The bytes live in image storage like the
+// originals; a row records which object holds each (image, size) pair.
+pub const MIGRATION: Migration = Migration {
+    version: 5,
+    name: "image_thumbnails",
+    statements: &[
+        r#"
+        CREATE TABLE image_thumbnail (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            size TEXT NOT NULL,
+            storage_key TEXT NOT NULL,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            UNIQUE(image_id, size)
+        )
+        "#,
+    ],
+};
@@ -0,0 +1,14 @@
+use super::Migration;
+
+// Per-group upload allow-list, stored as a JSON array of MIME types. NULL
+// accepts every supported format. Existing file types are rewritten to the
+// MIME types uploads now record.
+pub const MIGRATION: Migration = Migration {
+    version: 6,
+    name: "group_image_formats",
+    statements: &[
+        r#"ALTER TABLE "group" ADD COLUMN allowed_formats TEXT"#,
+        "UPDATE image SET filetype = 'image/png' WHERE lower(filetype) = 'png'",
+        "UPDATE image SET filetype = 'image/jpeg' WHERE lower(filetype) IN ('jpg', 'jpeg')",
+    ],
+};
@@ -0,0 +1,18 @@
+use super::Migration;
+
+// Perceptual hashes (16 hex digits each) for near-duplicate detection. The
+// exact content hash is the SHA-256 `storage_key`, indexed per group so
+// duplicate uploads can be found. Existing rows are hashed by
+// `storage::backfill_image_hashes`. `duplicate_of` points at the first copy
+// of an exact duplicate accepted by a group whose policy is 'flag'.
+pub const MIGRATION: Migration = Migration {
+    version: 7,
+    name: "image_hashes",
+    statements: &[
+        "ALTER TABLE image ADD COLUMN average_hash TEXT",
+        "ALTER TABLE image ADD COLUMN difference_hash TEXT",
+        "ALTER TABLE image ADD COLUMN duplicate_of INTEGER REFERENCES image(id) ON DELETE SET NULL",
+        "CREATE INDEX IF NOT EXISTS idx_image_group_storage_key ON image(group_id, storage_key)",
+        r#"ALTER TABLE "group" ADD COLUMN duplicate_policy TEXT NOT NULL DEFAULT 'reject'"#,
+    ],
+};
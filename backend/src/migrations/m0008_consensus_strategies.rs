@@ -0,0 +1,15 @@
+use super::Migration;
+
+// Consensus strategy (`consensus::ConsensusStrategy` as JSON) chosen for a
+// group, NULL meaning the default 50% majority, and the strategy that
+// produced each auto-generated final tag. Auto-generated rows so far all
+// came from the 50% majority rule; admin overrides and imports stay NULL.
+pub const MIGRATION: Migration = Migration {
+    version: 8,
+    name: "consensus_strategies",
+    statements: &[
+        r#"ALTER TABLE "group" ADD COLUMN consensus_strategy TEXT"#,
+        "ALTER TABLE final_tags ADD COLUMN consensus_strategy TEXT",
+        r#"UPDATE final_tags SET consensus_strategy = '{"type":"majority","threshold":0.5}' WHERE is_admin_override = 0"#,
+    ],
+};
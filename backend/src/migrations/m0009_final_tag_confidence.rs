@@ -0,0 +1,9 @@
+use super::Migration;
+
+// Posterior probability of a final tag when it was estimated by label
+// aggregation; NULL for vote-based tags and admin overrides.
+pub const MIGRATION: Migration = Migration {
+    version: 9,
+    name: "final_tag_confidence",
+    statements: &["ALTER TABLE final_tags ADD COLUMN confidence REAL"],
+};
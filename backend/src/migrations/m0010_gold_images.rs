@@ -0,0 +1,39 @@
+use super::Migration;
+
+// Gold (honeypot) images with their known tags, one scored result per
+// labeler and gold image, and the per-group rolling accuracy rule that can
+// suspend labelers. `gold_min_accuracy` NULL leaves suspension off.
+pub const MIGRATION: Migration = Migration {
+    version: 10,
+    name: "gold_images",
+    statements: &[
+        "ALTER TABLE image ADD COLUMN is_gold BOOLEAN NOT NULL DEFAULT 0",
+        r#"
+        CREATE TABLE gold_tag (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE,
+            UNIQUE(image_id, tag_id)
+        )
+        "#,
+        r#"
+        CREATE TABLE gold_result (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            labeler_id INTEGER NOT NULL,
+            image_id INTEGER NOT NULL,
+            correct BOOLEAN NOT NULL,
+            answered_at DATETIME NOT NULL,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            UNIQUE(labeler_id, image_id)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_gold_result_labeler ON gold_result(labeler_id, answered_at)",
+        r#"ALTER TABLE "group" ADD COLUMN gold_window INTEGER NOT NULL DEFAULT 10"#,
+        r#"ALTER TABLE "group" ADD COLUMN gold_min_accuracy REAL"#,
+        "ALTER TABLE labeler ADD COLUMN suspended BOOLEAN NOT NULL DEFAULT 0",
+        "ALTER TABLE labeler ADD COLUMN suspended_reason TEXT",
+    ],
+};
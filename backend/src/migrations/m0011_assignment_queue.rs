@@ -0,0 +1,25 @@
+use super::Migration;
+
+// Leases handed out by the labeler queue, at most one per image and labeler,
+// and the per-group redundancy and lease length the queue works with.
+pub const MIGRATION: Migration = Migration {
+    version: 11,
+    name: "assignment_queue",
+    statements: &[
+        r#"
+        CREATE TABLE assignment (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            leased_at DATETIME NOT NULL,
+            expires_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            UNIQUE(image_id, labeler_id)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_assignment_expires_at ON assignment(expires_at)",
+        r#"ALTER TABLE "group" ADD COLUMN labels_per_image INTEGER NOT NULL DEFAULT 3"#,
+        r#"ALTER TABLE "group" ADD COLUMN lease_seconds INTEGER NOT NULL DEFAULT 900"#,
+    ],
+};
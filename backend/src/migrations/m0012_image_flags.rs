@@ -0,0 +1,25 @@
+use super::Migration;
+
+// A labeler's skip or problem flag on an image, with a reason and an
+// optional note. One per image and labeler; a later action replaces it.
+pub const MIGRATION: Migration = Migration {
+    version: 12,
+    name: "image_flags",
+    statements: &[
+        r#"
+        CREATE TABLE image_flag (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            reason TEXT NOT NULL,
+            note TEXT,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            UNIQUE(image_id, labeler_id)
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_image_flag_kind ON image_flag(kind)",
+    ],
+};
@@ -0,0 +1,26 @@
+use super::Migration;
+
+// When each labeler last submitted each image, whether or not they chose any
+// tags. Existing tags count as submissions at the time they were saved.
+pub const MIGRATION: Migration = Migration {
+    version: 13,
+    name: "submissions",
+    statements: &[
+        r#"
+        CREATE TABLE submission (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            submitted_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            UNIQUE(image_id, labeler_id)
+        )
+        "#,
+        r#"
+        INSERT INTO submission (image_id, labeler_id, submitted_at)
+        SELECT image_id, labeler_id, MAX(created_at) FROM image_tags GROUP BY image_id, labeler_id
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_submission_labeler ON submission(labeler_id)",
+    ],
+};
@@ -0,0 +1,27 @@
+use super::Migration;
+
+// Timing of labeling work: images labelers have opened but not submitted
+// yet, and for each submission when it was first made, when the image was
+// opened before that and how long that took. `submitted_at` stays the time
+// of the latest change.
+pub const MIGRATION: Migration = Migration {
+    version: 14,
+    name: "label_timing",
+    statements: &[
+        r#"
+        CREATE TABLE image_view (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            opened_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            UNIQUE(image_id, labeler_id)
+        )
+        "#,
+        "ALTER TABLE submission ADD COLUMN first_submitted_at DATETIME NOT NULL DEFAULT '1970-01-01 00:00:00'",
+        "UPDATE submission SET first_submitted_at = submitted_at",
+        "ALTER TABLE submission ADD COLUMN opened_at DATETIME",
+        "ALTER TABLE submission ADD COLUMN seconds_spent INTEGER",
+    ],
+};
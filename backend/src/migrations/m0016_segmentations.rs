@@ -0,0 +1,41 @@
+use super::Migration;
+
+// Polygon outlines and RLE masks labelers draw for a group tag, in pixels of
+// the image, and the final mask admins merge from them per image and tag.
+// `geometry` holds the JSON of a `segmentation::Geometry`.
+pub const MIGRATION: Migration = Migration {
+    version: 16,
+    name: "segmentations",
+    statements: &[
+        r#"
+        CREATE TABLE segmentation (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            geometry TEXT NOT NULL,
+            area INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            updated_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_segmentation_image ON segmentation(image_id)",
+        r#"
+        CREATE TABLE final_segmentation (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            geometry TEXT NOT NULL,
+            area INTEGER NOT NULL,
+            votes INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE,
+            UNIQUE(image_id, tag_id)
+        )
+        "#,
+    ],
+};
@@ -0,0 +1,41 @@
+use super::Migration;
+
+// Keypoint schemas on tags (JSON of a `keypoint::KeypointSchema`), the
+// keypoint set each labeler places per image and tag, and the final set
+// aggregated from them. `keypoints` holds one `[x, y, visibility]` per point
+// of the schema, in pixels of the image.
+pub const MIGRATION: Migration = Migration {
+    version: 17,
+    name: "keypoints",
+    statements: &[
+        "ALTER TABLE tag ADD COLUMN keypoint_schema TEXT",
+        r#"
+        CREATE TABLE keypoint_set (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            keypoints TEXT NOT NULL,
+            created_at DATETIME NOT NULL,
+            updated_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE,
+            UNIQUE(image_id, labeler_id, tag_id)
+        )
+        "#,
+        r#"
+        CREATE TABLE final_keypoint_set (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            keypoints TEXT NOT NULL,
+            votes INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (tag_id) REFERENCES tag(id) ON DELETE CASCADE,
+            UNIQUE(image_id, tag_id)
+        )
+        "#,
+    ],
+};
@@ -0,0 +1,35 @@
+use super::Migration;
+
+// What labelers answer per group (JSON of a `task::TaskType`; unbounded
+// multi-choice when NULL), the captions labelers write in free-text groups,
+// one per image and labeler, and the final caption of an image.
+pub const MIGRATION: Migration = Migration {
+    version: 18,
+    name: "task_types",
+    statements: &[
+        r#"ALTER TABLE "group" ADD COLUMN task_type TEXT"#,
+        r#"
+        CREATE TABLE caption (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            labeler_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            created_at DATETIME NOT NULL,
+            updated_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            FOREIGN KEY (labeler_id) REFERENCES labeler(id) ON DELETE CASCADE,
+            UNIQUE(image_id, labeler_id)
+        )
+        "#,
+        r#"
+        CREATE TABLE final_caption (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL UNIQUE,
+            text TEXT NOT NULL,
+            votes INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE
+        )
+        "#,
+    ],
+};
@@ -0,0 +1,11 @@
+use super::Migration;
+
+// Whether an image's final caption was chosen by an admin rather than
+// reached by consensus.
+pub const MIGRATION: Migration = Migration {
+    version: 19,
+    name: "caption_selection",
+    statements: &[
+        "ALTER TABLE final_caption ADD COLUMN is_admin_override BOOLEAN NOT NULL DEFAULT 0",
+    ],
+};
@@ -0,0 +1,31 @@
+use super::Migration;
+
+// Thumbnails are cached per output format as well as size. SQLite cannot
+// change a UNIQUE constraint in place, so the table is rebuilt; existing
+// thumbnails were all JPEG.
+pub const MIGRATION: Migration = Migration {
+    version: 20,
+    name: "thumbnail_formats",
+    statements: &[
+        r#"
+        CREATE TABLE image_thumbnail_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            image_id INTEGER NOT NULL,
+            size TEXT NOT NULL,
+            format TEXT NOT NULL,
+            storage_key TEXT NOT NULL,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            created_at DATETIME NOT NULL,
+            FOREIGN KEY (image_id) REFERENCES image(id) ON DELETE CASCADE,
+            UNIQUE(image_id, size, format)
+        )
+        "#,
+        r#"
+        INSERT INTO image_thumbnail_new (id, image_id, size, format, storage_key, width, height, created_at)
+        SELECT id, image_id, size, 'image/jpeg', storage_key, width, height, created_at FROM image_thumbnail
+        "#,
+        "DROP TABLE image_thumbnail",
+        "ALTER TABLE image_thumbnail_new RENAME TO image_thumbnail",
+    ],
+};
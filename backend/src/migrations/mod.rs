@@ -0,0 +1,153 @@
+use sea_orm::*;
+
+mod m0001_initial_schema;
+mod m0002_image_storage;
+mod m0003_import_jobs;
+mod m0004_image_metadata;
+mod m0005_image_thumbnails;
+mod m0006_group_image_formats;
+mod m0007_image_hashes;
+mod m0008_consensus_strategies;
+mod m0009_final_tag_confidence;
+mod m0010_gold_images;
+mod m0011_assignment_queue;
+mod m0012_image_flags;
+mod m0013_submissions;
+mod m0014_label_timing;
+mod m0015_regions;
+mod m0016_segmentations;
+mod m0017_keypoints;
+mod m0018_task_types;
+mod m0019_caption_selection;
+mod m0020_thumbnail_formats;
+
+/// A single schema change. Migrations are applied in ascending `version`
+/// order and each one runs inside its own transaction.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub statements: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+}
+
+// Every migration known to this binary, oldest first. New migrations are
+// appended here; never edit or reorder one that has already shipped.
+const MIGRATIONS: &[Migration] = &[
+    m0001_initial_schema::MIGRATION,
+    m0002_image_storage::MIGRATION,
+    m0003_import_jobs::MIGRATION,
+    m0004_image_metadata::MIGRATION,
+    m0005_image_thumbnails::MIGRATION,
+    m0006_group_image_formats::MIGRATION,
+    m0007_image_hashes::MIGRATION,
+    m0008_consensus_strategies::MIGRATION,
+    m0009_final_tag_confidence::MIGRATION,
+    m0010_gold_images::MIGRATION,
+    m0011_assignment_queue::MIGRATION,
+    m0012_image_flags::MIGRATION,
+    m0013_submissions::MIGRATION,
+    m0014_label_timing::MIGRATION,
+    m0015_regions::MIGRATION,
+    m0016_segmentations::MIGRATION,
+    m0017_keypoints::MIGRATION,
+    m0018_task_types::MIGRATION,
+    m0019_caption_selection::MIGRATION,
+    m0020_thumbnail_formats::MIGRATION,
+];
+
+pub fn all() -> &'static [Migration] {
+    MIGRATIONS
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+async fn ensure_version_table(db: &DatabaseConnection) -> Result<(), DbErr> {
+    db.execute_unprepared(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at DATETIME NOT NULL
+        )
+        "#,
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn current_version(db: &DatabaseConnection) -> Result<i64, DbErr> {
+    ensure_version_table(db).await?;
+
+    let row = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT COALESCE(MAX(version), 0) AS version FROM schema_version".to_string(),
+        ))
+        .await?;
+
+    match row {
+        Some(row) => row.try_get("", "version"),
+        None => Ok(0),
+    }
+}
+
+pub async fn pending(db: &DatabaseConnection) -> Result<Vec<&'static Migration>, DbErr> {
+    let current = current_version(db).await?;
+    Ok(MIGRATIONS.iter().filter(|m| m.version > current).collect())
+}
+
+/// Brings the database up to `latest_version()`, applying each pending
+/// migration in order. Returns the migrations that were applied.
+pub async fn run(db: &DatabaseConnection) -> Result<Vec<AppliedMigration>, DbErr> {
+    let current = current_version(db).await?;
+    if current > latest_version() {
+        return Err(DbErr::Migration(format!(
+            "Database schema version {} is newer than the latest known migration {}",
+            current,
+            latest_version()
+        )));
+    }
+
+    let mut applied = Vec::new();
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let txn = db.begin().await?;
+
+        for statement in migration.statements {
+            txn.execute_unprepared(statement).await.map_err(|e| {
+                DbErr::Migration(format!(
+                    "Migration {} ({}) failed: {}",
+                    migration.version, migration.name, e
+                ))
+            })?;
+        }
+
+        txn.execute(Statement::from_sql_and_values(
+            txn.get_database_backend(),
+            "INSERT INTO schema_version (version, name, applied_at) VALUES (?, ?, ?)",
+            [
+                migration.version.into(),
+                migration.name.into(),
+                chrono::Utc::now().naive_utc().into(),
+            ],
+        ))
+        .await?;
+
+        txn.commit().await?;
+
+        applied.push(AppliedMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+        });
+    }
+
+    Ok(applied)
+}
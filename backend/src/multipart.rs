@@ -0,0 +1,57 @@
+use actix_multipart::{Field, MultipartError};
+use futures_util::TryStreamExt;
+use std::env;
+
+/// Limits applied while reading a multipart upload.
+#[derive(Clone, Debug)]
+pub struct MultipartLimits {
+    /// Largest accepted file part in bytes; larger parts are discarded.
+    pub max_file_size: usize,
+    /// Largest number of file parts accepted in one request.
+    pub max_files: usize,
+    /// Largest accepted archive for ZIP imports, in bytes.
+    pub max_archive_size: usize,
+}
+
+impl MultipartLimits {
+    /// Reads `UPLOAD_MAX_FILE_SIZE` (bytes), `UPLOAD_MAX_FILES` and
+    /// `UPLOAD_MAX_ARCHIVE_SIZE` (bytes).
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+
+        Self {
+            max_file_size: read("UPLOAD_MAX_FILE_SIZE", 20 * 1024 * 1024),
+            max_files: read("UPLOAD_MAX_FILES", 1000),
+            max_archive_size: read("UPLOAD_MAX_ARCHIVE_SIZE", 512 * 1024 * 1024),
+        }
+    }
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+/// Reads one field of a multipart body, keeping at most `max_size` bytes.
+/// A larger field is read to its end and discarded, returning `None`, so
+/// the fields after it can still be read.
+pub async fn read_field(field: &mut Field, max_size: usize) -> Result<Option<Vec<u8>>, MultipartError> {
+    let mut data = Vec::new();
+    let mut too_large = false;
+    while let Some(chunk) = field.try_next().await? {
+        if too_large {
+            continue;
+        }
+        if data.len() + chunk.len() > max_size {
+            too_large = true;
+            data = Vec::new();
+        } else {
+            data.extend_from_slice(&chunk);
+        }
+    }
+
+    Ok((!too_large).then_some(data))
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Default IoU at which two labelers' boxes count as the same object.
+pub const DEFAULT_IOU_THRESHOLD: f64 = 0.5;
+
+/// Axis-aligned box with its top-left corner at (`x`, `y`), in coordinates
+/// normalized to the image size, so (0, 0, 1, 1) covers the whole image.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    /// Checks that the box has an area and lies within the image.
+    pub fn validate(&self) -> Result<(), String> {
+        let coordinates = [self.x, self.y, self.width, self.height];
+        if coordinates.iter().any(|value| !value.is_finite()) {
+            return Err("Box coordinates must be numbers".to_string());
+        }
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return Err("Box width and height must be greater than 0".to_string());
+        }
+        let tolerance = 1e-9;
+        if self.x < 0.0 || self.y < 0.0 || self.x + self.width > 1.0 + tolerance || self.y + self.height > 1.0 + tolerance {
+            return Err("Box must lie within the image, in coordinates between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    /// Intersection over union with `other`, 0 for disjoint boxes.
+    pub fn iou(&self, other: &Self) -> f64 {
+        let width = (self.x + self.width).min(other.x + other.width) - self.x.max(other.x);
+        let height = (self.y + self.height).min(other.y + other.height) - self.y.max(other.y);
+        if width <= 0.0 || height <= 0.0 {
+            return 0.0;
+        }
+        let intersection = width * height;
+        intersection / (self.area() + other.area() - intersection)
+    }
+
+    /// Box with the mean coordinates of `boxes`.
+    pub fn mean(boxes: &[Self]) -> Self {
+        let n = boxes.len().max(1) as f64;
+        Self {
+            x: boxes.iter().map(|b| b.x).sum::<f64>() / n,
+            y: boxes.iter().map(|b| b.y).sum::<f64>() / n,
+            width: boxes.iter().map(|b| b.width).sum::<f64>() / n,
+            height: boxes.iter().map(|b| b.height).sum::<f64>() / n,
+        }
+    }
+}
+
+/// A box a labeler drew for a tag.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegionVote {
+    pub labeler_id: i32,
+    pub tag_id: i32,
+    pub bbox: BoundingBox,
+}
+
+/// Boxes of several labelers merged into one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConsensusRegion {
+    pub tag_id: i32,
+    pub bbox: BoundingBox,
+    /// Labelers whose boxes were merged.
+    pub votes: usize,
+}
+
+/// Clusters the boxes of each tag: a box joins the cluster whose mean box
+/// it overlaps most, by at least `iou_threshold`, unless the cluster
+/// already has a box of the same labeler. Clusters with boxes of at least
+/// `min_votes` labelers become their mean box, ordered by tag and then by
+/// votes.
+pub fn cluster(votes: &[RegionVote], iou_threshold: f64, min_votes: usize) -> Vec<ConsensusRegion> {
+    let mut by_tag: BTreeMap<i32, Vec<&RegionVote>> = BTreeMap::new();
+    for vote in votes {
+        by_tag.entry(vote.tag_id).or_default().push(vote);
+    }
+
+    let mut regions = Vec::new();
+    for (tag_id, mut votes) in by_tag {
+        // Larger boxes first, so small boxes do not seed clusters they are
+        // only a part of
+        votes.sort_by(|a, b| b.bbox.area().total_cmp(&a.bbox.area()).then(a.labeler_id.cmp(&b.labeler_id)));
+
+        let mut clusters: Vec<(Vec<i32>, Vec<BoundingBox>)> = Vec::new();
+        for vote in votes {
+            let best = clusters
+                .iter()
+                .enumerate()
+                .filter(|(_, (labelers, _))| !labelers.contains(&vote.labeler_id))
+                .map(|(index, (_, boxes))| (index, BoundingBox::mean(boxes).iou(&vote.bbox)))
+                .filter(|&(_, iou)| iou >= iou_threshold - 1e-9)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            match best {
+                Some((index, _)) => {
+                    clusters[index].0.push(vote.labeler_id);
+                    clusters[index].1.push(vote.bbox);
+                }
+                None => clusters.push((vec![vote.labeler_id], vec![vote.bbox])),
+            }
+        }
+
+        let mut merged: Vec<ConsensusRegion> = clusters
+            .into_iter()
+            .filter(|(labelers, _)| labelers.len() >= min_votes.max(1))
+            .map(|(labelers, boxes)| ConsensusRegion {
+                tag_id,
+                bbox: BoundingBox::mean(&boxes),
+                votes: labelers.len(),
+            })
+            .collect();
+        merged.sort_by(|a, b| b.votes.cmp(&a.votes).then(a.bbox.y.total_cmp(&b.bbox.y)).then(a.bbox.x.total_cmp(&b.bbox.x)));
+        regions.extend(merged);
+    }
+    regions
+}
@@ -0,0 +1,148 @@
+use sea_orm::*;
+use chrono::NaiveDateTime;
+use crate::entity::assignment::{self, Entity as Assignment, Model as AssignmentModel, ActiveModel as AssignmentActiveModel};
+
+/// The image a labeler should be leased next, as picked by
+/// [`AssignmentRepository::next_candidate`].
+#[derive(Clone, Debug, FromQueryResult)]
+pub struct LeaseCandidate {
+    pub image_id: i32,
+    pub is_gold: bool,
+    /// Whether the labeler already holds a live lease on the image.
+    pub held: bool,
+    /// Labelers who submitted the image.
+    pub done: i64,
+    /// Images the labeler could be leased, this one included.
+    pub remaining: i64,
+}
+
+pub struct AssignmentRepository;
+
+impl AssignmentRepository {
+    /// The image of `group_id` to lease `labeler_id` next, in one query:
+    /// one they have not labeled, skipped or flagged, that is gold, already
+    /// leased to them, or held by fewer than `labels_per_image` labelers
+    /// (done or under a live lease). Their own lease comes first, then the
+    /// least covered image, then the oldest.
+    pub async fn next_candidate(
+        db: &DatabaseConnection,
+        group_id: i32,
+        labeler_id: i32,
+        labels_per_image: i32,
+        now: NaiveDateTime,
+    ) -> Result<Option<LeaseCandidate>, DbErr> {
+        // Tags without a submission record count as a submission, as in
+        // `SubmissionRepository::submitters_by_group`
+        let sql = r#"
+            SELECT image_id, is_gold, held, done, COUNT(*) OVER () AS remaining
+            FROM (
+                SELECT
+                    image.id AS image_id,
+                    image.is_gold AS is_gold,
+                    EXISTS (
+                        SELECT 1 FROM assignment
+                        WHERE assignment.image_id = image.id AND assignment.labeler_id = ? AND assignment.expires_at > ?
+                    ) AS held,
+                    (SELECT COUNT(*) FROM submission WHERE submission.image_id = image.id)
+                        + (
+                            SELECT COUNT(DISTINCT image_tags.labeler_id) FROM image_tags
+                            WHERE image_tags.image_id = image.id AND NOT EXISTS (
+                                SELECT 1 FROM submission
+                                WHERE submission.image_id = image_tags.image_id
+                                    AND submission.labeler_id = image_tags.labeler_id
+                            )
+                        ) AS done,
+                    (
+                        SELECT COUNT(*) FROM assignment
+                        WHERE assignment.image_id = image.id AND assignment.labeler_id <> ? AND assignment.expires_at > ?
+                    ) AS leased
+                FROM image
+                WHERE image.group_id = ?
+                    AND NOT EXISTS (SELECT 1 FROM submission WHERE submission.image_id = image.id AND submission.labeler_id = ?)
+                    AND NOT EXISTS (SELECT 1 FROM image_tags WHERE image_tags.image_id = image.id AND image_tags.labeler_id = ?)
+                    AND NOT EXISTS (SELECT 1 FROM image_flag WHERE image_flag.image_id = image.id AND image_flag.labeler_id = ?)
+            ) AS candidate
+            WHERE is_gold OR held OR done + leased < ?
+            ORDER BY held DESC, done + leased, image_id
+            LIMIT 1
+        "#;
+        LeaseCandidate::find_by_statement(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            sql,
+            [
+                labeler_id.into(),
+                now.into(),
+                labeler_id.into(),
+                now.into(),
+                group_id.into(),
+                labeler_id.into(),
+                labeler_id.into(),
+                labeler_id.into(),
+                labels_per_image.into(),
+            ],
+        ))
+        .one(db)
+        .await
+    }
+
+    /// Live leases of `image_id` by other labelers taken before `lease_id`.
+    pub async fn count_earlier(
+        db: &DatabaseConnection,
+        image_id: i32,
+        lease_id: i32,
+        now: NaiveDateTime,
+    ) -> Result<u64, DbErr> {
+        Assignment::find()
+            .filter(assignment::Column::ImageId.eq(image_id))
+            .filter(assignment::Column::Id.lt(lease_id))
+            .filter(assignment::Column::ExpiresAt.gt(now))
+            .count(db)
+            .await
+    }
+
+    /// Leases `image_id` to `labeler_id` until `expires_at`, replacing any
+    /// earlier lease of the same pair.
+    pub async fn lease(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        now: NaiveDateTime,
+        expires_at: NaiveDateTime,
+    ) -> Result<AssignmentModel, DbErr> {
+        Self::release(db, image_id, labeler_id).await?;
+
+        let assignment = AssignmentActiveModel {
+            image_id: Set(image_id),
+            labeler_id: Set(labeler_id),
+            leased_at: Set(now),
+            expires_at: Set(expires_at),
+            ..Default::default()
+        };
+        assignment.insert(db).await
+    }
+
+    pub async fn release<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<(), DbErr> {
+        Assignment::delete_many()
+            .filter(assignment::Column::ImageId.eq(image_id))
+            .filter(assignment::Column::LabelerId.eq(labeler_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Drops every lease that expired by `now`.
+    pub async fn delete_expired(
+        db: &DatabaseConnection,
+        now: NaiveDateTime,
+    ) -> Result<u64, DbErr> {
+        let result = Assignment::delete_many()
+            .filter(assignment::Column::ExpiresAt.lte(now))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
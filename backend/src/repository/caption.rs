@@ -0,0 +1,125 @@
+use sea_orm::*;
+use chrono::Utc;
+use crate::entity::caption::{self, Entity as Caption, Model as CaptionModel, ActiveModel as CaptionActiveModel};
+use crate::entity::final_caption::{
+    self, Entity as FinalCaption, Model as FinalCaptionModel, ActiveModel as FinalCaptionActiveModel,
+};
+use crate::entity::image::{self, Entity as Image};
+
+pub struct CaptionRepository;
+
+impl CaptionRepository {
+    /// Saves the labeler's caption of an image, replacing the previous one.
+    pub async fn save<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+        text: String,
+    ) -> Result<CaptionModel, DbErr> {
+        let now = Utc::now().naive_utc();
+        match Self::find(db, image_id, labeler_id).await? {
+            Some(existing) => {
+                let mut caption: CaptionActiveModel = existing.into();
+                caption.text = Set(text);
+                caption.updated_at = Set(now);
+                caption.update(db).await
+            }
+            None => {
+                let caption = CaptionActiveModel {
+                    image_id: Set(image_id),
+                    labeler_id: Set(labeler_id),
+                    text: Set(text),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                };
+                caption.insert(db).await
+            }
+        }
+    }
+
+    pub async fn find<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<Option<CaptionModel>, DbErr> {
+        Caption::find()
+            .filter(caption::Column::ImageId.eq(image_id))
+            .filter(caption::Column::LabelerId.eq(labeler_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_by_id(db: &DatabaseConnection, id: i32) -> Result<Option<CaptionModel>, DbErr> {
+        Caption::find_by_id(id).one(db).await
+    }
+
+    pub async fn get_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<CaptionModel>, DbErr> {
+        Caption::find()
+            .filter(caption::Column::ImageId.eq(image_id))
+            .order_by_asc(caption::Column::LabelerId)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete_by_labeler_and_group(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<(), DbErr> {
+        let image_ids = Image::find()
+            .select_only()
+            .column(image::Column::Id)
+            .filter(image::Column::GroupId.eq(group_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+
+        Caption::delete_many()
+            .filter(caption::Column::LabelerId.eq(labeler_id))
+            .filter(caption::Column::ImageId.is_in(image_ids))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_final(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Option<FinalCaptionModel>, DbErr> {
+        FinalCaption::find()
+            .filter(final_caption::Column::ImageId.eq(image_id))
+            .one(db)
+            .await
+    }
+
+    /// Sets or, with `None`, removes the final caption of an image and the
+    /// number of labelers who wrote it.
+    pub async fn set_final(
+        db: &DatabaseConnection,
+        image_id: i32,
+        caption: Option<(String, i32)>,
+        is_admin_override: bool,
+    ) -> Result<Option<FinalCaptionModel>, DbErr> {
+        FinalCaption::delete_many()
+            .filter(final_caption::Column::ImageId.eq(image_id))
+            .exec(db)
+            .await?;
+        let Some((text, votes)) = caption else {
+            return Ok(None);
+        };
+
+        let final_caption = FinalCaptionActiveModel {
+            image_id: Set(image_id),
+            text: Set(text),
+            votes: Set(votes),
+            is_admin_override: Set(is_admin_override),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        final_caption.insert(db).await.map(Some)
+    }
+}
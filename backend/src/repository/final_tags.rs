@@ -31,6 +31,20 @@ impl FinalTagsRepository {
             .await
     }
 
+    /// Final tags of every image in `group_id`.
+    pub async fn get_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Vec<FinalTagsModel>, DbErr> {
+        use crate::entity::image::Entity as Image;
+
+        FinalTags::find()
+            .inner_join(Image)
+            .filter(crate::entity::image::Column::GroupId.eq(group_id))
+            .all(db)
+            .await
+    }
+
     pub async fn delete_by_image(
         db: &DatabaseConnection,
         image_id: i32,
@@ -62,6 +76,34 @@ impl FinalTagsRepository {
         Ok(results)
     }
 
+    /// Replaces the final tags of an image with ones picked by a consensus
+    /// strategy, recording the strategy (as JSON) on every row. Tags come
+    /// with their confidence when the strategy estimates one.
+    pub async fn replace_consensus_tags(
+        db: &DatabaseConnection,
+        image_id: i32,
+        tags: Vec<(i32, Option<f64>)>,
+        consensus_strategy: String,
+    ) -> Result<Vec<FinalTagsModel>, DbErr> {
+        Self::delete_by_image(db, image_id).await?;
+
+        let mut results = Vec::new();
+        for (tag_id, confidence) in tags {
+            let final_tag = FinalTagsActiveModel {
+                image_id: Set(image_id),
+                tag_id: Set(tag_id),
+                is_admin_override: Set(false),
+                created_at: Set(chrono::Utc::now().naive_utc()),
+                consensus_strategy: Set(Some(consensus_strategy.clone())),
+                confidence: Set(confidence),
+                ..Default::default()
+            };
+            results.push(final_tag.insert(db).await?);
+        }
+
+        Ok(results)
+    }
+
     pub async fn has_admin_override(
         db: &DatabaseConnection,
         image_id: i32,
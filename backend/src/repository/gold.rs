@@ -0,0 +1,103 @@
+use sea_orm::*;
+use crate::entity::gold_result::{self, Entity as GoldResult, Model as GoldResultModel, ActiveModel as GoldResultActiveModel};
+use crate::entity::gold_tag::{self, Entity as GoldTag, Model as GoldTagModel, ActiveModel as GoldTagActiveModel};
+use crate::entity::image::{self, Entity as Image};
+
+pub struct GoldRepository;
+
+impl GoldRepository {
+    /// Known tags of a gold image.
+    pub async fn get_tags<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+    ) -> Result<Vec<GoldTagModel>, DbErr> {
+        GoldTag::find()
+            .filter(gold_tag::Column::ImageId.eq(image_id))
+            .all(db)
+            .await
+    }
+
+    pub async fn replace_tags(
+        db: &DatabaseConnection,
+        image_id: i32,
+        tag_ids: Vec<i32>,
+    ) -> Result<Vec<GoldTagModel>, DbErr> {
+        GoldTag::delete_many()
+            .filter(gold_tag::Column::ImageId.eq(image_id))
+            .exec(db)
+            .await?;
+
+        let mut results = Vec::new();
+        for tag_id in tag_ids {
+            let gold_tag = GoldTagActiveModel {
+                image_id: Set(image_id),
+                tag_id: Set(tag_id),
+                ..Default::default()
+            };
+            results.push(gold_tag.insert(db).await?);
+        }
+
+        Ok(results)
+    }
+
+    /// Records whether a labeler's answer on a gold image was right. Only
+    /// the first answer counts, so a labeler cannot correct a miss by
+    /// answering the same image again. Returns whether a result was recorded.
+    pub async fn record_result<C: ConnectionTrait>(
+        db: &C,
+        labeler_id: i32,
+        image_id: i32,
+        correct: bool,
+    ) -> Result<bool, DbErr> {
+        let result = GoldResultActiveModel {
+            labeler_id: Set(labeler_id),
+            image_id: Set(image_id),
+            correct: Set(correct),
+            answered_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        let inserted = GoldResult::insert(result)
+            .on_conflict(
+                sea_query::OnConflict::columns([gold_result::Column::LabelerId, gold_result::Column::ImageId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+
+        Ok(inserted > 0)
+    }
+
+    /// The labeler's latest `limit` results on gold images of `group_id`,
+    /// newest first.
+    pub async fn recent_results<C: ConnectionTrait>(
+        db: &C,
+        labeler_id: i32,
+        group_id: i32,
+        limit: u64,
+    ) -> Result<Vec<GoldResultModel>, DbErr> {
+        GoldResult::find()
+            .inner_join(Image)
+            .filter(image::Column::GroupId.eq(group_id))
+            .filter(gold_result::Column::LabelerId.eq(labeler_id))
+            .order_by_desc(gold_result::Column::AnsweredAt)
+            .order_by_desc(gold_result::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await
+    }
+
+    /// Every gold result in `group_id`, oldest first.
+    pub async fn get_results_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Vec<GoldResultModel>, DbErr> {
+        GoldResult::find()
+            .inner_join(Image)
+            .filter(image::Column::GroupId.eq(group_id))
+            .order_by_asc(gold_result::Column::AnsweredAt)
+            .order_by_asc(gold_result::Column::Id)
+            .all(db)
+            .await
+    }
+}
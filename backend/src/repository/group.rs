@@ -3,6 +3,25 @@ use crate::entity::group::{Entity as Group, Model as GroupModel, ActiveModel as
 use crate::entity::image::{Entity as Image, Model as ImageModel};
 use crate::entity::tag::{Entity as Tag, Model as TagModel};
 use crate::entity::labeler::Model as LabelerModel;
+use super::pagination::{fetch_page, Page, PageRequest, SortOrder};
+
+/// Sort keys of group lists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GroupSort {
+    #[default]
+    Id,
+    Name,
+}
+
+impl GroupSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "id" => Some(Self::Id),
+            "name" => Some(Self::Name),
+            _ => None,
+        }
+    }
+}
 
 pub struct GroupRepository;
 
@@ -21,8 +40,8 @@ impl GroupRepository {
         group.insert(db).await
     }
 
-    pub async fn find_by_id(
-        db: &DatabaseConnection,
+    pub async fn find_by_id<C: ConnectionTrait>(
+        db: &C,
         id: i32,
     ) -> Result<Option<GroupModel>, DbErr> {
         Group::find_by_id(id).one(db).await
@@ -34,6 +53,21 @@ impl GroupRepository {
         Group::find().all(db).await
     }
 
+    pub async fn list(
+        db: &DatabaseConnection,
+        sort: GroupSort,
+        order: SortOrder,
+        page: PageRequest,
+    ) -> Result<Page<GroupModel>, DbErr> {
+        use crate::entity::group::Column;
+
+        let mut select = Group::find();
+        if sort == GroupSort::Name {
+            select = select.order_by(Column::Name, order.order());
+        }
+        fetch_page(db, select.order_by(Column::Id, order.order()), page).await
+    }
+
     pub async fn get_images(
         db: &DatabaseConnection,
         group_id: i32,
@@ -70,6 +104,38 @@ impl GroupRepository {
         Ok(labelers)
     }
 
+    /// Page of the group's tags, by name.
+    pub async fn list_possible_tags(
+        db: &DatabaseConnection,
+        group_id: i32,
+        page: PageRequest,
+    ) -> Result<Page<TagModel>, DbErr> {
+        use crate::entity::tag::Column;
+
+        let select = Tag::find()
+            .filter(Column::GroupId.eq(group_id))
+            .order_by_asc(Column::Name)
+            .order_by_asc(Column::Id);
+        fetch_page(db, select, page).await
+    }
+
+    /// Page of the group's labelers, by username.
+    pub async fn list_labelers(
+        db: &DatabaseConnection,
+        group_id: i32,
+        page: PageRequest,
+    ) -> Result<Page<LabelerModel>, DbErr> {
+        use crate::entity::labeler_groups::Entity as LabelerGroups;
+        use crate::entity::labeler::{Column, Entity as Labeler};
+
+        let select = Labeler::find()
+            .inner_join(LabelerGroups)
+            .filter(crate::entity::labeler_groups::Column::GroupId.eq(group_id))
+            .order_by_asc(Column::Username)
+            .order_by_asc(Column::Id);
+        fetch_page(db, select, page).await
+    }
+
     pub async fn update(
         db: &DatabaseConnection,
         id: i32,
@@ -92,6 +158,24 @@ impl GroupRepository {
         }
     }
 
+    pub async fn set_queue_settings(
+        db: &DatabaseConnection,
+        id: i32,
+        labels_per_image: i32,
+        lease_seconds: i32,
+    ) -> Result<GroupModel, DbErr> {
+        let group = Group::find_by_id(id).one(db).await?;
+        match group {
+            Some(group) => {
+                let mut group: GroupActiveModel = group.into();
+                group.labels_per_image = Set(labels_per_image);
+                group.lease_seconds = Set(lease_seconds);
+                group.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Group not found".to_string())),
+        }
+    }
+
     pub async fn delete(
         db: &DatabaseConnection,
         id: i32,
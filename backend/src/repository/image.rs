@@ -1,6 +1,44 @@
 use sea_orm::*;
 use crate::entity::image::{Entity as Image, Model as ImageModel, ActiveModel as ImageActiveModel};
 use crate::entity::image_tags::{Entity as ImageTags, Model as ImageTagsModel};
+use sea_orm::sea_query::{Expr, Query};
+use super::pagination::{fetch_page, Page, PageRequest, SortOrder};
+
+/// Sort keys of image lists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImageSort {
+    #[default]
+    Id,
+    UploadedAt,
+    Filename,
+    Size,
+}
+
+impl ImageSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "id" => Some(Self::Id),
+            "uploaded_at" => Some(Self::UploadedAt),
+            "filename" => Some(Self::Filename),
+            "size" => Some(Self::Size),
+            _ => None,
+        }
+    }
+}
+
+/// Ordering and filters of an image list. Unset filters match every image.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImageListQuery {
+    pub sort: ImageSort,
+    pub order: SortOrder,
+    /// Whether the image has been submitted, even with no tags, by
+    /// `labeled_by` or else by anyone.
+    pub labeled: Option<bool>,
+    pub labeled_by: Option<i32>,
+    pub has_final_tags: Option<bool>,
+    /// Whether the final tags were set by an admin.
+    pub has_admin_override: Option<bool>,
+}
 
 pub struct ImageRepository;
 
@@ -9,13 +47,15 @@ impl ImageRepository {
         db: &DatabaseConnection,
         filename: String,
         filetype: String,
-        base64_data: String,
+        storage_key: String,
+        size_bytes: i64,
         group_id: i32,
     ) -> Result<ImageModel, DbErr> {
         let image = ImageActiveModel {
             filename: Set(filename),
             filetype: Set(filetype),
-            base64_data: Set(base64_data),
+            storage_key: Set(Some(storage_key)),
+            size_bytes: Set(Some(size_bytes)),
             group_id: Set(group_id),
             uploaded_at: Set(chrono::Utc::now()),
             ..Default::default()
@@ -41,6 +81,79 @@ impl ImageRepository {
             .await
     }
 
+    /// Oldest image of `group_id` with exactly this content.
+    pub async fn find_by_storage_key(
+        db: &DatabaseConnection,
+        group_id: i32,
+        storage_key: &str,
+    ) -> Result<Option<ImageModel>, DbErr> {
+        Image::find()
+            .filter(crate::entity::image::Column::GroupId.eq(group_id))
+            .filter(crate::entity::image::Column::StorageKey.eq(storage_key))
+            .order_by_asc(crate::entity::image::Column::Id)
+            .one(db)
+            .await
+    }
+
+    /// Page of the images of `group_id` matching `query`. Filters run as
+    /// subqueries on the tag tables.
+    pub async fn list_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+        query: &ImageListQuery,
+        page: PageRequest,
+    ) -> Result<Page<ImageModel>, DbErr> {
+        use crate::entity::image::Column;
+        use crate::entity::{final_tags, image_tags, submission};
+
+        let image_ids = |tagged: sea_orm::sea_query::SelectStatement, include: bool| {
+            if include { Column::Id.in_subquery(tagged) } else { Column::Id.not_in_subquery(tagged) }
+        };
+
+        let mut condition = Condition::all().add(Column::GroupId.eq(group_id));
+        if let Some(labeled) = query.labeled {
+            // Submissions cover answers with no tags
+            let mut submitted = Query::select();
+            submitted.column(submission::Column::ImageId).from(submission::Entity);
+            let mut tagged = Query::select();
+            tagged.column(image_tags::Column::ImageId).from(image_tags::Entity);
+            if let Some(labeler_id) = query.labeled_by {
+                submitted.and_where(Expr::col(submission::Column::LabelerId).eq(labeler_id));
+                tagged.and_where(Expr::col(image_tags::Column::LabelerId).eq(labeler_id));
+            }
+            condition = if labeled {
+                condition.add(Condition::any().add(image_ids(submitted, true)).add(image_ids(tagged, true)))
+            } else {
+                condition.add(image_ids(submitted, false)).add(image_ids(tagged, false))
+            };
+        }
+        if let Some(has_final_tags) = query.has_final_tags {
+            let tagged = Query::select()
+                .column(final_tags::Column::ImageId)
+                .from(final_tags::Entity)
+                .to_owned();
+            condition = condition.add(image_ids(tagged, has_final_tags));
+        }
+        if let Some(has_admin_override) = query.has_admin_override {
+            let overridden = Query::select()
+                .column(final_tags::Column::ImageId)
+                .from(final_tags::Entity)
+                .and_where(Expr::col(final_tags::Column::IsAdminOverride).eq(true))
+                .to_owned();
+            condition = condition.add(image_ids(overridden, has_admin_override));
+        }
+
+        let order = query.order.order();
+        let mut select = Image::find().filter(condition);
+        select = match query.sort {
+            ImageSort::Id => select,
+            ImageSort::UploadedAt => select.order_by(Column::UploadedAt, order.clone()),
+            ImageSort::Filename => select.order_by(Column::Filename, order.clone()),
+            ImageSort::Size => select.order_by(Column::SizeBytes, order.clone()),
+        };
+        fetch_page(db, select.order_by(Column::Id, order), page).await
+    }
+
     pub async fn get_tags(
         db: &DatabaseConnection,
         image_id: i32,
@@ -56,7 +169,6 @@ impl ImageRepository {
         id: i32,
         filename: Option<String>,
         filetype: Option<String>,
-        base64_data: Option<String>,
         group_id: Option<i32>,
     ) -> Result<ImageModel, DbErr> {
         let image = Image::find_by_id(id).one(db).await?;
@@ -69,9 +181,6 @@ impl ImageRepository {
                 if let Some(filetype) = filetype {
                     image.filetype = Set(filetype);
                 }
-                if let Some(base64_data) = base64_data {
-                    image.base64_data = Set(base64_data);
-                }
                 if let Some(group_id) = group_id {
                     image.group_id = Set(group_id);
                 }
@@ -81,6 +190,62 @@ impl ImageRepository {
         }
     }
 
+    pub async fn set_metadata(
+        db: &DatabaseConnection,
+        id: i32,
+        width: i32,
+        height: i32,
+        color_mode: String,
+        orientation: Option<i32>,
+    ) -> Result<ImageModel, DbErr> {
+        let image = Image::find_by_id(id).one(db).await?;
+        match image {
+            Some(image) => {
+                let mut image: ImageActiveModel = image.into();
+                image.width = Set(Some(width));
+                image.height = Set(Some(height));
+                image.color_mode = Set(Some(color_mode));
+                image.orientation = Set(orientation);
+                image.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Image not found".to_string())),
+        }
+    }
+
+    pub async fn set_hashes(
+        db: &DatabaseConnection,
+        id: i32,
+        average_hash: String,
+        difference_hash: String,
+    ) -> Result<ImageModel, DbErr> {
+        let image = Image::find_by_id(id).one(db).await?;
+        match image {
+            Some(image) => {
+                let mut image: ImageActiveModel = image.into();
+                image.average_hash = Set(Some(average_hash));
+                image.difference_hash = Set(Some(difference_hash));
+                image.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Image not found".to_string())),
+        }
+    }
+
+    pub async fn set_gold(
+        db: &DatabaseConnection,
+        id: i32,
+        is_gold: bool,
+    ) -> Result<ImageModel, DbErr> {
+        let image = Image::find_by_id(id).one(db).await?;
+        match image {
+            Some(image) => {
+                let mut image: ImageActiveModel = image.into();
+                image.is_gold = Set(is_gold);
+                image.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Image not found".to_string())),
+        }
+    }
+
     pub async fn delete(
         db: &DatabaseConnection,
         id: i32,
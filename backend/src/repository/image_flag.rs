@@ -0,0 +1,80 @@
+use sea_orm::*;
+use crate::entity::image::{self, Entity as Image};
+use crate::entity::image_flag::{self, Entity as ImageFlag, Model as ImageFlagModel, ActiveModel as ImageFlagActiveModel};
+
+pub const FLAG_KIND_SKIP: &str = "skip";
+pub const FLAG_KIND_FLAG: &str = "flag";
+
+pub struct ImageFlagRepository;
+
+impl ImageFlagRepository {
+    /// Records a skip or flag, replacing the labeler's earlier one on the
+    /// image.
+    pub async fn set(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        kind: &str,
+        reason: &str,
+        note: Option<String>,
+    ) -> Result<ImageFlagModel, DbErr> {
+        Self::clear(db, image_id, labeler_id).await?;
+
+        let flag = ImageFlagActiveModel {
+            image_id: Set(image_id),
+            labeler_id: Set(labeler_id),
+            kind: Set(kind.to_string()),
+            reason: Set(reason.to_string()),
+            note: Set(note),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        flag.insert(db).await
+    }
+
+    pub async fn clear<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<(), DbErr> {
+        ImageFlag::delete_many()
+            .filter(image_flag::Column::ImageId.eq(image_id))
+            .filter(image_flag::Column::LabelerId.eq(labeler_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Skips and flags of a labeler in a group.
+    pub async fn get_by_labeler_and_group(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<Vec<ImageFlagModel>, DbErr> {
+        ImageFlag::find()
+            .inner_join(Image)
+            .filter(image::Column::GroupId.eq(group_id))
+            .filter(image_flag::Column::LabelerId.eq(labeler_id))
+            .all(db)
+            .await
+    }
+
+    /// Every `kind` record, in one group or in all of them, oldest first.
+    pub async fn get_by_kind(
+        db: &DatabaseConnection,
+        kind: &str,
+        group_id: Option<i32>,
+    ) -> Result<Vec<ImageFlagModel>, DbErr> {
+        let mut query = ImageFlag::find()
+            .inner_join(Image)
+            .filter(image_flag::Column::Kind.eq(kind));
+        if let Some(group_id) = group_id {
+            query = query.filter(image::Column::GroupId.eq(group_id));
+        }
+        query
+            .order_by_asc(image_flag::Column::CreatedAt)
+            .order_by_asc(image_flag::Column::Id)
+            .all(db)
+            .await
+    }
+}
@@ -4,8 +4,8 @@ use crate::entity::image_tags::{Entity as ImageTags, Model as ImageTagsModel, Ac
 pub struct ImageTagsRepository;
 
 impl ImageTagsRepository {
-    pub async fn create(
-        db: &DatabaseConnection,
+    pub async fn create<C: ConnectionTrait>(
+        db: &C,
         image_id: i32,
         labeler_id: i32,
         tag_id: i32,
@@ -96,8 +96,8 @@ impl ImageTagsRepository {
             .await
     }
 
-    pub async fn assign_multiple_tags(
-        db: &DatabaseConnection,
+    pub async fn assign_multiple_tags<C: ConnectionTrait>(
+        db: &C,
         image_id: i32,
         labeler_id: i32,
         tag_ids: Vec<i32>,
@@ -122,8 +122,8 @@ impl ImageTagsRepository {
         Ok(results)
     }
 
-    pub async fn replace_tags_for_image_by_labeler(
-        db: &DatabaseConnection,
+    pub async fn replace_tags_for_image_by_labeler<C: ConnectionTrait>(
+        db: &C,
         image_id: i32,
         labeler_id: i32,
         tag_ids: Vec<i32>,
@@ -149,6 +149,20 @@ impl ImageTagsRepository {
             .await
     }
 
+    /// Every tag any labeler put on an image of `group_id`.
+    pub async fn get_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Vec<ImageTagsModel>, DbErr> {
+        use crate::entity::image::Entity as Image;
+
+        ImageTags::find()
+            .inner_join(Image)
+            .filter(crate::entity::image::Column::GroupId.eq(group_id))
+            .all(db)
+            .await
+    }
+
     pub async fn delete_by_labeler_and_group(
         db: &DatabaseConnection,
         labeler_id: i32,
@@ -0,0 +1,43 @@
+use sea_orm::*;
+use crate::entity::image_thumbnail::{Entity as ImageThumbnail, Model as ImageThumbnailModel, ActiveModel as ImageThumbnailActiveModel};
+
+pub struct ImageThumbnailRepository;
+
+impl ImageThumbnailRepository {
+    pub async fn create(
+        db: &DatabaseConnection,
+        image_id: i32,
+        size: &str,
+        format: &str,
+        storage_key: String,
+        width: i32,
+        height: i32,
+    ) -> Result<ImageThumbnailModel, DbErr> {
+        let thumbnail = ImageThumbnailActiveModel {
+            image_id: Set(image_id),
+            size: Set(size.to_string()),
+            format: Set(format.to_string()),
+            storage_key: Set(storage_key),
+            width: Set(width),
+            height: Set(height),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            ..Default::default()
+        };
+
+        thumbnail.insert(db).await
+    }
+
+    pub async fn find(
+        db: &DatabaseConnection,
+        image_id: i32,
+        size: &str,
+        format: &str,
+    ) -> Result<Option<ImageThumbnailModel>, DbErr> {
+        ImageThumbnail::find()
+            .filter(crate::entity::image_thumbnail::Column::ImageId.eq(image_id))
+            .filter(crate::entity::image_thumbnail::Column::Size.eq(size))
+            .filter(crate::entity::image_thumbnail::Column::Format.eq(format))
+            .one(db)
+            .await
+    }
+}
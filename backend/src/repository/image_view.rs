@@ -0,0 +1,50 @@
+use sea_orm::*;
+use chrono::NaiveDateTime;
+use crate::entity::image_view::{self, Entity as ImageView, Model as ImageViewModel, ActiveModel as ImageViewActiveModel};
+
+pub struct ImageViewRepository;
+
+impl ImageViewRepository {
+    /// Records that the labeler opened the image at `now`, unless they
+    /// already had it open.
+    pub async fn open(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        now: NaiveDateTime,
+    ) -> Result<ImageViewModel, DbErr> {
+        if let Some(view) = ImageView::find()
+            .filter(image_view::Column::ImageId.eq(image_id))
+            .filter(image_view::Column::LabelerId.eq(labeler_id))
+            .one(db)
+            .await?
+        {
+            return Ok(view);
+        }
+
+        let view = ImageViewActiveModel {
+            image_id: Set(image_id),
+            labeler_id: Set(labeler_id),
+            opened_at: Set(now),
+            ..Default::default()
+        };
+        view.insert(db).await
+    }
+
+    /// Removes and returns the labeler's open view of the image.
+    pub async fn take<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<Option<ImageViewModel>, DbErr> {
+        let view = ImageView::find()
+            .filter(image_view::Column::ImageId.eq(image_id))
+            .filter(image_view::Column::LabelerId.eq(labeler_id))
+            .one(db)
+            .await?;
+        if let Some(view) = &view {
+            ImageView::delete_by_id(view.id).exec(db).await?;
+        }
+        Ok(view)
+    }
+}
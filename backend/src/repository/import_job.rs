@@ -0,0 +1,100 @@
+use sea_orm::*;
+use crate::entity::import_job::{Entity as ImportJob, Model as ImportJobModel, ActiveModel as ImportJobActiveModel};
+
+pub struct ImportJobRepository;
+
+impl ImportJobRepository {
+    pub async fn create(
+        db: &DatabaseConnection,
+        group_id: i32,
+        total_entries: i32,
+    ) -> Result<ImportJobModel, DbErr> {
+        let job = ImportJobActiveModel {
+            group_id: Set(group_id),
+            status: Set("pending".to_string()),
+            total_entries: Set(total_entries),
+            processed_entries: Set(0),
+            created_images: Set(0),
+            created_tags: Set(0),
+            errors: Set("[]".to_string()),
+            message: Set(None),
+            created_at: Set(chrono::Utc::now().naive_utc()),
+            finished_at: Set(None),
+            ..Default::default()
+        };
+
+        job.insert(db).await
+    }
+
+    pub async fn find_by_id(
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<Option<ImportJobModel>, DbErr> {
+        ImportJob::find_by_id(id).one(db).await
+    }
+
+    pub async fn get_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Vec<ImportJobModel>, DbErr> {
+        ImportJob::find()
+            .filter(crate::entity::import_job::Column::GroupId.eq(group_id))
+            .order_by_desc(crate::entity::import_job::Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Jobs that are still pending or running.
+    pub async fn get_unfinished(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<ImportJobModel>, DbErr> {
+        ImportJob::find()
+            .filter(crate::entity::import_job::Column::Status.is_in(["pending", "running"]))
+            .order_by_asc(crate::entity::import_job::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn update_progress(
+        db: &DatabaseConnection,
+        id: i32,
+        status: &str,
+        processed_entries: i32,
+        created_images: i32,
+        created_tags: i32,
+        errors: String,
+    ) -> Result<ImportJobModel, DbErr> {
+        let job = ImportJob::find_by_id(id).one(db).await?;
+        match job {
+            Some(job) => {
+                let mut job: ImportJobActiveModel = job.into();
+                job.status = Set(status.to_string());
+                job.processed_entries = Set(processed_entries);
+                job.created_images = Set(created_images);
+                job.created_tags = Set(created_tags);
+                job.errors = Set(errors);
+                job.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Import job not found".to_string())),
+        }
+    }
+
+    pub async fn finish(
+        db: &DatabaseConnection,
+        id: i32,
+        status: &str,
+        message: Option<String>,
+    ) -> Result<ImportJobModel, DbErr> {
+        let job = ImportJob::find_by_id(id).one(db).await?;
+        match job {
+            Some(job) => {
+                let mut job: ImportJobActiveModel = job.into();
+                job.status = Set(status.to_string());
+                job.message = Set(message);
+                job.finished_at = Set(Some(chrono::Utc::now().naive_utc()));
+                job.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Import job not found".to_string())),
+        }
+    }
+}
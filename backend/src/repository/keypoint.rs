@@ -0,0 +1,167 @@
+use sea_orm::*;
+use chrono::Utc;
+use crate::entity::final_keypoint_set::{
+    self, Entity as FinalKeypointSet, Model as FinalKeypointSetModel, ActiveModel as FinalKeypointSetActiveModel,
+};
+use crate::entity::image::{self, Entity as Image};
+use crate::entity::keypoint_set::{self, Entity as KeypointSet, Model as KeypointSetModel, ActiveModel as KeypointSetActiveModel};
+use crate::keypoint::Keypoint;
+
+pub struct KeypointRepository;
+
+impl KeypointRepository {
+    /// Saves the labeler's keypoint set of `tag_id` on an image, replacing
+    /// the previous one.
+    pub async fn save(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        tag_id: i32,
+        keypoints: &[Keypoint],
+    ) -> Result<KeypointSetModel, DbErr> {
+        let now = Utc::now().naive_utc();
+        let json = serde_json::to_string(keypoints).unwrap_or_default();
+        match Self::find(db, image_id, labeler_id, tag_id).await? {
+            Some(existing) => {
+                let mut set: KeypointSetActiveModel = existing.into();
+                set.keypoints = Set(json);
+                set.updated_at = Set(now);
+                set.update(db).await
+            }
+            None => {
+                let set = KeypointSetActiveModel {
+                    image_id: Set(image_id),
+                    labeler_id: Set(labeler_id),
+                    tag_id: Set(tag_id),
+                    keypoints: Set(json),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                };
+                set.insert(db).await
+            }
+        }
+    }
+
+    pub async fn find(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        tag_id: i32,
+    ) -> Result<Option<KeypointSetModel>, DbErr> {
+        KeypointSet::find()
+            .filter(keypoint_set::Column::ImageId.eq(image_id))
+            .filter(keypoint_set::Column::LabelerId.eq(labeler_id))
+            .filter(keypoint_set::Column::TagId.eq(tag_id))
+            .one(db)
+            .await
+    }
+
+    /// Returns whether there was a set to delete.
+    pub async fn delete(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        tag_id: i32,
+    ) -> Result<bool, DbErr> {
+        let result = KeypointSet::delete_many()
+            .filter(keypoint_set::Column::ImageId.eq(image_id))
+            .filter(keypoint_set::Column::LabelerId.eq(labeler_id))
+            .filter(keypoint_set::Column::TagId.eq(tag_id))
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
+
+    pub async fn get_by_image_and_labeler(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<Vec<KeypointSetModel>, DbErr> {
+        KeypointSet::find()
+            .filter(keypoint_set::Column::ImageId.eq(image_id))
+            .filter(keypoint_set::Column::LabelerId.eq(labeler_id))
+            .order_by_asc(keypoint_set::Column::TagId)
+            .all(db)
+            .await
+    }
+
+    pub async fn get_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<KeypointSetModel>, DbErr> {
+        KeypointSet::find()
+            .filter(keypoint_set::Column::ImageId.eq(image_id))
+            .order_by_asc(keypoint_set::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn count_by_tag(
+        db: &DatabaseConnection,
+        tag_id: i32,
+    ) -> Result<u64, DbErr> {
+        KeypointSet::find()
+            .filter(keypoint_set::Column::TagId.eq(tag_id))
+            .count(db)
+            .await
+    }
+
+    pub async fn delete_by_labeler_and_group(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<(), DbErr> {
+        let image_ids = Image::find()
+            .select_only()
+            .column(image::Column::Id)
+            .filter(image::Column::GroupId.eq(group_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+
+        KeypointSet::delete_many()
+            .filter(keypoint_set::Column::LabelerId.eq(labeler_id))
+            .filter(keypoint_set::Column::ImageId.is_in(image_ids))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_final_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<FinalKeypointSetModel>, DbErr> {
+        FinalKeypointSet::find()
+            .filter(final_keypoint_set::Column::ImageId.eq(image_id))
+            .order_by_asc(final_keypoint_set::Column::TagId)
+            .all(db)
+            .await
+    }
+
+    /// Sets the final keypoint set of `tag_id` on an image, replacing the
+    /// previous one.
+    pub async fn set_final(
+        db: &DatabaseConnection,
+        image_id: i32,
+        tag_id: i32,
+        keypoints: &[Keypoint],
+        votes: i32,
+    ) -> Result<FinalKeypointSetModel, DbErr> {
+        FinalKeypointSet::delete_many()
+            .filter(final_keypoint_set::Column::ImageId.eq(image_id))
+            .filter(final_keypoint_set::Column::TagId.eq(tag_id))
+            .exec(db)
+            .await?;
+
+        let final_set = FinalKeypointSetActiveModel {
+            image_id: Set(image_id),
+            tag_id: Set(tag_id),
+            keypoints: Set(serde_json::to_string(keypoints).unwrap_or_default()),
+            votes: Set(votes),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        final_set.insert(db).await
+    }
+}
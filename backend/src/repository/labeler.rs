@@ -1,10 +1,44 @@
 use sea_orm::*;
 use crate::entity::labeler::{Entity as Labeler, Model as LabelerModel, ActiveModel as LabelerActiveModel};
 use crate::entity::group::Model as GroupModel;
+use super::pagination::{fetch_page, Page, PageRequest, SortOrder};
+
+/// Sort keys of labeler lists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelerSort {
+    #[default]
+    Id,
+    Username,
+}
+
+impl LabelerSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "id" => Some(Self::Id),
+            "username" => Some(Self::Username),
+            _ => None,
+        }
+    }
+}
 
 pub struct LabelerRepository;
 
 impl LabelerRepository {
+    pub async fn list(
+        db: &DatabaseConnection,
+        sort: LabelerSort,
+        order: SortOrder,
+        page: PageRequest,
+    ) -> Result<Page<LabelerModel>, DbErr> {
+        use crate::entity::labeler::Column;
+
+        let mut select = Labeler::find();
+        if sort == LabelerSort::Username {
+            select = select.order_by(Column::Username, order.order());
+        }
+        fetch_page(db, select.order_by(Column::Id, order.order()), page).await
+    }
+
     pub async fn find_by_username(
         db: &DatabaseConnection,
         username: &str,
@@ -36,6 +70,26 @@ impl LabelerRepository {
         Labeler::find_by_id(id).one(db).await
     }
 
+    /// Suspends a labeler with the reason shown to them, or reinstates them
+    /// when `suspended` is false.
+    pub async fn set_suspended<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        suspended: bool,
+        reason: Option<String>,
+    ) -> Result<LabelerModel, DbErr> {
+        let labeler = Labeler::find_by_id(id).one(db).await?;
+        match labeler {
+            Some(labeler) => {
+                let mut labeler: LabelerActiveModel = labeler.into();
+                labeler.suspended = Set(suspended);
+                labeler.suspended_reason = Set(if suspended { reason } else { None });
+                labeler.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Labeler not found".to_string())),
+        }
+    }
+
     pub async fn get_groups(
         db: &DatabaseConnection,
         labeler_id: i32,
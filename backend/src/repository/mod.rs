@@ -6,6 +6,18 @@ pub mod group;
 pub mod tag;
 pub mod image_tags;
 pub mod labeler_groups;
+pub mod import_job;
+pub mod image_thumbnail;
+pub mod gold;
+pub mod assignment;
+pub mod image_flag;
+pub mod submission;
+pub mod image_view;
+pub mod region;
+pub mod segmentation;
+pub mod keypoint;
+pub mod caption;
+pub mod pagination;
 
 pub use admin::*;
 pub use final_tags::*;
@@ -15,3 +27,15 @@ pub use group::*;
 pub use tag::*;
 pub use image_tags::*;
 pub use labeler_groups::*;
+pub use import_job::*;
+pub use image_thumbnail::*;
+pub use gold::*;
+pub use assignment::*;
+pub use image_flag::*;
+pub use submission::*;
+pub use image_view::*;
+pub use region::*;
+pub use segmentation::*;
+pub use keypoint::*;
+pub use caption::*;
+pub use pagination::*;
@@ -0,0 +1,77 @@
+use sea_orm::*;
+
+/// Largest page a list endpoint will return in one response.
+pub const MAX_PAGE_SIZE: u64 = 1000;
+
+/// Offset window of a list query. Without a `limit` every row from
+/// `offset` on is returned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: Option<u64>,
+    pub offset: u64,
+}
+
+impl PageRequest {
+    /// Validates the raw `limit` and `offset` query parameters.
+    pub fn new(limit: Option<u64>, offset: Option<u64>) -> Result<Self, String> {
+        if let Some(limit) = limit
+            && !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", MAX_PAGE_SIZE));
+        }
+        Ok(Self { limit, offset: offset.unwrap_or(0) })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "asc" => Some(Self::Asc),
+            "desc" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn order(&self) -> Order {
+        match self {
+            Self::Asc => Order::Asc,
+            Self::Desc => Order::Desc,
+        }
+    }
+}
+
+/// One page of rows plus the number of rows matching the query overall.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+}
+
+// Counts the rows `select` matches, then fetches the requested window of them
+pub(crate) async fn fetch_page<E>(
+    db: &DatabaseConnection,
+    select: Select<E>,
+    page: PageRequest,
+) -> Result<Page<E::Model>, DbErr>
+where
+    E: EntityTrait,
+    E::Model: Sync,
+{
+    let total = select.clone().count(db).await?;
+
+    // SQLite only accepts OFFSET together with LIMIT
+    let limit = match (page.limit, page.offset) {
+        (None, 0) => None,
+        (limit, _) => Some(limit.unwrap_or(i64::MAX as u64)),
+    };
+    let offset = (page.offset > 0).then_some(page.offset);
+    let items = select.offset(offset).limit(limit).all(db).await?;
+
+    Ok(Page { items, total })
+}
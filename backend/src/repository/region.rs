@@ -0,0 +1,150 @@
+use sea_orm::*;
+use chrono::Utc;
+use crate::entity::final_region::{self, Entity as FinalRegion, Model as FinalRegionModel, ActiveModel as FinalRegionActiveModel};
+use crate::entity::image::{self, Entity as Image};
+use crate::entity::region::{self, Entity as Region, Model as RegionModel, ActiveModel as RegionActiveModel};
+use crate::region::{BoundingBox, ConsensusRegion};
+
+pub struct RegionRepository;
+
+impl RegionRepository {
+    pub async fn create(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        tag_id: i32,
+        bbox: BoundingBox,
+    ) -> Result<RegionModel, DbErr> {
+        let now = Utc::now().naive_utc();
+        let region = RegionActiveModel {
+            image_id: Set(image_id),
+            labeler_id: Set(labeler_id),
+            tag_id: Set(tag_id),
+            x: Set(bbox.x),
+            y: Set(bbox.y),
+            width: Set(bbox.width),
+            height: Set(bbox.height),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        region.insert(db).await
+    }
+
+    pub async fn find_by_id(
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<Option<RegionModel>, DbErr> {
+        Region::find_by_id(id).one(db).await
+    }
+
+    pub async fn update(
+        db: &DatabaseConnection,
+        region: RegionModel,
+        tag_id: i32,
+        bbox: BoundingBox,
+    ) -> Result<RegionModel, DbErr> {
+        let mut region: RegionActiveModel = region.into();
+        region.tag_id = Set(tag_id);
+        region.x = Set(bbox.x);
+        region.y = Set(bbox.y);
+        region.width = Set(bbox.width);
+        region.height = Set(bbox.height);
+        region.updated_at = Set(Utc::now().naive_utc());
+        region.update(db).await
+    }
+
+    pub async fn delete(
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<DeleteResult, DbErr> {
+        Region::delete_by_id(id).exec(db).await
+    }
+
+    pub async fn get_by_image_and_labeler(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<Vec<RegionModel>, DbErr> {
+        Region::find()
+            .filter(region::Column::ImageId.eq(image_id))
+            .filter(region::Column::LabelerId.eq(labeler_id))
+            .order_by_asc(region::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn get_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<RegionModel>, DbErr> {
+        Region::find()
+            .filter(region::Column::ImageId.eq(image_id))
+            .order_by_asc(region::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete_by_labeler_and_group(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<(), DbErr> {
+        let image_ids = Image::find()
+            .select_only()
+            .column(image::Column::Id)
+            .filter(image::Column::GroupId.eq(group_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+
+        Region::delete_many()
+            .filter(region::Column::LabelerId.eq(labeler_id))
+            .filter(region::Column::ImageId.is_in(image_ids))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Final boxes of an image, in the order consensus produced them.
+    pub async fn get_final_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<FinalRegionModel>, DbErr> {
+        FinalRegion::find()
+            .filter(final_region::Column::ImageId.eq(image_id))
+            .order_by_asc(final_region::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn replace_final(
+        db: &DatabaseConnection,
+        image_id: i32,
+        regions: Vec<ConsensusRegion>,
+    ) -> Result<Vec<FinalRegionModel>, DbErr> {
+        FinalRegion::delete_many()
+            .filter(final_region::Column::ImageId.eq(image_id))
+            .exec(db)
+            .await?;
+
+        let now = Utc::now().naive_utc();
+        let mut results = Vec::new();
+        for region in regions {
+            let final_region = FinalRegionActiveModel {
+                image_id: Set(image_id),
+                tag_id: Set(region.tag_id),
+                x: Set(region.bbox.x),
+                y: Set(region.bbox.y),
+                width: Set(region.bbox.width),
+                height: Set(region.bbox.height),
+                votes: Set(region.votes as i32),
+                created_at: Set(now),
+                ..Default::default()
+            };
+            results.push(final_region.insert(db).await?);
+        }
+
+        Ok(results)
+    }
+}
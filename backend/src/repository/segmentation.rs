@@ -0,0 +1,147 @@
+use sea_orm::*;
+use chrono::Utc;
+use crate::entity::final_segmentation::{
+    self, Entity as FinalSegmentation, Model as FinalSegmentationModel, ActiveModel as FinalSegmentationActiveModel,
+};
+use crate::entity::image::{self, Entity as Image};
+use crate::entity::segmentation::{self, Entity as Segmentation, Model as SegmentationModel, ActiveModel as SegmentationActiveModel};
+use crate::segmentation::Geometry;
+
+pub struct SegmentationRepository;
+
+impl SegmentationRepository {
+    pub async fn create(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+        tag_id: i32,
+        geometry: &Geometry,
+        area: i64,
+    ) -> Result<SegmentationModel, DbErr> {
+        let now = Utc::now().naive_utc();
+        let segmentation = SegmentationActiveModel {
+            image_id: Set(image_id),
+            labeler_id: Set(labeler_id),
+            tag_id: Set(tag_id),
+            geometry: Set(geometry.to_json()),
+            area: Set(area),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        segmentation.insert(db).await
+    }
+
+    pub async fn find_by_id(
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<Option<SegmentationModel>, DbErr> {
+        Segmentation::find_by_id(id).one(db).await
+    }
+
+    pub async fn update(
+        db: &DatabaseConnection,
+        segmentation: SegmentationModel,
+        tag_id: i32,
+        geometry: &Geometry,
+        area: i64,
+    ) -> Result<SegmentationModel, DbErr> {
+        let mut segmentation: SegmentationActiveModel = segmentation.into();
+        segmentation.tag_id = Set(tag_id);
+        segmentation.geometry = Set(geometry.to_json());
+        segmentation.area = Set(area);
+        segmentation.updated_at = Set(Utc::now().naive_utc());
+        segmentation.update(db).await
+    }
+
+    pub async fn delete(
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<DeleteResult, DbErr> {
+        Segmentation::delete_by_id(id).exec(db).await
+    }
+
+    pub async fn get_by_image_and_labeler(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<Vec<SegmentationModel>, DbErr> {
+        Segmentation::find()
+            .filter(segmentation::Column::ImageId.eq(image_id))
+            .filter(segmentation::Column::LabelerId.eq(labeler_id))
+            .order_by_asc(segmentation::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn get_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<SegmentationModel>, DbErr> {
+        Segmentation::find()
+            .filter(segmentation::Column::ImageId.eq(image_id))
+            .order_by_asc(segmentation::Column::Id)
+            .all(db)
+            .await
+    }
+
+    pub async fn delete_by_labeler_and_group(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<(), DbErr> {
+        let image_ids = Image::find()
+            .select_only()
+            .column(image::Column::Id)
+            .filter(image::Column::GroupId.eq(group_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+
+        Segmentation::delete_many()
+            .filter(segmentation::Column::LabelerId.eq(labeler_id))
+            .filter(segmentation::Column::ImageId.is_in(image_ids))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_final_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<FinalSegmentationModel>, DbErr> {
+        FinalSegmentation::find()
+            .filter(final_segmentation::Column::ImageId.eq(image_id))
+            .order_by_asc(final_segmentation::Column::TagId)
+            .all(db)
+            .await
+    }
+
+    /// Sets the final mask of `tag_id` on an image, replacing the previous
+    /// one.
+    pub async fn set_final(
+        db: &DatabaseConnection,
+        image_id: i32,
+        tag_id: i32,
+        geometry: &Geometry,
+        area: i64,
+        votes: i32,
+    ) -> Result<FinalSegmentationModel, DbErr> {
+        FinalSegmentation::delete_many()
+            .filter(final_segmentation::Column::ImageId.eq(image_id))
+            .filter(final_segmentation::Column::TagId.eq(tag_id))
+            .exec(db)
+            .await?;
+
+        let final_segmentation = FinalSegmentationActiveModel {
+            image_id: Set(image_id),
+            tag_id: Set(tag_id),
+            geometry: Set(geometry.to_json()),
+            area: Set(area),
+            votes: Set(votes),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        final_segmentation.insert(db).await
+    }
+}
@@ -0,0 +1,190 @@
+use sea_orm::*;
+use chrono::NaiveDateTime;
+use std::collections::{BTreeMap, BTreeSet};
+use crate::entity::image::{self, Entity as Image};
+use crate::entity::image_tags::{self, Entity as ImageTags};
+use crate::entity::submission::{self, Entity as Submission, Model as SubmissionModel, ActiveModel as SubmissionActiveModel};
+
+pub struct SubmissionRepository;
+
+impl SubmissionRepository {
+    /// Records that the labeler submitted the image now. The first
+    /// submission also records when the image was opened and the time spent
+    /// on it; later ones only move `submitted_at`.
+    pub async fn record<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+        opened_at: Option<NaiveDateTime>,
+    ) -> Result<SubmissionModel, DbErr> {
+        let now = chrono::Utc::now().naive_utc();
+        if let Some(submission) = Self::find(db, image_id, labeler_id).await? {
+            let mut submission: SubmissionActiveModel = submission.into();
+            submission.submitted_at = Set(now);
+            return submission.update(db).await;
+        }
+
+        let submission = SubmissionActiveModel {
+            image_id: Set(image_id),
+            labeler_id: Set(labeler_id),
+            submitted_at: Set(now),
+            first_submitted_at: Set(now),
+            opened_at: Set(opened_at),
+            seconds_spent: Set(opened_at.map(|opened_at| (now - opened_at).num_seconds().max(0))),
+            ..Default::default()
+        };
+        submission.insert(db).await
+    }
+
+    pub async fn find<C: ConnectionTrait>(
+        db: &C,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<Option<SubmissionModel>, DbErr> {
+        Submission::find()
+            .filter(submission::Column::ImageId.eq(image_id))
+            .filter(submission::Column::LabelerId.eq(labeler_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn get_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<Vec<SubmissionModel>, DbErr> {
+        Submission::find()
+            .filter(submission::Column::ImageId.eq(image_id))
+            .all(db)
+            .await
+    }
+
+    /// Every submission of an image of `group_id`.
+    pub async fn get_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Vec<SubmissionModel>, DbErr> {
+        Submission::find()
+            .inner_join(Image)
+            .filter(image::Column::GroupId.eq(group_id))
+            .all(db)
+            .await
+    }
+
+    /// Labelers who submitted each image of `group_id`, and when they first
+    /// did. Tags without a submission record count as a submission when they
+    /// were saved.
+    pub async fn submitters_by_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<BTreeMap<i32, BTreeMap<i32, NaiveDateTime>>, DbErr> {
+        let mut submitters: BTreeMap<i32, BTreeMap<i32, NaiveDateTime>> = BTreeMap::new();
+        for submission in Self::get_by_group(db, group_id).await? {
+            submitters.entry(submission.image_id).or_default().insert(submission.labeler_id, submission.first_submitted_at);
+        }
+        let image_tags = ImageTags::find()
+            .inner_join(Image)
+            .filter(image::Column::GroupId.eq(group_id))
+            .all(db)
+            .await?;
+        for image_tag in image_tags {
+            submitters
+                .entry(image_tag.image_id)
+                .or_default()
+                .entry(image_tag.labeler_id)
+                .or_insert(image_tag.created_at);
+        }
+        Ok(submitters)
+    }
+
+    /// Labelers who submitted `image_id`, counted as in
+    /// [`Self::submitters_by_group`].
+    pub async fn submitters_by_image(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<BTreeSet<i32>, DbErr> {
+        let mut submitters: BTreeSet<i32> = Self::get_by_image(db, image_id)
+            .await?
+            .into_iter()
+            .map(|submission| submission.labeler_id)
+            .collect();
+        let image_tags = ImageTags::find()
+            .filter(image_tags::Column::ImageId.eq(image_id))
+            .all(db)
+            .await?;
+        submitters.extend(image_tags.into_iter().map(|image_tag| image_tag.labeler_id));
+        Ok(submitters)
+    }
+
+    /// Whether the labeler submitted the image, counted as in
+    /// [`Self::submitters_by_group`].
+    pub async fn has_submitted(
+        db: &DatabaseConnection,
+        image_id: i32,
+        labeler_id: i32,
+    ) -> Result<bool, DbErr> {
+        if Self::find(db, image_id, labeler_id).await?.is_some() {
+            return Ok(true);
+        }
+        let tagged = ImageTags::find()
+            .filter(image_tags::Column::ImageId.eq(image_id))
+            .filter(image_tags::Column::LabelerId.eq(labeler_id))
+            .count(db)
+            .await?;
+        Ok(tagged > 0)
+    }
+
+    /// Which of `image_ids` the labeler submitted, counted as in
+    /// [`Self::has_submitted`].
+    pub async fn submitted_images(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        image_ids: &[i32],
+    ) -> Result<BTreeSet<i32>, DbErr> {
+        if image_ids.is_empty() {
+            return Ok(BTreeSet::new());
+        }
+        let mut submitted: BTreeSet<i32> = Submission::find()
+            .select_only()
+            .column(submission::Column::ImageId)
+            .filter(submission::Column::LabelerId.eq(labeler_id))
+            .filter(submission::Column::ImageId.is_in(image_ids.iter().copied()))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?
+            .into_iter()
+            .collect();
+        submitted.extend(
+            ImageTags::find()
+                .select_only()
+                .column(image_tags::Column::ImageId)
+                .distinct()
+                .filter(image_tags::Column::LabelerId.eq(labeler_id))
+                .filter(image_tags::Column::ImageId.is_in(image_ids.iter().copied()))
+                .into_tuple::<i32>()
+                .all(db)
+                .await?,
+        );
+        Ok(submitted)
+    }
+
+    pub async fn delete_by_labeler_and_group(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<(), DbErr> {
+        let image_ids = Image::find()
+            .select_only()
+            .column(image::Column::Id)
+            .filter(image::Column::GroupId.eq(group_id))
+            .into_tuple::<i32>()
+            .all(db)
+            .await?;
+
+        Submission::delete_many()
+            .filter(submission::Column::LabelerId.eq(labeler_id))
+            .filter(submission::Column::ImageId.is_in(image_ids))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
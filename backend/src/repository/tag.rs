@@ -1,5 +1,6 @@
 use sea_orm::*;
 use crate::entity::tag::{Entity as Tag, Model as TagModel, ActiveModel as TagActiveModel};
+use crate::keypoint::KeypointSchema;
 
 pub struct TagRepository;
 
@@ -71,6 +72,23 @@ impl TagRepository {
         }
     }
 
+    /// Sets or, with `None`, removes the tag's keypoint schema.
+    pub async fn set_keypoint_schema(
+        db: &DatabaseConnection,
+        id: i32,
+        schema: Option<&KeypointSchema>,
+    ) -> Result<TagModel, DbErr> {
+        let tag = Tag::find_by_id(id).one(db).await?;
+        match tag {
+            Some(tag) => {
+                let mut tag: TagActiveModel = tag.into();
+                tag.keypoint_schema = Set(schema.map(KeypointSchema::to_json));
+                tag.update(db).await
+            }
+            None => Err(DbErr::RecordNotFound("Tag not found".to_string())),
+        }
+    }
+
     pub async fn delete(
         db: &DatabaseConnection,
         id: i32,
@@ -0,0 +1,45 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use crate::schemas::admin::{AggregationResponse, ApiResponse, ApplyAggregationRequest};
+use crate::service::aggregation::AggregationService;
+
+#[derive(Debug, Deserialize)]
+pub struct AggregationQuery {
+    pub max_iterations: Option<u32>,
+}
+
+pub async fn get_aggregation(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    query: web::Query<AggregationQuery>,
+) -> Result<HttpResponse> {
+    respond(AggregationService::report(&db, path.into_inner(), query.max_iterations).await)
+}
+
+pub async fn apply_aggregation(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    request: web::Json<ApplyAggregationRequest>,
+) -> Result<HttpResponse> {
+    respond(AggregationService::apply(&db, path.into_inner(), request.into_inner()).await)
+}
+
+fn respond(result: Result<ApiResponse<AggregationResponse>, String>) -> Result<HttpResponse> {
+    match result {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
@@ -0,0 +1,94 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use crate::schemas::admin::ApiResponse;
+use crate::service::agreement::AgreementService;
+use crate::service::scoring::ScoringService;
+use crate::service::timing::TimingService;
+
+pub async fn get_group_agreement(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    match AgreementService::group_agreement(&db, path.into_inner()).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::NotFound().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScoreQuery {
+    /// Only report on this group.
+    pub group_id: Option<i32>,
+}
+
+pub async fn get_leaderboard(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ScoreQuery>,
+) -> Result<HttpResponse> {
+    match ScoringService::leaderboard(&db, query.group_id).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::NotFound().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+pub async fn get_labeler_score(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    query: web::Query<ScoreQuery>,
+) -> Result<HttpResponse> {
+    match ScoringService::labeler_score(&db, path.into_inner(), query.group_id).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::NotFound().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+pub async fn get_throughput(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<ScoreQuery>,
+) -> Result<HttpResponse> {
+    match TimingService::throughput(&db, query.group_id).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::NotFound().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
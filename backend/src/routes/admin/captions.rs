@@ -0,0 +1,48 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use crate::schemas::admin::{ApiResponse, SelectFinalCaptionRequest};
+use crate::service::caption::CaptionService;
+use crate::storage::ImageStorage;
+
+pub async fn get_image_captions(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    respond(CaptionService::image_captions(&db, path.into_inner()).await)
+}
+
+pub async fn select_final_caption(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    request: web::Json<SelectFinalCaptionRequest>,
+) -> Result<HttpResponse> {
+    respond(CaptionService::select_final_caption(&db, path.into_inner(), request.into_inner()).await)
+}
+
+pub async fn draft_final_caption(
+    db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    respond(CaptionService::draft_final_caption(&db, storage.get_ref(), path.into_inner()).await)
+}
+
+fn respond<T: Serialize>(result: Result<ApiResponse<T>, String>) -> Result<HttpResponse> {
+    match result {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
@@ -0,0 +1,51 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use crate::schemas::admin::ApiResponse;
+use crate::service::duplicate::{DuplicateService, HashKind, DEFAULT_THRESHOLD};
+
+#[derive(Debug, Deserialize)]
+pub struct DuplicateReportQuery {
+    /// Largest Hamming distance, in bits, between near-duplicates.
+    pub threshold: Option<u32>,
+    /// `dhash` (the default) or `ahash`.
+    pub hash: Option<String>,
+}
+
+pub async fn get_duplicate_report(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    query: web::Query<DuplicateReportQuery>,
+) -> Result<HttpResponse> {
+    let kind = match query.hash.as_deref() {
+        None => HashKind::Difference,
+        Some(value) => match HashKind::parse(value) {
+            Some(kind) => kind,
+            None => {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::<()> {
+                    success: false,
+                    message: format!("Invalid hash: {}. Expected dhash or ahash", value),
+                    data: None,
+                }));
+            }
+        },
+    };
+    let threshold = query.threshold.unwrap_or(DEFAULT_THRESHOLD);
+
+    match DuplicateService::report(&db, path.into_inner(), kind, threshold).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
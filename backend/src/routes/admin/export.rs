@@ -1,11 +1,16 @@
 use actix_web::{web, HttpResponse, Result};
-use crate::schemas::export::ExportResponse;
+use crate::schemas::export::{BulkExportQuery, ExportResponse};
+use crate::schemas::admin::ApiResponse;
 use crate::service::export::ExportService;
+use crate::storage::ImageStorage;
 
 pub async fn bulk_export(
     db: web::Data<sea_orm::DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
+    query: web::Query<BulkExportQuery>,
 ) -> Result<HttpResponse> {
-    match ExportService::bulk_export(&db).await {
+    let include_agreement = query.include_agreement.unwrap_or(false);
+    match ExportService::bulk_export(&db, storage.get_ref(), include_agreement).await {
         Ok(export_data) => {
             Ok(HttpResponse::Ok().json(ExportResponse {
                 success: true,
@@ -21,4 +26,59 @@ pub async fn bulk_export(
             }))
         }
     }
-}
\ No newline at end of file
+}
+
+/// The group's final masks, boxes and keypoints as a plain COCO JSON file.
+pub async fn coco_export(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    match ExportService::coco_export(&db, path.into_inner()).await {
+        Ok(Some(dataset)) => Ok(HttpResponse::Ok().json(dataset)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()> {
+            success: false,
+            message: "Group not found".to_string(),
+            data: None,
+        })),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+/// The group's final captions as JSON Lines, one image–caption pair per
+/// line.
+pub async fn caption_export(
+    db: web::Data<sea_orm::DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    let group_id = path.into_inner();
+    match ExportService::caption_export(&db, group_id).await {
+        Ok(Some(pairs)) => {
+            let mut body = String::new();
+            for pair in pairs {
+                body.push_str(&serde_json::to_string(&pair).unwrap_or_default());
+                body.push('\n');
+            }
+            Ok(HttpResponse::Ok()
+                .content_type("application/jsonl")
+                .insert_header((
+                    actix_web::http::header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"captions-{}.jsonl\"", group_id),
+                ))
+                .body(body))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::<()> {
+            success: false,
+            message: "Group not found".to_string(),
+            data: None,
+        })),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
@@ -1,7 +1,9 @@
 use actix_web::{web, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
 use crate::{
-    repository::{FinalTagsRepository, ImageTagsRepository, TagRepository},
+    consensus::ConsensusStrategy,
+    repository::{FinalTagsRepository, TagRepository},
+    service::consensus::ConsensusService,
 };
 
 #[derive(Serialize)]
@@ -17,6 +19,11 @@ pub struct FinalTagData {
     pub tag_id: i32,
     pub tag_name: String,
     pub is_admin_override: bool,
+    /// Strategy that generated the tag; `None` for admin overrides.
+    pub consensus_strategy: Option<ConsensusStrategy>,
+    /// Posterior probability of the tag when it was estimated by label
+    /// aggregation.
+    pub confidence: Option<f64>,
     pub created_at: String,
 }
 
@@ -25,6 +32,13 @@ pub struct UpdateFinalTagsRequest {
     pub tag_ids: Vec<i32>,
 }
 
+/// Optional body of the auto-generate endpoint. Without a strategy the
+/// image's group strategy is used.
+#[derive(Deserialize, Default)]
+pub struct AutoGenerateFinalTagsRequest {
+    pub strategy: Option<ConsensusStrategy>,
+}
+
 pub async fn get_final_tags(
     db: web::Data<sea_orm::DatabaseConnection>,
     path: web::Path<i32>,
@@ -43,6 +57,8 @@ pub async fn get_final_tags(
                             tag_id: final_tag.tag_id,
                             tag_name: tag.name,
                             is_admin_override: final_tag.is_admin_override,
+                            consensus_strategy: final_tag.consensus_strategy.as_deref().and_then(ConsensusStrategy::from_json),
+                            confidence: final_tag.confidence,
                             created_at: final_tag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
                         });
                     }
@@ -93,6 +109,8 @@ pub async fn update_final_tags(
                             tag_id: final_tag.tag_id,
                             tag_name: tag.name,
                             is_admin_override: final_tag.is_admin_override,
+                            consensus_strategy: final_tag.consensus_strategy.as_deref().and_then(ConsensusStrategy::from_json),
+                            confidence: final_tag.confidence,
                             created_at: final_tag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
                         });
                     }
@@ -127,89 +145,80 @@ pub async fn update_final_tags(
 pub async fn auto_generate_final_tags(
     db: web::Data<sea_orm::DatabaseConnection>,
     path: web::Path<i32>,
+    body: web::Bytes,
 ) -> Result<HttpResponse> {
     let image_id = path.into_inner();
-    // Get all image tags for this image
-    match ImageTagsRepository::get_all_tags_for_image(&db, image_id).await {
-        Ok(image_tags) => {
-            if image_tags.is_empty() {
-                return Ok(HttpResponse::Ok().json(FinalTagsResponse {
-                    success: true,
-                    message: "No tags found for this image".to_string(),
-                    data: Some(vec![]),
+    // The body is optional, so it is parsed here rather than by web::Json
+    let request: AutoGenerateFinalTagsRequest = if body.iter().all(u8::is_ascii_whitespace) {
+        AutoGenerateFinalTagsRequest::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(request) => request,
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(FinalTagsResponse {
+                    success: false,
+                    message: format!("Invalid request body: {}", e),
+                    data: None,
                 }));
             }
-            
-            // Group tags by tag_id and count occurrences
-            use std::collections::HashMap;
-            let mut tag_counts: HashMap<i32, i32> = HashMap::new();
-            
-            for image_tag in &image_tags {
-                *tag_counts.entry(image_tag.tag_id).or_insert(0) += 1;
+        }
+    };
+    let strategy = request.strategy;
+    let response = match ConsensusService::auto_generate_final_tags(&db, image_id, strategy).await {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(HttpResponse::InternalServerError().json(FinalTagsResponse {
+                success: false,
+                message: e,
+                data: None,
+            }));
+        }
+    };
+    if !response.success {
+        let body = FinalTagsResponse {
+            success: false,
+            message: response.message,
+            data: None,
+        };
+        return Ok(if body.message.contains("not found") {
+            HttpResponse::NotFound().json(body)
+        } else {
+            HttpResponse::BadRequest().json(body)
+        });
+    }
+
+    let mut final_tag_data = Vec::new();
+    for final_tag in response.data.unwrap_or_default() {
+        // Get tag name
+        match TagRepository::find_by_id(&db, final_tag.tag_id).await {
+            Ok(Some(tag)) => {
+                final_tag_data.push(FinalTagData {
+                    id: final_tag.id,
+                    tag_id: final_tag.tag_id,
+                    tag_name: tag.name,
+                    is_admin_override: final_tag.is_admin_override,
+                    consensus_strategy: final_tag.consensus_strategy.as_deref().and_then(ConsensusStrategy::from_json),
+                    confidence: final_tag.confidence,
+                    created_at: final_tag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+                });
             }
-            
-            // Get total number of labelers who tagged this image
-            let total_labelers = image_tags.iter()
-                .map(|it| it.labeler_id)
-                .collect::<std::collections::HashSet<_>>()
-                .len() as i32;
-            
-            // Find tags chosen by at least 50% of labelers
-            let threshold = (total_labelers as f64 * 0.5).ceil() as i32;
-            let final_tag_ids: Vec<i32> = tag_counts
-                .into_iter()
-                .filter(|(_, count)| *count >= threshold)
-                .map(|(tag_id, _)| tag_id)
-                .collect();
-            
-            // Replace final tags with auto-generated ones
-            match FinalTagsRepository::replace_final_tags(&db, image_id, final_tag_ids, false).await {
-                Ok(final_tags) => {
-                    let mut final_tag_data = Vec::new();
-                    
-                    for final_tag in final_tags {
-                        // Get tag name
-                        match TagRepository::find_by_id(&db, final_tag.tag_id).await {
-                            Ok(Some(tag)) => {
-                                final_tag_data.push(FinalTagData {
-                                    id: final_tag.id,
-                                    tag_id: final_tag.tag_id,
-                                    tag_name: tag.name,
-                                    is_admin_override: final_tag.is_admin_override,
-                                    created_at: final_tag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
-                                });
-                            }
-                            Ok(None) => {
-                                // Tag not found, skip this final tag
-                                continue;
-                            }
-                            Err(_) => {
-                                return Ok(HttpResponse::InternalServerError().json(FinalTagsResponse {
-                                    success: false,
-                                    message: "Database error".to_string(),
-                                    data: None,
-                                }));
-                            }
-                        }
-                    }
-                    
-                    Ok(HttpResponse::Ok().json(FinalTagsResponse {
-                        success: true,
-                        message: "Final tags auto-generated successfully".to_string(),
-                        data: Some(final_tag_data),
-                    }))
-                }
-                Err(_) => Ok(HttpResponse::InternalServerError().json(FinalTagsResponse {
+            Ok(None) => {
+                // Tag not found, skip this final tag
+                continue;
+            }
+            Err(_) => {
+                return Ok(HttpResponse::InternalServerError().json(FinalTagsResponse {
                     success: false,
                     message: "Database error".to_string(),
                     data: None,
-                })),
+                }));
             }
         }
-        Err(_) => Ok(HttpResponse::InternalServerError().json(FinalTagsResponse {
-            success: false,
-            message: "Database error".to_string(),
-            data: None,
-        })),
     }
+
+    Ok(HttpResponse::Ok().json(FinalTagsResponse {
+        success: true,
+        message: response.message,
+        data: Some(final_tag_data),
+    }))
 }
@@ -0,0 +1,30 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use crate::schemas::admin::ApiResponse;
+use crate::service::flag::FlagService;
+
+#[derive(Debug, Deserialize)]
+pub struct FlaggedImagesQuery {
+    pub group_id: Option<i32>,
+}
+
+pub async fn get_flagged_images(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<FlaggedImagesQuery>,
+) -> Result<HttpResponse> {
+    match FlagService::flagged_images(&db, query.group_id).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::NotFound().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
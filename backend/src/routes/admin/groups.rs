@@ -1,13 +1,21 @@
 use actix_web::{web, HttpResponse, Result};
 use sea_orm::DatabaseConnection;
-use crate::schemas::admin::{ApiResponse, CreateGroupRequest, AddLabelerToGroupRequest};
+use crate::schemas::admin::{ApiResponse, CreateGroupRequest, UpdateGroupRequest, AddLabelerToGroupRequest};
+use crate::schemas::pagination::{GroupDetailsQuery, ListQuery};
 use crate::service::admin::AdminService;
 
 pub async fn list_groups(
     db: web::Data<DatabaseConnection>,
+    query: web::Query<ListQuery>,
 ) -> Result<HttpResponse> {
-    match AdminService::list_groups(&db).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+    match AdminService::list_groups(&db, query.into_inner()).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
             success: false,
             message: e,
@@ -21,7 +29,13 @@ pub async fn create_group(
     request: web::Json<CreateGroupRequest>,
 ) -> Result<HttpResponse> {
     match AdminService::create_group(&db, request.into_inner()).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
             success: false,
             message: e,
@@ -33,10 +47,43 @@ pub async fn create_group(
 pub async fn get_group_details(
     db: web::Data<DatabaseConnection>,
     path: web::Path<i32>,
+    query: web::Query<GroupDetailsQuery>,
 ) -> Result<HttpResponse> {
     let group_id = path.into_inner();
-    match AdminService::get_group_details(&db, group_id).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+    match AdminService::get_group_details(&db, group_id, query.into_inner()).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+pub async fn update_group(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    request: web::Json<UpdateGroupRequest>,
+) -> Result<HttpResponse> {
+    let group_id = path.into_inner();
+    match AdminService::update_group(&db, group_id, request.into_inner()).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
             success: false,
             message: e,
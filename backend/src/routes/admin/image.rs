@@ -1,9 +1,14 @@
 use actix_web::{web, HttpResponse, Result};
 use sea_orm::DatabaseConnection;
 use serde::Serialize;
+use crate::consensus::ConsensusStrategy;
 use crate::schemas::admin::{ApiResponse, UploadImageRequest};
 use crate::service::admin::AdminService;
-use crate::repository::{ImageRepository, ImageTagsRepository, TagRepository, FinalTagsRepository, GroupRepository};
+use crate::service::image::ImageService;
+use crate::repository::{ImageRepository, ImageTagsRepository, TagRepository, FinalTagsRepository, GroupRepository, SubmissionRepository};
+use crate::storage::{self, ImageStorage};
+use crate::multipart::MultipartLimits;
+use actix_multipart::Multipart;
 
 #[derive(Serialize)]
 pub struct ImageDetailsResponse {
@@ -27,6 +32,13 @@ pub struct ImageData {
     pub filetype: String,
     pub base64_data: String,
     pub uploaded_at: String,
+    pub content_url: String,
+    pub thumbnail_url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub color_mode: Option<String>,
+    pub orientation: Option<i32>,
+    pub duplicate_of: Option<i32>,
 }
 
 #[derive(Serialize)]
@@ -44,14 +56,20 @@ pub struct FinalTagData {
     pub tag_id: i32,
     pub tag_name: String,
     pub is_admin_override: bool,
+    /// Strategy that generated the tag; `None` for admin overrides.
+    pub consensus_strategy: Option<ConsensusStrategy>,
+    /// Posterior probability of the tag when it was estimated by label
+    /// aggregation.
+    pub confidence: Option<f64>,
     pub created_at: String,
 }
 
 pub async fn upload_image(
     db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
     request: web::Json<UploadImageRequest>,
 ) -> Result<HttpResponse> {
-    match AdminService::upload_image(&db, request.into_inner()).await {
+    match AdminService::upload_image(&db, storage.get_ref(), request.into_inner()).await {
         Ok(response) => Ok(HttpResponse::Ok().json(response)),
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
             success: false,
@@ -61,8 +79,30 @@ pub async fn upload_image(
     }
 }
 
+pub async fn upload_images(
+    db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
+    limits: web::Data<MultipartLimits>,
+    path: web::Path<i32>,
+    mut multipart: Multipart,
+) -> Result<HttpResponse> {
+    let group_id = path.into_inner();
+
+    match AdminService::upload_images_multipart(&db, storage.get_ref(), group_id, &mut multipart, &limits).await {
+        Ok(response) if response.success => Ok(HttpResponse::Ok().json(response)),
+        Ok(response) if response.data.is_none() => Ok(HttpResponse::NotFound().json(response)),
+        Ok(response) => Ok(HttpResponse::BadRequest().json(response)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
 pub async fn get_image_details(
     db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
     path: web::Path<(i32, i32)>, // (group_id, image_id)
 ) -> Result<HttpResponse> {
     let (group_id, image_id) = path.into_inner();
@@ -115,11 +155,17 @@ pub async fn get_image_details(
         *tag_counts.entry(image_tag.tag_id).or_insert(0) += 1;
     }
     
-    // Get total number of unique labelers who tagged this image
-    let total_labelers = image_tags.iter()
-        .map(|it| it.labeler_id)
-        .collect::<std::collections::HashSet<_>>()
-        .len() as i32;
+    // Get total number of labelers who submitted this image, tags or not
+    let total_labelers = match SubmissionRepository::submitters_by_image(&db, image_id).await {
+        Ok(submitters) => submitters.len() as i32,
+        Err(_) => {
+            return Ok(HttpResponse::InternalServerError().json(ImageDetailsResponse {
+                success: false,
+                message: "Database error".to_string(),
+                data: None,
+            }));
+        }
+    };
     
     // Get all possible tags for this group
     let all_group_tags = match GroupRepository::get_possible_tags(&db, group_id).await {
@@ -167,6 +213,8 @@ pub async fn get_image_details(
                             tag_id: final_tag.tag_id,
                             tag_name: tag.name,
                             is_admin_override: final_tag.is_admin_override,
+                            consensus_strategy: final_tag.consensus_strategy.as_deref().and_then(ConsensusStrategy::from_json),
+                            confidence: final_tag.confidence,
                             created_at: final_tag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
                         });
                     }
@@ -182,12 +230,30 @@ pub async fn get_image_details(
     // Check if there's an admin override
     let has_admin_override = FinalTagsRepository::has_admin_override(&db, image_id).await.unwrap_or_default();
     
+    let base64_data = match storage::read_image_base64(storage.get_ref(), &image).await {
+        Ok(data) => data,
+        Err(e) => {
+            return Ok(HttpResponse::InternalServerError().json(ImageDetailsResponse {
+                success: false,
+                message: e,
+                data: None,
+            }));
+        }
+    };
+    
     let image_data = ImageData {
         id: image.id,
         filename: image.filename,
         filetype: image.filetype,
-        base64_data: image.base64_data,
+        base64_data,
         uploaded_at: image.uploaded_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        content_url: ImageService::content_url(image.id),
+        thumbnail_url: ImageService::thumbnail_url(image.id),
+        width: image.width,
+        height: image.height,
+        color_mode: image.color_mode,
+        orientation: image.orientation,
+        duplicate_of: image.duplicate_of,
     };
     
     Ok(HttpResponse::Ok().json(ImageDetailsResponse {
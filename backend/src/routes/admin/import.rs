@@ -0,0 +1,166 @@
+use actix_multipart::{Field, Multipart};
+use actix_web::{web, HttpResponse, Result};
+use futures_util::TryStreamExt;
+use sea_orm::DatabaseConnection;
+use crate::multipart::{self, MultipartLimits};
+use crate::schemas::admin::{ApiResponse, ZipImportRequest};
+use crate::service::import::ImportService;
+use crate::storage::ImageStorage;
+use std::fs::File;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+// Longest value read for the boolean form fields
+const MAX_FLAG_SIZE: usize = 16;
+
+fn bad_request(message: String) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::<()> {
+        success: false,
+        message,
+        data: None,
+    })
+}
+
+fn is_truthy(value: &[u8]) -> bool {
+    matches!(
+        String::from_utf8_lossy(value).trim().to_lowercase().as_str(),
+        "true" | "1" | "on" | "yes"
+    )
+}
+
+// Streams the archive into an anonymous temporary file, which the import
+// reads entries from and which disappears once the import is done with it.
+// Fails with the response to send.
+async fn spool(field: &mut Field, max_size: usize) -> Result<File, HttpResponse> {
+    let server_error = |e: std::io::Error| {
+        HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: format!("Failed to store archive: {}", e),
+            data: None,
+        })
+    };
+
+    let mut file = tokio::fs::File::from_std(tempfile::tempfile().map_err(server_error)?);
+    let mut size = 0;
+    while let Some(chunk) = field.try_next().await.map_err(|e| bad_request(e.to_string()))? {
+        size += chunk.len();
+        if size > max_size {
+            return Err(HttpResponse::PayloadTooLarge().json(ApiResponse::<()> {
+                success: false,
+                message: format!("Archive exceeds the maximum size of {} bytes", max_size),
+                data: None,
+            }));
+        }
+        file.write_all(&chunk).await.map_err(server_error)?;
+    }
+    file.rewind().await.map_err(server_error)?;
+
+    Ok(file.into_std().await)
+}
+
+/// Accepts a multipart body with an `archive` ZIP file, an optional
+/// `mapping` CSV/JSON file and the `create_missing_tags` and
+/// `seed_final_tags` flags, and starts a background import job.
+pub async fn import_zip(
+    db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
+    limits: web::Data<MultipartLimits>,
+    path: web::Path<i32>,
+    mut multipart: Multipart,
+) -> Result<HttpResponse> {
+    let group_id = path.into_inner();
+
+    let mut archive = None;
+    let mut mapping = None;
+    let mut create_missing_tags = false;
+    let mut seed_final_tags = false;
+    loop {
+        let mut field = match multipart.try_next().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return Ok(bad_request(e.to_string())),
+        };
+
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            "archive" => match spool(&mut field, limits.max_archive_size).await {
+                Ok(file) => archive = Some(file),
+                Err(response) => return Ok(response),
+            },
+            "mapping" => {
+                let filename = field
+                    .content_disposition()
+                    .and_then(|cd| cd.get_filename())
+                    .unwrap_or("mapping.csv")
+                    .to_string();
+                match multipart::read_field(&mut field, limits.max_file_size).await {
+                    Ok(Some(data)) => mapping = Some((filename, data)),
+                    Ok(None) => return Ok(bad_request("Mapping file is too large".to_string())),
+                    Err(e) => return Ok(bad_request(e.to_string())),
+                }
+            }
+            "create_missing_tags" | "seed_final_tags" => {
+                let value = match multipart::read_field(&mut field, MAX_FLAG_SIZE).await {
+                    Ok(data) => data.is_some_and(|data| is_truthy(&data)),
+                    Err(e) => return Ok(bad_request(e.to_string())),
+                };
+                if name == "create_missing_tags" {
+                    create_missing_tags = value;
+                } else {
+                    seed_final_tags = value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some(archive) = archive else {
+        return Ok(bad_request("Missing \"archive\" file".to_string()));
+    };
+    let request = ZipImportRequest {
+        group_id,
+        archive,
+        mapping,
+        create_missing_tags,
+        seed_final_tags,
+    };
+
+    match ImportService::start_zip_import(&db, storage.into_inner(), request, limits.max_file_size).await {
+        Ok(response) if response.success => Ok(HttpResponse::Accepted().json(response)),
+        Ok(response) if response.message == "Group not found" => Ok(HttpResponse::NotFound().json(response)),
+        Ok(response) => Ok(HttpResponse::BadRequest().json(response)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+pub async fn get_import_job(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    match ImportService::get_job(&db, path.into_inner()).await {
+        Ok(response) if response.success => Ok(HttpResponse::Ok().json(response)),
+        Ok(response) => Ok(HttpResponse::NotFound().json(response)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
+
+pub async fn list_import_jobs(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    match ImportService::list_jobs(&db, path.into_inner()).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
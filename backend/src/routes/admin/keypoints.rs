@@ -0,0 +1,54 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use crate::schemas::admin::{ApiResponse, KeypointAggregationRequest};
+use crate::service::keypoint::KeypointService;
+
+pub async fn get_image_keypoints(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    respond(KeypointService::image_keypoints(&db, path.into_inner()).await)
+}
+
+pub async fn aggregate_keypoints(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    body: web::Bytes,
+) -> Result<HttpResponse> {
+    // The body is optional, so it is parsed here rather than by web::Json
+    let request: KeypointAggregationRequest = if body.iter().all(u8::is_ascii_whitespace) {
+        KeypointAggregationRequest::default()
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(request) => request,
+            Err(e) => {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::<()> {
+                    success: false,
+                    message: format!("Invalid request body: {}", e),
+                    data: None,
+                }));
+            }
+        }
+    };
+    respond(KeypointService::aggregate_keypoints(&db, path.into_inner(), request).await)
+}
+
+fn respond<T: Serialize>(result: Result<ApiResponse<T>, String>) -> Result<HttpResponse> {
+    match result {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
@@ -3,6 +3,7 @@ use sea_orm::DatabaseConnection;
 use crate::schemas::admin::{
     CreateLabelerRequest, UpdateLabelerRequest, ApiResponse
 };
+use crate::schemas::pagination::ListQuery;
 use crate::service::admin::AdminService;
 
 pub async fn create_labeler(
@@ -49,9 +50,16 @@ pub async fn get_labeler(
 
 pub async fn list_labelers(
     db: web::Data<DatabaseConnection>,
+    query: web::Query<ListQuery>,
 ) -> Result<HttpResponse> {
-    match AdminService::list_labelers(&db).await {
-        Ok(response) => Ok(HttpResponse::Ok().json(response)),
+    match AdminService::list_labelers(&db, query.into_inner()).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
         Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
             success: false,
             message: e,
@@ -1,6 +1,16 @@
+pub mod aggregation;
+pub mod analytics;
+pub mod captions;
+pub mod duplicates;
 pub mod export;
 pub mod final_tags;
+pub mod flags;
+pub mod gold;
 pub mod labeler;
+pub mod regions;
+pub mod segmentations;
 pub mod groups;
 pub mod image;
+pub mod keypoints;
+pub mod import;
 pub mod tag;
\ No newline at end of file
@@ -0,0 +1,39 @@
+use actix_web::{web, HttpResponse, Result};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use crate::schemas::admin::{ApiResponse, MergeSegmentationsRequest};
+use crate::service::segmentation::SegmentationService;
+
+pub async fn get_image_segmentations(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse> {
+    respond(SegmentationService::image_segmentations(&db, path.into_inner()).await)
+}
+
+pub async fn merge_segmentations(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+    request: web::Json<MergeSegmentationsRequest>,
+) -> Result<HttpResponse> {
+    respond(SegmentationService::merge_segmentations(&db, path.into_inner(), request.into_inner()).await)
+}
+
+fn respond<T: Serialize>(result: Result<ApiResponse<T>, String>) -> Result<HttpResponse> {
+    match result {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => Ok(HttpResponse::InternalServerError().json(ApiResponse::<()> {
+            success: false,
+            message: e,
+            data: None,
+        })),
+    }
+}
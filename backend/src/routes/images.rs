@@ -0,0 +1,294 @@
+use actix_files::NamedFile;
+use actix_web::{mime, web, HttpResponse, HttpRequest, HttpMessage};
+use actix_web::http::header::{self, HeaderValue};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use crate::entity::image::Model as ImageModel;
+use crate::middleware::auth::SignedUrl;
+use crate::schemas::auth::Claims;
+use crate::service::image::ImageService;
+use crate::imaging::ImageFormat;
+use crate::service::thumbnail::{ThumbnailService, ThumbnailSize, THUMBNAIL_FORMATS};
+use crate::storage::ImageStorage;
+
+// Content for an image id never changes, so clients may cache it for good.
+// It is still private because access depends on the caller's groups.
+const CACHE_CONTROL: &str = "private, max-age=31536000, immutable";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ByteRange {
+    /// No usable range was requested; serve the whole body.
+    Full,
+    /// Inclusive byte offsets to serve with 206 Partial Content.
+    Partial(u64, u64),
+    /// The range lies outside the body; answer 416.
+    Unsatisfiable,
+}
+
+/// Parses a `Range` header for a body of `len` bytes. Only single
+/// `bytes=` ranges are honored; anything else is served in full, as
+/// RFC 9110 allows.
+pub fn parse_range(value: &str, len: u64) -> ByteRange {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // Suffix range: the last `n` bytes
+        return match end.parse::<u64>() {
+            Ok(0) => ByteRange::Unsatisfiable,
+            Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+            Ok(n) => ByteRange::Partial(len.saturating_sub(n), len - 1),
+            Err(_) => ByteRange::Full,
+        };
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    if start >= len {
+        return ByteRange::Unsatisfiable;
+    }
+
+    let end = if end.is_empty() {
+        len - 1
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end.min(len - 1),
+            _ => return ByteRange::Full,
+        }
+    };
+
+    ByteRange::Partial(start, end)
+}
+
+fn etag_matches(header_value: &str, etag: &str) -> bool {
+    header_value
+        .split(',')
+        .map(|candidate| candidate.trim().trim_start_matches("W/"))
+        .any(|candidate| candidate == "*" || candidate == etag)
+}
+
+fn access_error(e: String) -> HttpResponse {
+    let mut response = if e.contains("not authorized") {
+        HttpResponse::Forbidden()
+    } else if e.contains("not found") {
+        HttpResponse::NotFound()
+    } else {
+        eprintln!("Error fetching image content: {}", e);
+        HttpResponse::InternalServerError()
+    };
+    response.json(serde_json::json!({
+        "success": false,
+        "error": e,
+        "data": null
+    }))
+}
+
+fn if_none_match(req: &HttpRequest, etag: &str) -> bool {
+    req.headers().get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|value| etag_matches(value, etag))
+}
+
+fn not_modified(etag: String) -> HttpResponse {
+    HttpResponse::NotModified()
+        .insert_header((header::ETAG, etag))
+        .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+        .finish()
+}
+
+// Loads the image if the caller's token, or the signed URL the request came
+// through, grants access to it
+async fn authorized_image(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    image_id: i32,
+) -> Result<ImageModel, HttpResponse> {
+    let claims = req.extensions().get::<Claims>().cloned();
+    let signed = req.extensions().contains::<SignedUrl>();
+    let image = match claims {
+        Some(claims) => ImageService::get_authorized_image(db, &claims, image_id).await,
+        None if signed => ImageService::get_image(db, image_id).await,
+        None => Err("You are not authorized to access this image".to_string()),
+    };
+    image.map_err(access_error)
+}
+
+fn read_error(image_id: i32, e: String) -> HttpResponse {
+    eprintln!("Error reading image {} from storage: {}", image_id, e);
+    HttpResponse::InternalServerError().json(serde_json::json!({
+        "success": false,
+        "error": format!("Failed to read image: {}", e),
+        "data": null
+    }))
+}
+
+pub async fn get_image_content(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let image = match authorized_image(&req, &db, path.into_inner()).await {
+        Ok(image) => image,
+        Err(response) => return Ok(response),
+    };
+    let Some(key) = image.storage_key.clone() else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "error": "Image has no stored content",
+            "data": null
+        })));
+    };
+
+    let etag = format!("\"{}\"", key);
+    let content_type = ImageService::content_type(&image.filetype);
+
+    // Conditional request: the client already has this version
+    if if_none_match(&req, &etag) {
+        return Ok(not_modified(etag));
+    }
+
+    // A Range only applies if If-Range (when present) still matches
+    let if_range_ok = req.headers().get(header::IF_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|value| value.trim() == etag);
+
+    // Files on local disk are served as they are, ranges included
+    if if_range_ok && let Some(file_path) = storage.local_path(&key) {
+        let file = match NamedFile::open_async(&file_path).await {
+            Ok(file) => file,
+            Err(e) => return Ok(read_error(image.id, e.to_string())),
+        };
+        let mut response = file
+            .use_etag(false)
+            .use_last_modified(false)
+            .disable_content_disposition()
+            .set_content_type(content_type.parse().unwrap_or(mime::APPLICATION_OCTET_STREAM))
+            .into_response(&req);
+        if response.status().is_success() {
+            response.headers_mut().insert(header::ETAG, HeaderValue::from_str(&etag)?);
+            response.headers_mut().insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+        }
+        return Ok(response);
+    }
+
+    let len = match storage.size(&key).await {
+        Ok(len) => len,
+        Err(e) => return Ok(read_error(image.id, e)),
+    };
+    let range = match req.headers().get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(range) if if_range_ok => parse_range(range, len),
+        _ => ByteRange::Full,
+    };
+
+    match range {
+        ByteRange::Full => {
+            let body = match storage.stream(&key, None).await {
+                Ok(body) => body,
+                Err(e) => return Ok(read_error(image.id, e)),
+            };
+            Ok(HttpResponse::Ok()
+                .content_type(content_type)
+                .insert_header((header::ETAG, etag))
+                .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+                .insert_header((header::ACCEPT_RANGES, "bytes"))
+                .no_chunking(len)
+                .streaming(body))
+        }
+        ByteRange::Partial(start, end) => {
+            let body = match storage.stream(&key, Some((start, end))).await {
+                Ok(body) => body,
+                Err(e) => return Ok(read_error(image.id, e)),
+            };
+            Ok(HttpResponse::PartialContent()
+                .content_type(content_type)
+                .insert_header((header::ETAG, etag))
+                .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+                .insert_header((header::ACCEPT_RANGES, "bytes"))
+                .insert_header((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len)))
+                .no_chunking(end - start + 1)
+                .streaming(body))
+        }
+        ByteRange::Unsatisfiable => Ok(HttpResponse::RangeNotSatisfiable()
+            .insert_header((header::CONTENT_RANGE, format!("bytes */{}", len)))
+            .finish()),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ThumbnailQuery {
+    pub size: Option<String>,
+    pub format: Option<String>,
+}
+
+pub async fn get_image_thumbnail(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    query: web::Query<ThumbnailQuery>,
+    db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let size = match query.size.as_deref() {
+        None => ThumbnailSize::Medium,
+        Some(value) => match ThumbnailSize::parse(value) {
+            Some(size) => size,
+            None => return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "error": "Invalid size, expected small, medium or large",
+                "data": null
+            }))),
+        },
+    };
+
+    // JPEG unless WebP is asked for: the WebP encoder is lossless only,
+    // which makes photos larger than JPEG at the configured quality
+    let format = match query.format.as_deref() {
+        None => ImageFormat::Jpeg,
+        Some(value) => match ImageFormat::from_filetype(value).filter(|f| THUMBNAIL_FORMATS.contains(f)) {
+            Some(format) => format,
+            None => return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "success": false,
+                "error": "Invalid format, expected webp or jpeg",
+                "data": null
+            }))),
+        },
+    };
+
+    let image = match authorized_image(&req, &db, path.into_inner()).await {
+        Ok(image) => image,
+        Err(response) => return Ok(response),
+    };
+
+    // Answer revalidations from the index alone, without touching storage
+    if let Ok(Some(key)) = ThumbnailService::cached_key(&db, image.id, size, format).await {
+        let etag = format!("\"{}\"", key);
+        if if_none_match(&req, &etag) {
+            return Ok(not_modified(etag));
+        }
+    }
+
+    match ThumbnailService::get_or_create(&db, storage.get_ref(), &image, size, format).await {
+        Ok(thumbnail) => Ok(HttpResponse::Ok()
+            .content_type(format.mime())
+            .insert_header((header::ETAG, format!("\"{}\"", thumbnail.storage_key)))
+            .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+            .body(thumbnail.bytes)),
+        Err(e) => {
+            eprintln!("Error creating thumbnail for image {}: {}", image.id, e);
+            Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to create thumbnail: {}", e),
+                "data": null
+            })))
+        }
+    }
+}
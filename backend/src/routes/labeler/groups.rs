@@ -1,6 +1,8 @@
 use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
 use sea_orm::DatabaseConnection;
 use crate::schemas::auth::Claims;
+use crate::schemas::pagination::ListQuery;
+use crate::service::assignment::AssignmentService;
 use crate::service::labeler::LabelerService;
 
 pub async fn get_groups(
@@ -27,9 +29,12 @@ pub async fn get_groups(
     }
 }
 
+/// Responds with the page of images as a plain array; the number of
+/// matching images is sent in the `X-Total-Count` header.
 pub async fn get_group_images(
     req: HttpRequest,
     path: web::Path<i32>,
+    query: web::Query<ListQuery>,
     db: web::Data<DatabaseConnection>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // Extract user info from request extensions (set by middleware)
@@ -40,10 +45,15 @@ pub async fn get_group_images(
     let labeler_id = claims.user_id;
     let group_id = path.into_inner();
     
-    match LabelerService::get_group_images(&db, labeler_id, group_id).await {
-        Ok(response) => {
-            Ok(HttpResponse::Ok().json(response.data.unwrap().images))
-        }
+    match LabelerService::get_group_images(&db, labeler_id, group_id, query.into_inner()).await {
+        Ok(response) => match response.data {
+            Some(data) => Ok(HttpResponse::Ok()
+                .insert_header(("X-Total-Count", data.total.to_string()))
+                .json(data.images)),
+            None => Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": response.message
+            }))),
+        },
         Err(e) => {
             if e.contains("not authorized") {
                 Ok(HttpResponse::Forbidden().json(serde_json::json!({
@@ -58,3 +68,38 @@ pub async fn get_group_images(
         }
     }
 }
+
+/// Leases the labeler the next image of the group that needs labels, or
+/// answers 204 when there is none.
+pub async fn get_next_image(
+    req: HttpRequest,
+    path: web::Path<i32>,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Extract user info from request extensions (set by middleware)
+    let claims = req.extensions().get::<Claims>()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("User not authenticated"))?
+        .clone();
+
+    let labeler_id = claims.user_id;
+    let group_id = path.into_inner();
+
+    match AssignmentService::next_image(&db, labeler_id, group_id).await {
+        Ok(response) => match response.data {
+            Some(next) => Ok(HttpResponse::Ok().json(next)),
+            None => Ok(HttpResponse::NoContent().finish()),
+        },
+        Err(e) => {
+            if e.contains("not authorized") {
+                Ok(HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": e
+                })))
+            } else {
+                eprintln!("Error leasing next image in group {}: {}", group_id, e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": e
+                })))
+            }
+        }
+    }
+}
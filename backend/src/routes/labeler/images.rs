@@ -1,7 +1,11 @@
 use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
 use sea_orm::DatabaseConnection;
 use crate::schemas::auth::Claims;
+use crate::repository::{FLAG_KIND_FLAG, FLAG_KIND_SKIP};
+use crate::schemas::labeler::ImageFlagRequest;
+use crate::service::flag::FlagService;
 use crate::service::labeler::LabelerService;
+use crate::storage::ImageStorage;
 
 pub async fn get_labeler_image_details(
     req: HttpRequest,
@@ -80,6 +84,7 @@ pub async fn suggest_tags(
     req: HttpRequest,
     path: web::Path<i32>,
     db: web::Data<DatabaseConnection>,
+    storage: web::Data<dyn ImageStorage>,
     suggest_request: web::Json<crate::schemas::labeler::SuggestTagsRequest>,
 ) -> Result<HttpResponse, actix_web::Error> {
     // Extract user info from request extensions (set by middleware)
@@ -90,7 +95,7 @@ pub async fn suggest_tags(
     let labeler_id = claims.user_id;
     let image_id = path.into_inner();
     
-    match LabelerService::suggest_tags(&db, labeler_id, image_id, suggest_request.into_inner()).await {
+    match LabelerService::suggest_tags(&db, storage.get_ref(), labeler_id, image_id, suggest_request.into_inner()).await {
         Ok(response) => {
             Ok(HttpResponse::Ok().json(response))
         }
@@ -104,3 +109,63 @@ pub async fn suggest_tags(
         }
     }
 }
+
+pub async fn skip_image(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    flag_request: web::Json<ImageFlagRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    mark_image(req, path, db, FLAG_KIND_SKIP, flag_request.into_inner()).await
+}
+
+pub async fn flag_image(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    flag_request: web::Json<ImageFlagRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    mark_image(req, path, db, FLAG_KIND_FLAG, flag_request.into_inner()).await
+}
+
+async fn mark_image(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    kind: &str,
+    flag_request: ImageFlagRequest,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Extract user info from request extensions (set by middleware)
+    let claims = req.extensions().get::<Claims>()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("User not authenticated"))?
+        .clone();
+
+    let labeler_id = claims.user_id;
+    let (group_id, image_id) = path.into_inner();
+
+    match FlagService::mark_image(&db, labeler_id, group_id, image_id, kind, flag_request).await {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => {
+            if e.contains("not authorized") {
+                Ok(HttpResponse::Forbidden().json(serde_json::json!({
+                    "success": false,
+                    "error": e,
+                    "data": null
+                })))
+            } else {
+                eprintln!("Error saving {} on image {}: {}", kind, image_id, e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "success": false,
+                    "error": e,
+                    "data": null
+                })))
+            }
+        }
+    }
+}
@@ -1,2 +1,6 @@
+pub mod captions;
 pub mod groups;
 pub mod images;
+pub mod keypoints;
+pub mod regions;
+pub mod segmentations;
@@ -0,0 +1,86 @@
+use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use crate::schemas::auth::Claims;
+use crate::schemas::labeler::{ApiResponse, RegionRequest};
+use crate::service::region::RegionService;
+
+pub async fn list_regions(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id) = path.into_inner();
+    respond(RegionService::list_regions(&db, labeler_id, group_id, image_id).await)
+}
+
+pub async fn create_region(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    region_request: web::Json<RegionRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id) = path.into_inner();
+    respond(RegionService::create_region(&db, labeler_id, group_id, image_id, region_request.into_inner()).await)
+}
+
+pub async fn update_region(
+    req: HttpRequest,
+    path: web::Path<(i32, i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    region_request: web::Json<RegionRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id, region_id) = path.into_inner();
+    respond(RegionService::update_region(&db, labeler_id, group_id, image_id, region_id, region_request.into_inner()).await)
+}
+
+pub async fn delete_region(
+    req: HttpRequest,
+    path: web::Path<(i32, i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id, region_id) = path.into_inner();
+    respond(RegionService::delete_region(&db, labeler_id, group_id, image_id, region_id).await)
+}
+
+// Extract user info from request extensions (set by middleware)
+fn labeler_id(req: &HttpRequest) -> Result<i32, actix_web::Error> {
+    req.extensions()
+        .get::<Claims>()
+        .map(|claims| claims.user_id)
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("User not authenticated"))
+}
+
+fn respond<T: Serialize>(result: Result<ApiResponse<T>, String>) -> Result<HttpResponse, actix_web::Error> {
+    match result {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => {
+            if e.contains("not authorized") {
+                Ok(HttpResponse::Forbidden().json(serde_json::json!({
+                    "success": false,
+                    "error": e,
+                    "data": null
+                })))
+            } else {
+                eprintln!("Error handling region: {}", e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "success": false,
+                    "error": e,
+                    "data": null
+                })))
+            }
+        }
+    }
+}
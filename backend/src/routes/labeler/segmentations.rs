@@ -0,0 +1,86 @@
+use actix_web::{web, HttpResponse, HttpRequest, HttpMessage};
+use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use crate::schemas::auth::Claims;
+use crate::schemas::labeler::{ApiResponse, SegmentationRequest};
+use crate::service::segmentation::SegmentationService;
+
+pub async fn list_segmentations(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id) = path.into_inner();
+    respond(SegmentationService::list_segmentations(&db, labeler_id, group_id, image_id).await)
+}
+
+pub async fn create_segmentation(
+    req: HttpRequest,
+    path: web::Path<(i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    segmentation_request: web::Json<SegmentationRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id) = path.into_inner();
+    respond(SegmentationService::create_segmentation(&db, labeler_id, group_id, image_id, segmentation_request.into_inner()).await)
+}
+
+pub async fn update_segmentation(
+    req: HttpRequest,
+    path: web::Path<(i32, i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+    segmentation_request: web::Json<SegmentationRequest>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id, segmentation_id) = path.into_inner();
+    respond(SegmentationService::update_segmentation(&db, labeler_id, group_id, image_id, segmentation_id, segmentation_request.into_inner()).await)
+}
+
+pub async fn delete_segmentation(
+    req: HttpRequest,
+    path: web::Path<(i32, i32, i32)>,
+    db: web::Data<DatabaseConnection>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let labeler_id = labeler_id(&req)?;
+    let (group_id, image_id, segmentation_id) = path.into_inner();
+    respond(SegmentationService::delete_segmentation(&db, labeler_id, group_id, image_id, segmentation_id).await)
+}
+
+// Extract user info from request extensions (set by middleware)
+fn labeler_id(req: &HttpRequest) -> Result<i32, actix_web::Error> {
+    req.extensions()
+        .get::<Claims>()
+        .map(|claims| claims.user_id)
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("User not authenticated"))
+}
+
+fn respond<T: Serialize>(result: Result<ApiResponse<T>, String>) -> Result<HttpResponse, actix_web::Error> {
+    match result {
+        Ok(response) => {
+            if response.success {
+                Ok(HttpResponse::Ok().json(response))
+            } else if response.message.contains("not found") {
+                Ok(HttpResponse::NotFound().json(response))
+            } else {
+                Ok(HttpResponse::BadRequest().json(response))
+            }
+        }
+        Err(e) => {
+            if e.contains("not authorized") {
+                Ok(HttpResponse::Forbidden().json(serde_json::json!({
+                    "success": false,
+                    "error": e,
+                    "data": null
+                })))
+            } else {
+                eprintln!("Error handling segmentation: {}", e);
+                Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "success": false,
+                    "error": e,
+                    "data": null
+                })))
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod auth;
 pub mod admin;
 pub mod labeler;
+pub mod images;
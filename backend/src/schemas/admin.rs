@@ -1,4 +1,11 @@
 use serde::{Deserialize, Serialize};
+use crate::keypoint::{Keypoint, KeypointSchema};
+use crate::consensus::ConsensusStrategy;
+use crate::schemas::analytics::GroupProgressResponse;
+use crate::region::BoundingBox;
+use crate::segmentation::Geometry;
+use crate::task::TaskType;
+use crate::schemas::labeler::{CaptionResponse, FlagReason, KeypointSetResponse, RegionResponse, SegmentationResponse};
 
 #[derive(Debug, Deserialize)]
 pub struct CreateLabelerRequest {
@@ -12,12 +19,51 @@ pub struct UpdateLabelerRequest {
     pub username: Option<String>,
     pub password: Option<String>,
     pub group_ids: Option<Vec<i32>>,
+    /// Suspends the labeler, or reinstates one, e.g. after an automatic
+    /// suspension for low gold accuracy.
+    pub suspended: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateGroupRequest {
     pub name: String,
     pub description: Option<String>,
+    /// Image formats accepted for upload, as MIME types or extensions.
+    /// Every supported format when omitted.
+    pub allowed_formats: Option<Vec<String>>,
+    /// What to do with an upload whose content already exists in the group:
+    /// `reject` (the default) or `flag`.
+    pub duplicate_policy: Option<String>,
+    /// How final tags are auto-generated; a 50% majority when omitted.
+    pub consensus_strategy: Option<ConsensusStrategy>,
+    /// Number of latest gold answers the rolling gold accuracy covers; 10
+    /// when omitted.
+    pub gold_window: Option<i32>,
+    /// Rolling gold accuracy below which labelers are suspended once the
+    /// window is full; 0 or omitted turns suspension off.
+    pub gold_min_accuracy: Option<f64>,
+    /// Labelers the queue hands each image to; 3 when omitted.
+    pub labels_per_image: Option<i32>,
+    /// How long a queued image stays reserved for a labeler; 900 when
+    /// omitted.
+    pub lease_seconds: Option<i32>,
+    /// What labelers answer per image; any number of tags when omitted.
+    pub task_type: Option<TaskType>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateGroupRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub allowed_formats: Option<Vec<String>>,
+    pub duplicate_policy: Option<String>,
+    pub consensus_strategy: Option<ConsensusStrategy>,
+    pub gold_window: Option<i32>,
+    /// 0 turns suspension off.
+    pub gold_min_accuracy: Option<f64>,
+    pub labels_per_image: Option<i32>,
+    pub lease_seconds: Option<i32>,
+    pub task_type: Option<TaskType>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,6 +71,8 @@ pub struct LabelerResponse {
     pub id: i32,
     pub username: String,
     pub group_ids: Vec<i32>,
+    pub suspended: bool,
+    pub suspended_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -32,18 +80,32 @@ pub struct GroupResponse {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
+    pub allowed_formats: Vec<String>,
+    pub duplicate_policy: String,
+    pub consensus_strategy: ConsensusStrategy,
+    pub gold_window: i32,
+    pub gold_min_accuracy: Option<f64>,
+    pub labels_per_image: i32,
+    pub lease_seconds: i32,
+    pub task_type: TaskType,
 }
 
 #[derive(Debug, Serialize)]
 pub struct GroupListResponse {
     pub groups: Vec<GroupResponse>,
-    pub total: usize,
+    /// Number of groups overall, not just on this page.
+    pub total: u64,
+    pub limit: Option<u64>,
+    pub offset: u64,
 }
 
 #[derive(Debug, Serialize)]
 pub struct LabelerListResponse {
     pub labelers: Vec<LabelerResponse>,
-    pub total: usize,
+    /// Number of labelers overall, not just on this page.
+    pub total: u64,
+    pub limit: Option<u64>,
+    pub offset: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -57,6 +119,8 @@ pub struct TagResponse {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keypoint_schema: Option<KeypointSchema>,
 }
 
 #[derive(Debug, Serialize)]
@@ -65,6 +129,13 @@ pub struct ImageResponse {
     pub filename: String,
     pub filetype: String,
     pub uploaded_at: String,
+    pub content_url: String,
+    pub thumbnail_url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub color_mode: Option<String>,
+    pub orientation: Option<i32>,
+    pub duplicate_of: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -73,6 +144,11 @@ pub struct GroupDetailResponse {
     pub labelers: Vec<SimpleLabelerResponse>,
     pub tags: Vec<TagResponse>,
     pub images: Vec<ImageResponse>,
+    /// Sizes of the full lists the pages above were taken from.
+    pub labeler_total: u64,
+    pub tag_total: u64,
+    pub image_total: u64,
+    pub progress: GroupProgressResponse,
 }
 
 #[derive(Debug, Deserialize)]
@@ -88,12 +164,16 @@ pub struct CreateTagRequest {
     pub name: String,
     pub description: Option<String>,
     pub group_id: i32,
+    /// Makes the tag a keypoint tag.
+    pub keypoint_schema: Option<KeypointSchema>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateTagRequest {
     pub name: Option<String>,
     pub description: Option<String>,
+    /// Replaces the keypoint schema; one without keypoints removes it.
+    pub keypoint_schema: Option<KeypointSchema>,
 }
 
 #[derive(Debug, Serialize)]
@@ -102,6 +182,83 @@ pub struct ImageUploadResponse {
     pub filename: String,
     pub filetype: String,
     pub uploaded_at: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub color_mode: Option<String>,
+    pub orientation: Option<i32>,
+    pub duplicate_of: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchUploadResult {
+    pub filename: String,
+    pub success: bool,
+    pub message: String,
+    pub image: Option<ImageUploadResponse>,
+}
+
+impl BatchUploadResult {
+    pub fn failed(filename: String, message: String) -> Self {
+        Self {
+            filename,
+            success: false,
+            message,
+            image: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchUploadResponse {
+    pub total: usize,
+    pub uploaded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchUploadResult>,
+}
+
+impl BatchUploadResponse {
+    pub fn from_results(results: Vec<BatchUploadResult>) -> Self {
+        let uploaded = results.iter().filter(|r| r.success).count();
+        Self {
+            total: results.len(),
+            uploaded,
+            failed: results.len() - uploaded,
+            results,
+        }
+    }
+}
+
+/// A ZIP import read from a multipart request.
+#[derive(Debug)]
+pub struct ZipImportRequest {
+    pub group_id: i32,
+    /// Temporary file the uploaded archive was written to.
+    pub archive: std::fs::File,
+    /// Sidecar mapping file given alongside the archive, as (filename, bytes).
+    pub mapping: Option<(String, Vec<u8>)>,
+    pub create_missing_tags: bool,
+    pub seed_final_tags: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportEntryError {
+    pub entry: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportJobResponse {
+    pub id: i32,
+    pub group_id: i32,
+    pub status: String,
+    pub total_entries: i32,
+    pub processed_entries: i32,
+    pub created_images: i32,
+    pub created_tags: i32,
+    pub errors: Vec<ImportEntryError>,
+    pub message: Option<String>,
+    pub created_at: String,
+    pub finished_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -120,3 +277,278 @@ pub struct ApiResponse<T> {
     pub message: String,
     pub data: Option<T>,
 }
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateReportResponse {
+    pub group_id: i32,
+    /// Which perceptual hash was compared: `dhash` or `ahash`.
+    pub hash: String,
+    pub threshold: u32,
+    pub clusters: Vec<DuplicateClusterResponse>,
+    /// Images without a perceptual hash, e.g. formats that cannot be decoded.
+    pub unhashed_images: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateClusterResponse {
+    /// Largest Hamming distance between two images of the cluster.
+    pub max_distance: u32,
+    /// Whether every image of the cluster has the same content.
+    pub exact: bool,
+    pub images: Vec<DuplicateImageResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateImageResponse {
+    pub id: i32,
+    pub filename: String,
+    pub thumbnail_url: String,
+    pub content_hash: String,
+    pub perceptual_hash: Option<String>,
+    /// Hamming distance to the first image of the cluster.
+    pub distance: u32,
+    pub duplicate_of: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ApplyAggregationRequest {
+    /// Smallest posterior probability for a tag to become a final tag;
+    /// 0.5 when omitted.
+    pub threshold: Option<f64>,
+    pub max_iterations: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AggregationResponse {
+    pub group_id: i32,
+    pub tags: Vec<TagAggregationResponse>,
+    /// Images whose final tags were replaced, when the estimates were applied.
+    pub images_updated: Option<usize>,
+}
+
+/// Dawid–Skene estimates for one tag of a group, treated as a yes/no
+/// question on every image someone labeled.
+#[derive(Debug, Serialize)]
+pub struct TagAggregationResponse {
+    pub tag_id: i32,
+    pub tag_name: String,
+    /// Estimated share of images that have the tag.
+    pub prior: f64,
+    pub iterations: u32,
+    pub converged: bool,
+    pub labelers: Vec<LabelerConfusionResponse>,
+    pub images: Vec<ImagePosteriorResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelerConfusionResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    /// Images of the group the labeler labeled.
+    pub images: usize,
+    pub sensitivity: f64,
+    pub specificity: f64,
+    /// Rows are the true answer (no, yes), columns the labeler's answer.
+    pub confusion_matrix: [[f64; 2]; 2],
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImagePosteriorResponse {
+    pub image_id: i32,
+    /// Probability that the image has the tag.
+    pub posterior: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetGoldRequest {
+    pub is_gold: bool,
+    /// The known tags of the image; required when marking it as gold.
+    pub tag_ids: Option<Vec<i32>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoldImageResponse {
+    pub image_id: i32,
+    pub is_gold: bool,
+    pub tag_ids: Vec<i32>,
+}
+
+/// An image labelers flagged as problematic, with every flag on it.
+#[derive(Debug, Serialize)]
+pub struct FlaggedImageResponse {
+    pub group_id: i32,
+    pub image: ImageResponse,
+    pub flags: Vec<ImageFlagEntryResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageFlagEntryResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub reason: FlagReason,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlaggedImageListResponse {
+    pub images: Vec<FlaggedImageResponse>,
+}
+
+/// Settings of box consensus. Boxes of different labelers overlapping by
+/// at least `iou_threshold` (0.5 by default) are the same object, kept
+/// when at least `min_votes` labelers drew it (by default half of those
+/// who drew boxes on or submitted the image, rounded up).
+#[derive(Debug, Default, Deserialize)]
+pub struct RegionConsensusRequest {
+    pub iou_threshold: Option<f64>,
+    pub min_votes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalRegionResponse {
+    pub id: i32,
+    pub tag_id: i32,
+    pub tag_name: String,
+    #[serde(flatten)]
+    pub bbox: BoundingBox,
+    /// Labelers whose boxes were merged into this one.
+    pub votes: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelerRegionsResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub regions: Vec<RegionResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageRegionsResponse {
+    pub image_id: i32,
+    pub labelers: Vec<LabelerRegionsResponse>,
+    pub final_regions: Vec<FinalRegionResponse>,
+}
+
+/// Merges labelers' masks of one tag into the image's final mask: pixels
+/// covered by at least `min_votes` labelers (by default half of them,
+/// rounded up). Without `segmentation_ids` every mask of the tag is used.
+#[derive(Debug, Deserialize)]
+pub struct MergeSegmentationsRequest {
+    pub tag_id: i32,
+    pub segmentation_ids: Option<Vec<i32>>,
+    pub min_votes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalSegmentationResponse {
+    pub id: i32,
+    pub tag_id: i32,
+    pub tag_name: String,
+    pub geometry: Geometry,
+    pub area: i64,
+    /// Labelers whose masks were merged into this one.
+    pub votes: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelerSegmentationsResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub segmentations: Vec<SegmentationResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageSegmentationsResponse {
+    pub image_id: i32,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub labelers: Vec<LabelerSegmentationsResponse>,
+    pub final_segmentations: Vec<FinalSegmentationResponse>,
+}
+
+/// Aggregates the labelers' keypoint sets of an image, for `tag_id` or
+/// every keypoint tag. Points farther than `outlier_distance` pixels from
+/// the median (by default 5% of the image's diagonal) are outliers; points
+/// placed by fewer than `min_votes` labelers (by default half of them,
+/// rounded up) are left unlabeled.
+#[derive(Debug, Default, Deserialize)]
+pub struct KeypointAggregationRequest {
+    pub tag_id: Option<i32>,
+    pub outlier_distance: Option<f64>,
+    pub min_votes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalKeypointSetResponse {
+    pub id: i32,
+    pub tag_id: i32,
+    pub tag_name: String,
+    pub keypoints: Vec<Keypoint>,
+    /// Points that are labeled.
+    pub num_keypoints: usize,
+    /// Labelers whose keypoint sets were aggregated into this one.
+    pub votes: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeypointOutlierResponse {
+    pub tag_id: i32,
+    pub labeler_id: i32,
+    pub keypoint: String,
+    pub distance: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeypointAggregationResponse {
+    pub final_keypoint_sets: Vec<FinalKeypointSetResponse>,
+    pub outliers: Vec<KeypointOutlierResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelerKeypointsResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub keypoint_sets: Vec<KeypointSetResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageKeypointsResponse {
+    pub image_id: i32,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub labelers: Vec<LabelerKeypointsResponse>,
+    pub final_keypoint_sets: Vec<FinalKeypointSetResponse>,
+}
+
+/// Picks an image's final caption: one of its labelers' captions by
+/// `caption_id`, or the admin's own `text`.
+#[derive(Debug, Deserialize)]
+pub struct SelectFinalCaptionRequest {
+    pub caption_id: Option<i32>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FinalCaptionResponse {
+    pub id: i32,
+    pub text: String,
+    /// Labelers who wrote this caption, ignoring case and whitespace.
+    pub votes: i32,
+    pub is_admin_override: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelerCaptionResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub caption: CaptionResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageCaptionsResponse {
+    pub image_id: i32,
+    pub captions: Vec<LabelerCaptionResponse>,
+    pub final_caption: Option<FinalCaptionResponse>,
+}
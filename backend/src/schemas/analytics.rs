@@ -0,0 +1,176 @@
+use serde::Serialize;
+
+/// Inter-annotator agreement in a group. Every tag is a yes/no question
+/// answered by each labeler who tagged an image; group figures pool the
+/// answers to all tags. Statistics are `None` where they are undefined,
+/// e.g. without images labeled twice or when everyone always gave the same
+/// answer.
+#[derive(Clone, Debug, Serialize)]
+pub struct AgreementResponse {
+    pub group_id: i32,
+    /// Images tagged by at least two labelers, the only ones that count.
+    pub images: usize,
+    pub fleiss_kappa: Option<f64>,
+    pub krippendorff_alpha: Option<f64>,
+    pub pairs: Vec<PairAgreementResponse>,
+    pub tags: Vec<TagAgreementResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TagAgreementResponse {
+    pub tag_id: i32,
+    pub tag_name: String,
+    pub fleiss_kappa: Option<f64>,
+    pub krippendorff_alpha: Option<f64>,
+    pub pairs: Vec<PairAgreementResponse>,
+}
+
+/// Cohen's kappa of two labelers over the images both of them tagged.
+#[derive(Clone, Debug, Serialize)]
+pub struct PairAgreementResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub other_labeler_id: i32,
+    pub other_username: String,
+    pub shared_images: usize,
+    pub cohen_kappa: Option<f64>,
+}
+
+/// Labelers ranked by F1 against admin-approved final tags.
+#[derive(Clone, Debug, Serialize)]
+pub struct LeaderboardResponse {
+    /// The group scored, or `None` for all groups.
+    pub group_id: Option<i32>,
+    pub labelers: Vec<LabelerScoreResponse>,
+}
+
+/// How a labeler's tags compare with the final tags of images an admin
+/// reviewed (`is_admin_override`), counted per (image, tag).
+#[derive(Clone, Debug, Serialize)]
+pub struct LabelerScoreResponse {
+    /// Position on the leaderboard, starting at 1.
+    pub rank: usize,
+    pub labeler_id: i32,
+    pub username: String,
+    /// Reviewed images the labeler tagged.
+    pub reviewed_images: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub precision: Option<f64>,
+    pub recall: Option<f64>,
+    pub f1: Option<f64>,
+    pub tags: Vec<TagErrorResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TagErrorResponse {
+    pub tag_id: i32,
+    pub tag_name: String,
+    pub images: usize,
+    /// Images where the labeler chose the tag and the admin did not.
+    pub false_positives: usize,
+    /// Images where the admin chose the tag and the labeler did not.
+    pub false_negatives: usize,
+    pub error_rate: f64,
+}
+
+/// How labelers did on the gold images of a group.
+#[derive(Clone, Debug, Serialize)]
+pub struct GoldReportResponse {
+    pub group_id: i32,
+    pub gold_images: usize,
+    pub gold_window: i32,
+    pub gold_min_accuracy: Option<f64>,
+    pub labelers: Vec<LabelerGoldResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LabelerGoldResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub answered: usize,
+    pub correct: usize,
+    pub accuracy: f64,
+    /// Accuracy over the latest `gold_window` answers.
+    pub rolling_accuracy: f64,
+    pub suspended: bool,
+}
+
+/// How far a group is from `labels_per_image` labels on every image. Gold
+/// images are not counted.
+#[derive(Clone, Debug, Serialize)]
+pub struct GroupProgressResponse {
+    pub labels_per_image: i32,
+    pub total_images: usize,
+    /// Images with at least `labels_per_image` labelers.
+    pub fully_labeled: usize,
+    pub partially_labeled: usize,
+    pub untouched: usize,
+    /// Labels counted towards the target, at most `labels_per_image` per
+    /// image.
+    pub labels_done: usize,
+    pub labels_needed: usize,
+    pub percent_complete: f64,
+    /// Labels saved per hour over the last week; `None` without activity.
+    pub labels_per_hour: Option<f64>,
+    pub eta_seconds: Option<i64>,
+    pub estimated_completion_at: Option<String>,
+    pub labelers: Vec<LabelerProgressResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LabelerProgressResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub images_labeled: usize,
+    /// When the labeler last labeled an image they had not submitted before.
+    pub last_labeled_at: Option<String>,
+}
+
+/// Time spent per image and labeling speed, per group and per labeler.
+/// Timing runs from opening an image to first submitting it; later edits
+/// and images left open for over an hour are not timed.
+#[derive(Clone, Debug, Serialize)]
+pub struct ThroughputResponse {
+    /// The group reported on, or `None` for all groups.
+    pub group_id: Option<i32>,
+    pub groups: Vec<GroupThroughputResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GroupThroughputResponse {
+    pub group_id: i32,
+    pub name: String,
+    pub timing: TimingStatsResponse,
+    pub labelers: Vec<LabelerThroughputResponse>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LabelerThroughputResponse {
+    pub labeler_id: i32,
+    pub username: String,
+    pub timing: TimingStatsResponse,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TimingStatsResponse {
+    /// Images submitted.
+    pub images: usize,
+    /// Submitted images with a time spent.
+    pub timed_images: usize,
+    pub mean_seconds: Option<f64>,
+    pub median_seconds: Option<f64>,
+    pub p90_seconds: Option<f64>,
+    /// Timed images per hour of time spent on them.
+    pub labels_per_hour: Option<f64>,
+    pub histogram: Vec<TimeBucketResponse>,
+}
+
+/// Timed images that took at most `up_to_seconds` and longer than the
+/// previous bucket; the last bucket has no upper bound.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TimeBucketResponse {
+    pub up_to_seconds: Option<i64>,
+    pub count: usize,
+}
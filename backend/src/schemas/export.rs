@@ -1,4 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use crate::schemas::admin::FinalRegionResponse;
+use crate::schemas::analytics::AgreementResponse;
 
 #[derive(Serialize)]
 pub struct ExportResponse {
@@ -7,6 +9,12 @@ pub struct ExportResponse {
     pub data: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct BulkExportQuery {
+    /// Add each group's inter-annotator agreement under `agreement`.
+    pub include_agreement: Option<bool>,
+}
+
 #[derive(Serialize)]
 pub struct ExportData {
     #[serde(flatten)]
@@ -17,6 +25,8 @@ pub struct ExportData {
 pub struct GroupData {
     #[serde(flatten)]
     pub images: std::collections::HashMap<String, ImageData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agreement: Option<AgreementResponse>,
 }
 
 #[derive(Serialize)]
@@ -28,6 +38,10 @@ pub struct ImageData {
     pub final_tags: Vec<String>,
     pub tag_statistics: Vec<TagStatistic>,
     pub has_admin_override: bool,
+    /// Final bounding boxes, in normalized coordinates.
+    pub regions: Vec<FinalRegionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -38,3 +52,66 @@ pub struct TagStatistic {
     pub count: i32,
     pub total_labelers: i32,
 }
+
+/// A group's final annotations in COCO format. Image ids are image ids and
+/// category ids are tag ids.
+#[derive(Debug, Serialize)]
+pub struct CocoDataset {
+    pub images: Vec<CocoImage>,
+    pub categories: Vec<CocoCategory>,
+    pub annotations: Vec<CocoAnnotation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CocoImage {
+    pub id: i32,
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CocoCategory {
+    pub id: i32,
+    pub name: String,
+    /// Keypoint names and 1-based skeleton edges, for keypoint tags.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keypoints: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skeleton: Option<Vec<[usize; 2]>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CocoAnnotation {
+    pub id: i32,
+    pub image_id: i32,
+    pub category_id: i32,
+    /// Missing for bounding boxes and keypoint sets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segmentation: Option<CocoSegmentation>,
+    pub area: f64,
+    /// `[x, y, width, height]` in pixels.
+    pub bbox: [f64; 4],
+    pub iscrowd: u8,
+    /// `[x1, y1, v1, x2, y2, v2, ...]`, for keypoint sets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keypoints: Option<Vec<f64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_keypoints: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CocoSegmentation {
+    /// `[[x1, y1, x2, y2, ...]]`
+    Polygon(Vec<Vec<f64>>),
+    Rle { counts: Vec<u32>, size: [u32; 2] },
+}
+
+/// One line of the caption export.
+#[derive(Debug, Serialize)]
+pub struct CaptionPair {
+    pub image_id: i32,
+    pub file_name: String,
+    pub caption: String,
+}
@@ -1,10 +1,15 @@
 use serde::{Serialize, Deserialize};
+use crate::keypoint::{Keypoint, KeypointSchema};
+use crate::region::BoundingBox;
+use crate::segmentation::Geometry;
+use crate::task::TaskType;
 
 #[derive(Debug, Serialize)]
 pub struct GroupResponse {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
+    pub task_type: TaskType,
 }
 
 #[derive(Debug, Serialize)]
@@ -12,8 +17,16 @@ pub struct ImageResponse {
     pub id: i32,
     pub filename: String,
     pub status: String, // "done" or "pending"
-    pub base64_data: String,
+    // Only embedded in detail responses; lists link to thumbnails instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base64_data: Option<String>,
     pub filetype: String,
+    pub content_url: String,
+    pub thumbnail_url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub color_mode: Option<String>,
+    pub orientation: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -24,6 +37,8 @@ pub struct GroupListResponse {
 #[derive(Debug, Serialize)]
 pub struct ImageListResponse {
     pub images: Vec<ImageResponse>,
+    /// Number of matching images overall, not just on this page.
+    pub total: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -38,6 +53,8 @@ pub struct TagResponse {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keypoint_schema: Option<KeypointSchema>,
 }
 
 #[derive(Debug, Serialize)]
@@ -45,11 +62,153 @@ pub struct ImageDetailResponse {
     pub image: ImageResponse,
     pub group_tags: Vec<TagResponse>,
     pub current_tags: Vec<TagResponse>,
+    pub task_type: TaskType,
+    /// The labeler's text answer, in free-text groups.
+    pub current_text: Option<String>,
+}
+
+/// The image handed out by the labeling queue.
+#[derive(Debug, Serialize)]
+pub struct NextImageResponse {
+    pub image: ImageResponse,
+    /// The image is reserved for the labeler until then.
+    pub lease_expires_at: String,
+    /// Images the labeler could still be given, this one included.
+    pub remaining: usize,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateImageTagsRequest {
     pub tag_ids: Vec<i32>,
+    /// The answer in free-text groups, where `tag_ids` must be empty.
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Why a labeler skipped or flagged an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlagReason {
+    Unreadable,
+    Ambiguous,
+    Inappropriate,
+    Other,
+}
+
+impl FlagReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unreadable => "unreadable",
+            Self::Ambiguous => "ambiguous",
+            Self::Inappropriate => "inappropriate",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "unreadable" => Some(Self::Unreadable),
+            "ambiguous" => Some(Self::Ambiguous),
+            "inappropriate" => Some(Self::Inappropriate),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Body of the skip and flag actions.
+#[derive(Debug, Deserialize)]
+pub struct ImageFlagRequest {
+    pub reason: FlagReason,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImageFlagResponse {
+    pub image_id: i32,
+    /// "skip" or "flag".
+    pub kind: String,
+    pub reason: FlagReason,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// A bounding box to draw or change, for one of the group's tags.
+#[derive(Debug, Deserialize)]
+pub struct RegionRequest {
+    pub tag_id: i32,
+    #[serde(flatten)]
+    pub bbox: BoundingBox,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionResponse {
+    pub id: i32,
+    pub image_id: i32,
+    pub tag_id: i32,
+    #[serde(flatten)]
+    pub bbox: BoundingBox,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A polygon or mask to draw or change, for one of the group's tags, in
+/// pixels of the image.
+#[derive(Debug, Deserialize)]
+pub struct SegmentationRequest {
+    pub tag_id: i32,
+    pub geometry: Geometry,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentationResponse {
+    pub id: i32,
+    pub image_id: i32,
+    pub tag_id: i32,
+    pub geometry: Geometry,
+    /// Pixels covered.
+    pub area: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// The labeler's keypoints for a keypoint tag of the group: one `[x, y,
+/// visibility]` per point of the tag's schema, in pixels of the image.
+#[derive(Debug, Deserialize)]
+pub struct KeypointSetRequest {
+    pub tag_id: i32,
+    pub keypoints: Vec<Keypoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeypointSetResponse {
+    pub id: i32,
+    pub image_id: i32,
+    pub tag_id: i32,
+    pub keypoints: Vec<Keypoint>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// The labeler's caption of an image in a free-text group.
+#[derive(Debug, Deserialize)]
+pub struct CaptionRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptionResponse {
+    pub id: i32,
+    pub image_id: i32,
+    pub text: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// An AI-written caption to start from; it is not saved.
+#[derive(Debug, Serialize)]
+pub struct CaptionDraftResponse {
+    pub text: String,
 }
 
 #[derive(Debug, Deserialize)]
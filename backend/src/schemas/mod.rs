@@ -1,4 +1,6 @@
 pub mod auth;
 pub mod admin;
+pub mod analytics;
 pub mod export;
 pub mod labeler;
+pub mod pagination;
@@ -0,0 +1,79 @@
+use serde::Deserialize;
+use crate::repository::{PageRequest, SortOrder};
+
+/// Query parameters of list endpoints. Everything is optional: without
+/// `limit` the whole list is returned, in ascending `id` order.
+#[derive(Debug, Default, Deserialize)]
+pub struct ListQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub sort: Option<String>,
+    /// `asc` or `desc`.
+    pub order: Option<String>,
+    /// Image lists only: `labeled`/`unlabeled` for admins, `done`/`pending`
+    /// for labelers.
+    pub status: Option<String>,
+    pub has_final_tags: Option<bool>,
+    pub has_admin_override: Option<bool>,
+}
+
+impl ListQuery {
+    pub fn page(&self) -> Result<PageRequest, String> {
+        PageRequest::new(self.limit, self.offset)
+    }
+
+    pub fn order(&self) -> Result<SortOrder, String> {
+        match self.order.as_deref() {
+            None => Ok(SortOrder::default()),
+            Some(value) => SortOrder::parse(value)
+                .ok_or_else(|| format!("Invalid order: {}. Expected asc or desc", value)),
+        }
+    }
+
+    /// Parses `sort` with `parse`, naming the accepted `keys` on failure.
+    pub fn sort<S: Default>(&self, parse: fn(&str) -> Option<S>, keys: &str) -> Result<S, String> {
+        match self.sort.as_deref() {
+            None => Ok(S::default()),
+            Some(value) => parse(value).ok_or_else(|| format!("Invalid sort key: {}. Expected one of {}", value, keys)),
+        }
+    }
+}
+
+/// Query parameters of the group details endpoint. The list parameters
+/// apply to the images; labelers and tags are paged separately.
+#[derive(Debug, Default, Deserialize)]
+pub struct GroupDetailsQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub sort: Option<String>,
+    pub order: Option<String>,
+    pub status: Option<String>,
+    pub has_final_tags: Option<bool>,
+    pub has_admin_override: Option<bool>,
+    pub labeler_limit: Option<u64>,
+    pub labeler_offset: Option<u64>,
+    pub tag_limit: Option<u64>,
+    pub tag_offset: Option<u64>,
+}
+
+impl GroupDetailsQuery {
+    pub fn images(&self) -> ListQuery {
+        ListQuery {
+            limit: self.limit,
+            offset: self.offset,
+            sort: self.sort.clone(),
+            order: self.order.clone(),
+            status: self.status.clone(),
+            has_final_tags: self.has_final_tags,
+            has_admin_override: self.has_admin_override,
+        }
+    }
+
+    pub fn labeler_page(&self) -> Result<PageRequest, String> {
+        PageRequest::new(self.labeler_limit, self.labeler_offset)
+    }
+
+    pub fn tag_page(&self) -> Result<PageRequest, String> {
+        PageRequest::new(self.tag_limit, self.tag_offset)
+    }
+}
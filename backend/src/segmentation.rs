@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+/// Largest number of vertices a polygon may have.
+pub const MAX_POLYGON_POINTS: usize = 10_000;
+
+/// Outline or mask of one object, in pixels of the image. Serialized as
+/// `{"type": "polygon", "points": [[x, y], ...]}` or, in COCO's
+/// uncompressed RLE, as `{"type": "rle", "size": [height, width], "counts":
+/// [...]}`: alternating runs of background and foreground pixels in
+/// column-major order, starting with background.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Geometry {
+    Polygon { points: Vec<[f64; 2]> },
+    Rle { size: [u32; 2], counts: Vec<u32> },
+}
+
+impl Geometry {
+    /// Checks that the geometry fits an image of `width` × `height` pixels
+    /// and is not empty: a polygon must enclose an area and a mask must have
+    /// a foreground run. Nothing is rasterized.
+    pub fn validate(&self, width: u32, height: u32) -> Result<(), String> {
+        match self {
+            Self::Polygon { points } => {
+                if points.len() < 3 {
+                    return Err("A polygon needs at least 3 points".to_string());
+                }
+                if points.len() > MAX_POLYGON_POINTS {
+                    return Err(format!("A polygon may have at most {} points", MAX_POLYGON_POINTS));
+                }
+                let outside = |&[x, y]: &[f64; 2]| {
+                    !x.is_finite() || !y.is_finite() || x < 0.0 || y < 0.0 || x > width as f64 || y > height as f64
+                };
+                if points.iter().any(outside) {
+                    return Err(format!("Polygon points must lie within the image ({}x{})", width, height));
+                }
+                if polygon_area(points) <= 0.0 {
+                    return Err("A polygon must enclose an area".to_string());
+                }
+            }
+            Self::Rle { size, counts } => {
+                if *size != [height, width] {
+                    return Err(format!("Mask size must be [{}, {}], the image's [height, width]", height, width));
+                }
+                let total: u64 = counts.iter().map(|&count| count as u64).sum();
+                if total != width as u64 * height as u64 {
+                    return Err(format!("Mask counts must add up to {} pixels", width as u64 * height as u64));
+                }
+                if !counts.iter().skip(1).step_by(2).any(|&count| count > 0) {
+                    return Err("The annotation does not cover any pixel".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of pixels covered, counted the way `to_mask` rasterizes.
+    pub fn area(&self, width: u32, height: u32) -> u64 {
+        match self {
+            Self::Polygon { points } => {
+                let mut area = 0;
+                polygon_spans(points, width, height, |_, first, end| area += (end - first) as u64);
+                area
+            }
+            Self::Rle { counts, .. } => {
+                let total = width as u64 * height as u64;
+                let mut index = 0u64;
+                let mut area = 0;
+                for (run, &count) in counts.iter().enumerate() {
+                    let end = (index + count as u64).min(total);
+                    if run % 2 == 1 {
+                        area += end - index;
+                    }
+                    index = end;
+                }
+                area
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    pub fn to_mask(&self, width: u32, height: u32) -> Mask {
+        match self {
+            Self::Polygon { points } => Mask::from_polygon(points, width, height),
+            Self::Rle { counts, .. } => Mask::from_rle(counts, width, height),
+        }
+    }
+}
+
+/// Area enclosed by a polygon, by the shoelace formula.
+pub fn polygon_area(points: &[[f64; 2]]) -> f64 {
+    let twice: f64 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a[0] * b[1] - b[0] * a[1])
+        .sum();
+    twice.abs() / 2.0
+}
+
+// Calls `span(y, first, end)` for each run of pixels `first..end` of row `y`
+// whose centers lie inside the polygon (even-odd rule).
+fn polygon_spans(points: &[[f64; 2]], width: u32, height: u32, mut span: impl FnMut(u32, u32, u32)) {
+    for y in 0..height {
+        let center = y as f64 + 0.5;
+        let mut crossings: Vec<f64> = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .filter(|(a, b)| (a[1] <= center) != (b[1] <= center))
+            .map(|(a, b)| a[0] + (center - a[1]) / (b[1] - a[1]) * (b[0] - a[0]))
+            .collect();
+        crossings.sort_by(f64::total_cmp);
+        for pair in crossings.chunks_exact(2) {
+            // Pixels with pair[0] <= x + 0.5 < pair[1]
+            let first = ((pair[0] - 0.5).ceil().max(0.0) as u32).min(width);
+            let end = ((pair[1] - 0.5).ceil().max(0.0) as u32).min(width);
+            if first < end {
+                span(y, first, end);
+            }
+        }
+    }
+}
+
+/// Binary mask of an image, row-major, one bit per pixel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    pub width: u32,
+    pub height: u32,
+    bits: Vec<u64>,
+}
+
+impl Mask {
+    pub fn empty(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            bits: vec![0; (width as usize * height as usize).div_ceil(64)],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        let index = y as usize * self.width as usize + x as usize;
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set(&mut self, x: u32, y: u32) {
+        let index = y as usize * self.width as usize + x as usize;
+        self.bits[index / 64] |= 1 << (index % 64);
+    }
+
+    /// Pixels whose centers lie inside the polygon (even-odd rule).
+    pub fn from_polygon(points: &[[f64; 2]], width: u32, height: u32) -> Self {
+        let mut mask = Self::empty(width, height);
+        polygon_spans(points, width, height, |y, first, end| {
+            for x in first..end {
+                mask.set(x, y);
+            }
+        });
+        mask
+    }
+
+    pub fn from_rle(counts: &[u32], width: u32, height: u32) -> Self {
+        let mut mask = Self::empty(width, height);
+        let total = width as usize * height as usize;
+        let mut index = 0usize;
+        for (run, &count) in counts.iter().enumerate() {
+            let end = (index + count as usize).min(total);
+            if run % 2 == 1 {
+                for i in index..end {
+                    // Column-major index to (x, y)
+                    mask.set((i / height as usize) as u32, (i % height as usize) as u32);
+                }
+            }
+            index = end;
+        }
+        mask
+    }
+
+    /// Uncompressed COCO RLE counts.
+    pub fn to_rle(&self) -> Vec<u32> {
+        let mut counts = Vec::new();
+        let mut current = false;
+        let mut run = 0u32;
+        for x in 0..self.width {
+            for y in 0..self.height {
+                if self.get(x, y) != current {
+                    counts.push(run);
+                    current = !current;
+                    run = 0;
+                }
+                run += 1;
+            }
+        }
+        counts.push(run);
+        counts
+    }
+
+    pub fn area(&self) -> u64 {
+        self.bits.iter().map(|word| word.count_ones() as u64).sum()
+    }
+
+    /// `[x, y, width, height]` of the smallest box around the mask, in
+    /// pixels; `None` for an empty mask.
+    pub fn bbox(&self) -> Option<[u32; 4]> {
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.get(x, y) {
+                    min_x = min_x.min(x);
+                    min_y = min_y.min(y);
+                    max_x = max_x.max(x);
+                    max_y = max_y.max(y);
+                }
+            }
+        }
+        (min_x != u32::MAX).then(|| [min_x, min_y, max_x - min_x + 1, max_y - min_y + 1])
+    }
+
+    /// Pixels covered by at least `min_votes` of `masks`, which must all
+    /// have the given size.
+    pub fn merge(masks: &[Mask], width: u32, height: u32, min_votes: usize) -> Self {
+        let mut merged = Self::empty(width, height);
+        // Votes are counted a row at a time, so only the masks are image-sized
+        let mut votes = vec![0usize; width as usize];
+        for y in 0..height {
+            votes.fill(0);
+            for mask in masks {
+                for (x, vote) in votes.iter_mut().enumerate() {
+                    *vote += mask.get(x as u32, y) as usize;
+                }
+            }
+            for (x, &vote) in votes.iter().enumerate() {
+                if vote >= min_votes.max(1) {
+                    merged.set(x as u32, y);
+                }
+            }
+        }
+        merged
+    }
+}
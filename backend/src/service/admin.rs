@@ -1,12 +1,32 @@
-use sea_orm::{DatabaseConnection, EntityTrait, ActiveModelTrait, ColumnTrait, QueryFilter};
+use sea_orm::{DatabaseConnection, EntityTrait, ActiveModelTrait, ColumnTrait, QueryFilter, Set};
 use bcrypt::hash;
-use crate::repository::{LabelerRepository, GroupRepository, ImageRepository, TagRepository, ImageTagsRepository};
+use base64::Engine;
+use crate::keypoint::KeypointSchema;
+use crate::repository::{CaptionRepository, LabelerRepository, LabelerSort, GroupRepository, GroupSort, ImageRepository, TagRepository, ImageTagsRepository, KeypointRepository, RegionRepository, SegmentationRepository, SubmissionRepository};
 use crate::schemas::admin::{
     CreateLabelerRequest, UpdateLabelerRequest, LabelerResponse, 
     LabelerListResponse, GroupResponse, GroupListResponse, ApiResponse,
-    CreateGroupRequest, GroupDetailResponse, SimpleLabelerResponse, TagResponse, ImageResponse,
-    UploadImageRequest, ImageUploadResponse, CreateTagRequest, UpdateTagRequest
+    CreateGroupRequest, UpdateGroupRequest, GroupDetailResponse, SimpleLabelerResponse, TagResponse, ImageResponse,
+    UploadImageRequest, ImageUploadResponse, CreateTagRequest, UpdateTagRequest,
+    BatchUploadResponse, BatchUploadResult
 };
+use crate::schemas::pagination::{GroupDetailsQuery, ListQuery};
+use crate::multipart::{self, MultipartLimits};
+use crate::consensus::ConsensusStrategy;
+use crate::task::TaskType;
+use crate::imaging::{self, ImageFormat};
+use actix_multipart::Multipart;
+use futures_util::TryStreamExt;
+use crate::service::image::ImageService;
+use crate::service::progress::ProgressService;
+use crate::storage::{self, ImageStorage};
+use crate::entity::group::{ActiveModel as GroupActiveModel, Model as GroupModel};
+use crate::entity::image::ActiveModel as ImageActiveModel;
+
+/// Group duplicate policies: refuse uploads whose content is already in the
+/// group, or accept them with `duplicate_of` pointing at the first copy.
+pub const DUPLICATE_POLICY_REJECT: &str = "reject";
+pub const DUPLICATE_POLICY_FLAG: &str = "flag";
 
 pub struct AdminService;
 
@@ -52,6 +72,8 @@ impl AdminService {
                     id: labeler.id,
                     username: labeler.username,
                     group_ids,
+                    suspended: labeler.suspended,
+                    suspended_reason: labeler.suspended_reason,
                 };
                 Ok(ApiResponse {
                     success: true,
@@ -77,6 +99,8 @@ impl AdminService {
                     id: labeler.id,
                     username: labeler.username,
                     group_ids,
+                    suspended: labeler.suspended,
+                    suspended_reason: labeler.suspended_reason,
                 };
                 Ok(ApiResponse {
                     success: true,
@@ -97,14 +121,24 @@ impl AdminService {
 
     pub async fn list_labelers(
         db: &DatabaseConnection,
+        query: ListQuery,
     ) -> Result<ApiResponse<LabelerListResponse>, String> {
-        use crate::entity::labeler::Entity as Labeler;
-        
-        match Labeler::find().all(db).await {
+        let (page, sort, order) = match (query.page(), query.sort(LabelerSort::parse, "id, username"), query.order()) {
+            (Ok(page), Ok(sort), Ok(order)) => (page, sort, order),
+            (Err(message), _, _) | (_, Err(message), _) | (_, _, Err(message)) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+
+        match LabelerRepository::list(db, sort, order, page).await {
             Ok(labelers) => {
                 let mut labeler_responses = Vec::new();
                 
-                for labeler in labelers {
+                for labeler in labelers.items {
                     // Get the labeler's groups
                     let labeler_groups = LabelerRepository::get_groups(db, labeler.id).await.unwrap_or_default();
                     let group_ids = labeler_groups.into_iter().map(|g| g.id).collect();
@@ -113,12 +147,16 @@ impl AdminService {
                         id: labeler.id,
                         username: labeler.username,
                         group_ids,
+                        suspended: labeler.suspended,
+                        suspended_reason: labeler.suspended_reason,
                     });
                 }
 
                 let response = LabelerListResponse {
-                    total: labeler_responses.len(),
                     labelers: labeler_responses,
+                    total: labelers.total,
+                    limit: page.limit,
+                    offset: page.offset,
                 };
 
                 Ok(ApiResponse {
@@ -167,7 +205,6 @@ impl AdminService {
 
         // Update the labeler
         use crate::entity::labeler::ActiveModel as LabelerActiveModel;
-        use sea_orm::Set;
 
         let mut labeler_active: LabelerActiveModel = labeler.into();
 
@@ -181,6 +218,11 @@ impl AdminService {
             labeler_active.password_hash = Set(password_hash);
         }
 
+        if let Some(suspended) = request.suspended {
+            labeler_active.suspended = Set(suspended);
+            labeler_active.suspended_reason = Set(suspended.then(|| "Suspended by an admin".to_string()));
+        }
+
         match labeler_active.update(db).await {
             Ok(updated_labeler) => {
                 // Update group assignments if provided
@@ -206,6 +248,8 @@ impl AdminService {
                     id: updated_labeler.id,
                     username: updated_labeler.username,
                     group_ids,
+                    suspended: updated_labeler.suspended,
+                    suspended_reason: updated_labeler.suspended_reason,
                 };
                 Ok(ApiResponse {
                     success: true,
@@ -256,13 +300,91 @@ impl AdminService {
         db: &DatabaseConnection,
         request: CreateGroupRequest,
     ) -> Result<ApiResponse<GroupResponse>, String> {
-        match GroupRepository::create(db, request.name, request.description).await {
+        let allowed_formats = match request.allowed_formats.as_deref().map(Self::encode_allowed_formats) {
+            Some(Ok(formats)) => formats,
+            Some(Err(message)) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+            None => None,
+        };
+
+        let duplicate_policy = match request.duplicate_policy.as_deref().map(Self::parse_duplicate_policy) {
+            Some(Ok(policy)) => Some(policy),
+            Some(Err(message)) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+            None => None,
+        };
+
+        if let Some(Err(message)) = request.consensus_strategy.as_ref().map(ConsensusStrategy::validate) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        if let Some(Err(message)) = request.task_type.as_ref().map(TaskType::validate) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        if let Err(message) = Self::validate_gold_settings(request.gold_window, request.gold_min_accuracy) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        if let Err(message) = Self::validate_queue_settings(request.labels_per_image, request.lease_seconds) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
+        // Insert every setting at once so a failure cannot leave a
+        // half-configured group behind
+        let mut group = GroupActiveModel {
+            name: Set(request.name),
+            description: Set(request.description),
+            allowed_formats: Set(allowed_formats),
+            ..Default::default()
+        };
+        if let Some(duplicate_policy) = duplicate_policy {
+            group.duplicate_policy = Set(duplicate_policy);
+        }
+        if let Some(strategy) = &request.consensus_strategy {
+            group.consensus_strategy = Set(Some(strategy.to_json()));
+        }
+        if let Some(gold_window) = request.gold_window {
+            group.gold_window = Set(gold_window);
+        }
+        group.gold_min_accuracy = Set(request.gold_min_accuracy.filter(|accuracy| *accuracy > 0.0));
+        if let Some(labels_per_image) = request.labels_per_image {
+            group.labels_per_image = Set(labels_per_image);
+        }
+        if let Some(lease_seconds) = request.lease_seconds {
+            group.lease_seconds = Set(lease_seconds);
+        }
+        if let Some(task_type) = &request.task_type {
+            group.task_type = Set(Some(task_type.to_json()));
+        }
+
+        let created = group.insert(db).await;
+        match created {
             Ok(group) => {
-                let response = GroupResponse {
-                    id: group.id,
-                    name: group.name,
-                    description: group.description,
-                };
+                let response = Self::group_response(group);
                 Ok(ApiResponse {
                     success: true,
                     message: "Group created successfully".to_string(),
@@ -275,21 +397,32 @@ impl AdminService {
 
     pub async fn list_groups(
         db: &DatabaseConnection,
+        query: ListQuery,
     ) -> Result<ApiResponse<GroupListResponse>, String> {
-        match GroupRepository::get_all(db).await {
+        let (page, sort, order) = match (query.page(), query.sort(GroupSort::parse, "id, name"), query.order()) {
+            (Ok(page), Ok(sort), Ok(order)) => (page, sort, order),
+            (Err(message), _, _) | (_, Err(message), _) | (_, _, Err(message)) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+
+        match GroupRepository::list(db, sort, order, page).await {
             Ok(groups) => {
                 let group_responses: Vec<GroupResponse> = groups
+                    .items
                     .into_iter()
-                    .map(|group| GroupResponse {
-                        id: group.id,
-                        name: group.name,
-                        description: group.description,
-                    })
+                    .map(Self::group_response)
                     .collect();
 
                 let response = GroupListResponse {
-                    total: group_responses.len(),
                     groups: group_responses,
+                    total: groups.total,
+                    limit: page.limit,
+                    offset: page.offset,
                 };
 
                 Ok(ApiResponse {
@@ -302,6 +435,228 @@ impl AdminService {
         }
     }
 
+    pub async fn update_group(
+        db: &DatabaseConnection,
+        group_id: i32,
+        request: UpdateGroupRequest,
+    ) -> Result<ApiResponse<GroupResponse>, String> {
+        let group = match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(group)) => group,
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Group not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let allowed_formats = match request.allowed_formats.as_deref().map(Self::encode_allowed_formats) {
+            Some(Ok(formats)) => Some(formats),
+            Some(Err(message)) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+            None => None,
+        };
+
+        let duplicate_policy = match request.duplicate_policy.as_deref().map(Self::parse_duplicate_policy) {
+            Some(Ok(policy)) => Some(policy),
+            Some(Err(message)) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+            None => None,
+        };
+
+        if let Some(Err(message)) = request.consensus_strategy.as_ref().map(ConsensusStrategy::validate) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        if let Some(Err(message)) = request.task_type.as_ref().map(TaskType::validate) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        if let Err(message) = Self::validate_gold_settings(request.gold_window, request.gold_min_accuracy) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        if let Err(message) = Self::validate_queue_settings(request.labels_per_image, request.lease_seconds) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
+        // A single UPDATE, so either every change is applied or none is
+        let mut group: GroupActiveModel = group.into();
+        if let Some(name) = request.name {
+            group.name = Set(name);
+        }
+        if let Some(description) = request.description {
+            group.description = Set(Some(description));
+        }
+        if let Some(allowed_formats) = allowed_formats {
+            group.allowed_formats = Set(allowed_formats);
+        }
+        if let Some(duplicate_policy) = duplicate_policy {
+            group.duplicate_policy = Set(duplicate_policy);
+        }
+        if let Some(strategy) = &request.consensus_strategy {
+            group.consensus_strategy = Set(Some(strategy.to_json()));
+        }
+        if let Some(gold_window) = request.gold_window {
+            group.gold_window = Set(gold_window);
+        }
+        if let Some(accuracy) = request.gold_min_accuracy {
+            group.gold_min_accuracy = Set(Some(accuracy).filter(|accuracy| *accuracy > 0.0));
+        }
+        if let Some(labels_per_image) = request.labels_per_image {
+            group.labels_per_image = Set(labels_per_image);
+        }
+        if let Some(lease_seconds) = request.lease_seconds {
+            group.lease_seconds = Set(lease_seconds);
+        }
+        if let Some(task_type) = &request.task_type {
+            group.task_type = Set(Some(task_type.to_json()));
+        }
+
+        let updated = group.update(db).await;
+
+        match updated {
+            Ok(group) => Ok(ApiResponse {
+                success: true,
+                message: "Group updated successfully".to_string(),
+                data: Some(Self::group_response(group)),
+            }),
+            Err(e) => Err(format!("Failed to update group: {}", e)),
+        }
+    }
+
+    fn group_response(group: GroupModel) -> GroupResponse {
+        let allowed_formats = Self::allowed_formats(&group)
+            .iter()
+            .map(|format| format.mime().to_string())
+            .collect();
+        let consensus_strategy = Self::consensus_strategy(&group);
+        let task_type = Self::task_type(&group);
+        GroupResponse {
+            id: group.id,
+            name: group.name,
+            description: group.description,
+            allowed_formats,
+            duplicate_policy: group.duplicate_policy,
+            consensus_strategy,
+            gold_window: group.gold_window,
+            gold_min_accuracy: group.gold_min_accuracy,
+            labels_per_image: group.labels_per_image,
+            lease_seconds: group.lease_seconds,
+            task_type,
+        }
+    }
+
+    fn validate_gold_settings(gold_window: Option<i32>, gold_min_accuracy: Option<f64>) -> Result<(), String> {
+        if let Some(window) = gold_window
+            && !(1..=1000).contains(&window) {
+            return Err("gold_window must be between 1 and 1000".to_string());
+        }
+        if let Some(accuracy) = gold_min_accuracy
+            && !(0.0..=1.0).contains(&accuracy) {
+            return Err("gold_min_accuracy must be between 0 and 1".to_string());
+        }
+        Ok(())
+    }
+
+    fn validate_queue_settings(labels_per_image: Option<i32>, lease_seconds: Option<i32>) -> Result<(), String> {
+        if let Some(labels) = labels_per_image
+            && !(1..=100).contains(&labels) {
+            return Err("labels_per_image must be between 1 and 100".to_string());
+        }
+        if let Some(seconds) = lease_seconds
+            && !(30..=86_400).contains(&seconds) {
+            return Err("lease_seconds must be between 30 and 86400".to_string());
+        }
+        Ok(())
+    }
+
+    /// Strategy used when final tags of `group` are auto-generated without
+    /// one being requested.
+    pub(crate) fn consensus_strategy(group: &GroupModel) -> ConsensusStrategy {
+        group.consensus_strategy
+            .as_deref()
+            .and_then(ConsensusStrategy::from_json)
+            .unwrap_or_default()
+    }
+
+    /// What labelers of `group` answer per image.
+    pub(crate) fn task_type(group: &GroupModel) -> TaskType {
+        group.task_type
+            .as_deref()
+            .and_then(TaskType::from_json)
+            .unwrap_or_default()
+    }
+
+    /// Image formats that may be uploaded into `group`.
+    pub(crate) fn allowed_formats(group: &GroupModel) -> Vec<ImageFormat> {
+        group.allowed_formats
+            .as_deref()
+            .and_then(|json| serde_json::from_str::<Vec<String>>(json).ok())
+            .map(|types| types.iter().filter_map(|t| ImageFormat::from_filetype(t)).collect())
+            .unwrap_or_else(|| ImageFormat::ALL.to_vec())
+    }
+
+    // Validates a requested allow-list and encodes it for the group row.
+    // Allowing everything is stored as NULL so new formats are picked up.
+    fn encode_allowed_formats(requested: &[String]) -> Result<Option<String>, String> {
+        let mut formats = Vec::new();
+        for filetype in requested {
+            let format = ImageFormat::from_filetype(filetype)
+                .ok_or_else(|| format!("Unsupported image format: {}", filetype))?;
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+
+        if formats.is_empty() {
+            return Err("At least one image format must be allowed".to_string());
+        }
+        if ImageFormat::ALL.iter().all(|format| formats.contains(format)) {
+            return Ok(None);
+        }
+        let types: Vec<&str> = formats.iter().map(|format| format.mime()).collect();
+        serde_json::to_string(&types).map(Some).map_err(|e| e.to_string())
+    }
+
+    // Exact duplicate uploads are either refused or stored with `duplicate_of` set
+    fn parse_duplicate_policy(requested: &str) -> Result<String, String> {
+        let policy = requested.trim().to_lowercase();
+        if policy == DUPLICATE_POLICY_REJECT || policy == DUPLICATE_POLICY_FLAG {
+            Ok(policy)
+        } else {
+            Err(format!(
+                "Invalid duplicate policy: {}. Expected {} or {}",
+                requested, DUPLICATE_POLICY_REJECT, DUPLICATE_POLICY_FLAG
+            ))
+        }
+    }
+
     pub async fn delete_group(
         db: &DatabaseConnection,
         group_id: i32,
@@ -335,7 +690,21 @@ impl AdminService {
     pub async fn get_group_details(
         db: &DatabaseConnection,
         group_id: i32,
+        query: GroupDetailsQuery,
     ) -> Result<ApiResponse<GroupDetailResponse>, String> {
+        let pages = ImageService::list_query(&query.images(), None)
+            .and_then(|images| Ok((images, query.labeler_page()?, query.tag_page()?)));
+        let ((image_query, image_page), labeler_page, tag_page) = match pages {
+            Ok(pages) => pages,
+            Err(message) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+
         // Get the group
         let group = match GroupRepository::find_by_id(db, group_id).await {
             Ok(Some(group)) => group,
@@ -350,64 +719,76 @@ impl AdminService {
         };
 
         // Get group labelers
-        let labelers = match GroupRepository::get_labelers(db, group_id).await {
-            Ok(labelers) => labelers
+        let (labelers, labeler_total) = match GroupRepository::list_labelers(db, group_id, labeler_page).await {
+            Ok(labelers) => (labelers
+                .items
                 .into_iter()
                 .map(|labeler| SimpleLabelerResponse {
                     id: labeler.id,
                     username: labeler.username,
                 })
-                .collect(),
+                .collect(), labelers.total),
             Err(e) => {
                 eprintln!("Warning: Failed to load labelers for group {}: {}", group_id, e);
-                Vec::new()
+                (Vec::new(), 0)
             }
         };
 
         // Get group tags
-        let tags = match GroupRepository::get_possible_tags(db, group_id).await {
-            Ok(tags) => tags
+        let (tags, tag_total) = match GroupRepository::list_possible_tags(db, group_id, tag_page).await {
+            Ok(tags) => (tags
+                .items
                 .into_iter()
                 .map(|tag| TagResponse {
                     id: tag.id,
                     name: tag.name,
                     description: tag.description,
+                    keypoint_schema: tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                 })
-                .collect(),
+                .collect(), tags.total),
             Err(e) => {
                 eprintln!("Warning: Failed to load tags for group {}: {}", group_id, e);
-                Vec::new()
+                (Vec::new(), 0)
             }
         };
 
         // Get group images
-        let images = match GroupRepository::get_images(db, group_id).await {
-            Ok(images) => images
+        let (images, image_total) = match ImageRepository::list_by_group(db, group_id, &image_query, image_page).await {
+            Ok(images) => (images
+                .items
                 .into_iter()
                 .map(|image| ImageResponse {
                     id: image.id,
                     filename: image.filename,
                     filetype: image.filetype,
                     uploaded_at: image.uploaded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    content_url: ImageService::content_url(image.id),
+                    thumbnail_url: ImageService::thumbnail_url(image.id),
+                    width: image.width,
+                    height: image.height,
+                    color_mode: image.color_mode,
+                    orientation: image.orientation,
+                    duplicate_of: image.duplicate_of,
                 })
-                .collect(),
+                .collect(), images.total),
             Err(e) => {
                 eprintln!("Warning: Failed to load images for group {}: {}", group_id, e);
-                Vec::new()
+                (Vec::new(), 0)
             }
         };
 
-        let group_response = GroupResponse {
-            id: group.id,
-            name: group.name,
-            description: group.description,
-        };
+        let progress = ProgressService::group_progress(db, &group).await?;
+        let group_response = Self::group_response(group);
 
         let response = GroupDetailResponse {
             group: group_response,
             labelers,
             tags,
             images,
+            labeler_total,
+            tag_total,
+            image_total,
+            progress,
         };
 
         Ok(ApiResponse {
@@ -419,22 +800,154 @@ impl AdminService {
 
     pub async fn upload_image(
         db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
         request: UploadImageRequest,
     ) -> Result<ApiResponse<ImageUploadResponse>, String> {
-        // Validate file type
-        let allowed_types = ["png", "jpeg", "jpg"];
-        let file_extension = request.filetype.to_lowercase();
-        if !allowed_types.contains(&file_extension.as_str()) {
+        // Verify group exists
+        if let Some(response) = Self::check_upload_group(db, request.group_id).await? {
+            return Ok(response);
+        }
+
+        // Decode the payload
+        let bytes = match base64::engine::general_purpose::STANDARD.decode(request.base64_data.trim()) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Invalid base64 image data".to_string(),
+                    data: None,
+                });
+            }
+        };
+
+        Self::store_image(db, storage, request.group_id, request.filename, request.filetype, bytes).await
+    }
+
+    /// Reads every file part of a multipart upload into `group_id`, storing
+    /// each file as soon as it has been received. Failures are reported per
+    /// file and do not stop the rest of the batch.
+    pub async fn upload_images_multipart(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        group_id: i32,
+        multipart: &mut Multipart,
+        limits: &MultipartLimits,
+    ) -> Result<ApiResponse<BatchUploadResponse>, String> {
+        // Verify group exists
+        if let Some(response) = Self::check_upload_group::<()>(db, group_id).await? {
             return Ok(ApiResponse {
                 success: false,
-                message: "Invalid file type. Only PNG and JPEG files are allowed.".to_string(),
+                message: response.message,
                 data: None,
             });
         }
 
-        // Verify group exists
-        match GroupRepository::find_by_id(db, request.group_id).await {
-            Ok(Some(_)) => {} // Group exists, continue
+        let mut results = Vec::new();
+        let mut file_count = 0;
+
+        loop {
+            let mut field = match multipart.try_next().await {
+                Ok(Some(field)) => field,
+                Ok(None) => break,
+                Err(e) => {
+                    // The body is unreadable from here on; report what was processed
+                    return Ok(ApiResponse {
+                        success: false,
+                        message: e.to_string(),
+                        data: Some(BatchUploadResponse::from_results(results)),
+                    });
+                }
+            };
+
+            // Only file parts are uploads
+            let Some(filename) = field.content_disposition().and_then(|cd| cd.get_filename()).map(str::to_string) else {
+                continue;
+            };
+            let content_type = field.content_type().map(|mime| mime.to_string());
+            file_count += 1;
+
+            if file_count > limits.max_files {
+                results.push(BatchUploadResult::failed(filename, format!(
+                    "Too many files in one request (maximum {})", limits.max_files
+                )));
+                continue;
+            }
+            let data = match multipart::read_field(&mut field, limits.max_file_size).await {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    results.push(BatchUploadResult::failed(filename, format!(
+                        "File exceeds the maximum size of {} bytes", limits.max_file_size
+                    )));
+                    continue;
+                }
+                Err(e) => {
+                    return Ok(ApiResponse {
+                        success: false,
+                        message: e.to_string(),
+                        data: Some(BatchUploadResponse::from_results(results)),
+                    });
+                }
+            };
+
+            // Derive the file type from the extension, falling back to the part's Content-Type
+            let filetype = filename
+                .rsplit_once('.')
+                .map(|(_, ext)| ext.to_lowercase())
+                .or_else(|| content_type.as_ref().and_then(|ct| ct.split('/').nth(1).map(|s| s.to_lowercase())))
+                .unwrap_or_default();
+
+            match Self::store_image(db, storage, group_id, filename.clone(), filetype, data).await {
+                Ok(ApiResponse { success: true, data: Some(image), .. }) => {
+                    results.push(BatchUploadResult {
+                        filename,
+                        success: true,
+                        message: "Image uploaded successfully".to_string(),
+                        image: Some(image),
+                    });
+                }
+                Ok(response) => results.push(BatchUploadResult::failed(filename, response.message)),
+                Err(e) => {
+                    eprintln!("Warning: Failed to upload {} to group {}: {}", filename, group_id, e);
+                    results.push(BatchUploadResult::failed(filename, e));
+                }
+            }
+        }
+
+        let response = BatchUploadResponse::from_results(results);
+        Ok(ApiResponse {
+            success: true,
+            message: format!("Uploaded {} of {} images", response.uploaded, response.total),
+            data: Some(response),
+        })
+    }
+
+    // Returns the failure response to send when uploads into `group_id` are not possible
+    pub(crate) async fn check_upload_group<T>(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Option<ApiResponse<T>>, String> {
+        match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(_)) => Ok(None), // Group exists, continue
+            Ok(None) => Ok(Some(ApiResponse {
+                success: false,
+                message: "Group not found".to_string(),
+                data: None,
+            })),
+            Err(e) => Err(format!("Database error: {}", e)),
+        }
+    }
+
+    // Validates one uploaded file and stores it in `group_id`
+    pub(crate) async fn store_image(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        group_id: i32,
+        filename: String,
+        filetype: String,
+        bytes: Vec<u8>,
+    ) -> Result<ApiResponse<ImageUploadResponse>, String> {
+        let group = match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(group)) => group,
             Ok(None) => {
                 return Ok(ApiResponse {
                     success: false,
@@ -443,26 +956,121 @@ impl AdminService {
                 });
             }
             Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        // Validate file type against the group's allow-list
+        let allowed = Self::allowed_formats(&group);
+        let allowed_names = allowed.iter().map(|format| format.name()).collect::<Vec<_>>().join(", ");
+        let Some(format) = ImageFormat::from_filetype(&filetype) else {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("Invalid file type. Allowed types: {}", allowed_names),
+                data: None,
+            });
+        };
+        if !allowed.contains(&format) {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("{} files are not allowed in this group. Allowed types: {}", format.name(), allowed_names),
+                data: None,
+            });
+        }
+
+        // Sniff and decode the content rather than trusting the declared type.
+        // Decoding is CPU-bound, so keep it off the async workers.
+        let (bytes, info) = tokio::task::spawn_blocking(move || {
+            let info = imaging::inspect(&bytes);
+            (bytes, info)
+        })
+        .await
+        .map_err(|e| format!("Image decoding failed: {}", e))?;
+        let info = match info {
+            Ok(info) => info,
+            Err(e) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: format!("Invalid image data: {}", e),
+                    data: None,
+                });
+            }
+        };
+        if format != info.format {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("File content is {}, which does not match the file type {}", info.format.name(), filetype),
+                data: None,
+            });
+        }
+
+        // Storage keys are content hashes, so exact duplicates share one
+        let original = ImageRepository::find_by_storage_key(db, group_id, &storage::content_key(&bytes))
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        if let Some(original) = &original && group.duplicate_policy != DUPLICATE_POLICY_FLAG {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("Duplicate of image {} ({}) already in this group", original.id, original.filename),
+                data: None,
+            });
         }
 
-        // Create the image
-        match ImageRepository::create(
-            db,
-            request.filename,
-            request.filetype,
-            request.base64_data,
-            request.group_id,
-        ).await {
+        // Perceptual hashes feed the near-duplicate report
+        let (bytes, hash) = tokio::task::spawn_blocking(move || {
+            let hash = imaging::perceptual_hash(&bytes);
+            (bytes, hash)
+        })
+        .await
+        .map_err(|e| format!("Image hashing failed: {}", e))?;
+        let hash = match hash {
+            Ok(hash) => Some(hash),
+            Err(e) => {
+                eprintln!("Warning: Could not hash {}: {}", filename, e);
+                None
+            }
+        };
+
+        // Hand the bytes to the storage backend
+        let storage_key = storage.put(&bytes).await
+            .map_err(|e| format!("Failed to store image: {}", e))?;
+
+        // Create the image with its metadata in one INSERT
+        let image = ImageActiveModel {
+            filename: Set(filename),
+            filetype: Set(info.format.mime().to_string()),
+            storage_key: Set(Some(storage_key)),
+            size_bytes: Set(Some(bytes.len() as i64)),
+            group_id: Set(group_id),
+            uploaded_at: Set(chrono::Utc::now()),
+            width: Set(Some(info.width as i32)),
+            height: Set(Some(info.height as i32)),
+            color_mode: Set(Some(info.color_mode)),
+            orientation: Set(info.orientation.map(i32::from)),
+            average_hash: Set(hash.map(|hash| imaging::hash::to_hex(hash.average))),
+            difference_hash: Set(hash.map(|hash| imaging::hash::to_hex(hash.difference))),
+            duplicate_of: Set(original.map(|original| original.id)),
+            ..Default::default()
+        };
+        let created = image.insert(db).await;
+        match created {
             Ok(image) => {
+                let message = match image.duplicate_of {
+                    Some(original) => format!("Image uploaded, flagged as a duplicate of image {}", original),
+                    None => "Image uploaded successfully".to_string(),
+                };
                 let response = ImageUploadResponse {
                     id: image.id,
                     filename: image.filename,
                     filetype: image.filetype,
                     uploaded_at: image.uploaded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    width: image.width,
+                    height: image.height,
+                    color_mode: image.color_mode,
+                    orientation: image.orientation,
+                    duplicate_of: image.duplicate_of,
                 };
                 Ok(ApiResponse {
                     success: true,
-                    message: "Image uploaded successfully".to_string(),
+                    message,
                     data: Some(response),
                 })
             }
@@ -487,13 +1095,30 @@ impl AdminService {
             Err(e) => return Err(format!("Database error: {}", e)),
         }
 
+        if let Some(schema) = &request.keypoint_schema
+            && let Err(message) = schema.validate() {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
         // Create the tag
-        match TagRepository::create(db, request.name, request.description, request.group_id).await {
+        let created = match TagRepository::create(db, request.name, request.description, request.group_id).await {
+            Ok(tag) => match &request.keypoint_schema {
+                Some(schema) => TagRepository::set_keypoint_schema(db, tag.id, Some(schema)).await,
+                None => Ok(tag),
+            },
+            Err(e) => Err(e),
+        };
+        match created {
             Ok(tag) => {
                 let response = TagResponse {
                     id: tag.id,
                     name: tag.name,
                     description: tag.description,
+                    keypoint_schema: tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                 };
                 Ok(ApiResponse {
                     success: true,
@@ -515,6 +1140,7 @@ impl AdminService {
                     id: tag.id,
                     name: tag.name,
                     description: tag.description,
+                    keypoint_schema: tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                 };
                 Ok(ApiResponse {
                     success: true,
@@ -545,6 +1171,7 @@ impl AdminService {
                         id: tag.id,
                         name: tag.name,
                         description: tag.description,
+                        keypoint_schema: tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                     })
                     .collect();
 
@@ -592,13 +1219,50 @@ impl AdminService {
             }
         }
 
+        // An empty schema removes it. Keypoint sets already placed must keep
+        // matching the schema's points.
+        let keypoint_schema = request.keypoint_schema.map(|schema| Some(schema).filter(|schema| !schema.keypoints.is_empty()));
+        if let Some(schema) = &keypoint_schema {
+            if let Some(schema) = schema
+                && let Err(message) = schema.validate() {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+            let current = tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json);
+            let count = |schema: Option<&KeypointSchema>| schema.map(|schema| schema.keypoints.len());
+            if count(schema.as_ref()) != count(current.as_ref()) {
+                match KeypointRepository::count_by_tag(db, tag_id).await {
+                    Ok(0) => {}
+                    Ok(_) => {
+                        return Ok(ApiResponse {
+                            success: false,
+                            message: "The number of keypoints cannot change once keypoints have been placed".to_string(),
+                            data: None,
+                        });
+                    }
+                    Err(e) => return Err(format!("Database error: {}", e)),
+                }
+            }
+        }
+
         // Update the tag
-        match TagRepository::update(db, tag_id, request.name, request.description).await {
+        let updated = match TagRepository::update(db, tag_id, request.name, request.description).await {
+            Ok(tag) => match &keypoint_schema {
+                Some(schema) => TagRepository::set_keypoint_schema(db, tag.id, schema.as_ref()).await,
+                None => Ok(tag),
+            },
+            Err(e) => Err(e),
+        };
+        match updated {
             Ok(updated_tag) => {
                 let response = TagResponse {
                     id: updated_tag.id,
                     name: updated_tag.name,
                     description: updated_tag.description,
+                    keypoint_schema: updated_tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                 };
                 Ok(ApiResponse {
                     success: true,
@@ -753,6 +1417,21 @@ impl AdminService {
                     eprintln!("Warning: Failed to remove image tags for labeler {} from group {}: {}", labeler_id, group_id, e);
                     // Continue with the response even if tag removal fails
                 }
+                if let Err(e) = SubmissionRepository::delete_by_labeler_and_group(db, labeler_id, group_id).await {
+                    eprintln!("Warning: Failed to remove submissions for labeler {} from group {}: {}", labeler_id, group_id, e);
+                }
+                if let Err(e) = RegionRepository::delete_by_labeler_and_group(db, labeler_id, group_id).await {
+                    eprintln!("Warning: Failed to remove regions for labeler {} from group {}: {}", labeler_id, group_id, e);
+                }
+                if let Err(e) = SegmentationRepository::delete_by_labeler_and_group(db, labeler_id, group_id).await {
+                    eprintln!("Warning: Failed to remove segmentations for labeler {} from group {}: {}", labeler_id, group_id, e);
+                }
+                if let Err(e) = KeypointRepository::delete_by_labeler_and_group(db, labeler_id, group_id).await {
+                    eprintln!("Warning: Failed to remove keypoints for labeler {} from group {}: {}", labeler_id, group_id, e);
+                }
+                if let Err(e) = CaptionRepository::delete_by_labeler_and_group(db, labeler_id, group_id).await {
+                    eprintln!("Warning: Failed to remove captions for labeler {} from group {}: {}", labeler_id, group_id, e);
+                }
                 
                 Ok(ApiResponse {
                     success: true,
@@ -0,0 +1,269 @@
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeSet, HashSet};
+use crate::aggregation::{self, BinaryLabel, DawidSkene, DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE};
+use crate::consensus::ConsensusStrategy;
+use crate::entity::tag::Model as TagModel;
+use crate::repository::{FinalTagsRepository, GroupRepository, ImageRepository, ImageTagsRepository, LabelerRepository, SubmissionRepository, TagRepository};
+use crate::schemas::admin::{
+    AggregationResponse, ApiResponse, ApplyAggregationRequest, ImagePosteriorResponse, LabelerConfusionResponse,
+    TagAggregationResponse,
+};
+use crate::service::admin::AdminService;
+use crate::task::TaskType;
+
+/// Dawid–Skene estimates for every tag of a group. Images and labelers are
+/// the ones with at least one tag in the group, in ascending id order, and
+/// index the estimates.
+pub struct GroupEstimates {
+    pub image_ids: Vec<i32>,
+    pub labeler_ids: Vec<i32>,
+    /// Number of images each labeler labeled.
+    pub labeled_images: Vec<usize>,
+    pub tags: Vec<(TagModel, DawidSkene)>,
+}
+
+impl GroupEstimates {
+    /// Posterior probability of every tag of the group on `image_id`.
+    pub fn posteriors(&self, image_id: i32) -> Vec<(i32, f64)> {
+        match self.image_ids.binary_search(&image_id) {
+            Ok(item) => self.tags.iter().map(|(tag, estimate)| (tag.id, estimate.posteriors[item])).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+pub struct AggregationService;
+
+impl AggregationService {
+    /// Runs Dawid–Skene per tag of `group_id`, each tag being a yes/no
+    /// question answered by every labeler who tagged an image.
+    pub async fn estimate(
+        db: &DatabaseConnection,
+        group_id: i32,
+        max_iterations: u32,
+    ) -> Result<GroupEstimates, String> {
+        let mut tags = TagRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        tags.sort_by_key(|tag| tag.id);
+        let gold: HashSet<i32> = ImageRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|image| image.is_gold)
+            .map(|image| image.id)
+            .collect();
+        let mut image_tags = ImageTagsRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        image_tags.retain(|t| !gold.contains(&t.image_id));
+        let mut submitters = SubmissionRepository::submitters_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        submitters.retain(|image_id, _| !gold.contains(image_id));
+
+        let image_ids: Vec<i32> = submitters.keys().copied().collect();
+        let labeler_ids: Vec<i32> = submitters
+            .values()
+            .flat_map(|labelers| labelers.keys().copied())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let index = |ids: &[i32], id: i32| ids.binary_search(&id).unwrap_or_default();
+
+        // An image submitted with no tags is an answer of "no" to every tag
+        let mut answered: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for (&image_id, labelers) in &submitters {
+            for &labeler_id in labelers.keys() {
+                answered.insert((index(&image_ids, image_id), index(&labeler_ids, labeler_id)));
+            }
+        }
+        let chosen: HashSet<(i32, i32, i32)> = image_tags.iter().map(|t| (t.image_id, t.labeler_id, t.tag_id)).collect();
+
+        let mut labeled_images = vec![0; labeler_ids.len()];
+        for &(_, labeler) in &answered {
+            labeled_images[labeler] += 1;
+        }
+
+        let tags = tags
+            .into_iter()
+            .map(|tag| {
+                let labels: Vec<BinaryLabel> = answered
+                    .iter()
+                    .map(|&(item, labeler)| BinaryLabel {
+                        item,
+                        labeler,
+                        positive: chosen.contains(&(image_ids[item], labeler_ids[labeler], tag.id)),
+                    })
+                    .collect();
+                let estimate = aggregation::dawid_skene(
+                    &labels,
+                    image_ids.len(),
+                    labeler_ids.len(),
+                    max_iterations,
+                    DEFAULT_TOLERANCE,
+                );
+                (tag, estimate)
+            })
+            .collect();
+
+        Ok(GroupEstimates { image_ids, labeler_ids, labeled_images, tags })
+    }
+
+    /// Per-labeler confusion matrices and per-image posteriors of every tag
+    /// of a group, without changing any final tags.
+    pub async fn report(
+        db: &DatabaseConnection,
+        group_id: i32,
+        max_iterations: Option<u32>,
+    ) -> Result<ApiResponse<AggregationResponse>, String> {
+        let estimates = match Self::checked_estimate(db, group_id, max_iterations).await? {
+            Ok(estimates) => estimates,
+            Err(message) => return Ok(ApiResponse { success: false, message, data: None }),
+        };
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Label aggregation estimated successfully".to_string(),
+            data: Some(Self::response(db, group_id, &estimates, None).await?),
+        })
+    }
+
+    /// Replaces the final tags of every labeled image of a group with the
+    /// tags whose posterior reaches the threshold, as many as the group's
+    /// task type allows, recording the posterior as their confidence. Images
+    /// with admin-set final tags are left alone.
+    pub async fn apply(
+        db: &DatabaseConnection,
+        group_id: i32,
+        request: ApplyAggregationRequest,
+    ) -> Result<ApiResponse<AggregationResponse>, String> {
+        let threshold = request.threshold.unwrap_or(0.5);
+        let strategy = ConsensusStrategy::DawidSkene { threshold };
+        if let Err(message) = strategy.validate() {
+            return Ok(ApiResponse { success: false, message, data: None });
+        }
+
+        let estimates = match Self::checked_estimate(db, group_id, request.max_iterations).await? {
+            Ok(estimates) => estimates,
+            Err(message) => return Ok(ApiResponse { success: false, message, data: None }),
+        };
+        let task_type = GroupRepository::find_by_id(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .as_ref()
+            .map(AdminService::task_type)
+            .unwrap_or_default();
+        if task_type == TaskType::FreeText {
+            let message = "Free-text groups have no tags to aggregate".to_string();
+            return Ok(ApiResponse { success: false, message, data: None });
+        }
+
+        let overridden: HashSet<i32> = FinalTagsRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|tag| tag.is_admin_override)
+            .map(|tag| tag.image_id)
+            .collect();
+
+        let mut updated = 0;
+        for &image_id in estimates.image_ids.iter().filter(|id| !overridden.contains(id)) {
+            let tags = Self::select(&estimates, image_id, threshold)
+                .into_iter()
+                .map(|(tag_id, posterior)| (tag_id, posterior.unwrap_or_default(), posterior))
+                .collect();
+            let tags = task_type.limit(tags).into_iter().map(|(tag_id, _, posterior)| (tag_id, posterior)).collect();
+            FinalTagsRepository::replace_consensus_tags(db, image_id, tags, strategy.to_json())
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            updated += 1;
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: format!("Final tags of {} images replaced with aggregated estimates", updated),
+            data: Some(Self::response(db, group_id, &estimates, Some(updated)).await?),
+        })
+    }
+
+    /// Tags of `image_id` whose posterior reaches `threshold`, with the
+    /// posterior as confidence.
+    pub fn select(estimates: &GroupEstimates, image_id: i32, threshold: f64) -> Vec<(i32, Option<f64>)> {
+        estimates
+            .posteriors(image_id)
+            .into_iter()
+            .filter(|&(_, posterior)| posterior >= threshold - 1e-9)
+            .map(|(tag_id, posterior)| (tag_id, Some(posterior)))
+            .collect()
+    }
+
+    // Estimates after checking the group exists and the iteration limit is
+    // valid; the inner error is the message for the client
+    async fn checked_estimate(
+        db: &DatabaseConnection,
+        group_id: i32,
+        max_iterations: Option<u32>,
+    ) -> Result<Result<GroupEstimates, String>, String> {
+        match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(_)) => {} // Group exists, continue
+            Ok(None) => return Ok(Err("Group not found".to_string())),
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+
+        let max_iterations = max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+        if !(1..=1000).contains(&max_iterations) {
+            return Ok(Err("max_iterations must be between 1 and 1000".to_string()));
+        }
+
+        Self::estimate(db, group_id, max_iterations).await.map(Ok)
+    }
+
+    async fn response(
+        db: &DatabaseConnection,
+        group_id: i32,
+        estimates: &GroupEstimates,
+        images_updated: Option<usize>,
+    ) -> Result<AggregationResponse, String> {
+        let mut usernames = Vec::new();
+        for &labeler_id in &estimates.labeler_ids {
+            let labeler = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            usernames.push(labeler.map(|labeler| labeler.username).unwrap_or_default());
+        }
+
+        let tags = estimates
+            .tags
+            .iter()
+            .map(|(tag, estimate)| TagAggregationResponse {
+                tag_id: tag.id,
+                tag_name: tag.name.clone(),
+                prior: estimate.prior,
+                iterations: estimate.iterations,
+                converged: estimate.converged,
+                labelers: estimate
+                    .confusion
+                    .iter()
+                    .enumerate()
+                    .map(|(j, matrix)| LabelerConfusionResponse {
+                        labeler_id: estimates.labeler_ids[j],
+                        username: usernames[j].clone(),
+                        images: estimates.labeled_images[j],
+                        sensitivity: matrix.sensitivity,
+                        specificity: matrix.specificity,
+                        confusion_matrix: matrix.rows(),
+                    })
+                    .collect(),
+                images: estimate
+                    .posteriors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &posterior)| ImagePosteriorResponse { image_id: estimates.image_ids[i], posterior })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(AggregationResponse { group_id, tags, images_updated })
+    }
+}
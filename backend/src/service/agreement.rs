@@ -0,0 +1,144 @@
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use crate::agreement;
+use crate::repository::{GroupRepository, ImageRepository, ImageTagsRepository, LabelerRepository, SubmissionRepository, TagRepository};
+use crate::schemas::admin::ApiResponse;
+use crate::schemas::analytics::{AgreementResponse, PairAgreementResponse, TagAgreementResponse};
+
+pub struct AgreementService;
+
+impl AgreementService {
+    /// Cohen's kappa per labeler pair, and Fleiss' kappa and Krippendorff's
+    /// alpha, for a whole group and for each of its tags.
+    pub async fn group_agreement(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<ApiResponse<AgreementResponse>, String> {
+        match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(_)) => {} // Group exists, continue
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Group not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Agreement computed successfully".to_string(),
+            data: Some(Self::compute(db, group_id).await?),
+        })
+    }
+
+    /// The agreement of a group that is known to exist.
+    pub async fn compute(db: &DatabaseConnection, group_id: i32) -> Result<AgreementResponse, String> {
+        let mut tags = TagRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        tags.sort_by_key(|tag| tag.id);
+        // Gold answers are scored against a key rather than given freely
+        let gold: HashSet<i32> = ImageRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|image| image.is_gold)
+            .map(|image| image.id)
+            .collect();
+        let image_tags = ImageTagsRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        // Labelers who submitted each image, and the tags they chose
+        let mut labelers: BTreeMap<i32, BTreeSet<i32>> = SubmissionRepository::submitters_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|(image_id, _)| !gold.contains(image_id))
+            .map(|(image_id, labelers)| (image_id, labelers.into_keys().collect()))
+            .collect();
+        let chosen: HashSet<(i32, i32, i32)> = image_tags
+            .iter()
+            .map(|image_tag| (image_tag.image_id, image_tag.labeler_id, image_tag.tag_id))
+            .collect();
+        labelers.retain(|_, labelers| labelers.len() >= 2);
+        let says = |image_id: i32, labeler_id: i32, tag_id: i32| chosen.contains(&(image_id, labeler_id, tag_id));
+
+        // [no, yes] counts of every image for one tag
+        let units = |tag_id: i32| -> Vec<Vec<usize>> {
+            labelers
+                .iter()
+                .map(|(&image_id, labelers)| {
+                    let yes = labelers.iter().filter(|&&labeler_id| says(image_id, labeler_id, tag_id)).count();
+                    vec![labelers.len() - yes, yes]
+                })
+                .collect()
+        };
+
+        let mut usernames = BTreeMap::new();
+        for &labeler_id in labelers.values().flatten().collect::<BTreeSet<_>>() {
+            let labeler = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            usernames.insert(labeler_id, labeler.map(|labeler| labeler.username).unwrap_or_default());
+        }
+
+        // Images each pair of labelers both tagged
+        let mut shared: BTreeMap<(i32, i32), Vec<i32>> = BTreeMap::new();
+        for (&image_id, labelers) in &labelers {
+            for &a in labelers {
+                for &b in labelers.range(a + 1..) {
+                    shared.entry((a, b)).or_default().push(image_id);
+                }
+            }
+        }
+        let pairs = |tag_ids: &[i32]| -> Vec<PairAgreementResponse> {
+            shared
+                .iter()
+                .map(|(&(a, b), images)| {
+                    let answers: Vec<(bool, bool)> = images
+                        .iter()
+                        .flat_map(|&image_id| {
+                            tag_ids.iter().map(move |&tag_id| (says(image_id, a, tag_id), says(image_id, b, tag_id)))
+                        })
+                        .collect();
+                    PairAgreementResponse {
+                        labeler_id: a,
+                        username: usernames[&a].clone(),
+                        other_labeler_id: b,
+                        other_username: usernames[&b].clone(),
+                        shared_images: images.len(),
+                        cohen_kappa: agreement::cohen_kappa(&answers),
+                    }
+                })
+                .collect()
+        };
+
+        let tag_ids: Vec<i32> = tags.iter().map(|tag| tag.id).collect();
+        let all_units: Vec<Vec<usize>> = tag_ids.iter().flat_map(|&tag_id| units(tag_id)).collect();
+        let tag_agreement = tags
+            .iter()
+            .map(|tag| {
+                let units = units(tag.id);
+                TagAgreementResponse {
+                    tag_id: tag.id,
+                    tag_name: tag.name.clone(),
+                    fleiss_kappa: agreement::fleiss_kappa(&units),
+                    krippendorff_alpha: agreement::krippendorff_alpha(&units),
+                    pairs: pairs(&[tag.id]),
+                }
+            })
+            .collect();
+
+        Ok(AgreementResponse {
+            group_id,
+            images: labelers.len(),
+            fleiss_kappa: agreement::fleiss_kappa(&all_units),
+            krippendorff_alpha: agreement::krippendorff_alpha(&all_units),
+            pairs: pairs(&tag_ids),
+            tags: tag_agreement,
+        })
+    }
+}
@@ -0,0 +1,95 @@
+use chrono::{Duration, Utc};
+use sea_orm::DatabaseConnection;
+use crate::repository::{AssignmentRepository, GroupRepository, ImageRepository, LabelerRepository};
+use crate::schemas::labeler::{ApiResponse, ImageResponse, NextImageResponse};
+use crate::service::image::ImageService;
+
+pub struct AssignmentService;
+
+impl AssignmentService {
+    /// Leases the labeler the next image of the group that still needs
+    /// labels: one they have not labeled, skipped or flagged, held by fewer
+    /// than `labels_per_image` labelers (done or leased), least covered
+    /// first. A labeler asking again before their lease runs out gets the
+    /// same image with a renewed lease. Gold images are handed to every
+    /// labeler.
+    pub async fn next_image(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+    ) -> Result<ApiResponse<NextImageResponse>, String> {
+        match LabelerRepository::get_groups(db, labeler_id).await {
+            Ok(groups) => {
+                if !groups.iter().any(|group| group.id == group_id) {
+                    return Err("You are not authorized to access this group".to_string());
+                }
+            }
+            Err(e) => return Err(format!("Failed to verify group access: {}", e)),
+        }
+        let group = GroupRepository::find_by_id(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .ok_or_else(|| "Group not found".to_string())?;
+
+        let now = Utc::now().naive_utc();
+        AssignmentRepository::delete_expired(db, now)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        let expires_at = now + Duration::seconds(group.lease_seconds.max(1) as i64);
+        while let Some(candidate) = AssignmentRepository::next_candidate(db, group_id, labeler_id, group.labels_per_image.max(1), now)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+        {
+            let lease = AssignmentRepository::lease(db, candidate.image_id, labeler_id, now, expires_at)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+
+            // Someone else may have leased the same image meanwhile; the
+            // earlier leases win, and the image is no candidate anymore
+            if !candidate.held && !candidate.is_gold {
+                let earlier = AssignmentRepository::count_earlier(db, candidate.image_id, lease.id, now)
+                    .await
+                    .map_err(|e| format!("Database error: {}", e))?;
+                if candidate.done + earlier as i64 >= group.labels_per_image.max(1) as i64 {
+                    AssignmentRepository::release(db, candidate.image_id, labeler_id)
+                        .await
+                        .map_err(|e| format!("Database error: {}", e))?;
+                    continue;
+                }
+            }
+
+            let image = ImageRepository::find_by_id(db, candidate.image_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .ok_or_else(|| "Image not found".to_string())?;
+            return Ok(ApiResponse {
+                success: true,
+                message: "Image leased successfully".to_string(),
+                data: Some(NextImageResponse {
+                    image: ImageResponse {
+                        id: image.id,
+                        filename: image.filename,
+                        status: "pending".to_string(),
+                        base64_data: None,
+                        filetype: image.filetype,
+                        content_url: ImageService::content_url(image.id),
+                        thumbnail_url: ImageService::thumbnail_url(image.id),
+                        width: image.width,
+                        height: image.height,
+                        color_mode: image.color_mode,
+                        orientation: image.orientation,
+                    },
+                    lease_expires_at: expires_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+                    remaining: candidate.remaining as usize,
+                }),
+            });
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: "No images need labels right now".to_string(),
+            data: None,
+        })
+    }
+}
@@ -60,6 +60,10 @@ impl AuthService {
             return Err("Invalid credentials".to_string());
         }
 
+        if labeler.suspended {
+            return Err("Account suspended".to_string());
+        }
+
         let token = Self::generate_jwt(labeler.id, "labeler")?;
         Ok(LoginResponse { user_id: labeler.id, token })
     }
@@ -0,0 +1,285 @@
+use sea_orm::DatabaseConnection;
+use crate::entity::caption::Model as CaptionModel;
+use crate::entity::final_caption::Model as FinalCaptionModel;
+use crate::entity::image::Model as ImageModel;
+use crate::repository::{CaptionRepository, GroupRepository, ImageRepository, LabelerRepository};
+use crate::schemas::admin::{
+    ApiResponse as AdminApiResponse, FinalCaptionResponse, ImageCaptionsResponse, LabelerCaptionResponse,
+    SelectFinalCaptionRequest,
+};
+use crate::schemas::labeler::{
+    ApiResponse, CaptionDraftResponse, CaptionRequest, CaptionResponse, UpdateImageTagsRequest,
+};
+use crate::service::admin::AdminService;
+use crate::service::labeler::LabelerService;
+use crate::service::openai::OpenAIService;
+use crate::storage::{self, ImageStorage};
+use crate::task::{self, TaskType};
+
+pub struct CaptionService;
+
+impl CaptionService {
+    /// The labeler's caption of an image.
+    pub async fn get_caption(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+    ) -> Result<ApiResponse<CaptionResponse>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let caption = CaptionRepository::find(db, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(match caption {
+            Some(caption) => ApiResponse {
+                success: true,
+                message: "Caption retrieved successfully".to_string(),
+                data: Some(Self::caption_response(caption)),
+            },
+            None => ApiResponse {
+                success: false,
+                message: "Caption not found".to_string(),
+                data: None,
+            },
+        })
+    }
+
+    /// Submits the labeler's caption of an image in a free-text group,
+    /// replacing the previous one. Like saving tags, it marks the image as
+    /// labeled.
+    pub async fn save_caption(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        request: CaptionRequest,
+    ) -> Result<ApiResponse<CaptionResponse>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let task_type = match GroupRepository::find_by_id(db, group_id).await {
+            Ok(group) => group.as_ref().map(AdminService::task_type).unwrap_or_default(),
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        if task_type != TaskType::FreeText {
+            return Ok(ApiResponse {
+                success: false,
+                message: "This group takes tags, not captions".to_string(),
+                data: None,
+            });
+        }
+
+        let answer = UpdateImageTagsRequest {
+            tag_ids: Vec::new(),
+            text: Some(request.text),
+        };
+        let saved = LabelerService::update_image_tags(db, labeler_id, group_id, image_id, answer).await?;
+        if !saved.success {
+            return Ok(ApiResponse {
+                success: false,
+                message: saved.message,
+                data: None,
+            });
+        }
+
+        match CaptionRepository::find(db, image_id, labeler_id).await {
+            Ok(caption) => Ok(ApiResponse {
+                success: true,
+                message: "Caption saved successfully".to_string(),
+                data: caption.map(Self::caption_response),
+            }),
+            Err(e) => Err(format!("Database error: {}", e)),
+        }
+    }
+
+    /// An AI-written caption of the image for the labeler to edit.
+    pub async fn draft_caption(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+    ) -> Result<ApiResponse<CaptionDraftResponse>, String> {
+        let image = LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Caption drafted successfully".to_string(),
+            data: Some(Self::draft(storage, &image).await?),
+        })
+    }
+
+    /// An AI-written caption of the image for the admin to edit and select.
+    pub async fn draft_final_caption(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        image_id: i32,
+    ) -> Result<AdminApiResponse<CaptionDraftResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Caption drafted successfully".to_string(),
+            data: Some(Self::draft(storage, &image).await?),
+        })
+    }
+
+    /// Every labeler's caption of an image, and its final caption.
+    pub async fn image_captions(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<AdminApiResponse<ImageCaptionsResponse>, String> {
+        match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+
+        let mut captions = Vec::new();
+        for caption in CaptionRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+        {
+            let username = LabelerRepository::find_by_id(db, caption.labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+            captions.push(LabelerCaptionResponse {
+                labeler_id: caption.labeler_id,
+                username,
+                caption: Self::caption_response(caption),
+            });
+        }
+        let final_caption = CaptionRepository::get_final(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Captions retrieved successfully".to_string(),
+            data: Some(ImageCaptionsResponse {
+                image_id,
+                captions,
+                final_caption: final_caption.map(Self::final_caption_response),
+            }),
+        })
+    }
+
+    /// Makes one of the image's captions, or the admin's own text, its
+    /// final caption.
+    pub async fn select_final_caption(
+        db: &DatabaseConnection,
+        image_id: i32,
+        request: SelectFinalCaptionRequest,
+    ) -> Result<AdminApiResponse<FinalCaptionResponse>, String> {
+        match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+
+        let text = match (request.caption_id, request.text) {
+            (Some(caption_id), None) => {
+                let caption = CaptionRepository::find_by_id(db, caption_id)
+                    .await
+                    .map_err(|e| format!("Database error: {}", e))?
+                    .filter(|caption| caption.image_id == image_id);
+                match caption {
+                    Some(caption) => caption.text,
+                    None => {
+                        return Ok(AdminApiResponse {
+                            success: false,
+                            message: format!("Caption {} not found on this image", caption_id),
+                            data: None,
+                        });
+                    }
+                }
+            }
+            (None, Some(text)) => {
+                if let Err(message) = TaskType::FreeText.check_answer(&[], Some(&text)) {
+                    return Ok(AdminApiResponse {
+                        success: false,
+                        message,
+                        data: None,
+                    });
+                }
+                text.trim().to_string()
+            }
+            _ => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Give either caption_id or text".to_string(),
+                    data: None,
+                });
+            }
+        };
+
+        let normalized = task::normalize_text(&text);
+        let votes = CaptionRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .iter()
+            .filter(|caption| task::normalize_text(&caption.text) == normalized)
+            .count();
+        let final_caption = CaptionRepository::set_final(db, image_id, Some((text, votes as i32)), true)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Final caption selected successfully".to_string(),
+            data: final_caption.map(Self::final_caption_response),
+        })
+    }
+
+    async fn draft(storage: &dyn ImageStorage, image: &ImageModel) -> Result<CaptionDraftResponse, String> {
+        let base64_data = storage::read_image_base64(storage, image)
+            .await
+            .map_err(|e| format!("Failed to read image: {}", e))?;
+        let text = OpenAIService::new()
+            .draft_caption(&base64_data, &image.filetype)
+            .await
+            .map_err(|e| format!("Failed to draft caption: {}", e))?;
+        Ok(CaptionDraftResponse { text })
+    }
+
+    fn caption_response(caption: CaptionModel) -> CaptionResponse {
+        CaptionResponse {
+            id: caption.id,
+            image_id: caption.image_id,
+            text: caption.text,
+            created_at: caption.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            updated_at: caption.updated_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        }
+    }
+
+    fn final_caption_response(caption: FinalCaptionModel) -> FinalCaptionResponse {
+        FinalCaptionResponse {
+            id: caption.id,
+            text: caption.text,
+            votes: caption.votes,
+            is_admin_override: caption.is_admin_override,
+            created_at: caption.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use sea_orm::DatabaseConnection;
+use std::collections::{HashMap, HashSet};
+use crate::aggregation::DEFAULT_MAX_ITERATIONS;
+use crate::consensus::{ConsensusStrategy, DEFAULT_RELIABILITY};
+use crate::entity::final_tags::Model as FinalTagsModel;
+use crate::repository::{
+    CaptionRepository, FinalTagsRepository, GroupRepository, ImageRepository, ImageTagsRepository, SubmissionRepository,
+    TagRepository,
+};
+use crate::schemas::admin::ApiResponse;
+use crate::service::admin::AdminService;
+use crate::service::aggregation::AggregationService;
+use crate::task::{self, TaskType};
+
+pub struct ConsensusService;
+
+impl ConsensusService {
+    /// Replaces the final tags of an image with the ones `strategy` picks
+    /// from its labelers' tags, falling back to the group's strategy, and
+    /// narrowed to what the group's task type allows. In free-text groups
+    /// the final caption is the answer most labelers gave instead. Images
+    /// nobody has tagged are left alone.
+    pub async fn auto_generate_final_tags(
+        db: &DatabaseConnection,
+        image_id: i32,
+        strategy: Option<ConsensusStrategy>,
+    ) -> Result<ApiResponse<Vec<FinalTagsModel>>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        if image.is_gold {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Gold images have known tags and no consensus".to_string(),
+                data: None,
+            });
+        }
+
+        let group = GroupRepository::find_by_id(db, image.group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let task_type = group.as_ref().map(AdminService::task_type).unwrap_or_default();
+        let strategy = match strategy {
+            Some(strategy) => strategy,
+            None => group.as_ref().map(AdminService::consensus_strategy).unwrap_or_default(),
+        };
+        if let Err(message) = strategy.validate() {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
+        let image_tags = ImageTagsRepository::get_all_tags_for_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let submitters = SubmissionRepository::submitters_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        if submitters.is_empty() {
+            return Ok(ApiResponse {
+                success: true,
+                message: "No tags found for this image".to_string(),
+                data: Some(vec![]),
+            });
+        }
+
+        if task_type == TaskType::FreeText {
+            let reliability = match strategy {
+                ConsensusStrategy::WeightedReliability { .. } => Self::labeler_reliability(db, image.group_id).await?,
+                _ => HashMap::new(),
+            };
+            return Self::generate_final_caption(db, image_id, &strategy, &reliability).await;
+        }
+
+        let tags: Vec<(i32, f64, Option<f64>)> = match strategy {
+            ConsensusStrategy::DawidSkene { threshold } => {
+                let estimates = AggregationService::estimate(db, image.group_id, DEFAULT_MAX_ITERATIONS).await?;
+                AggregationService::select(&estimates, image_id, threshold)
+                    .into_iter()
+                    .map(|(tag_id, posterior)| (tag_id, posterior.unwrap_or_default(), posterior))
+                    .collect()
+            }
+            _ => {
+                let reliability = match strategy {
+                    ConsensusStrategy::WeightedReliability { .. } => Self::labeler_reliability(db, image.group_id).await?,
+                    _ => HashMap::new(),
+                };
+                let votes: Vec<(i32, i32)> = image_tags.iter().map(|tag| (tag.labeler_id, tag.tag_id)).collect();
+                strategy
+                    .select_among(&submitters, &votes, &reliability)
+                    .into_iter()
+                    .map(|tag_id| (tag_id, Self::support(&strategy, &votes, &reliability, tag_id), None))
+                    .collect()
+            }
+        };
+        let tags = task_type
+            .limit(tags)
+            .into_iter()
+            .map(|(tag_id, _, confidence)| (tag_id, confidence))
+            .collect();
+
+        match FinalTagsRepository::replace_consensus_tags(db, image_id, tags, strategy.to_json()).await {
+            Ok(final_tags) => Ok(ApiResponse {
+                success: true,
+                message: "Final tags auto-generated successfully".to_string(),
+                data: Some(final_tags),
+            }),
+            Err(e) => Err(format!("Database error: {}", e)),
+        }
+    }
+
+    // Free-text answers are candidates voted for by the labelers who wrote
+    // them; the strategy's pick with the most support wins, none on a tie
+    async fn generate_final_caption(
+        db: &DatabaseConnection,
+        image_id: i32,
+        strategy: &ConsensusStrategy,
+        reliability: &HashMap<i32, f64>,
+    ) -> Result<ApiResponse<Vec<FinalTagsModel>>, String> {
+        let captions = CaptionRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let submitters = SubmissionRepository::submitters_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        let mut candidates: Vec<(String, String)> = Vec::new();
+        let mut votes = Vec::new();
+        for caption in captions.iter().filter(|caption| submitters.contains(&caption.labeler_id)) {
+            let normalized = task::normalize_text(&caption.text);
+            let candidate = match candidates.iter().position(|(key, _)| *key == normalized) {
+                Some(candidate) => candidate,
+                None => {
+                    candidates.push((normalized, caption.text.clone()));
+                    candidates.len() - 1
+                }
+            };
+            votes.push((caption.labeler_id, candidate as i32));
+        }
+
+        let picked = strategy
+            .select_among(&submitters, &votes, reliability)
+            .into_iter()
+            .map(|candidate| (candidate, Self::support(strategy, &votes, reliability, candidate), ()))
+            .collect();
+        let winner = TaskType::SingleChoice.limit(picked).into_iter().next().map(|(candidate, _, _)| {
+            let voters = votes.iter().filter(|&&(_, voted)| voted == candidate).count();
+            (candidates[candidate as usize].1.clone(), voters as i32)
+        });
+        let reached = winner.is_some();
+        CaptionRepository::set_final(db, image_id, winner, false)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: if reached {
+                "Final caption auto-generated successfully".to_string()
+            } else {
+                "No caption reached consensus".to_string()
+            },
+            data: Some(vec![]),
+        })
+    }
+
+    // Votes for a choice, each weighted by the labeler's reliability when
+    // the strategy weighs labelers
+    fn support(strategy: &ConsensusStrategy, votes: &[(i32, i32)], reliability: &HashMap<i32, f64>, choice: i32) -> f64 {
+        votes
+            .iter()
+            .filter(|&&(_, voted)| voted == choice)
+            .map(|(labeler_id, _)| match strategy {
+                ConsensusStrategy::WeightedReliability { .. } => {
+                    reliability.get(labeler_id).copied().unwrap_or(DEFAULT_RELIABILITY)
+                }
+                _ => 1.0,
+            })
+            .sum()
+    }
+
+    /// Estimates how often each labeler of a group agrees with the admin:
+    /// every (image, tag) pair of an image whose final tags an admin set is
+    /// one decision the labeler got right or wrong. The rate is smoothed
+    /// with one right and one wrong pseudo-decision, so labelers without
+    /// history get `consensus::DEFAULT_RELIABILITY`.
+    pub async fn labeler_reliability(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<HashMap<i32, f64>, String> {
+        let final_tags = FinalTagsRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let truth: HashSet<(i32, i32)> = final_tags
+            .iter()
+            .filter(|tag| tag.is_admin_override)
+            .map(|tag| (tag.image_id, tag.tag_id))
+            .collect();
+        let reviewed: HashSet<i32> = truth.iter().map(|&(image_id, _)| image_id).collect();
+
+        let group_tags = TagRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let image_tags = ImageTagsRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let submitters = SubmissionRepository::submitters_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        // Images each labeler submitted, and the tags they chose
+        let mut labeled: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for (image_id, labelers) in submitters.iter().filter(|(image_id, _)| reviewed.contains(image_id)) {
+            for &labeler_id in labelers.keys() {
+                labeled.entry(labeler_id).or_default().insert(*image_id);
+            }
+        }
+        let chosen: HashSet<(i32, i32, i32)> = image_tags
+            .iter()
+            .map(|image_tag| (image_tag.labeler_id, image_tag.image_id, image_tag.tag_id))
+            .collect();
+
+        Ok(labeled
+            .into_iter()
+            .map(|(labeler_id, images)| {
+                let mut agreed = 0;
+                let mut decisions = 0;
+                for &image_id in &images {
+                    for tag in &group_tags {
+                        let labeler_says = chosen.contains(&(labeler_id, image_id, tag.id));
+                        let admin_says = truth.contains(&(image_id, tag.id));
+                        agreed += (labeler_says == admin_says) as usize;
+                        decisions += 1;
+                    }
+                }
+                (labeler_id, (agreed as f64 + 1.0) / (decisions as f64 + 2.0))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,172 @@
+use sea_orm::DatabaseConnection;
+use crate::entity::image::Model as ImageModel;
+use crate::imaging::hash;
+use crate::repository::{GroupRepository, ImageRepository};
+use crate::schemas::admin::{ApiResponse, DuplicateClusterResponse, DuplicateImageResponse, DuplicateReportResponse};
+use crate::service::image::ImageService;
+
+/// Hamming distance (out of 64 bits) under which images are reported as
+/// near-duplicates when the request does not pick one.
+pub const DEFAULT_THRESHOLD: u32 = 10;
+
+/// Perceptual hash compared by the near-duplicate report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashKind {
+    Average,
+    Difference,
+}
+
+impl HashKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "ahash" | "average" => Some(Self::Average),
+            "dhash" | "difference" => Some(Self::Difference),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Average => "ahash",
+            Self::Difference => "dhash",
+        }
+    }
+
+    fn of(&self, image: &ImageModel) -> Option<u64> {
+        let hex = match self {
+            Self::Average => image.average_hash.as_deref(),
+            Self::Difference => image.difference_hash.as_deref(),
+        };
+        hex.and_then(hash::from_hex)
+    }
+}
+
+pub struct DuplicateService;
+
+impl DuplicateService {
+    /// Groups the images of `group_id` into clusters of exact duplicates and
+    /// images whose hashes are at most `threshold` bits apart. Images only
+    /// need to be close to one other member, so a cluster can span more than
+    /// `threshold` overall.
+    pub async fn report(
+        db: &DatabaseConnection,
+        group_id: i32,
+        kind: HashKind,
+        threshold: u32,
+    ) -> Result<ApiResponse<DuplicateReportResponse>, String> {
+        match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(_)) => {} // Group exists, continue
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Group not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        }
+
+        if threshold > 64 {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Threshold must be between 0 and 64".to_string(),
+                data: None,
+            });
+        }
+
+        let mut images = ImageRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        images.sort_by_key(|image| image.id);
+        let hashes: Vec<Option<u64>> = images.iter().map(|image| kind.of(image)).collect();
+
+        // Pairs of the same content, or with close hashes, share a cluster
+        let distance = |a: usize, b: usize| -> Option<u32> {
+            if images[a].storage_key.is_some() && images[a].storage_key == images[b].storage_key {
+                return Some(0);
+            }
+            Some(hash::hamming_distance(hashes[a]?, hashes[b]?))
+        };
+        let mut clusters = DisjointSet::new(images.len());
+        for a in 0..images.len() {
+            for b in a + 1..images.len() {
+                if distance(a, b).is_some_and(|d| d <= threshold) {
+                    clusters.union(a, b);
+                }
+            }
+        }
+
+        let mut members: Vec<Vec<usize>> = vec![Vec::new(); images.len()];
+        for i in 0..images.len() {
+            members[clusters.find(i)].push(i);
+        }
+
+        // Roots are the lowest index of their cluster, so clusters come out
+        // ordered by their oldest image
+        let clusters = members
+            .into_iter()
+            .filter(|cluster| cluster.len() > 1)
+            .map(|cluster| {
+                let first = cluster[0];
+                let max_distance = cluster
+                    .iter()
+                    .flat_map(|&a| cluster.iter().map(move |&b| (a, b)))
+                    .filter_map(|(a, b)| distance(a, b))
+                    .max()
+                    .unwrap_or(0);
+                let exact = cluster.iter().all(|&i| images[i].storage_key == images[first].storage_key);
+                let images = cluster
+                    .iter()
+                    .map(|&i| DuplicateImageResponse {
+                        id: images[i].id,
+                        filename: images[i].filename.clone(),
+                        thumbnail_url: ImageService::thumbnail_url(images[i].id),
+                        content_hash: images[i].storage_key.clone().unwrap_or_default(),
+                        perceptual_hash: hashes[i].map(hash::to_hex),
+                        distance: distance(first, i).unwrap_or(0),
+                        duplicate_of: images[i].duplicate_of,
+                    })
+                    .collect();
+                DuplicateClusterResponse { max_distance, exact, images }
+            })
+            .collect();
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Duplicate report generated successfully".to_string(),
+            data: Some(DuplicateReportResponse {
+                group_id,
+                hash: kind.name().to_string(),
+                threshold,
+                clusters,
+                unhashed_images: hashes.iter().filter(|hash| hash.is_none()).count(),
+            }),
+        })
+    }
+}
+
+// Union-find over image indices; the root of a set is its smallest index
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect() }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a.max(b)] = a.min(b);
+        }
+    }
+}
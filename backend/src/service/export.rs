@@ -1,11 +1,28 @@
 use sea_orm::DatabaseConnection;
-use crate::repository::{GroupRepository, ImageRepository, FinalTagsRepository, TagRepository, ImageTagsRepository};
-use crate::schemas::export::{ExportData, GroupData, ImageData, TagStatistic};
+use crate::repository::{
+    CaptionRepository, GroupRepository, ImageRepository, FinalTagsRepository, TagRepository, ImageTagsRepository, KeypointRepository,
+    RegionRepository, SegmentationRepository, SubmissionRepository,
+};
+use crate::schemas::export::{
+    CaptionPair, CocoAnnotation, CocoCategory, CocoDataset, CocoImage, CocoSegmentation, ExportData, GroupData, ImageData,
+    TagStatistic,
+};
+use crate::keypoint::{self, KeypointSchema};
+use crate::segmentation::Geometry;
+use crate::service::agreement::AgreementService;
+use crate::service::keypoint::KeypointService;
+use crate::service::region::RegionService;
+use crate::service::segmentation::SegmentationService;
+use crate::storage::{self, ImageStorage};
 
 pub struct ExportService;
 
 impl ExportService {
-    pub async fn bulk_export(db: &DatabaseConnection) -> Result<ExportData, String> {
+    pub async fn bulk_export(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        include_agreement: bool,
+    ) -> Result<ExportData, String> {
         let groups = GroupRepository::get_all(db).await
             .map_err(|_| "Failed to retrieve groups".to_string())?;
         
@@ -19,7 +36,8 @@ impl ExportService {
             let images = ImageRepository::get_by_group(db, group.id).await
                 .map_err(|_| format!("Failed to retrieve images for group {}", group.id))?;
             
-            for image in images {
+            // Gold images are quality checks, not part of the dataset
+            for image in images.into_iter().filter(|image| !image.is_gold) {
                 let image_id = image.id.to_string();
                 
                 // Get final tags for this image
@@ -32,19 +50,39 @@ impl ExportService {
                 let has_admin_override = FinalTagsRepository::has_admin_override(db, image.id).await
                     .map_err(|_| format!("Failed to check admin override for image {}", image.id))?;
                 
+                let final_regions = RegionRepository::get_final_by_image(db, image.id).await
+                    .map_err(|_| format!("Failed to retrieve regions for image {}", image.id))?;
+                let regions = RegionService::final_region_responses(db, group.id, final_regions).await?;
+                let caption = CaptionRepository::get_final(db, image.id).await
+                    .map_err(|_| format!("Failed to retrieve caption for image {}", image.id))?
+                    .map(|caption| caption.text);
+                
+                // Load the image content from storage
+                let base64 = storage::read_image_base64(storage, &image).await
+                    .map_err(|e| format!("Failed to read image {}: {}", image.id, e))?;
+                
                 group_data.insert(image_id, ImageData {
                     filename: image.filename,
                     filetype: image.filetype,
-                    base64: image.base64_data,
+                    base64,
                     uploaded_at: image.uploaded_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
                     final_tags,
                     tag_statistics,
                     has_admin_override,
+                    regions,
+                    caption,
                 });
             }
             
+            let agreement = if include_agreement {
+                Some(AgreementService::compute(db, group.id).await?)
+            } else {
+                None
+            };
+
             export_data.insert(group_id, GroupData {
                 images: group_data,
+                agreement,
             });
         }
         
@@ -53,6 +91,158 @@ impl ExportService {
         })
     }
     
+    /// Final masks, boxes and keypoints of a group in COCO format, or `None`
+    /// if the group does not exist. Gold images and images of unknown size
+    /// are left out.
+    pub async fn coco_export(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Option<CocoDataset>, String> {
+        let group = GroupRepository::find_by_id(db, group_id).await
+            .map_err(|_| "Failed to retrieve group".to_string())?;
+        if group.is_none() {
+            return Ok(None);
+        }
+
+        let categories = GroupRepository::get_possible_tags(db, group_id).await
+            .map_err(|_| format!("Failed to retrieve possible tags for group {}", group_id))?
+            .into_iter()
+            .map(|tag| {
+                let schema = tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json);
+                CocoCategory {
+                    id: tag.id,
+                    name: tag.name,
+                    keypoints: schema.as_ref().map(|schema| schema.keypoints.clone()),
+                    skeleton: schema.map(|schema| schema.skeleton),
+                }
+            })
+            .collect();
+
+        let images = ImageRepository::get_by_group(db, group_id).await
+            .map_err(|_| format!("Failed to retrieve images for group {}", group_id))?;
+        let mut coco_images = Vec::new();
+        let mut annotations = Vec::new();
+        for image in images.into_iter().filter(|image| !image.is_gold) {
+            let Some((width, height)) = SegmentationService::dimensions(&image) else {
+                continue;
+            };
+
+            let final_segmentations = SegmentationRepository::get_final_by_image(db, image.id).await
+                .map_err(|_| format!("Failed to retrieve segmentations for image {}", image.id))?;
+            for segmentation in final_segmentations {
+                let Some(geometry) = Geometry::from_json(&segmentation.geometry) else {
+                    continue;
+                };
+                let Some([x, y, w, h]) = geometry.to_mask(width, height).bbox() else {
+                    continue;
+                };
+                let (segmentation_json, iscrowd) = match geometry {
+                    Geometry::Polygon { points } => {
+                        (CocoSegmentation::Polygon(vec![points.into_iter().flatten().collect()]), 0)
+                    }
+                    Geometry::Rle { size, counts } => (CocoSegmentation::Rle { counts, size }, 1),
+                };
+                annotations.push(CocoAnnotation {
+                    id: annotations.len() as i32 + 1,
+                    image_id: image.id,
+                    category_id: segmentation.tag_id,
+                    segmentation: Some(segmentation_json),
+                    area: segmentation.area as f64,
+                    bbox: [x as f64, y as f64, w as f64, h as f64],
+                    iscrowd,
+                    keypoints: None,
+                    num_keypoints: None,
+                });
+            }
+
+            // Final boxes are stored normalized to the image size
+            let final_regions = RegionRepository::get_final_by_image(db, image.id).await
+                .map_err(|_| format!("Failed to retrieve regions for image {}", image.id))?;
+            for region in final_regions {
+                let bbox = [
+                    region.x * width as f64,
+                    region.y * height as f64,
+                    region.width * width as f64,
+                    region.height * height as f64,
+                ];
+                annotations.push(CocoAnnotation {
+                    id: annotations.len() as i32 + 1,
+                    image_id: image.id,
+                    category_id: region.tag_id,
+                    segmentation: None,
+                    area: bbox[2] * bbox[3],
+                    bbox,
+                    iscrowd: 0,
+                    keypoints: None,
+                    num_keypoints: None,
+                });
+            }
+
+            let final_keypoint_sets = KeypointRepository::get_final_by_image(db, image.id).await
+                .map_err(|_| format!("Failed to retrieve keypoints for image {}", image.id))?;
+            let final_keypoint_sets = KeypointService::final_keypoint_set_responses(db, group_id, final_keypoint_sets).await?;
+            for set in final_keypoint_sets {
+                let Some(bbox) = keypoint::bbox(&set.keypoints) else {
+                    continue;
+                };
+                annotations.push(CocoAnnotation {
+                    id: annotations.len() as i32 + 1,
+                    image_id: image.id,
+                    category_id: set.tag_id,
+                    segmentation: None,
+                    area: bbox[2] * bbox[3],
+                    bbox,
+                    iscrowd: 0,
+                    keypoints: Some(set.keypoints.iter().flat_map(|k| [k.0, k.1, k.2 as f64]).collect()),
+                    num_keypoints: Some(set.num_keypoints),
+                });
+            }
+
+            coco_images.push(CocoImage {
+                id: image.id,
+                file_name: image.filename,
+                width,
+                height,
+            });
+        }
+
+        Ok(Some(CocoDataset {
+            images: coco_images,
+            categories,
+            annotations,
+        }))
+    }
+
+    /// Final captions of a group as image–caption pairs, or `None` if the
+    /// group does not exist. Gold images and images without a final caption
+    /// are left out.
+    pub async fn caption_export(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<Option<Vec<CaptionPair>>, String> {
+        let group = GroupRepository::find_by_id(db, group_id).await
+            .map_err(|_| "Failed to retrieve group".to_string())?;
+        if group.is_none() {
+            return Ok(None);
+        }
+
+        let images = ImageRepository::get_by_group(db, group_id).await
+            .map_err(|_| format!("Failed to retrieve images for group {}", group_id))?;
+        let mut pairs = Vec::new();
+        for image in images.into_iter().filter(|image| !image.is_gold) {
+            let final_caption = CaptionRepository::get_final(db, image.id).await
+                .map_err(|_| format!("Failed to retrieve caption for image {}", image.id))?;
+            if let Some(final_caption) = final_caption {
+                pairs.push(CaptionPair {
+                    image_id: image.id,
+                    file_name: image.filename,
+                    caption: final_caption.text,
+                });
+            }
+        }
+        Ok(Some(pairs))
+    }
+
     async fn get_final_tags_for_image(db: &DatabaseConnection, image_id: i32) -> Result<Vec<String>, String> {
         let final_tags = FinalTagsRepository::get_by_image(db, image_id).await
             .map_err(|_| format!("Failed to retrieve final tags for image {}", image_id))?;
@@ -84,10 +274,9 @@ impl ExportService {
             *tag_counts.entry(image_tag.tag_id).or_insert(0) += 1;
         }
         
-        // Get total number of unique labelers who tagged this image
-        let total_labelers = image_tags.iter()
-            .map(|it| it.labeler_id)
-            .collect::<std::collections::HashSet<_>>()
+        // Get total number of labelers who submitted this image, tags or not
+        let total_labelers = SubmissionRepository::submitters_by_image(db, image_id).await
+            .map_err(|_| format!("Failed to retrieve submissions for image {}", image_id))?
             .len() as i32;
         
         // Get all possible tags for this group
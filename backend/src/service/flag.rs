@@ -0,0 +1,153 @@
+use sea_orm::DatabaseConnection;
+use std::collections::BTreeMap;
+use crate::repository::{
+    AssignmentRepository, GroupRepository, ImageFlagRepository, ImageRepository, LabelerRepository, FLAG_KIND_FLAG,
+};
+use crate::schemas::admin::{
+    ApiResponse as AdminApiResponse, FlaggedImageListResponse, FlaggedImageResponse, ImageFlagEntryResponse, ImageResponse,
+};
+use crate::schemas::labeler::{ApiResponse, FlagReason, ImageFlagRequest, ImageFlagResponse};
+use crate::service::image::ImageService;
+
+/// Longest note a labeler can attach to a skip or flag.
+const MAX_NOTE_LENGTH: usize = 1000;
+
+pub struct FlagService;
+
+impl FlagService {
+    /// Records that a labeler skipped (`kind` "skip") or flagged (`kind`
+    /// "flag") an image. Either way the image leaves their queue; flags also
+    /// go to the admins' review list.
+    pub async fn mark_image(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        kind: &str,
+        request: ImageFlagRequest,
+    ) -> Result<ApiResponse<ImageFlagResponse>, String> {
+        match LabelerRepository::get_groups(db, labeler_id).await {
+            Ok(groups) => {
+                if !groups.iter().any(|group| group.id == group_id) {
+                    return Err("You are not authorized to access this group".to_string());
+                }
+            }
+            Err(e) => return Err(format!("Failed to verify group access: {}", e)),
+        }
+        match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => {
+                if image.group_id != group_id {
+                    return Err("Image does not belong to this group".to_string());
+                }
+            }
+            Ok(None) => return Err("Image not found".to_string()),
+            Err(e) => return Err(format!("Failed to fetch image: {}", e)),
+        }
+
+        let note = request.note.map(|note| note.trim().to_string()).filter(|note| !note.is_empty());
+        if note.as_ref().is_some_and(|note| note.chars().count() > MAX_NOTE_LENGTH) {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("note must be at most {} characters", MAX_NOTE_LENGTH),
+                data: None,
+            });
+        }
+
+        let flag = ImageFlagRepository::set(db, image_id, labeler_id, kind, request.reason.as_str(), note)
+            .await
+            .map_err(|e| format!("Failed to save {}: {}", kind, e))?;
+        AssignmentRepository::release(db, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Failed to release assignment: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: if kind == FLAG_KIND_FLAG {
+                "Image flagged successfully".to_string()
+            } else {
+                "Image skipped successfully".to_string()
+            },
+            data: Some(ImageFlagResponse {
+                image_id,
+                kind: flag.kind,
+                reason: request.reason,
+                note: flag.note,
+                created_at: flag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            }),
+        })
+    }
+
+    /// Images flagged as problematic, in one group or in all of them, most
+    /// flagged first.
+    pub async fn flagged_images(
+        db: &DatabaseConnection,
+        group_id: Option<i32>,
+    ) -> Result<AdminApiResponse<FlaggedImageListResponse>, String> {
+        if let Some(group_id) = group_id {
+            match GroupRepository::find_by_id(db, group_id).await {
+                Ok(Some(_)) => {} // Group exists, continue
+                Ok(None) => {
+                    return Ok(AdminApiResponse {
+                        success: false,
+                        message: "Group not found".to_string(),
+                        data: None,
+                    });
+                }
+                Err(e) => return Err(format!("Database error: {}", e)),
+            }
+        }
+
+        let flags = ImageFlagRepository::get_by_kind(db, FLAG_KIND_FLAG, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let mut by_image: BTreeMap<i32, Vec<ImageFlagEntryResponse>> = BTreeMap::new();
+        for flag in flags {
+            let username = LabelerRepository::find_by_id(db, flag.labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+            by_image.entry(flag.image_id).or_default().push(ImageFlagEntryResponse {
+                labeler_id: flag.labeler_id,
+                username,
+                reason: FlagReason::parse(&flag.reason).unwrap_or(FlagReason::Other),
+                note: flag.note,
+                created_at: flag.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            });
+        }
+
+        let mut images = Vec::new();
+        for (image_id, flags) in by_image {
+            let Some(image) = ImageRepository::find_by_id(db, image_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+            else {
+                continue;
+            };
+            images.push(FlaggedImageResponse {
+                group_id: image.group_id,
+                image: ImageResponse {
+                    id: image.id,
+                    filename: image.filename,
+                    filetype: image.filetype,
+                    uploaded_at: image.uploaded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    content_url: ImageService::content_url(image.id),
+                    thumbnail_url: ImageService::thumbnail_url(image.id),
+                    width: image.width,
+                    height: image.height,
+                    color_mode: image.color_mode,
+                    orientation: image.orientation,
+                    duplicate_of: image.duplicate_of,
+                },
+                flags,
+            });
+        }
+        images.sort_by(|a, b| b.flags.len().cmp(&a.flags.len()).then(a.image.id.cmp(&b.image.id)));
+
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Flagged images retrieved successfully".to_string(),
+            data: Some(FlaggedImageListResponse { images }),
+        })
+    }
+}
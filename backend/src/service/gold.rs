@@ -0,0 +1,200 @@
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use std::collections::{BTreeMap, HashSet};
+use crate::entity::image::Model as ImageModel;
+use crate::repository::{GoldRepository, GroupRepository, ImageRepository, LabelerRepository, TagRepository};
+use crate::schemas::admin::{ApiResponse, GoldImageResponse, SetGoldRequest};
+use crate::schemas::analytics::{GoldReportResponse, LabelerGoldResponse};
+
+pub struct GoldService;
+
+impl GoldService {
+    /// Marks an image as gold with its known tags, or turns it back into a
+    /// regular image.
+    pub async fn set_gold(
+        db: &DatabaseConnection,
+        image_id: i32,
+        request: SetGoldRequest,
+    ) -> Result<ApiResponse<GoldImageResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let mut tag_ids = Vec::new();
+        if request.is_gold {
+            let Some(requested) = request.tag_ids else {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Gold images need their known tag_ids".to_string(),
+                    data: None,
+                });
+            };
+            let group_tags: HashSet<i32> = TagRepository::get_by_group(db, image.group_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .into_iter()
+                .map(|tag| tag.id)
+                .collect();
+            if let Some(tag_id) = requested.iter().find(|tag_id| !group_tags.contains(tag_id)) {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: format!("Tag {} does not belong to the image's group", tag_id),
+                    data: None,
+                });
+            }
+            tag_ids = requested;
+            tag_ids.sort_unstable();
+            tag_ids.dedup();
+        }
+
+        ImageRepository::set_gold(db, image_id, request.is_gold)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        GoldRepository::replace_tags(db, image_id, tag_ids.clone())
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: if request.is_gold {
+                "Image marked as gold".to_string()
+            } else {
+                "Image is no longer gold".to_string()
+            },
+            data: Some(GoldImageResponse {
+                image_id,
+                is_gold: request.is_gold,
+                tag_ids,
+            }),
+        })
+    }
+
+    /// Records whether `tag_ids` exactly match the known tags of a gold
+    /// image, and suspends the labeler once their rolling accuracy over a
+    /// full window drops below the group's minimum. Resubmissions are not
+    /// scored; the labeler's first answer on the image stands.
+    pub async fn score_answer<C: ConnectionTrait>(
+        db: &C,
+        labeler_id: i32,
+        image: &ImageModel,
+        tag_ids: &[i32],
+    ) -> Result<bool, String> {
+        let known: HashSet<i32> = GoldRepository::get_tags(db, image.id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|tag| tag.tag_id)
+            .collect();
+        let correct = known == tag_ids.iter().copied().collect();
+        let recorded = GoldRepository::record_result(db, labeler_id, image.id, correct)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        if !recorded {
+            return Ok(correct);
+        }
+
+        let Some(group) = GroupRepository::find_by_id(db, image.group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+        else {
+            return Ok(correct);
+        };
+        let Some(min_accuracy) = group.gold_min_accuracy else {
+            return Ok(correct);
+        };
+
+        let window = GoldRepository::recent_results(db, labeler_id, group.id, group.gold_window.max(1) as u64)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        if window.len() < group.gold_window.max(1) as usize {
+            return Ok(correct);
+        }
+        let accuracy = window.iter().filter(|result| result.correct).count() as f64 / window.len() as f64;
+        if accuracy < min_accuracy {
+            let reason = format!(
+                "Gold accuracy {:.0}% over the last {} answers in group {} is below {:.0}%",
+                accuracy * 100.0,
+                window.len(),
+                group.name,
+                min_accuracy * 100.0,
+            );
+            LabelerRepository::set_suspended(db, labeler_id, true, Some(reason))
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+        }
+
+        Ok(correct)
+    }
+
+    /// Gold accuracy of every labeler who answered a gold image of the group.
+    pub async fn group_report(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<ApiResponse<GoldReportResponse>, String> {
+        let group = match GroupRepository::find_by_id(db, group_id).await {
+            Ok(Some(group)) => group,
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Group not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let gold_images = ImageRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .iter()
+            .filter(|image| image.is_gold)
+            .count();
+        let results = GoldRepository::get_results_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        // Answers of each labeler, oldest first
+        let mut answers: BTreeMap<i32, Vec<bool>> = BTreeMap::new();
+        for result in results {
+            answers.entry(result.labeler_id).or_default().push(result.correct);
+        }
+
+        let window = group.gold_window.max(1) as usize;
+        let mut labelers = Vec::new();
+        for (labeler_id, answers) in answers {
+            let labeler = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            let correct = answers.iter().filter(|&&correct| correct).count();
+            let recent = &answers[answers.len().saturating_sub(window)..];
+            labelers.push(LabelerGoldResponse {
+                labeler_id,
+                username: labeler.as_ref().map(|labeler| labeler.username.clone()).unwrap_or_default(),
+                answered: answers.len(),
+                correct,
+                accuracy: correct as f64 / answers.len() as f64,
+                rolling_accuracy: recent.iter().filter(|&&correct| correct).count() as f64 / recent.len() as f64,
+                suspended: labeler.is_some_and(|labeler| labeler.suspended),
+            });
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Gold report computed successfully".to_string(),
+            data: Some(GoldReportResponse {
+                group_id,
+                gold_images,
+                gold_window: group.gold_window,
+                gold_min_accuracy: group.gold_min_accuracy,
+                labelers,
+            }),
+        })
+    }
+}
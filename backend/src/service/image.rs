@@ -0,0 +1,94 @@
+use sea_orm::DatabaseConnection;
+use crate::entity::image::Model as ImageModel;
+use crate::imaging::ImageFormat;
+use crate::middleware::auth;
+use crate::repository::{ImageListQuery, ImageRepository, ImageSort, LabelerRepository, PageRequest};
+use crate::schemas::auth::Claims;
+use crate::schemas::pagination::ListQuery;
+
+pub struct ImageService;
+
+impl ImageService {
+    /// Loads an image without checking who asks for it, e.g. for a request
+    /// whose signed URL already grants access.
+    pub async fn get_image(db: &DatabaseConnection, image_id: i32) -> Result<ImageModel, String> {
+        match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => Ok(image),
+            Ok(None) => Err("Image not found".to_string()),
+            Err(e) => {
+                eprintln!("Error fetching image {}: {}", image_id, e);
+                Err(format!("Failed to fetch image: {}", e))
+            }
+        }
+    }
+
+    /// Loads an image if the caller may see it: admins can access every
+    /// image, labelers only images of groups they belong to.
+    pub async fn get_authorized_image(
+        db: &DatabaseConnection,
+        claims: &Claims,
+        image_id: i32,
+    ) -> Result<ImageModel, String> {
+        let image = Self::get_image(db, image_id).await?;
+
+        match claims.role.as_str() {
+            "admin" => Ok(image),
+            "labeler" => match LabelerRepository::get_groups(db, claims.user_id).await {
+                Ok(groups) => {
+                    if groups.iter().any(|group| group.id == image.group_id) {
+                        Ok(image)
+                    } else {
+                        Err("You are not authorized to access this image".to_string())
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Error verifying group access for labeler {}: {}", claims.user_id, e);
+                    Err(format!("Failed to verify group access: {}", e))
+                }
+            },
+            _ => Err("You are not authorized to access this image".to_string()),
+        }
+    }
+
+    /// Signed URL of the binary content endpoint for an image. Only hand
+    /// it to callers that may see the image.
+    pub fn content_url(image_id: i32) -> String {
+        auth::sign_url(&format!("/api/v1/images/{}/content", image_id))
+    }
+
+    /// Signed URL of the thumbnail endpoint for an image. Only hand it to
+    /// callers that may see the image.
+    pub fn thumbnail_url(image_id: i32) -> String {
+        auth::sign_url(&format!("/api/v1/images/{}/thumbnail", image_id))
+    }
+
+    /// Validates the paging, sorting and filter parameters of an image list.
+    /// Labelers filter on their own work (`done`/`pending`); admins on
+    /// whether anyone tagged the image (`labeled`/`unlabeled`).
+    pub fn list_query(query: &ListQuery, labeler_id: Option<i32>) -> Result<(ImageListQuery, PageRequest), String> {
+        let labeled = match (query.status.as_deref().map(str::to_lowercase).as_deref(), labeler_id) {
+            (None, _) => None,
+            (Some("done"), Some(_)) | (Some("labeled"), None) => Some(true),
+            (Some("pending"), Some(_)) | (Some("unlabeled"), None) => Some(false),
+            (Some(_), Some(_)) => return Err("Invalid status. Expected done or pending".to_string()),
+            (Some(_), None) => return Err("Invalid status. Expected labeled or unlabeled".to_string()),
+        };
+
+        let list_query = ImageListQuery {
+            sort: query.sort(ImageSort::parse, "id, uploaded_at, filename, size")?,
+            order: query.order()?,
+            labeled,
+            labeled_by: labeler_id,
+            has_final_tags: query.has_final_tags,
+            has_admin_override: query.has_admin_override,
+        };
+        Ok((list_query, query.page()?))
+    }
+
+    /// MIME type to serve an image with, based on its stored `filetype`.
+    pub fn content_type(filetype: &str) -> &'static str {
+        ImageFormat::from_filetype(filetype)
+            .map(|format| format.mime())
+            .unwrap_or("application/octet-stream")
+    }
+}
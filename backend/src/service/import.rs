@@ -0,0 +1,350 @@
+use crate::entity::import_job::Model as ImportJobModel;
+use crate::import::{mapping, TagMapping, ZipArchive, ZipEntry};
+use crate::repository::{FinalTagsRepository, ImportJobRepository, TagRepository};
+use crate::schemas::admin::{ApiResponse, ImportEntryError, ImportJobResponse, ZipImportRequest};
+use crate::service::admin::AdminService;
+use crate::storage::ImageStorage;
+use sea_orm::DatabaseConnection;
+use std::collections::HashMap;
+use std::fs::File;
+use std::sync::Arc;
+
+pub struct ImportService;
+
+// State of a running import; the counters are written back to its
+// `import_job` row after every entry
+#[derive(Default)]
+struct ImportProgress {
+    tag_ids: HashMap<String, i32>,
+    processed_entries: i32,
+    created_images: i32,
+    created_tags: i32,
+    errors: Vec<ImportEntryError>,
+}
+
+// How a running import treats each entry
+struct ImportOptions {
+    group_id: i32,
+    create_missing_tags: bool,
+    seed_final_tags: bool,
+    tag_mapping: TagMapping,
+}
+
+impl ImportService {
+    /// Validates the archive and mapping, records an import job and starts
+    /// processing it in the background. The returned job can be polled with
+    /// [`ImportService::get_job`].
+    pub async fn start_zip_import(
+        db: &DatabaseConnection,
+        storage: Arc<dyn ImageStorage>,
+        request: ZipImportRequest,
+        max_file_size: usize,
+    ) -> Result<ApiResponse<ImportJobResponse>, String> {
+        // Verify group exists
+        if let Some(response) = AdminService::check_upload_group(db, request.group_id).await? {
+            return Ok(response);
+        }
+
+        let file = request.archive;
+        let archive = tokio::task::spawn_blocking(move || ZipArchive::new(file))
+            .await
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+        let mut archive = match archive {
+            Ok(archive) => archive,
+            Err(e) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: format!("Invalid ZIP archive: {}", e),
+                    data: None,
+                });
+            }
+        };
+
+        // An explicit mapping file wins over a sidecar at the archive root
+        let sidecar = archive
+            .entries()
+            .iter()
+            .find(|entry| {
+                !entry.name.contains('/')
+                    && matches!(mapping::extension(&entry.name).as_deref(), Some("csv") | Some("json"))
+            })
+            .cloned();
+        let tag_mapping = match (&request.mapping, &sidecar) {
+            (Some((filename, bytes)), _) => TagMapping::parse(filename, bytes),
+            (None, Some(entry)) => {
+                let bytes;
+                (archive, bytes) = read_entry(archive, entry.clone(), max_file_size).await?;
+                bytes.and_then(|bytes| TagMapping::parse(&entry.name, &bytes))
+            }
+            (None, None) => Ok(TagMapping::default()),
+        };
+        let tag_mapping = match tag_mapping {
+            Ok(tag_mapping) => tag_mapping,
+            Err(e) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: format!("Invalid tag mapping: {}", e),
+                    data: None,
+                });
+            }
+        };
+
+        let entries: Vec<ZipEntry> = archive
+            .entries()
+            .iter()
+            .filter(|entry| !entry.is_dir() && !is_hidden(&entry.name))
+            .filter(|entry| request.mapping.is_some() || sidecar.as_ref().is_none_or(|s| s.name != entry.name))
+            .cloned()
+            .collect();
+
+        let job = ImportJobRepository::create(db, request.group_id, entries.len() as i32)
+            .await
+            .map_err(|e| format!("Failed to create import job: {}", e))?;
+
+        let options = ImportOptions {
+            group_id: request.group_id,
+            create_missing_tags: request.create_missing_tags,
+            seed_final_tags: request.seed_final_tags,
+            tag_mapping,
+        };
+        let job_id = job.id;
+        let task_db = db.clone();
+        let task = tokio::spawn(async move {
+            Self::run_zip_import(&task_db, storage.as_ref(), job_id, archive, entries, options, max_file_size).await
+        });
+
+        // A panicking import never reaches its own error handling, so the
+        // job is failed from here instead
+        let db = db.clone();
+        tokio::spawn(async move {
+            let message = match task.await {
+                Ok(Ok(())) => return,
+                Ok(Err(e)) => e,
+                Err(e) => format!("Import stopped unexpectedly: {}", e),
+            };
+            eprintln!("Import job {} failed: {}", job_id, message);
+            if let Err(e) = ImportJobRepository::finish(&db, job_id, "failed", Some(message)).await {
+                eprintln!("Failed to record failure of import job {}: {}", job_id, e);
+            }
+        });
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Import started".to_string(),
+            data: Some(Self::job_response(job)),
+        })
+    }
+
+    /// Fails the jobs a previous run of the server left pending or running,
+    /// since nothing is processing them anymore. Returns how many there were.
+    pub async fn fail_interrupted_jobs(db: &DatabaseConnection) -> Result<usize, String> {
+        let jobs = ImportJobRepository::get_unfinished(db)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        for job in &jobs {
+            ImportJobRepository::finish(db, job.id, "failed", Some("Interrupted by a server restart".to_string()))
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+        }
+        Ok(jobs.len())
+    }
+
+    async fn run_zip_import(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        job_id: i32,
+        mut archive: ZipArchive<File>,
+        entries: Vec<ZipEntry>,
+        options: ImportOptions,
+        max_file_size: usize,
+    ) -> Result<(), String> {
+        let tag_ids = TagRepository::get_by_group(db, options.group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|tag| (tag.name, tag.id))
+            .collect();
+
+        let mut progress = ImportProgress {
+            tag_ids,
+            ..Default::default()
+        };
+        Self::save_progress(db, job_id, "running", &progress).await?;
+
+        for entry in &entries {
+            let bytes;
+            (archive, bytes) = read_entry(archive, entry.clone(), max_file_size).await?;
+            let result = match bytes {
+                Ok(bytes) => Self::import_entry(db, storage, &options, &entry.name, bytes, &mut progress).await,
+                Err(e) => Err(e),
+            };
+
+            if let Err(message) = result {
+                progress.errors.push(ImportEntryError {
+                    entry: entry.name.clone(),
+                    message,
+                });
+            }
+            progress.processed_entries += 1;
+            Self::save_progress(db, job_id, "running", &progress).await?;
+        }
+
+        let message = format!(
+            "Imported {} of {} entries ({} errors)",
+            progress.created_images,
+            entries.len(),
+            progress.errors.len()
+        );
+        ImportJobRepository::finish(db, job_id, "completed", Some(message))
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(())
+    }
+
+    // Stores one archive entry as an image and applies its mapped tags
+    async fn import_entry(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        options: &ImportOptions,
+        name: &str,
+        bytes: Vec<u8>,
+        progress: &mut ImportProgress,
+    ) -> Result<(), String> {
+        let filename = name.rsplit('/').next().unwrap_or(name).to_string();
+        let filetype = mapping::extension(&filename).unwrap_or_default();
+
+        let image = match AdminService::store_image(db, storage, options.group_id, filename, filetype, bytes).await? {
+            ApiResponse { success: true, data: Some(image), .. } => image,
+            response => return Err(response.message),
+        };
+        progress.created_images += 1;
+
+        let Some(tags) = options.tag_mapping.tags_for(name) else {
+            return Ok(());
+        };
+
+        let mut resolved = Vec::new();
+        let mut unknown = Vec::new();
+        for tag in tags {
+            if let Some(id) = progress.tag_ids.get(tag) {
+                resolved.push(*id);
+            } else if options.create_missing_tags {
+                let created = TagRepository::create(db, tag.clone(), None, options.group_id)
+                    .await
+                    .map_err(|e| format!("Failed to create tag {}: {}", tag, e))?;
+                progress.tag_ids.insert(created.name, created.id);
+                progress.created_tags += 1;
+                resolved.push(created.id);
+            } else {
+                unknown.push(tag.as_str());
+            }
+        }
+
+        // Imported labels stand in for consensus until it is computed. They
+        // are not an admin decision, so scoring does not take them as truth
+        if options.seed_final_tags && !resolved.is_empty() {
+            FinalTagsRepository::replace_final_tags(db, image.id, resolved, false)
+                .await
+                .map_err(|e| format!("Failed to seed final tags: {}", e))?;
+        }
+
+        if !unknown.is_empty() {
+            return Err(format!("Image imported without unknown tags: {}", unknown.join(", ")));
+        }
+
+        Ok(())
+    }
+
+    async fn save_progress(
+        db: &DatabaseConnection,
+        job_id: i32,
+        status: &str,
+        progress: &ImportProgress,
+    ) -> Result<(), String> {
+        let errors = serde_json::to_string(&progress.errors)
+            .map_err(|e| format!("Failed to serialize import errors: {}", e))?;
+        ImportJobRepository::update_progress(
+            db,
+            job_id,
+            status,
+            progress.processed_entries,
+            progress.created_images,
+            progress.created_tags,
+            errors,
+        )
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn get_job(
+        db: &DatabaseConnection,
+        job_id: i32,
+    ) -> Result<ApiResponse<ImportJobResponse>, String> {
+        match ImportJobRepository::find_by_id(db, job_id).await {
+            Ok(Some(job)) => Ok(ApiResponse {
+                success: true,
+                message: "Import job retrieved successfully".to_string(),
+                data: Some(Self::job_response(job)),
+            }),
+            Ok(None) => Ok(ApiResponse {
+                success: false,
+                message: "Import job not found".to_string(),
+                data: None,
+            }),
+            Err(e) => Err(format!("Database error: {}", e)),
+        }
+    }
+
+    pub async fn list_jobs(
+        db: &DatabaseConnection,
+        group_id: i32,
+    ) -> Result<ApiResponse<Vec<ImportJobResponse>>, String> {
+        match ImportJobRepository::get_by_group(db, group_id).await {
+            Ok(jobs) => Ok(ApiResponse {
+                success: true,
+                message: "Import jobs retrieved successfully".to_string(),
+                data: Some(jobs.into_iter().map(Self::job_response).collect()),
+            }),
+            Err(e) => Err(format!("Database error: {}", e)),
+        }
+    }
+
+    fn job_response(job: ImportJobModel) -> ImportJobResponse {
+        ImportJobResponse {
+            id: job.id,
+            group_id: job.group_id,
+            status: job.status,
+            total_entries: job.total_entries,
+            processed_entries: job.processed_entries,
+            created_images: job.created_images,
+            created_tags: job.created_tags,
+            errors: serde_json::from_str(&job.errors).unwrap_or_default(),
+            message: job.message,
+            created_at: job.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            finished_at: job.finished_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+        }
+    }
+}
+
+// Decompresses an entry off the async runtime, handing the archive back
+// along with the entry's bytes
+async fn read_entry(
+    mut archive: ZipArchive<File>,
+    entry: ZipEntry,
+    max_size: usize,
+) -> Result<(ZipArchive<File>, Result<Vec<u8>, String>), String> {
+    tokio::task::spawn_blocking(move || {
+        let bytes = archive.read(&entry, max_size);
+        (archive, bytes)
+    })
+    .await
+    .map_err(|e| format!("Failed to read archive: {}", e))
+}
+
+// Skips OS metadata such as `__MACOSX/` folders and dotfiles
+fn is_hidden(path: &str) -> bool {
+    path.split('/').any(|part| part.starts_with('.') || part == "__MACOSX")
+}
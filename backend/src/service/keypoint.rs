@@ -0,0 +1,329 @@
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeMap, HashMap};
+use crate::entity::final_keypoint_set::Model as FinalKeypointSetModel;
+use crate::entity::keypoint_set::Model as KeypointSetModel;
+use crate::entity::tag::Model as TagModel;
+use crate::keypoint::{self, Keypoint, KeypointSchema, DEFAULT_OUTLIER_FRACTION};
+use crate::repository::{ImageRepository, KeypointRepository, LabelerRepository, TagRepository};
+use crate::schemas::admin::{
+    ApiResponse as AdminApiResponse, FinalKeypointSetResponse, ImageKeypointsResponse, KeypointAggregationRequest,
+    KeypointAggregationResponse, KeypointOutlierResponse, LabelerKeypointsResponse,
+};
+use crate::schemas::labeler::{ApiResponse, KeypointSetRequest, KeypointSetResponse};
+use crate::service::labeler::LabelerService;
+use crate::service::segmentation::SegmentationService;
+
+pub struct KeypointService;
+
+impl KeypointService {
+    /// Keypoint sets the labeler placed on an image.
+    pub async fn list_keypoint_sets(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+    ) -> Result<ApiResponse<Vec<KeypointSetResponse>>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let sets = KeypointRepository::get_by_image_and_labeler(db, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Keypoints retrieved successfully".to_string(),
+            data: Some(sets.into_iter().map(Self::keypoint_set_response).collect()),
+        })
+    }
+
+    /// Saves the labeler's keypoint set of a tag, replacing the previous one.
+    pub async fn save_keypoint_set(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        request: KeypointSetRequest,
+    ) -> Result<ApiResponse<KeypointSetResponse>, String> {
+        let image = LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let schema = match Self::keypoint_schema(db, group_id, request.tag_id).await? {
+            Ok(schema) => schema,
+            Err(message) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+        let Some((width, height)) = SegmentationService::dimensions(&image) else {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Image dimensions are unknown".to_string(),
+                data: None,
+            });
+        };
+        if let Err(message) = keypoint::validate(&request.keypoints, &schema, width, height) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
+        let set = KeypointRepository::save(db, image_id, labeler_id, request.tag_id, &request.keypoints)
+            .await
+            .map_err(|e| format!("Failed to save keypoints: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Keypoints saved successfully".to_string(),
+            data: Some(Self::keypoint_set_response(set)),
+        })
+    }
+
+    pub async fn delete_keypoint_set(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        tag_id: i32,
+    ) -> Result<ApiResponse<()>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let deleted = KeypointRepository::delete(db, image_id, labeler_id, tag_id)
+            .await
+            .map_err(|e| format!("Failed to delete keypoints: {}", e))?;
+        if !deleted {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Keypoints not found".to_string(),
+                data: None,
+            });
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Keypoints deleted successfully".to_string(),
+            data: Some(()),
+        })
+    }
+
+    /// Every labeler's keypoint sets on an image, and its final ones.
+    pub async fn image_keypoints(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<AdminApiResponse<ImageKeypointsResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let mut by_labeler: BTreeMap<i32, Vec<KeypointSetResponse>> = BTreeMap::new();
+        for set in KeypointRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+        {
+            by_labeler.entry(set.labeler_id).or_default().push(Self::keypoint_set_response(set));
+        }
+        let mut labelers = Vec::new();
+        for (labeler_id, keypoint_sets) in by_labeler {
+            let username = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+            labelers.push(LabelerKeypointsResponse {
+                labeler_id,
+                username,
+                keypoint_sets,
+            });
+        }
+
+        let final_sets = KeypointRepository::get_final_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Keypoints retrieved successfully".to_string(),
+            data: Some(ImageKeypointsResponse {
+                image_id,
+                width: image.width,
+                height: image.height,
+                labelers,
+                final_keypoint_sets: Self::final_keypoint_set_responses(db, image.group_id, final_sets).await?,
+            }),
+        })
+    }
+
+    /// Replaces the final keypoint sets of an image with the per-point
+    /// medians of its labelers' sets, and reports the outliers left out.
+    pub async fn aggregate_keypoints(
+        db: &DatabaseConnection,
+        image_id: i32,
+        request: KeypointAggregationRequest,
+    ) -> Result<AdminApiResponse<KeypointAggregationResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        let Some((width, height)) = SegmentationService::dimensions(&image) else {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "Image dimensions are unknown".to_string(),
+                data: None,
+            });
+        };
+        let outlier_distance = request
+            .outlier_distance
+            .unwrap_or(DEFAULT_OUTLIER_FRACTION * (width as f64).hypot(height as f64));
+        if !(outlier_distance > 0.0 && outlier_distance.is_finite()) {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "outlier_distance must be greater than 0".to_string(),
+                data: None,
+            });
+        }
+        if request.min_votes == Some(0) {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "min_votes must be at least 1".to_string(),
+                data: None,
+            });
+        }
+
+        let tags: Vec<(TagModel, KeypointSchema)> = TagRepository::get_by_group(db, image.group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|tag| request.tag_id.is_none_or(|tag_id| tag.id == tag_id))
+            .filter_map(|tag| {
+                let schema = KeypointSchema::from_json(tag.keypoint_schema.as_deref()?)?;
+                Some((tag, schema))
+            })
+            .collect();
+        if let Some(tag_id) = request.tag_id
+            && tags.is_empty() {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: format!("Tag {} is not a keypoint tag of this group", tag_id),
+                data: None,
+            });
+        }
+
+        let sets = KeypointRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        let mut final_sets = Vec::new();
+        let mut outliers = Vec::new();
+        for (tag, schema) in &tags {
+            let votes: Vec<(i32, Vec<Keypoint>)> = sets
+                .iter()
+                .filter(|set| set.tag_id == tag.id)
+                .filter_map(|set| Some((set.labeler_id, Self::keypoints(&set.keypoints)?)))
+                .filter(|(_, keypoints)| keypoints.len() == schema.keypoints.len())
+                .collect();
+            if votes.is_empty() {
+                continue;
+            }
+
+            let min_votes = request.min_votes.unwrap_or(votes.len().div_ceil(2));
+            let aggregated = keypoint::aggregate(&votes, outlier_distance, min_votes);
+            outliers.extend(aggregated.outliers.into_iter().map(|outlier| KeypointOutlierResponse {
+                tag_id: tag.id,
+                labeler_id: outlier.labeler_id,
+                keypoint: schema.keypoints[outlier.keypoint].clone(),
+                distance: outlier.distance,
+            }));
+            if !aggregated.keypoints.iter().any(Keypoint::is_labeled) {
+                continue;
+            }
+            final_sets.push(
+                KeypointRepository::set_final(db, image_id, tag.id, &aggregated.keypoints, votes.len() as i32)
+                    .await
+                    .map_err(|e| format!("Database error: {}", e))?,
+            );
+        }
+
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Keypoints aggregated successfully".to_string(),
+            data: Some(KeypointAggregationResponse {
+                final_keypoint_sets: Self::final_keypoint_set_responses(db, image.group_id, final_sets).await?,
+                outliers,
+            }),
+        })
+    }
+
+    pub async fn final_keypoint_set_responses(
+        db: &DatabaseConnection,
+        group_id: i32,
+        final_sets: Vec<FinalKeypointSetModel>,
+    ) -> Result<Vec<FinalKeypointSetResponse>, String> {
+        let tag_names: HashMap<i32, String> = TagRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|tag| (tag.id, tag.name))
+            .collect();
+        Ok(final_sets
+            .into_iter()
+            .filter_map(|set| {
+                let keypoints = Self::keypoints(&set.keypoints)?;
+                Some(FinalKeypointSetResponse {
+                    id: set.id,
+                    tag_id: set.tag_id,
+                    tag_name: tag_names.get(&set.tag_id).cloned().unwrap_or_default(),
+                    num_keypoints: keypoints.iter().filter(|keypoint| keypoint.is_labeled()).count(),
+                    keypoints,
+                    votes: set.votes,
+                })
+            })
+            .collect())
+    }
+
+    // The schema of a keypoint tag of the group, or the reason there is none
+    async fn keypoint_schema(
+        db: &DatabaseConnection,
+        group_id: i32,
+        tag_id: i32,
+    ) -> Result<Result<KeypointSchema, String>, String> {
+        let tag = TagRepository::find_by_id(db, tag_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .filter(|tag| tag.group_id == group_id);
+        Ok(match tag {
+            None => Err(format!("Tag {} does not belong to this group", tag_id)),
+            Some(tag) => tag
+                .keypoint_schema
+                .as_deref()
+                .and_then(KeypointSchema::from_json)
+                .ok_or_else(|| format!("Tag {} has no keypoint schema", tag_id)),
+        })
+    }
+
+    fn keypoints(json: &str) -> Option<Vec<Keypoint>> {
+        serde_json::from_str(json).ok()
+    }
+
+    fn keypoint_set_response(set: KeypointSetModel) -> KeypointSetResponse {
+        KeypointSetResponse {
+            id: set.id,
+            image_id: set.image_id,
+            tag_id: set.tag_id,
+            keypoints: Self::keypoints(&set.keypoints).unwrap_or_default(),
+            created_at: set.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            updated_at: set.updated_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        }
+    }
+}
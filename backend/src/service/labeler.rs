@@ -1,9 +1,20 @@
-use sea_orm::DatabaseConnection;
-use crate::repository::{LabelerRepository, GroupRepository, ImageTagsRepository};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use crate::keypoint::KeypointSchema;
+use crate::entity::image::Model as ImageModel;
+use crate::repository::{
+    AssignmentRepository, CaptionRepository, GroupRepository, ImageFlagRepository, ImageViewRepository, LabelerRepository, ImageRepository,
+    ImageTagsRepository, SubmissionRepository, TagRepository,
+};
 use crate::schemas::labeler::{
     GroupResponse, ImageResponse, GroupListResponse, ImageListResponse, ApiResponse,
     TagResponse, ImageDetailResponse, UpdateImageTagsRequest, SuggestTagsResponse
 };
+use crate::schemas::pagination::ListQuery;
+use crate::service::admin::AdminService;
+use crate::service::gold::GoldService;
+use crate::service::image::ImageService;
+use crate::storage::{self, ImageStorage};
+use crate::task::TaskType;
 
 pub struct LabelerService;
 
@@ -17,6 +28,7 @@ impl LabelerService {
                 let group_responses: Vec<GroupResponse> = groups
                     .into_iter()
                     .map(|group| GroupResponse {
+                        task_type: AdminService::task_type(&group),
                         id: group.id,
                         name: group.name,
                         description: group.description,
@@ -42,6 +54,7 @@ impl LabelerService {
         db: &DatabaseConnection,
         labeler_id: i32,
         group_id: i32,
+        query: ListQuery,
     ) -> Result<ApiResponse<ImageListResponse>, String> {
         // First verify that the labeler is part of this group
         match LabelerRepository::get_groups(db, labeler_id).await {
@@ -56,31 +69,43 @@ impl LabelerService {
             }
         }
         
+        let (image_query, page) = match ImageService::list_query(&query, Some(labeler_id)) {
+            Ok(query) => query,
+            Err(message) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+
         // Get images for the group
-        match GroupRepository::get_images(db, group_id).await {
+        match ImageRepository::list_by_group(db, group_id, &image_query, page).await {
             Ok(images) => {
-                // For each image, check if the labeler has tagged it
+                // Images of the page this labeler has submitted, even with no tags
+                let image_ids: Vec<i32> = images.items.iter().map(|image| image.id).collect();
+                let submitted = SubmissionRepository::submitted_images(db, labeler_id, &image_ids)
+                    .await
+                    .map_err(|e| format!("Database error: {}", e))?;
+
                 let mut image_responses = Vec::new();
                 
-                for image in images {
-                    // Check if this labeler has any tags for this image
-                    let has_tags = match ImageTagsRepository::get_by_image_and_labeler(
-                        db, 
-                        image.id, 
-                        labeler_id
-                    ).await {
-                        Ok(tags) => !tags.is_empty(),
-                        Err(_) => false,
-                    };
-                    
-                    let status = if has_tags { "done" } else { "pending" };
+                for image in images.items {
+                    let status = if submitted.contains(&image.id) { "done" } else { "pending" };
                     
                     image_responses.push(ImageResponse {
                         id: image.id,
                         filename: image.filename,
                         status: status.to_string(),
-                        base64_data: image.base64_data,
+                        base64_data: None,
                         filetype: image.filetype,
+                        content_url: ImageService::content_url(image.id),
+                        thumbnail_url: ImageService::thumbnail_url(image.id),
+                        width: image.width,
+                        height: image.height,
+                        color_mode: image.color_mode,
+                        orientation: image.orientation,
                     });
                 }
                 
@@ -89,6 +114,7 @@ impl LabelerService {
                     message: "Images retrieved successfully".to_string(),
                     data: Some(ImageListResponse {
                         images: image_responses,
+                        total: images.total,
                     }),
                 })
             }
@@ -132,6 +158,7 @@ impl LabelerService {
                         id: tag.id,
                         name: tag.name,
                         description: tag.description,
+                        keypoint_schema: tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                     }).collect(),
                     Err(e) => {
                         eprintln!("Error fetching group tags: {}", e);
@@ -150,6 +177,7 @@ impl LabelerService {
                                     id: tag.id,
                                     name: tag.name,
                                     description: tag.description,
+                                    keypoint_schema: tag.keypoint_schema.as_deref().and_then(KeypointSchema::from_json),
                                 });
                             }
                         }
@@ -161,12 +189,41 @@ impl LabelerService {
                     }
                 };
 
+                let task_type = match GroupRepository::find_by_id(db, group_id).await {
+                    Ok(group) => group.as_ref().map(AdminService::task_type).unwrap_or_default(),
+                    Err(e) => return Err(format!("Failed to fetch group: {}", e)),
+                };
+                let current_text = match task_type {
+                    TaskType::FreeText => CaptionRepository::find(db, image_id, labeler_id)
+                        .await
+                        .map_err(|e| format!("Failed to fetch caption: {}", e))?
+                        .map(|caption| caption.text),
+                    _ => None,
+                };
+
+                let submitted = SubmissionRepository::has_submitted(db, image_id, labeler_id)
+                    .await
+                    .map_err(|e| format!("Failed to fetch submission: {}", e))?;
+                // Time spent on an image runs from opening it to the first submission
+                if !submitted {
+                    ImageViewRepository::open(db, image_id, labeler_id, chrono::Utc::now().naive_utc())
+                        .await
+                        .map_err(|e| format!("Failed to record image view: {}", e))?;
+                }
+
                 let image_response = ImageResponse {
                     id: image.id,
                     filename: image.filename,
-                    status: if current_tags.is_empty() { "pending".to_string() } else { "done".to_string() },
-                    base64_data: image.base64_data,
+                    status: if submitted { "done".to_string() } else { "pending".to_string() },
+                    // The content is loaded from the signed URL, never embedded
+                    base64_data: None,
                     filetype: image.filetype,
+                    content_url: ImageService::content_url(image.id),
+                    thumbnail_url: ImageService::thumbnail_url(image.id),
+                    width: image.width,
+                    height: image.height,
+                    color_mode: image.color_mode,
+                    orientation: image.orientation,
                 };
 
                 Ok(ApiResponse {
@@ -176,6 +233,8 @@ impl LabelerService {
                         image: image_response,
                         group_tags,
                         current_tags,
+                        task_type,
+                        current_text,
                     }),
                 })
             }
@@ -208,37 +267,112 @@ impl LabelerService {
         }
 
         // Verify the image belongs to the group
-        match crate::repository::ImageRepository::find_by_id(db, image_id).await {
+        let image = match crate::repository::ImageRepository::find_by_id(db, image_id).await {
             Ok(Some(image)) => {
                 if image.group_id != group_id {
                     return Err("Image does not belong to this group".to_string());
                 }
+                image
             }
             Ok(None) => return Err("Image not found".to_string()),
             Err(e) => {
                 eprintln!("Error fetching image {}: {}", image_id, e);
                 return Err(format!("Failed to fetch image: {}", e));
             }
+        };
+
+        // The answer must fit the group's task
+        let task_type = match GroupRepository::find_by_id(db, group_id).await {
+            Ok(group) => group.as_ref().map(AdminService::task_type).unwrap_or_default(),
+            Err(e) => return Err(format!("Failed to fetch group: {}", e)),
+        };
+        let group_tag_ids: Vec<i32> = match TagRepository::get_by_group(db, group_id).await {
+            Ok(tags) => tags.into_iter().map(|tag| tag.id).collect(),
+            Err(e) => return Err(format!("Failed to fetch group tags: {}", e)),
+        };
+        if let Some(tag_id) = request.tag_ids.iter().find(|tag_id| !group_tag_ids.contains(tag_id)) {
+            return Ok(ApiResponse {
+                success: false,
+                message: format!("Tag {} does not belong to this group", tag_id),
+                data: None,
+            });
+        }
+        if let Err(message) = task_type.check_answer(&request.tag_ids, request.text.as_deref()) {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+        // Every write of the answer lands together, or none does
+        let txn = db.begin().await.map_err(|e| format!("Database error: {}", e))?;
+        if task_type == TaskType::FreeText {
+            let text = request.text.as_deref().unwrap_or_default().trim().to_string();
+            CaptionRepository::save(&txn, image_id, labeler_id, text)
+                .await
+                .map_err(|e| format!("Failed to save caption: {}", e))?;
         }
 
         // Replace tags for this image by this labeler
-        match ImageTagsRepository::replace_tags_for_image_by_labeler(db, image_id, labeler_id, request.tag_ids).await {
-            Ok(_) => {
-                Ok(ApiResponse {
-                    success: true,
-                    message: "Image tags updated successfully".to_string(),
-                    data: Some(()),
-                })
-            }
-            Err(e) => {
-                eprintln!("Error updating image tags: {}", e);
-                Err(format!("Failed to update image tags: {}", e))
+        if let Err(e) = ImageTagsRepository::replace_tags_for_image_by_labeler(&txn, image_id, labeler_id, request.tag_ids.clone()).await {
+            eprintln!("Error updating image tags: {}", e);
+            return Err(format!("Failed to update image tags: {}", e));
+        }
+        // An empty answer still counts as labeled
+        let view = ImageViewRepository::take(&txn, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Failed to fetch image view: {}", e))?;
+        SubmissionRepository::record(&txn, image_id, labeler_id, view.map(|view| view.opened_at))
+            .await
+            .map_err(|e| format!("Failed to record submission: {}", e))?;
+        // Gold answers are scored silently, the labeler is not told
+        if image.is_gold && task_type != TaskType::FreeText {
+            GoldService::score_answer(&txn, labeler_id, &image, &request.tag_ids).await?;
+        }
+        // The image is done for this labeler, so its lease and any earlier
+        // skip or flag can go
+        AssignmentRepository::release(&txn, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Failed to release assignment: {}", e))?;
+        ImageFlagRepository::clear(&txn, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Failed to clear skip or flag: {}", e))?;
+        txn.commit().await.map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Image tags updated successfully".to_string(),
+            data: Some(()),
+        })
+    }
+
+    /// Checks that the labeler belongs to the group and the image to the
+    /// group, and returns the image.
+    pub(crate) async fn check_image_access(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+    ) -> Result<ImageModel, String> {
+        match LabelerRepository::get_groups(db, labeler_id).await {
+            Ok(groups) => {
+                if !groups.iter().any(|group| group.id == group_id) {
+                    return Err("You are not authorized to access this group".to_string());
+                }
             }
+            Err(e) => return Err(format!("Failed to verify group access: {}", e)),
+        }
+        match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) if image.group_id == group_id => Ok(image),
+            Ok(Some(_)) => Err("Image does not belong to this group".to_string()),
+            Ok(None) => Err("Image not found".to_string()),
+            Err(e) => Err(format!("Failed to fetch image: {}", e)),
         }
     }
 
     pub async fn suggest_tags(
         db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
         labeler_id: i32,
         image_id: i32,
         request: crate::schemas::labeler::SuggestTagsRequest,
@@ -288,9 +422,18 @@ impl LabelerService {
             }
         };
 
+        // Load the image content for the vision model
+        let base64_data = match storage::read_image_base64(storage, &image).await {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Error reading image {} from storage: {}", image.id, e);
+                return Err(format!("Failed to read image: {}", e));
+            }
+        };
+
         // Call OpenAI API for suggestions
         let openai_service = crate::service::openai::OpenAIService::new();
-        match openai_service.suggest_tags(&image.base64_data, &image.filetype, &group_tags, &ignored_tags).await {
+        match openai_service.suggest_tags(&base64_data, &image.filetype, &group_tags, &ignored_tags).await {
             Ok(suggested_tags) => {
                 Ok(ApiResponse {
                     success: true,
@@ -1,5 +1,22 @@
 pub mod auth;
 pub mod admin;
+pub mod aggregation;
+pub mod assignment;
+pub mod agreement;
+pub mod caption;
+pub mod consensus;
+pub mod duplicate;
 pub mod export;
+pub mod flag;
+pub mod gold;
+pub mod image;
+pub mod keypoint;
 pub mod labeler;
 pub mod openai;
+pub mod progress;
+pub mod region;
+pub mod scoring;
+pub mod segmentation;
+pub mod import;
+pub mod thumbnail;
+pub mod timing;
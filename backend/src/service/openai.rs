@@ -3,9 +3,10 @@ use openai_api_rust::chat::{ChatApi, ChatBody};
 use openai_api_rust::apis::{Message, Role};
 use serde_json;
 use std::env;
+use std::sync::Arc;
 
 pub struct OpenAIService {
-    client: Option<OpenAI>,
+    client: Option<Arc<OpenAI>>,
 }
 
 impl OpenAIService {
@@ -14,7 +15,7 @@ impl OpenAIService {
             Ok(api_key) => {
                 println!("OpenAI API key found, initializing client");
                 let auth = Auth::new(&api_key);
-                Some(OpenAI::new(auth, "https://api.openai.com/v1/"))
+                Some(Arc::new(OpenAI::new(auth, "https://api.openai.com/v1/")))
             }
             Err(_) => {
                 println!("OpenAI API key not found, will use mock suggestions");
@@ -42,6 +43,16 @@ impl OpenAIService {
         }
     }
 
+    /// Drafts a caption of the image for a labeler or admin to edit. Unlike
+    /// tag suggestions there is no sensible stand-in, so a missing API key or
+    /// a failed request is an error.
+    pub async fn draft_caption(&self, base64_data: &str, filetype: &str) -> Result<String, String> {
+        match &self.client {
+            Some(client) => Self::call_caption_api(client.clone(), base64_data, filetype).await,
+            None => Err("OpenAI is not configured".to_string()),
+        }
+    }
+
     async fn call_openai_api(
         &self,
         client: &OpenAI,
@@ -149,6 +160,61 @@ impl OpenAIService {
         }
     }
 
+    async fn call_caption_api(client: Arc<OpenAI>, base64_data: &str, filetype: &str) -> Result<String, String> {
+        let image_url = format!("data:{};base64,{}", filetype, base64_data);
+
+        let messages = vec![
+            Message {
+                role: Role::System,
+                content: "You are a helpful AI assistant that writes accurate, objective image captions.".to_string(),
+            },
+            Message {
+                role: Role::User,
+                content: serde_json::json!([
+                    {
+                        "type": "text",
+                        "text": "Describe the image in one or two plain sentences. Return ***only*** the caption, nothing else."
+                    },
+                    {
+                        "type": "image_url",
+                        "image_url": {
+                            "url": image_url
+                        }
+                    }
+                ]).to_string(),
+            },
+        ];
+
+        let body = ChatBody {
+            model: "gpt-4o".to_string(),
+            messages,
+            temperature: Some(0.3),
+            max_tokens: Some(150),
+            top_p: Some(1.0),
+            n: Some(1),
+            stream: Some(false),
+            stop: None,
+            presence_penalty: None,
+            frequency_penalty: None,
+            logit_bias: None,
+            user: None,
+        };
+
+        // The client does blocking HTTP, keep it off the async workers
+        let response = tokio::task::spawn_blocking(move || client.chat_completion_create(&body))
+            .await
+            .map_err(|e| format!("OpenAI request failed: {}", e))?
+            .map_err(|e| format!("OpenAI API error: {}", e))?;
+
+        response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.as_ref())
+            .map(|message| message.content.trim().trim_matches('"').to_string())
+            .filter(|caption| !caption.is_empty())
+            .ok_or_else(|| "No caption in OpenAI response".to_string())
+    }
+
     fn generate_mock_suggestions(
         &self,
         group_tags: &[String],
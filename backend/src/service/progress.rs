@@ -0,0 +1,116 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeMap, HashSet};
+use crate::entity::group::Model as GroupModel;
+use crate::repository::{GroupRepository, ImageRepository, LabelerRepository, SubmissionRepository};
+use crate::schemas::analytics::{GroupProgressResponse, LabelerProgressResponse};
+
+/// How far back labeling throughput is measured for the ETA.
+const THROUGHPUT_WINDOW_DAYS: i64 = 7;
+
+pub struct ProgressService;
+
+impl ProgressService {
+    /// Completion of a group against its `labels_per_image`, per labeler
+    /// counts, and an ETA from the labeling rate of the last week.
+    pub async fn group_progress(db: &DatabaseConnection, group: &GroupModel) -> Result<GroupProgressResponse, String> {
+        let images: HashSet<i32> = ImageRepository::get_by_group(db, group.id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|image| !image.is_gold)
+            .map(|image| image.id)
+            .collect();
+
+        // When each labeler first submitted each image. Resubmitting does not
+        // add a label, so it moves neither the rate nor `last_labeled_at`
+        let mut labeled: BTreeMap<(i32, i32), NaiveDateTime> = BTreeMap::new();
+        for (image_id, labelers) in SubmissionRepository::submitters_by_group(db, group.id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|(image_id, _)| images.contains(image_id))
+        {
+            for (labeler_id, submitted_at) in labelers {
+                labeled.insert((labeler_id, image_id), submitted_at);
+            }
+        }
+
+        let target = group.labels_per_image.max(1) as usize;
+        let mut labelers_per_image: BTreeMap<i32, usize> = BTreeMap::new();
+        for &(_, image_id) in labeled.keys() {
+            *labelers_per_image.entry(image_id).or_default() += 1;
+        }
+        let fully_labeled = labelers_per_image.values().filter(|&&count| count >= target).count();
+        let partially_labeled = labelers_per_image.len() - fully_labeled;
+        let labels_done: usize = labelers_per_image.values().map(|&count| count.min(target)).sum();
+        let labels_needed = images.len() * target;
+
+        let now = Utc::now().naive_utc();
+        let (labels_per_hour, eta_seconds) = Self::eta(labeled.values().copied(), labels_needed - labels_done, now);
+
+        let mut per_labeler: BTreeMap<i32, (usize, Option<NaiveDateTime>)> = GroupRepository::get_labelers(db, group.id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|labeler| (labeler.id, (0, None)))
+            .collect();
+        for (&(labeler_id, _), &saved) in &labeled {
+            let (count, last) = per_labeler.entry(labeler_id).or_default();
+            *count += 1;
+            *last = (*last).max(Some(saved));
+        }
+        let mut labelers = Vec::new();
+        for (labeler_id, (images_labeled, last_labeled_at)) in per_labeler {
+            let username = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+            labelers.push(LabelerProgressResponse {
+                labeler_id,
+                username,
+                images_labeled,
+                last_labeled_at: last_labeled_at.map(|at| at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()),
+            });
+        }
+        labelers.sort_by(|a, b| b.images_labeled.cmp(&a.images_labeled).then(a.username.cmp(&b.username)));
+
+        Ok(GroupProgressResponse {
+            labels_per_image: group.labels_per_image,
+            total_images: images.len(),
+            fully_labeled,
+            partially_labeled,
+            untouched: images.len() - labelers_per_image.len(),
+            labels_done,
+            labels_needed,
+            percent_complete: if labels_needed == 0 { 100.0 } else { labels_done as f64 * 100.0 / labels_needed as f64 },
+            labels_per_hour,
+            eta_seconds,
+            estimated_completion_at: eta_seconds
+                .map(|seconds| (now + Duration::seconds(seconds)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()),
+            labelers,
+        })
+    }
+
+    /// Labels per hour over the throughput window, from the times labels
+    /// were saved, and the seconds `remaining` labels take at that rate.
+    /// Nothing remaining takes no time; without recent labels there is no
+    /// estimate.
+    pub fn eta(
+        saved_at: impl IntoIterator<Item = NaiveDateTime>,
+        remaining: usize,
+        now: NaiveDateTime,
+    ) -> (Option<f64>, Option<i64>) {
+        let window_start = now - Duration::days(THROUGHPUT_WINDOW_DAYS);
+        let recent: Vec<NaiveDateTime> = saved_at.into_iter().filter(|&at| at > window_start).collect();
+        let Some(&first) = recent.iter().min() else {
+            return (None, (remaining == 0).then_some(0));
+        };
+
+        // At least an hour, so a quick burst does not promise too much
+        let hours = ((now - first).num_seconds() as f64 / 3600.0).max(1.0);
+        let per_hour = recent.len() as f64 / hours;
+        (Some(per_hour), Some((remaining as f64 / per_hour * 3600.0).round() as i64))
+    }
+}
@@ -0,0 +1,313 @@
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::entity::final_region::Model as FinalRegionModel;
+use crate::entity::region::Model as RegionModel;
+use crate::region::{self, BoundingBox, RegionVote, DEFAULT_IOU_THRESHOLD};
+use crate::repository::{ImageRepository, LabelerRepository, RegionRepository, SubmissionRepository, TagRepository};
+use crate::schemas::admin::{
+    ApiResponse as AdminApiResponse, FinalRegionResponse, ImageRegionsResponse, LabelerRegionsResponse,
+    RegionConsensusRequest,
+};
+use crate::schemas::labeler::{ApiResponse, RegionRequest, RegionResponse};
+use crate::service::labeler::LabelerService;
+
+pub struct RegionService;
+
+impl RegionService {
+    /// Boxes the labeler drew on an image.
+    pub async fn list_regions(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+    ) -> Result<ApiResponse<Vec<RegionResponse>>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let regions = RegionRepository::get_by_image_and_labeler(db, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Regions retrieved successfully".to_string(),
+            data: Some(regions.into_iter().map(Self::region_response).collect()),
+        })
+    }
+
+    pub async fn create_region(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        request: RegionRequest,
+    ) -> Result<ApiResponse<RegionResponse>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        if let Some(message) = Self::validate(db, group_id, &request).await? {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
+        let region = RegionRepository::create(db, image_id, labeler_id, request.tag_id, request.bbox)
+            .await
+            .map_err(|e| format!("Failed to save region: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Region created successfully".to_string(),
+            data: Some(Self::region_response(region)),
+        })
+    }
+
+    pub async fn update_region(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        region_id: i32,
+        request: RegionRequest,
+    ) -> Result<ApiResponse<RegionResponse>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let Some(region) = Self::find_own(db, labeler_id, image_id, region_id).await? else {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Region not found".to_string(),
+                data: None,
+            });
+        };
+        if let Some(message) = Self::validate(db, group_id, &request).await? {
+            return Ok(ApiResponse {
+                success: false,
+                message,
+                data: None,
+            });
+        }
+
+        let region = RegionRepository::update(db, region, request.tag_id, request.bbox)
+            .await
+            .map_err(|e| format!("Failed to save region: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Region updated successfully".to_string(),
+            data: Some(Self::region_response(region)),
+        })
+    }
+
+    pub async fn delete_region(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        region_id: i32,
+    ) -> Result<ApiResponse<()>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        if Self::find_own(db, labeler_id, image_id, region_id).await?.is_none() {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Region not found".to_string(),
+                data: None,
+            });
+        }
+
+        RegionRepository::delete(db, region_id)
+            .await
+            .map_err(|e| format!("Failed to delete region: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Region deleted successfully".to_string(),
+            data: Some(()),
+        })
+    }
+
+    /// Every labeler's boxes on an image, and its final boxes.
+    pub async fn image_regions(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<AdminApiResponse<ImageRegionsResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let mut by_labeler: BTreeMap<i32, Vec<RegionResponse>> = BTreeMap::new();
+        for region in RegionRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+        {
+            by_labeler.entry(region.labeler_id).or_default().push(Self::region_response(region));
+        }
+        let mut labelers = Vec::new();
+        for (labeler_id, regions) in by_labeler {
+            let username = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+            labelers.push(LabelerRegionsResponse {
+                labeler_id,
+                username,
+                regions,
+            });
+        }
+
+        let final_regions = RegionRepository::get_final_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Regions retrieved successfully".to_string(),
+            data: Some(ImageRegionsResponse {
+                image_id,
+                labelers,
+                final_regions: Self::final_region_responses(db, image.group_id, final_regions).await?,
+            }),
+        })
+    }
+
+    /// Replaces the final boxes of an image with the clusters of its
+    /// labelers' boxes.
+    pub async fn auto_generate_final_regions(
+        db: &DatabaseConnection,
+        image_id: i32,
+        request: RegionConsensusRequest,
+    ) -> Result<AdminApiResponse<Vec<FinalRegionResponse>>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        let iou_threshold = request.iou_threshold.unwrap_or(DEFAULT_IOU_THRESHOLD);
+        if !(iou_threshold > 0.0 && iou_threshold <= 1.0) {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "iou_threshold must be greater than 0 and at most 1".to_string(),
+                data: None,
+            });
+        }
+        if request.min_votes == Some(0) {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "min_votes must be at least 1".to_string(),
+                data: None,
+            });
+        }
+
+        let regions = RegionRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        // Labelers who submitted the image without drawing boxes saw nothing
+        // worth a box
+        let mut labelers: BTreeSet<i32> = SubmissionRepository::submitters_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        labelers.extend(regions.iter().map(|region| region.labeler_id));
+        let min_votes = request.min_votes.unwrap_or(labelers.len().div_ceil(2).max(1));
+
+        let votes: Vec<RegionVote> = regions
+            .iter()
+            .map(|region| RegionVote {
+                labeler_id: region.labeler_id,
+                tag_id: region.tag_id,
+                bbox: Self::bbox(region),
+            })
+            .collect();
+        let final_regions = RegionRepository::replace_final(db, image_id, region::cluster(&votes, iou_threshold, min_votes))
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Final regions auto-generated successfully".to_string(),
+            data: Some(Self::final_region_responses(db, image.group_id, final_regions).await?),
+        })
+    }
+
+    pub async fn final_region_responses(
+        db: &DatabaseConnection,
+        group_id: i32,
+        final_regions: Vec<FinalRegionModel>,
+    ) -> Result<Vec<FinalRegionResponse>, String> {
+        let tag_names: HashMap<i32, String> = TagRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|tag| (tag.id, tag.name))
+            .collect();
+        Ok(final_regions
+            .into_iter()
+            .map(|region| FinalRegionResponse {
+                id: region.id,
+                tag_id: region.tag_id,
+                tag_name: tag_names.get(&region.tag_id).cloned().unwrap_or_default(),
+                bbox: BoundingBox {
+                    x: region.x,
+                    y: region.y,
+                    width: region.width,
+                    height: region.height,
+                },
+                votes: region.votes,
+            })
+            .collect())
+    }
+
+    async fn find_own(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        image_id: i32,
+        region_id: i32,
+    ) -> Result<Option<RegionModel>, String> {
+        Ok(RegionRepository::find_by_id(db, region_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .filter(|region| region.labeler_id == labeler_id && region.image_id == image_id))
+    }
+
+    // The reason a box cannot be saved, if any
+    async fn validate(
+        db: &DatabaseConnection,
+        group_id: i32,
+        request: &RegionRequest,
+    ) -> Result<Option<String>, String> {
+        if let Err(message) = request.bbox.validate() {
+            return Ok(Some(message));
+        }
+        let in_group = TagRepository::find_by_id(db, request.tag_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .is_some_and(|tag| tag.group_id == group_id);
+        Ok((!in_group).then(|| format!("Tag {} does not belong to this group", request.tag_id)))
+    }
+
+    fn bbox(region: &RegionModel) -> BoundingBox {
+        BoundingBox {
+            x: region.x,
+            y: region.y,
+            width: region.width,
+            height: region.height,
+        }
+    }
+
+    fn region_response(region: RegionModel) -> RegionResponse {
+        RegionResponse {
+            id: region.id,
+            image_id: region.image_id,
+            tag_id: region.tag_id,
+            bbox: Self::bbox(&region),
+            created_at: region.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            updated_at: region.updated_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use sea_orm::DatabaseConnection;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::repository::{FinalTagsRepository, GroupRepository, ImageTagsRepository, LabelerRepository, SubmissionRepository, TagRepository};
+use crate::schemas::admin::ApiResponse;
+use crate::schemas::analytics::{LabelerScoreResponse, LeaderboardResponse, TagErrorResponse};
+
+// Counts for one labeler, and for each tag they were scored on
+#[derive(Default)]
+struct Tally {
+    images: usize,
+    true_positives: usize,
+    false_positives: usize,
+    false_negatives: usize,
+    tags: BTreeMap<i32, TagTally>,
+}
+
+#[derive(Default)]
+struct TagTally {
+    images: usize,
+    false_positives: usize,
+    false_negatives: usize,
+}
+
+pub struct ScoringService;
+
+impl ScoringService {
+    /// Every labeler with reviewed images in `group_id` (or in any group),
+    /// best F1 first.
+    pub async fn leaderboard(
+        db: &DatabaseConnection,
+        group_id: Option<i32>,
+    ) -> Result<ApiResponse<LeaderboardResponse>, String> {
+        if let Some(group_id) = group_id {
+            match GroupRepository::find_by_id(db, group_id).await {
+                Ok(Some(_)) => {} // Group exists, continue
+                Ok(None) => {
+                    return Ok(ApiResponse {
+                        success: false,
+                        message: "Group not found".to_string(),
+                        data: None,
+                    });
+                }
+                Err(e) => return Err(format!("Database error: {}", e)),
+            }
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Leaderboard computed successfully".to_string(),
+            data: Some(LeaderboardResponse {
+                group_id,
+                labelers: Self::scores(db, group_id).await?,
+            }),
+        })
+    }
+
+    /// Scores of one labeler, in `group_id` or across all groups. A labeler
+    /// without reviewed images gets zero counts and no rates.
+    pub async fn labeler_score(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: Option<i32>,
+    ) -> Result<ApiResponse<LabelerScoreResponse>, String> {
+        let labeler = match LabelerRepository::find_by_id(db, labeler_id).await {
+            Ok(Some(labeler)) => labeler,
+            Ok(None) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message: "Labeler not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let score = Self::scores(db, group_id)
+            .await?
+            .into_iter()
+            .find(|score| score.labeler_id == labeler_id)
+            .unwrap_or_else(|| LabelerScoreResponse {
+                rank: 0,
+                labeler_id,
+                username: labeler.username,
+                reviewed_images: 0,
+                true_positives: 0,
+                false_positives: 0,
+                false_negatives: 0,
+                precision: None,
+                recall: None,
+                f1: None,
+                tags: Vec::new(),
+            });
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Labeler score computed successfully".to_string(),
+            data: Some(score),
+        })
+    }
+
+    /// Compares each labeler's tags with the final tags of images that have
+    /// an admin override, and ranks labelers by F1, then by the number of
+    /// reviewed images.
+    pub async fn scores(
+        db: &DatabaseConnection,
+        group_id: Option<i32>,
+    ) -> Result<Vec<LabelerScoreResponse>, String> {
+        let group_ids = match group_id {
+            Some(group_id) => vec![group_id],
+            None => GroupRepository::get_all(db)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .into_iter()
+                .map(|group| group.id)
+                .collect(),
+        };
+
+        let mut tallies: BTreeMap<i32, Tally> = BTreeMap::new();
+        let mut tag_names: HashMap<i32, String> = HashMap::new();
+        for group_id in group_ids {
+            let tags = TagRepository::get_by_group(db, group_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            let final_tags = FinalTagsRepository::get_by_group(db, group_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            let image_tags = ImageTagsRepository::get_by_group(db, group_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+
+            let mut truth: HashMap<i32, HashSet<i32>> = HashMap::new();
+            for final_tag in final_tags.iter().filter(|tag| tag.is_admin_override) {
+                truth.entry(final_tag.image_id).or_default().insert(final_tag.tag_id);
+            }
+            // Submissions with no tags miss every tag the admin chose
+            let mut chosen: BTreeMap<(i32, i32), HashSet<i32>> = BTreeMap::new();
+            for (image_id, labelers) in SubmissionRepository::submitters_by_group(db, group_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .into_iter()
+                .filter(|(image_id, _)| truth.contains_key(image_id))
+            {
+                for labeler_id in labelers.into_keys() {
+                    chosen.entry((labeler_id, image_id)).or_default();
+                }
+            }
+            for image_tag in image_tags.iter().filter(|tag| truth.contains_key(&tag.image_id)) {
+                chosen.entry((image_tag.labeler_id, image_tag.image_id)).or_default().insert(image_tag.tag_id);
+            }
+
+            for ((labeler_id, image_id), chosen) in chosen {
+                let truth = &truth[&image_id];
+                let tally = tallies.entry(labeler_id).or_default();
+                tally.images += 1;
+                for tag in &tags {
+                    let (labeler_says, admin_says) = (chosen.contains(&tag.id), truth.contains(&tag.id));
+                    let tag_tally = tally.tags.entry(tag.id).or_default();
+                    tag_tally.images += 1;
+                    match (labeler_says, admin_says) {
+                        (true, true) => tally.true_positives += 1,
+                        (true, false) => {
+                            tally.false_positives += 1;
+                            tag_tally.false_positives += 1;
+                        }
+                        (false, true) => {
+                            tally.false_negatives += 1;
+                            tag_tally.false_negatives += 1;
+                        }
+                        (false, false) => {}
+                    }
+                }
+            }
+            tag_names.extend(tags.into_iter().map(|tag| (tag.id, tag.name)));
+        }
+
+        let mut scores = Vec::new();
+        for (labeler_id, tally) in tallies {
+            let username = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+
+            let ratio = |part: usize, whole: usize| (whole > 0).then(|| part as f64 / whole as f64);
+            let precision = ratio(tally.true_positives, tally.true_positives + tally.false_positives);
+            let recall = ratio(tally.true_positives, tally.true_positives + tally.false_negatives);
+            let f1 = match (precision, recall) {
+                (Some(p), Some(r)) if p + r > 0.0 => Some(2.0 * p * r / (p + r)),
+                (Some(_), Some(_)) => Some(0.0),
+                _ => None,
+            };
+
+            scores.push(LabelerScoreResponse {
+                rank: 0,
+                labeler_id,
+                username,
+                reviewed_images: tally.images,
+                true_positives: tally.true_positives,
+                false_positives: tally.false_positives,
+                false_negatives: tally.false_negatives,
+                precision,
+                recall,
+                f1,
+                tags: tally
+                    .tags
+                    .into_iter()
+                    .map(|(tag_id, tag)| TagErrorResponse {
+                        tag_id,
+                        tag_name: tag_names.get(&tag_id).cloned().unwrap_or_default(),
+                        images: tag.images,
+                        false_positives: tag.false_positives,
+                        false_negatives: tag.false_negatives,
+                        error_rate: (tag.false_positives + tag.false_negatives) as f64 / tag.images as f64,
+                    })
+                    .collect(),
+            });
+        }
+
+        // Labelers without an F1 go last
+        scores.sort_by(|a, b| {
+            let f1 = b.f1.unwrap_or(-1.0).partial_cmp(&a.f1.unwrap_or(-1.0)).unwrap_or(Ordering::Equal);
+            f1.then(b.reviewed_images.cmp(&a.reviewed_images)).then(a.username.cmp(&b.username))
+        });
+        for (i, score) in scores.iter_mut().enumerate() {
+            score.rank = i + 1;
+        }
+
+        Ok(scores)
+    }
+}
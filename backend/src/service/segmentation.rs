@@ -0,0 +1,376 @@
+use sea_orm::DatabaseConnection;
+use std::collections::{BTreeMap, HashMap};
+use crate::entity::final_segmentation::Model as FinalSegmentationModel;
+use crate::entity::image::Model as ImageModel;
+use crate::entity::segmentation::Model as SegmentationModel;
+use crate::repository::{ImageRepository, LabelerRepository, SegmentationRepository, TagRepository};
+use crate::schemas::admin::{
+    ApiResponse as AdminApiResponse, FinalSegmentationResponse, ImageSegmentationsResponse,
+    LabelerSegmentationsResponse, MergeSegmentationsRequest,
+};
+use crate::schemas::labeler::{ApiResponse, SegmentationRequest, SegmentationResponse};
+use crate::segmentation::{Geometry, Mask};
+use crate::service::labeler::LabelerService;
+
+pub struct SegmentationService;
+
+impl SegmentationService {
+    /// Polygons and masks the labeler drew on an image.
+    pub async fn list_segmentations(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+    ) -> Result<ApiResponse<Vec<SegmentationResponse>>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let segmentations = SegmentationRepository::get_by_image_and_labeler(db, image_id, labeler_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Segmentations retrieved successfully".to_string(),
+            data: Some(segmentations.into_iter().map(Self::segmentation_response).collect()),
+        })
+    }
+
+    pub async fn create_segmentation(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        request: SegmentationRequest,
+    ) -> Result<ApiResponse<SegmentationResponse>, String> {
+        let image = LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let area = match Self::validate(db, &image, &request).await? {
+            Ok(area) => area,
+            Err(message) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+
+        let segmentation = SegmentationRepository::create(db, image_id, labeler_id, request.tag_id, &request.geometry, area)
+            .await
+            .map_err(|e| format!("Failed to save segmentation: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Segmentation created successfully".to_string(),
+            data: Some(Self::segmentation_response(segmentation)),
+        })
+    }
+
+    pub async fn update_segmentation(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        segmentation_id: i32,
+        request: SegmentationRequest,
+    ) -> Result<ApiResponse<SegmentationResponse>, String> {
+        let image = LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        let Some(segmentation) = Self::find_own(db, labeler_id, image_id, segmentation_id).await? else {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Segmentation not found".to_string(),
+                data: None,
+            });
+        };
+        let area = match Self::validate(db, &image, &request).await? {
+            Ok(area) => area,
+            Err(message) => {
+                return Ok(ApiResponse {
+                    success: false,
+                    message,
+                    data: None,
+                });
+            }
+        };
+
+        let segmentation = SegmentationRepository::update(db, segmentation, request.tag_id, &request.geometry, area)
+            .await
+            .map_err(|e| format!("Failed to save segmentation: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Segmentation updated successfully".to_string(),
+            data: Some(Self::segmentation_response(segmentation)),
+        })
+    }
+
+    pub async fn delete_segmentation(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        group_id: i32,
+        image_id: i32,
+        segmentation_id: i32,
+    ) -> Result<ApiResponse<()>, String> {
+        LabelerService::check_image_access(db, labeler_id, group_id, image_id).await?;
+        if Self::find_own(db, labeler_id, image_id, segmentation_id).await?.is_none() {
+            return Ok(ApiResponse {
+                success: false,
+                message: "Segmentation not found".to_string(),
+                data: None,
+            });
+        }
+
+        SegmentationRepository::delete(db, segmentation_id)
+            .await
+            .map_err(|e| format!("Failed to delete segmentation: {}", e))?;
+        Ok(ApiResponse {
+            success: true,
+            message: "Segmentation deleted successfully".to_string(),
+            data: Some(()),
+        })
+    }
+
+    /// Every labeler's polygons and masks on an image, and its final masks.
+    pub async fn image_segmentations(
+        db: &DatabaseConnection,
+        image_id: i32,
+    ) -> Result<AdminApiResponse<ImageSegmentationsResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+
+        let mut by_labeler: BTreeMap<i32, Vec<SegmentationResponse>> = BTreeMap::new();
+        for segmentation in SegmentationRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+        {
+            by_labeler
+                .entry(segmentation.labeler_id)
+                .or_default()
+                .push(Self::segmentation_response(segmentation));
+        }
+        let mut labelers = Vec::new();
+        for (labeler_id, segmentations) in by_labeler {
+            let username = LabelerRepository::find_by_id(db, labeler_id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?
+                .map(|labeler| labeler.username)
+                .unwrap_or_default();
+            labelers.push(LabelerSegmentationsResponse {
+                labeler_id,
+                username,
+                segmentations,
+            });
+        }
+
+        let final_segmentations = SegmentationRepository::get_final_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Segmentations retrieved successfully".to_string(),
+            data: Some(ImageSegmentationsResponse {
+                image_id,
+                width: image.width,
+                height: image.height,
+                labelers,
+                final_segmentations: Self::final_segmentation_responses(db, image.group_id, final_segmentations).await?,
+            }),
+        })
+    }
+
+    /// Merges labelers' masks of one tag into the final mask of the image,
+    /// pixel by pixel. A labeler's masks of the tag count as one vote.
+    pub async fn merge_segmentations(
+        db: &DatabaseConnection,
+        image_id: i32,
+        request: MergeSegmentationsRequest,
+    ) -> Result<AdminApiResponse<FinalSegmentationResponse>, String> {
+        let image = match ImageRepository::find_by_id(db, image_id).await {
+            Ok(Some(image)) => image,
+            Ok(None) => {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: "Image not found".to_string(),
+                    data: None,
+                });
+            }
+            Err(e) => return Err(format!("Database error: {}", e)),
+        };
+        let Some((width, height)) = Self::dimensions(&image) else {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "Image dimensions are unknown".to_string(),
+                data: None,
+            });
+        };
+        if request.min_votes == Some(0) {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: "min_votes must be at least 1".to_string(),
+                data: None,
+            });
+        }
+
+        let mut segmentations: Vec<SegmentationModel> = SegmentationRepository::get_by_image(db, image_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .filter(|segmentation| segmentation.tag_id == request.tag_id)
+            .collect();
+        if let Some(ids) = &request.segmentation_ids {
+            if let Some(missing) = ids.iter().find(|id| !segmentations.iter().any(|s| s.id == **id)) {
+                return Ok(AdminApiResponse {
+                    success: false,
+                    message: format!("Segmentation {} of tag {} not found on this image", missing, request.tag_id),
+                    data: None,
+                });
+            }
+            segmentations.retain(|segmentation| ids.contains(&segmentation.id));
+        }
+
+        let mut by_labeler: BTreeMap<i32, Vec<Mask>> = BTreeMap::new();
+        for segmentation in &segmentations {
+            let Some(geometry) = Geometry::from_json(&segmentation.geometry) else {
+                continue;
+            };
+            by_labeler
+                .entry(segmentation.labeler_id)
+                .or_default()
+                .push(geometry.to_mask(width, height));
+        }
+        if by_labeler.is_empty() {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: format!("No segmentations of tag {} to merge", request.tag_id),
+                data: None,
+            });
+        }
+
+        let votes = by_labeler.len();
+        let masks: Vec<Mask> = by_labeler
+            .into_values()
+            .map(|masks| Mask::merge(&masks, width, height, 1))
+            .collect();
+        let min_votes = request.min_votes.unwrap_or(votes.div_ceil(2));
+        let merged = Mask::merge(&masks, width, height, min_votes);
+        if merged.area() == 0 {
+            return Ok(AdminApiResponse {
+                success: false,
+                message: format!("No pixel is covered by at least {} labelers", min_votes),
+                data: None,
+            });
+        }
+
+        let geometry = Geometry::Rle {
+            size: [height, width],
+            counts: merged.to_rle(),
+        };
+        let final_segmentation = SegmentationRepository::set_final(
+            db,
+            image_id,
+            request.tag_id,
+            &geometry,
+            merged.area() as i64,
+            votes as i32,
+        )
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let response = Self::final_segmentation_responses(db, image.group_id, vec![final_segmentation]).await?;
+        Ok(AdminApiResponse {
+            success: true,
+            message: "Segmentations merged successfully".to_string(),
+            data: response.into_iter().next(),
+        })
+    }
+
+    pub async fn final_segmentation_responses(
+        db: &DatabaseConnection,
+        group_id: i32,
+        final_segmentations: Vec<FinalSegmentationModel>,
+    ) -> Result<Vec<FinalSegmentationResponse>, String> {
+        let tag_names: HashMap<i32, String> = TagRepository::get_by_group(db, group_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .into_iter()
+            .map(|tag| (tag.id, tag.name))
+            .collect();
+        Ok(final_segmentations
+            .into_iter()
+            .filter_map(|segmentation| {
+                Some(FinalSegmentationResponse {
+                    id: segmentation.id,
+                    tag_id: segmentation.tag_id,
+                    tag_name: tag_names.get(&segmentation.tag_id).cloned().unwrap_or_default(),
+                    geometry: Geometry::from_json(&segmentation.geometry)?,
+                    area: segmentation.area,
+                    votes: segmentation.votes,
+                })
+            })
+            .collect())
+    }
+
+    /// `(width, height)` of an image, if known.
+    pub fn dimensions(image: &ImageModel) -> Option<(u32, u32)> {
+        match (image.width, image.height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => Some((width as u32, height as u32)),
+            _ => None,
+        }
+    }
+
+    async fn find_own(
+        db: &DatabaseConnection,
+        labeler_id: i32,
+        image_id: i32,
+        segmentation_id: i32,
+    ) -> Result<Option<SegmentationModel>, String> {
+        Ok(SegmentationRepository::find_by_id(db, segmentation_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .filter(|segmentation| segmentation.labeler_id == labeler_id && segmentation.image_id == image_id))
+    }
+
+    // The covered area, or the reason the annotation cannot be saved
+    async fn validate(
+        db: &DatabaseConnection,
+        image: &ImageModel,
+        request: &SegmentationRequest,
+    ) -> Result<Result<i64, String>, String> {
+        let Some((width, height)) = Self::dimensions(image) else {
+            return Ok(Err("Image dimensions are unknown".to_string()));
+        };
+        if let Err(message) = request.geometry.validate(width, height) {
+            return Ok(Err(message));
+        }
+        // A sliver can enclose an area without covering any pixel center
+        let area = request.geometry.area(width, height);
+        if area == 0 {
+            return Ok(Err("The annotation does not cover any pixel".to_string()));
+        }
+        let in_group = TagRepository::find_by_id(db, request.tag_id)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?
+            .is_some_and(|tag| tag.group_id == image.group_id);
+        if !in_group {
+            return Ok(Err(format!("Tag {} does not belong to this group", request.tag_id)));
+        }
+        Ok(Ok(area as i64))
+    }
+
+    fn segmentation_response(segmentation: SegmentationModel) -> SegmentationResponse {
+        SegmentationResponse {
+            id: segmentation.id,
+            image_id: segmentation.image_id,
+            tag_id: segmentation.tag_id,
+            geometry: Geometry::from_json(&segmentation.geometry).unwrap_or(Geometry::Polygon { points: Vec::new() }),
+            area: segmentation.area,
+            created_at: segmentation.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+            updated_at: segmentation.updated_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        }
+    }
+}
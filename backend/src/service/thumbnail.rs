@@ -0,0 +1,119 @@
+use sea_orm::DatabaseConnection;
+use crate::entity::image::Model as ImageModel;
+use crate::imaging::{self, ImageFormat};
+use crate::repository::ImageThumbnailRepository;
+use crate::storage::{self, ImageStorage};
+
+const THUMBNAIL_QUALITY: u8 = 80;
+
+/// Thumbnail sizes, named by the longest side they are scaled to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThumbnailSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl ThumbnailSize {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "small" => Some(Self::Small),
+            "medium" => Some(Self::Medium),
+            "large" => Some(Self::Large),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Small => "small",
+            Self::Medium => "medium",
+            Self::Large => "large",
+        }
+    }
+
+    pub fn max_side(&self) -> u32 {
+        match self {
+            Self::Small => 128,
+            Self::Medium => 256,
+            Self::Large => 512,
+        }
+    }
+}
+
+/// Formats thumbnails can be encoded as, preferred first.
+pub const THUMBNAIL_FORMATS: [ImageFormat; 2] = [ImageFormat::Jpeg, ImageFormat::WebP];
+
+/// A generated thumbnail and the storage key it is cached under.
+pub struct ThumbnailContent {
+    pub storage_key: String,
+    pub bytes: Vec<u8>,
+}
+
+pub struct ThumbnailService;
+
+impl ThumbnailService {
+    /// Storage key of an already generated thumbnail, if any.
+    pub async fn cached_key(
+        db: &DatabaseConnection,
+        image_id: i32,
+        size: ThumbnailSize,
+        format: ImageFormat,
+    ) -> Result<Option<String>, String> {
+        ImageThumbnailRepository::find(db, image_id, size.name(), format.mime())
+            .await
+            .map(|thumbnail| thumbnail.map(|t| t.storage_key))
+            .map_err(|e| format!("Database error: {}", e))
+    }
+
+    /// Returns the cached thumbnail of `image`, generating and storing it on
+    /// first use. Fails when the original cannot be decoded.
+    pub async fn get_or_create(
+        db: &DatabaseConnection,
+        storage: &dyn ImageStorage,
+        image: &ImageModel,
+        size: ThumbnailSize,
+        format: ImageFormat,
+    ) -> Result<ThumbnailContent, String> {
+        if !THUMBNAIL_FORMATS.contains(&format) {
+            return Err(format!("Thumbnails cannot be encoded as {}", format.name()));
+        }
+
+        if let Some(storage_key) = Self::cached_key(db, image.id, size, format).await? {
+            match storage.get(&storage_key).await {
+                Ok(bytes) => return Ok(ThumbnailContent { storage_key, bytes }),
+                // Regenerate below if the object went missing
+                Err(e) => eprintln!("Warning: Cached thumbnail of image {} is unreadable: {}", image.id, e),
+            }
+        }
+
+        let original = storage::read_image(storage, image).await?;
+
+        // Decoding is CPU-bound, keep it off the async workers
+        let max_side = size.max_side();
+        let thumbnail = tokio::task::spawn_blocking(move || {
+            imaging::thumbnail(&original, max_side, format, THUMBNAIL_QUALITY)
+        })
+        .await
+        .map_err(|e| format!("Thumbnail generation failed: {}", e))??;
+
+        let storage_key = storage.put(&thumbnail.bytes).await
+            .map_err(|e| format!("Failed to store thumbnail: {}", e))?;
+
+        // A concurrent request may have recorded the same thumbnail already
+        if let Err(e) = ImageThumbnailRepository::create(
+            db,
+            image.id,
+            size.name(),
+            format.mime(),
+            storage_key.clone(),
+            thumbnail.width as i32,
+            thumbnail.height as i32,
+        ).await
+            && Self::cached_key(db, image.id, size, format).await?.is_none() {
+            return Err(format!("Failed to record thumbnail: {}", e));
+        }
+
+        Ok(ThumbnailContent { storage_key, bytes: thumbnail.bytes })
+    }
+}
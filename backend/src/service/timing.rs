@@ -0,0 +1,133 @@
+use sea_orm::DatabaseConnection;
+use std::collections::BTreeMap;
+use crate::repository::{GroupRepository, LabelerRepository, SubmissionRepository};
+use crate::schemas::admin::ApiResponse;
+use crate::schemas::analytics::{
+    GroupThroughputResponse, LabelerThroughputResponse, ThroughputResponse, TimeBucketResponse, TimingStatsResponse,
+};
+
+/// Images open longer than this before their first submission were left
+/// idle, so their time says nothing about the work.
+pub const IDLE_SECONDS: i64 = 3600;
+
+/// Upper bounds of the time-per-image histogram buckets.
+const BUCKETS: [i64; 6] = [10, 30, 60, 120, 300, 600];
+
+pub struct TimingService;
+
+impl TimingService {
+    /// Time-per-image distributions and labels per hour of `group_id`, or
+    /// of every group, with a breakdown by labeler.
+    pub async fn throughput(
+        db: &DatabaseConnection,
+        group_id: Option<i32>,
+    ) -> Result<ApiResponse<ThroughputResponse>, String> {
+        let groups = match group_id {
+            Some(group_id) => match GroupRepository::find_by_id(db, group_id).await {
+                Ok(Some(group)) => vec![group],
+                Ok(None) => {
+                    return Ok(ApiResponse {
+                        success: false,
+                        message: "Group not found".to_string(),
+                        data: None,
+                    });
+                }
+                Err(e) => return Err(format!("Database error: {}", e)),
+            },
+            None => GroupRepository::get_all(db).await.map_err(|e| format!("Database error: {}", e))?,
+        };
+
+        let mut usernames: BTreeMap<i32, String> = BTreeMap::new();
+        let mut responses = Vec::new();
+        for group in groups {
+            let submissions = SubmissionRepository::get_by_group(db, group.id)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            let mut by_labeler: BTreeMap<i32, Vec<Option<i64>>> = BTreeMap::new();
+            for submission in &submissions {
+                by_labeler.entry(submission.labeler_id).or_default().push(submission.seconds_spent);
+            }
+
+            let mut labelers = Vec::new();
+            for (labeler_id, seconds) in by_labeler {
+                let username = match usernames.get(&labeler_id) {
+                    Some(username) => username.clone(),
+                    None => {
+                        let username = LabelerRepository::find_by_id(db, labeler_id)
+                            .await
+                            .map_err(|e| format!("Database error: {}", e))?
+                            .map(|labeler| labeler.username)
+                            .unwrap_or_default();
+                        usernames.insert(labeler_id, username.clone());
+                        username
+                    }
+                };
+                labelers.push(LabelerThroughputResponse {
+                    labeler_id,
+                    username,
+                    timing: Self::stats(&seconds),
+                });
+            }
+            labelers.sort_by(|a, b| a.username.cmp(&b.username));
+
+            let seconds: Vec<Option<i64>> = submissions.iter().map(|submission| submission.seconds_spent).collect();
+            responses.push(GroupThroughputResponse {
+                group_id: group.id,
+                name: group.name,
+                timing: Self::stats(&seconds),
+                labelers,
+            });
+        }
+
+        Ok(ApiResponse {
+            success: true,
+            message: "Throughput computed successfully".to_string(),
+            data: Some(ThroughputResponse {
+                group_id,
+                groups: responses,
+            }),
+        })
+    }
+
+    /// Statistics of the time spent on submitted images, `None` where it
+    /// was not recorded.
+    pub fn stats(seconds: &[Option<i64>]) -> TimingStatsResponse {
+        let mut timed: Vec<i64> = seconds
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|&seconds| seconds <= IDLE_SECONDS)
+            .collect();
+        timed.sort_unstable();
+
+        let total: i64 = timed.iter().sum();
+        let n = timed.len();
+        let median = match n {
+            0 => None,
+            _ if n % 2 == 1 => Some(timed[n / 2] as f64),
+            _ => Some((timed[n / 2 - 1] + timed[n / 2]) as f64 / 2.0),
+        };
+        // Nearest-rank percentile
+        let p90 = (n > 0).then(|| timed[(n * 9).div_ceil(10).max(1) - 1] as f64);
+
+        let mut histogram: Vec<TimeBucketResponse> = BUCKETS
+            .iter()
+            .map(|&up_to| TimeBucketResponse { up_to_seconds: Some(up_to), count: 0 })
+            .chain(std::iter::once(TimeBucketResponse { up_to_seconds: None, count: 0 }))
+            .collect();
+        for &seconds in &timed {
+            let bucket = BUCKETS.iter().position(|&up_to| seconds <= up_to).unwrap_or(BUCKETS.len());
+            histogram[bucket].count += 1;
+        }
+
+        TimingStatsResponse {
+            images: seconds.len(),
+            timed_images: n,
+            mean_seconds: (n > 0).then(|| total as f64 / n as f64),
+            median_seconds: median,
+            p90_seconds: p90,
+            labels_per_hour: (total > 0).then(|| n as f64 * 3600.0 / total as f64),
+            histogram,
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use std::io::{ErrorKind, SeekFrom};
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use super::{content_key, ByteStream, ImageStorage};
+
+/// Stores blobs on the local filesystem as `<root>/<k[0..2]>/<k[2..4]>/<k>`.
+#[derive(Clone, Debug)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, String> {
+        if key.len() < 4 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid storage key: {}", key));
+        }
+
+        Ok(self.root.join(&key[0..2]).join(&key[2..4]).join(key))
+    }
+}
+
+#[async_trait]
+impl ImageStorage for LocalStorage {
+    async fn put(&self, bytes: &[u8]) -> Result<String, String> {
+        let key = content_key(bytes);
+        let path = self.path_for(&key)?;
+
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(key);
+        }
+
+        let dir = path.parent().expect("storage path always has a parent");
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+
+        // Write to a temporary file first so readers never see a partial blob
+        let tmp_path = dir.join(format!("{}.{}.tmp", key, fastrand::u64(..)));
+        tokio::fs::write(&tmp_path, bytes)
+            .await
+            .map_err(|e| format!("Failed to write image to storage: {}", e))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .map_err(|e| format!("Failed to write image to storage: {}", e))?;
+
+        Ok(key)
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let path = self.path_for(key)?;
+        tokio::fs::read(&path)
+            .await
+            .map_err(|e| format!("Failed to read image {} from storage: {}", key, e))
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, String> {
+        let path = self.path_for(key)?;
+        tokio::fs::try_exists(&path)
+            .await
+            .map_err(|e| format!("Failed to check storage for {}: {}", key, e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to delete image {} from storage: {}", key, e)),
+        }
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, String> {
+        let path = self.path_for(key)?;
+        tokio::fs::metadata(&path)
+            .await
+            .map(|metadata| metadata.len())
+            .map_err(|e| format!("Failed to read image {} from storage: {}", key, e))
+    }
+
+    async fn stream(&self, key: &str, range: Option<(u64, u64)>) -> Result<ByteStream, String> {
+        let path = self.path_for(key)?;
+        let read_error = |e: std::io::Error| format!("Failed to read image {} from storage: {}", key, e);
+
+        let mut file = tokio::fs::File::open(&path).await.map_err(read_error)?;
+        let len = match range {
+            Some((start, end)) => {
+                file.seek(SeekFrom::Start(start)).await.map_err(read_error)?;
+                end - start + 1
+            }
+            None => file.metadata().await.map_err(read_error)?.len(),
+        };
+
+        Ok(Box::pin(ReaderStream::new(file.take(len)).map_err(|e| e.to_string())))
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        self.path_for(key).ok()
+    }
+}
@@ -0,0 +1,232 @@
+use actix_web::web::Bytes;
+use async_trait::async_trait;
+use base64::Engine;
+use futures_util::Stream;
+use sea_orm::*;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::entity::image::Model as ImageModel;
+
+pub mod local;
+pub mod s3;
+
+pub use local::LocalStorage;
+pub use s3::{S3Config, S3Storage};
+
+/// Backend holding the raw bytes of uploaded images. Objects are
+/// content-addressed: the key of a blob is the hex SHA-256 of its bytes, so
+/// storing the same file twice is a no-op.
+#[async_trait]
+pub trait ImageStorage: Send + Sync {
+    /// Stores `bytes` and returns the content key they can be fetched with.
+    async fn put(&self, bytes: &[u8]) -> Result<String, String>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+
+    async fn exists(&self, key: &str) -> Result<bool, String>;
+
+    async fn delete(&self, key: &str) -> Result<(), String>;
+
+    /// Size of a blob in bytes.
+    async fn size(&self, key: &str) -> Result<u64, String>;
+
+    /// Streams a blob, or only the inclusive byte `range` of it, without
+    /// loading it into memory.
+    async fn stream(&self, key: &str, range: Option<(u64, u64)>) -> Result<ByteStream, String>;
+
+    /// Location of the blob on the local filesystem, for backends that keep
+    /// one there, so it can be served straight from the file.
+    fn local_path(&self, _key: &str) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// Body of a blob being streamed out of storage.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, String>> + Send>>;
+
+pub fn content_key(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Builds the storage backend selected by `IMAGE_STORAGE` (`local` or `s3`).
+pub fn from_env() -> Result<Arc<dyn ImageStorage>, String> {
+    let backend = env::var("IMAGE_STORAGE").unwrap_or_else(|_| "local".to_string());
+
+    match backend.as_str() {
+        "local" => {
+            let root = env::var("IMAGE_STORAGE_DIR").unwrap_or_else(|_| "storage/images".to_string());
+            Ok(Arc::new(LocalStorage::new(root)))
+        }
+        "s3" => Ok(Arc::new(S3Storage::from_env()?)),
+        other => Err(format!("Unknown IMAGE_STORAGE backend: {}", other)),
+    }
+}
+
+/// Reads the original bytes of an image from storage.
+pub async fn read_image(storage: &dyn ImageStorage, image: &ImageModel) -> Result<Vec<u8>, String> {
+    match &image.storage_key {
+        Some(key) => storage.get(key).await,
+        None => Err(format!("Image {} has no stored content", image.id)),
+    }
+}
+
+/// Reads an image from storage and encodes it as base64 for JSON responses.
+pub async fn read_image_base64(storage: &dyn ImageStorage, image: &ImageModel) -> Result<String, String> {
+    let bytes = read_image(storage, image).await?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// One-shot move of image payloads that were stored as base64 TEXT in the
+/// database (copied into `legacy_image_data` by migration 2) into `storage`.
+/// Rows are removed from the legacy table as they are moved, so the function
+/// is safe to re-run and returns the number of images moved.
+pub async fn migrate_legacy_images(
+    db: &DatabaseConnection,
+    storage: &dyn ImageStorage,
+) -> Result<usize, String> {
+    let backend = db.get_database_backend();
+    let rows = db
+        .query_all(Statement::from_string(
+            backend,
+            "SELECT image_id, base64_data FROM legacy_image_data".to_string(),
+        ))
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut moved = 0;
+
+    for row in rows {
+        let image_id: i32 = row.try_get("", "image_id").map_err(|e| format!("Database error: {}", e))?;
+        let base64_data: String = row.try_get("", "base64_data").map_err(|e| format!("Database error: {}", e))?;
+
+        let bytes = match base64::engine::general_purpose::STANDARD.decode(base64_data.trim()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Warning: Skipping image {} with invalid base64 data: {}", image_id, e);
+                continue;
+            }
+        };
+
+        let key = storage.put(&bytes).await?;
+
+        db.execute(Statement::from_sql_and_values(
+            backend,
+            "UPDATE image SET storage_key = ?, size_bytes = ? WHERE id = ?",
+            [key.into(), (bytes.len() as i64).into(), image_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        db.execute(Statement::from_sql_and_values(
+            backend,
+            "DELETE FROM legacy_image_data WHERE image_id = ?",
+            [image_id.into()],
+        ))
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        moved += 1;
+    }
+
+    Ok(moved)
+}
+
+/// Fills in the header metadata (migration 4) of images uploaded before it
+/// was recorded. Images whose content cannot be decoded are left NULL and
+/// reported; returns the number of images updated.
+pub async fn backfill_image_metadata(
+    db: &DatabaseConnection,
+    storage: &dyn ImageStorage,
+) -> Result<usize, String> {
+    let images = crate::entity::image::Entity::find()
+        .filter(crate::entity::image::Column::Width.is_null())
+        .filter(crate::entity::image::Column::StorageKey.is_not_null())
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut updated = 0;
+
+    for image in images {
+        let info = match read_image(storage, &image).await {
+            Ok(bytes) => tokio::task::spawn_blocking(move || crate::imaging::inspect(&bytes))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|info| info),
+            Err(e) => Err(e),
+        };
+        let info = match info {
+            Ok(info) => info,
+            Err(e) => {
+                eprintln!("Warning: Could not read metadata of image {}: {}", image.id, e);
+                continue;
+            }
+        };
+
+        crate::repository::ImageRepository::set_metadata(
+            db,
+            image.id,
+            info.width as i32,
+            info.height as i32,
+            info.color_mode,
+            info.orientation.map(i32::from),
+        )
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        updated += 1;
+    }
+
+    Ok(updated)
+}
+
+/// Computes the perceptual hashes (migration 7) of images uploaded before
+/// they were recorded. Images that fail to decode are reported and left
+/// NULL; returns the number of images hashed.
+pub async fn backfill_image_hashes(
+    db: &DatabaseConnection,
+    storage: &dyn ImageStorage,
+) -> Result<usize, String> {
+    let images = crate::entity::image::Entity::find()
+        .filter(crate::entity::image::Column::DifferenceHash.is_null())
+        .filter(crate::entity::image::Column::StorageKey.is_not_null())
+        .all(db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+    let mut updated = 0;
+
+    for image in images {
+        let hash = match read_image(storage, &image).await {
+            Ok(bytes) => tokio::task::spawn_blocking(move || crate::imaging::perceptual_hash(&bytes))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|hash| hash),
+            Err(e) => Err(e),
+        };
+        let hash = match hash {
+            Ok(hash) => hash,
+            Err(e) => {
+                eprintln!("Warning: Could not hash image {}: {}", image.id, e);
+                continue;
+            }
+        };
+
+        crate::repository::ImageRepository::set_hashes(
+            db,
+            image.id,
+            crate::imaging::hash::to_hex(hash.average),
+            crate::imaging::hash::to_hex(hash.difference),
+        )
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        updated += 1;
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,306 @@
+use actix_web::web::Bytes;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::io::Read;
+
+use super::{content_key, ByteStream, ImageStorage};
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone, Debug)]
+pub struct S3Config {
+    /// Base URL of the service, e.g. `https://s3.us-east-1.amazonaws.com` or
+    /// `http://localhost:9000` for MinIO. Buckets are addressed path-style.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Prepended to every object key, e.g. `images/`.
+    pub prefix: String,
+}
+
+/// Stores blobs in an S3-compatible bucket (AWS S3, MinIO, ...), signing
+/// requests with AWS Signature Version 4.
+pub struct S3Storage {
+    config: S3Config,
+}
+
+struct S3Response {
+    status: u16,
+    content_length: Option<u64>,
+    body: Vec<u8>,
+}
+
+// Bytes read from S3 per chunk of a streamed object
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Self {
+        Self { config }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        let require = |name: &str| env::var(name).map_err(|_| format!("{} must be set when IMAGE_STORAGE=s3", name));
+
+        Ok(Self::new(S3Config {
+            endpoint: require("S3_ENDPOINT")?,
+            bucket: require("S3_BUCKET")?,
+            region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            access_key_id: require("S3_ACCESS_KEY_ID")?,
+            secret_access_key: require("S3_SECRET_ACCESS_KEY")?,
+            prefix: env::var("S3_PREFIX").unwrap_or_else(|_| "images/".to_string()),
+        }))
+    }
+
+    fn check_key(key: &str) -> Result<(), String> {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid storage key: {}", key));
+        }
+        Ok(())
+    }
+
+    async fn send(&self, method: &'static str, key: &str, body: Option<Vec<u8>>) -> Result<S3Response, String> {
+        Self::check_key(key)?;
+
+        let config = self.config.clone();
+        let key = key.to_string();
+
+        tokio::task::spawn_blocking(move || Self::send_blocking(&config, method, &key, body))
+            .await
+            .map_err(|e| format!("S3 request task failed: {}", e))?
+    }
+
+    fn send_blocking(config: &S3Config, method: &str, key: &str, body: Option<Vec<u8>>) -> Result<S3Response, String> {
+        let response = Self::request_blocking(config, method, key, body, None)?;
+
+        let status = response.status();
+        let content_length = response.header("Content-Length").and_then(|value| value.parse().ok());
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|e| format!("Failed to read S3 response: {}", e))?;
+
+        Ok(S3Response { status, content_length, body })
+    }
+
+    // Signs and sends a request, returning the response before its body is read
+    fn request_blocking(
+        config: &S3Config,
+        method: &str,
+        key: &str,
+        body: Option<Vec<u8>>,
+        range: Option<(u64, u64)>,
+    ) -> Result<ureq::Response, String> {
+        let endpoint = config.endpoint.trim_end_matches('/');
+        let host = endpoint
+            .split("://")
+            .nth(1)
+            .unwrap_or(endpoint)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let path = format!("/{}/{}{}", config.bucket, config.prefix, key);
+        let url = format!("{}{}", endpoint, path);
+
+        let payload = body.unwrap_or_default();
+        let payload_hash = hex::encode(Sha256::digest(&payload));
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+        let headers = vec![
+            ("host".to_string(), host),
+            ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+            ("x-amz-date".to_string(), amz_date.clone()),
+        ];
+        let authorization = sign_v4(
+            method,
+            &path,
+            "",
+            &headers,
+            &payload_hash,
+            &config.access_key_id,
+            &config.secret_access_key,
+            &config.region,
+            &amz_date,
+        );
+
+        let mut request = ureq::request(method, &url)
+            .set("x-amz-content-sha256", &payload_hash)
+            .set("x-amz-date", &amz_date)
+            .set("Authorization", &authorization);
+        if let Some((start, end)) = range {
+            request = request.set("Range", &format!("bytes={}-{}", start, end));
+        }
+
+        let result = if method == "PUT" {
+            request.send_bytes(&payload)
+        } else {
+            request.call()
+        };
+
+        match result {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(_, response)) => Ok(response),
+            Err(e) => Err(format!("S3 request failed: {}", e)),
+        }
+    }
+}
+
+#[async_trait]
+impl ImageStorage for S3Storage {
+    async fn put(&self, bytes: &[u8]) -> Result<String, String> {
+        let key = content_key(bytes);
+        let response = self.send("PUT", &key, Some(bytes.to_vec())).await?;
+
+        match response.status {
+            200..=299 => Ok(key),
+            status => Err(format!(
+                "S3 PUT for {} failed with status {}: {}",
+                key,
+                status,
+                String::from_utf8_lossy(&response.body)
+            )),
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let response = self.send("GET", key, None).await?;
+
+        match response.status {
+            200..=299 => Ok(response.body),
+            status => Err(format!("S3 GET for {} failed with status {}", key, status)),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, String> {
+        let response = self.send("HEAD", key, None).await?;
+
+        match response.status {
+            200..=299 => Ok(true),
+            404 => Ok(false),
+            status => Err(format!("S3 HEAD for {} failed with status {}", key, status)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self.send("DELETE", key, None).await?;
+
+        match response.status {
+            200..=299 | 404 => Ok(()),
+            status => Err(format!("S3 DELETE for {} failed with status {}", key, status)),
+        }
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, String> {
+        let response = self.send("HEAD", key, None).await?;
+
+        match (response.status, response.content_length) {
+            (200..=299, Some(len)) => Ok(len),
+            (200..=299, None) => Err(format!("S3 HEAD for {} returned no Content-Length", key)),
+            (status, _) => Err(format!("S3 HEAD for {} failed with status {}", key, status)),
+        }
+    }
+
+    async fn stream(&self, key: &str, range: Option<(u64, u64)>) -> Result<ByteStream, String> {
+        Self::check_key(key)?;
+
+        let config = self.config.clone();
+        let key = key.to_string();
+        let response = tokio::task::spawn_blocking(move || Self::request_blocking(&config, "GET", &key, None, range))
+            .await
+            .map_err(|e| format!("S3 request task failed: {}", e))??;
+        if !(200..=299).contains(&response.status()) {
+            return Err(format!("S3 GET failed with status {}", response.status()));
+        }
+
+        // The response is read on a blocking thread and handed over chunk by chunk
+        let (sender, receiver) = tokio::sync::mpsc::channel(4);
+        tokio::task::spawn_blocking(move || {
+            let mut reader = response.into_reader();
+            let mut buf = vec![0; STREAM_CHUNK_SIZE];
+            loop {
+                let chunk = match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => Ok(Bytes::copy_from_slice(&buf[..n])),
+                    Err(e) => Err(format!("Failed to read S3 response: {}", e)),
+                };
+                let failed = chunk.is_err();
+                if sender.blocking_send(chunk).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Box::pin(futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|chunk| (chunk, receiver))
+        })))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Computes the `Authorization` header value for an S3 request using AWS
+/// Signature Version 4. `headers` are the headers to sign as lowercase
+/// `(name, value)` pairs and `amz_date` is the request time in
+/// `YYYYMMDDTHHMMSSZ` format (the same value sent as `x-amz-date`).
+#[allow(clippy::too_many_arguments)]
+pub fn sign_v4(
+    method: &str,
+    path: &str,
+    query: &str,
+    headers: &[(String, String)],
+    payload_hash: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    amz_date: &str,
+) -> String {
+    let mut headers: Vec<(String, String)> = headers
+        .iter()
+        .map(|(name, value)| (name.to_lowercase(), value.trim().to_string()))
+        .collect();
+    headers.sort();
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method, path, query, canonical_headers, signed_headers, payload_hash
+    );
+
+    let date = &amz_date[..8];
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let date_key = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, b"s3");
+    let signing_key = hmac_sha256(&service_key, b"aws4_request");
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{},SignedHeaders={},Signature={}",
+        access_key_id, scope, signed_headers, signature
+    )
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Longest free-text answer, in characters.
+pub const MAX_TEXT_LENGTH: usize = 2000;
+
+/// What labelers answer for each image of a group. Serialized as e.g.
+/// `{"type": "multi_choice", "min": 1, "max": 3}`, which is also how it is
+/// recorded on groups.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskType {
+    /// Exactly one of the group's tags.
+    SingleChoice,
+    /// Between `min` and `max` of the group's tags; any number by default.
+    MultiChoice {
+        #[serde(default)]
+        min: u32,
+        #[serde(default)]
+        max: Option<u32>,
+    },
+    /// A caption instead of tags.
+    FreeText,
+}
+
+impl Default for TaskType {
+    fn default() -> Self {
+        Self::MultiChoice { min: 0, max: None }
+    }
+}
+
+impl TaskType {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Self::MultiChoice { min, max: Some(max) } = self {
+            if *max == 0 {
+                return Err("Task max must be at least 1".to_string());
+            }
+            if min > max {
+                return Err("Task min must not be greater than max".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// Checks a labeler's answer: the distinct tags chosen and the text
+    /// written.
+    pub fn check_answer(&self, tag_ids: &[i32], text: Option<&str>) -> Result<(), String> {
+        let mut distinct = tag_ids.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let count = distinct.len() as u32;
+        match self {
+            Self::FreeText => {
+                if count > 0 {
+                    return Err("This group takes a text answer, not tags".to_string());
+                }
+                let text = text.map(str::trim).unwrap_or_default();
+                if text.is_empty() {
+                    return Err("A text answer is required".to_string());
+                }
+                if text.chars().count() > MAX_TEXT_LENGTH {
+                    return Err(format!("The text answer may be at most {} characters", MAX_TEXT_LENGTH));
+                }
+                return Ok(());
+            }
+            Self::SingleChoice => {
+                if count != 1 {
+                    return Err("Choose exactly one tag".to_string());
+                }
+            }
+            Self::MultiChoice { min, max } => {
+                if count < *min {
+                    return Err(format!("Choose at least {} tags", min));
+                }
+                if let Some(max) = max
+                    && count > *max {
+                    return Err(format!("Choose at most {} tags", max));
+                }
+            }
+        }
+        if text.is_some_and(|text| !text.trim().is_empty()) {
+            return Err("This group takes tags, not a text answer".to_string());
+        }
+        Ok(())
+    }
+
+    /// Narrows the tags a consensus strategy picked, each with its support
+    /// (votes or posterior), to what the task allows: for single-choice the
+    /// best supported tag, and none on a tie; for multi-choice at most `max`
+    /// of the best supported tags. Returned in ascending tag order.
+    pub fn limit<T>(&self, mut picked: Vec<(i32, f64, T)>) -> Vec<(i32, f64, T)> {
+        picked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal).then(a.0.cmp(&b.0)));
+        match self {
+            Self::FreeText => picked.clear(),
+            Self::SingleChoice => {
+                if picked.len() > 1 && (picked[0].1 - picked[1].1).abs() < 1e-9 {
+                    picked.clear();
+                }
+                picked.truncate(1);
+            }
+            Self::MultiChoice { max, .. } => {
+                if let Some(max) = max {
+                    picked.truncate(*max as usize);
+                }
+            }
+        }
+        picked.sort_by_key(|&(tag_id, _, _)| tag_id);
+        picked
+    }
+}
+
+/// Free-text answers compare equal when they only differ in case and
+/// whitespace.
+pub fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
@@ -1 +1,4 @@
+// Shared helpers; not every test crate uses all of them
+#![allow(dead_code)]
+
 pub mod test_utils;
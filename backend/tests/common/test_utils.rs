@@ -1,4 +1,4 @@
-use image_labeling_website::database::{establish_connection_with_url, create_tables};
+use image_labeling_website::database::{establish_connection_with_url, run_migrations};
 use std::fs;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -13,6 +13,15 @@ pub struct TestDatabase {
 
 impl TestDatabase {
     pub async fn new() -> Self {
+        // The server does not start without it, and signs image URLs with it
+        unsafe { std::env::set_var("JWT_SECRET", TEST_JWT_SECRET); }
+        let test_db = Self::new_unmigrated().await;
+        run_migrations(&test_db.connection).await.expect("Failed to migrate test database");
+        test_db
+    }
+
+    // Empty database file with no schema, for exercising the migration runner
+    pub async fn new_unmigrated() -> Self {
         // Generate unique database filename using counter + timestamp + random
         let counter = COUNTER.fetch_add(1, Ordering::SeqCst);
         let timestamp = SystemTime::now()
@@ -35,7 +44,6 @@ impl TestDatabase {
         // Establish connection with specific database URL (no environment variable)
         let database_url = format!("sqlite:{}", db_name);
         let connection = establish_connection_with_url(&database_url).await.expect("Failed to connect to test database");
-        create_tables(&connection).await.expect("Failed to create test tables");
         
         Self { connection, db_name }
     }
@@ -56,3 +64,181 @@ impl Drop for TestDatabase {
 pub async fn setup_test_db() -> TestDatabase {
     TestDatabase::new().await
 }
+
+pub struct TestStorage {
+    pub storage: image_labeling_website::storage::LocalStorage,
+    pub dir: std::path::PathBuf,
+}
+
+impl TestStorage {
+    pub fn new() -> Self {
+        let counter = COUNTER.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("test_storage_{}_{}", counter, fastrand::u64(..)));
+        let storage = image_labeling_website::storage::LocalStorage::new(&dir);
+
+        Self { storage, dir }
+    }
+}
+
+impl Drop for TestStorage {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+pub const TEST_JWT_SECRET: &str = "test-secret-key-for-testing";
+
+// Issues a bearer token the auth middlewares accept
+pub fn test_token(user_id: i32, role: &str) -> String {
+    unsafe { std::env::set_var("JWT_SECRET", TEST_JWT_SECRET); }
+
+    let claims = image_labeling_website::schemas::auth::Claims {
+        user_id,
+        role: role.to_string(),
+        exp: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp() as usize,
+    };
+
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(TEST_JWT_SECRET.as_ref()),
+    )
+    .expect("Failed to encode test token")
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32fast::hash(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Valid RGB PNG with a gradient, so different sizes give different bytes
+pub fn test_png(width: u32, height: u32) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut raw = Vec::new();
+    for y in 0..height {
+        raw.push(0);
+        for x in 0..width {
+            raw.extend_from_slice(&[(x * 255 / width.max(1)) as u8, (y * 255 / height.max(1)) as u8, 128]);
+        }
+    }
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(&raw).unwrap();
+
+    let mut ihdr = Vec::new();
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    png_chunk(&mut out, b"IHDR", &ihdr);
+    png_chunk(&mut out, b"IDAT", &encoder.finish().unwrap());
+    png_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+// Baseline JPEG with the same gradient as `test_png`, and an EXIF
+// orientation in APP1 when given
+pub fn test_jpeg(width: u32, height: u32, orientation: Option<u16>) -> Vec<u8> {
+    let pixels = image::RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb([(x * 255 / width.max(1)) as u8, (y * 255 / height.max(1)) as u8, 128])
+    });
+    let mut encoded = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, 90)
+        .encode_image(&pixels)
+        .expect("Failed to encode test JPEG");
+
+    let mut out = encoded[..2].to_vec();
+    if let Some(orientation) = orientation {
+        let mut tiff = b"MM\0*\0\0\0\x08".to_vec();
+        tiff.extend_from_slice(&1u16.to_be_bytes());
+        tiff.extend_from_slice(&0x0112u16.to_be_bytes());
+        tiff.extend_from_slice(&3u16.to_be_bytes());
+        tiff.extend_from_slice(&1u32.to_be_bytes());
+        tiff.extend_from_slice(&orientation.to_be_bytes());
+        tiff.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend_from_slice(&tiff);
+        out.extend_from_slice(&[0xFF, 0xE1]);
+        out.extend_from_slice(&((app1.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(&app1);
+    }
+    out.extend_from_slice(&encoded[2..]);
+    out
+}
+
+// Uncompressed 24-bit bottom-up BMP with the same gradient as `test_png`
+pub fn test_bmp(width: u32, height: u32) -> Vec<u8> {
+    let stride = (width as usize * 3).div_ceil(4) * 4;
+    let mut pixels = Vec::new();
+    for y in (0..height).rev() {
+        let start = pixels.len();
+        for x in 0..width {
+            pixels.extend_from_slice(&[128, (y * 255 / height.max(1)) as u8, (x * 255 / width.max(1)) as u8]);
+        }
+        pixels.resize(start + stride, 0);
+    }
+
+    let mut out = b"BM".to_vec();
+    out.extend_from_slice(&(54 + pixels.len() as u32).to_le_bytes());
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    out.extend_from_slice(&54u32.to_le_bytes());
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&(height as i32).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&24u16.to_le_bytes());
+    out.extend_from_slice(&[0; 24]);
+    out.extend_from_slice(&pixels);
+    out
+}
+
+// Uncompressed little-endian RGB TIFF in one strip, with the same gradient
+// as `test_png`
+pub fn test_tiff(width: u32, height: u32) -> Vec<u8> {
+    let mut pixels = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            pixels.extend_from_slice(&[(x * 255 / width.max(1)) as u8, (y * 255 / height.max(1)) as u8, 128]);
+        }
+    }
+
+    // Bits per sample don't fit in the entry, so they follow the directory
+    let ifd = 8 + pixels.len() as u32;
+    let bits = ifd + 2 + 8 * 12 + 4;
+    let entries: [(u16, u16, u32, u32); 8] = [
+        (256, 4, 1, width),
+        (257, 4, 1, height),
+        (258, 3, 3, bits),
+        (259, 3, 1, 1),
+        (262, 3, 1, 2),
+        (273, 4, 1, 8),
+        (277, 3, 1, 3),
+        (279, 4, 1, pixels.len() as u32),
+    ];
+
+    let mut out = b"II*\0".to_vec();
+    out.extend_from_slice(&ifd.to_le_bytes());
+    out.extend_from_slice(&pixels);
+    out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    for (tag, field_type, count, value) in entries {
+        out.extend_from_slice(&tag.to_le_bytes());
+        out.extend_from_slice(&field_type.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        if field_type == 3 && count == 1 {
+            out.extend_from_slice(&(value as u16).to_le_bytes());
+            out.extend_from_slice(&[0, 0]);
+        } else {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    for _ in 0..3 {
+        out.extend_from_slice(&8u16.to_le_bytes());
+    }
+    out
+}
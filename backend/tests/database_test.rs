@@ -81,7 +81,8 @@ async fn test_database_operations(db: &sea_orm::DatabaseConnection) -> Result<()
         db,
         "test_image.jpg".to_string(),
         "jpg".to_string(),
-        "base64_encoded_image_data".to_string(),
+        "test_storage_key".to_string(),
+        1024,
         group.id,
     ).await?;
     println!("Created image: {:?}", image);
@@ -22,7 +22,8 @@ async fn test_multiple_tags_assignment() -> Result<(), Box<dyn std::error::Error
         &test_db.connection,
         "test.jpg".to_string(),
         "jpg".to_string(),
-        "base64_encoded_image_data".to_string(),
+        "test_storage_key".to_string(),
+        1024,
         group.id,
     ).await?;
     
@@ -17,13 +17,15 @@ async fn test_image_operations() -> Result<(), Box<dyn std::error::Error>> {
         &test_db.connection,
         "test.jpg".to_string(),
         "jpg".to_string(),
-        "base64_encoded_image_data".to_string(),
+        "test_storage_key".to_string(),
+        1024,
         group.id,
     ).await?;
     
     assert_eq!(image.filename, "test.jpg");
     assert_eq!(image.filetype, "jpg");
-    assert_eq!(image.base64_data, "base64_encoded_image_data");
+    assert_eq!(image.storage_key.as_deref(), Some("test_storage_key"));
+    assert_eq!(image.size_bytes, Some(1024));
     assert_eq!(image.group_id, group.id);
     
     // Test getting images by group
@@ -0,0 +1,175 @@
+mod common;
+
+use common::test_utils::{test_bmp, test_jpeg, test_png, test_tiff};
+use image_labeling_website::imaging::{self, hash, ImageFormat, PerceptualHash, Raster};
+
+#[test]
+fn test_inspect_png() {
+    let info = imaging::inspect(&test_png(7, 3)).unwrap();
+    assert_eq!(info.format, ImageFormat::Png);
+    assert_eq!((info.width, info.height), (7, 3));
+    assert_eq!(info.color_mode, "RGB");
+    assert_eq!(info.orientation, None);
+}
+
+#[test]
+fn test_inspect_jpeg_with_exif_orientation() {
+    let info = imaging::inspect(&test_jpeg(64, 48, Some(6))).unwrap();
+    assert_eq!(info.format, ImageFormat::Jpeg);
+    assert_eq!((info.width, info.height), (64, 48));
+    assert_eq!(info.color_mode, "RGB");
+    assert_eq!(info.orientation, Some(6));
+
+    let info = imaging::inspect(&test_jpeg(10, 10, None)).unwrap();
+    assert_eq!(info.orientation, None);
+}
+
+#[test]
+fn test_inspect_rejects_corrupt_and_unknown_content() {
+    // Flipped byte inside IHDR breaks the chunk CRC
+    let mut png = test_png(4, 4);
+    png[18] ^= 0xFF;
+    assert!(imaging::inspect(&png).is_err());
+
+    // Truncated before IEND
+    let png = test_png(4, 4);
+    assert!(imaging::inspect(&png[..png.len() - 20]).is_err());
+
+    // JPEG without a frame header
+    assert!(imaging::inspect(&[0xFF, 0xD8, 0xFF, 0xD9]).is_err());
+
+    // Valid headers, but the scan data is cut off
+    let jpeg = test_jpeg(64, 64, None);
+    assert!(imaging::inspect(&jpeg[..jpeg.len() / 2]).is_err());
+
+    assert!(imaging::inspect(b"GIF89a truncated").is_err());
+    assert_eq!(ImageFormat::detect(b"hello"), None);
+    assert_eq!(ImageFormat::from_filetype("JPG"), Some(ImageFormat::Jpeg));
+}
+
+#[test]
+fn test_decode_png_and_jpeg_roundtrip() {
+    let raster = imaging::decode(&test_png(16, 8)).unwrap();
+    assert_eq!((raster.width, raster.height), (16, 8));
+    assert_eq!(raster.pixels.len(), 16 * 8 * 3);
+
+    let jpeg = imaging::encode(&raster, ImageFormat::Jpeg, 90).unwrap();
+    let info = imaging::inspect(&jpeg).unwrap();
+    assert_eq!(info.format, ImageFormat::Jpeg);
+    assert_eq!((info.width, info.height), (16, 8));
+
+    // Lossy, but every channel should land close to the source
+    let decoded = imaging::decode(&jpeg).unwrap();
+    let max_error = raster.pixels.iter()
+        .zip(&decoded.pixels)
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap();
+    assert!(max_error < 40, "max error {}", max_error);
+}
+
+#[test]
+fn test_raster_resize_and_orientation() {
+    let raster = Raster::new(400, 100, vec![128; 400 * 100 * 3]);
+
+    let small = raster.fit_within(128);
+    assert_eq!((small.width, small.height), (128, 32));
+    assert!(small.pixels.iter().all(|&p| p == 128));
+
+    // Never upscales
+    let same = raster.fit_within(1000);
+    assert_eq!((same.width, same.height), (400, 100));
+
+    // Orientations 5-8 swap the axes
+    let rotated = small.clone().oriented(6);
+    assert_eq!((rotated.width, rotated.height), (32, 128));
+    let flipped = small.oriented(2);
+    assert_eq!((flipped.width, flipped.height), (128, 32));
+}
+
+#[test]
+fn test_thumbnail_applies_exif_orientation() {
+    // 4x2 image with a red left half and a blue right half
+    let mut pixels = Vec::new();
+    for _ in 0..2 {
+        for x in 0..4 {
+            pixels.extend_from_slice(if x < 2 { &[255, 0, 0] } else { &[0, 0, 255] });
+        }
+    }
+    let raster = Raster::new(4, 2, pixels);
+    let rotated = raster.oriented(6);
+    assert_eq!((rotated.width, rotated.height), (2, 4));
+    // Rotating 90° clockwise puts the former left column on top
+    assert_eq!(rotated.pixel(0, 0), [255, 0, 0]);
+    assert_eq!(rotated.pixel(0, 3), [0, 0, 255]);
+
+    let thumbnail = imaging::thumbnail(&test_png(300, 150), 100, ImageFormat::Jpeg, 80).unwrap();
+    assert_eq!((thumbnail.width, thumbnail.height), (100, 50));
+    assert_eq!(ImageFormat::detect(&thumbnail.bytes), Some(ImageFormat::Jpeg));
+
+    // Orientation 6 turns the landscape image into a portrait thumbnail
+    let thumbnail = imaging::thumbnail(&test_jpeg(60, 30, Some(6)), 20, ImageFormat::WebP, 80).unwrap();
+    assert_eq!((thumbnail.width, thumbnail.height), (10, 20));
+    let info = imaging::inspect(&thumbnail.bytes).unwrap();
+    assert_eq!((info.format, info.width, info.height), (ImageFormat::WebP, 10, 20));
+    assert!(imaging::thumbnail(&test_png(10, 10), 20, ImageFormat::Gif, 80).is_err());
+
+    // Headers without scan data cannot be decoded
+    let jpeg = test_jpeg(10, 10, None);
+    assert!(imaging::thumbnail(&jpeg[..jpeg.len() / 2], 100, ImageFormat::Jpeg, 80).is_err());
+}
+
+#[test]
+fn test_inspect_and_decode_other_formats() {
+    // BMP and TIFF fixtures hold the same pixels as the PNG one
+    let reference = imaging::decode(&test_png(9, 5)).unwrap();
+    for (bytes, format) in [(test_bmp(9, 5), ImageFormat::Bmp), (test_tiff(9, 5), ImageFormat::Tiff)] {
+        let info = imaging::inspect(&bytes).unwrap();
+        assert_eq!(info.format, format);
+        assert_eq!((info.width, info.height), (9, 5));
+        assert_eq!(info.color_mode, "RGB");
+        assert_eq!(imaging::decode(&bytes).unwrap(), reference);
+    }
+
+    // 1x1 GIF whose only pixel is transparent
+    let gif = b"GIF89a\x01\x00\x01\x00\x80\x00\x00\x00\x00\x00\xff\xff\xff\
+        !\xf9\x04\x01\x00\x00\x00\x00,\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02D\x01\x00;";
+    let info = imaging::inspect(gif).unwrap();
+    assert_eq!((info.format, info.width, info.height), (ImageFormat::Gif, 1, 1));
+    assert_eq!(info.color_mode, "P");
+    assert_eq!(imaging::decode(gif).unwrap().pixels, vec![255, 255, 255]);
+
+    // Lossless WebP with alpha
+    let mut webp = Vec::new();
+    let pixels = image::RgbaImage::from_pixel(30, 20, image::Rgba([0, 0, 255, 128]));
+    image::codecs::webp::WebPEncoder::new_lossless(&mut webp).encode(&pixels, 30, 20, image::ExtendedColorType::Rgba8).unwrap();
+    let info = imaging::inspect(&webp).unwrap();
+    assert_eq!((info.format, info.width, info.height), (ImageFormat::WebP, 30, 20));
+    assert_eq!(info.color_mode, "RGBA");
+    // Half-transparent blue over white
+    assert_eq!(imaging::decode(&webp).unwrap().pixel(0, 0), [127, 127, 255]);
+
+    assert_eq!(ImageFormat::from_filetype("image/tiff"), Some(ImageFormat::Tiff));
+    assert_eq!(ImageFormat::from_filetype("tif"), Some(ImageFormat::Tiff));
+    assert_eq!(ImageFormat::Jpeg.mime(), "image/jpeg");
+}
+
+#[test]
+fn test_perceptual_hashes_match_across_encodings() {
+    let png = imaging::perceptual_hash(&test_png(48, 32)).unwrap();
+    assert_eq!(imaging::perceptual_hash(&test_bmp(48, 32)).unwrap(), png);
+
+    // Re-encoding and rescaling barely move the hashes
+    let raster = imaging::decode(&test_png(48, 32)).unwrap();
+    let jpeg = imaging::perceptual_hash(&imaging::encode(&raster.fit_within(24), ImageFormat::Jpeg, 60).unwrap()).unwrap();
+    assert!(hash::hamming_distance(png.average, jpeg.average) <= 4);
+    assert!(hash::hamming_distance(png.difference, jpeg.difference) <= 4);
+
+    // A mirrored image is a different picture
+    let mirrored = PerceptualHash::of(&raster.oriented(2));
+    assert!(hash::hamming_distance(png.difference, mirrored.difference) > 20);
+
+    assert_eq!(hash::from_hex(&hash::to_hex(png.difference)), Some(png.difference));
+    assert_eq!(hash::from_hex("xyz"), None);
+    assert!(imaging::perceptual_hash(&[0xFF, 0xD8, 0xFF, 0xD9]).is_err());
+}
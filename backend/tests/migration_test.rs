@@ -0,0 +1,70 @@
+mod common;
+
+use image_labeling_website::migrations;
+use image_labeling_website::repository::*;
+use sea_orm::{ConnectionTrait, Statement};
+use crate::common::test_utils::{setup_test_db, TestDatabase};
+
+async fn table_columns(db: &sea_orm::DatabaseConnection, table: &str) -> Vec<String> {
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            format!("PRAGMA table_info(\"{}\")", table),
+        ))
+        .await
+        .expect("Failed to read table info");
+
+    rows.iter()
+        .map(|row| row.try_get::<String>("", "name").unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_fresh_database_is_migrated_to_latest() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+
+    assert_eq!(migrations::current_version(&test_db.connection).await?, migrations::latest_version());
+    assert!(migrations::pending(&test_db.connection).await?.is_empty());
+
+    // Running again is a no-op
+    let applied = migrations::run(&test_db.connection).await?;
+    assert!(applied.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migrations_apply_in_order() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = TestDatabase::new_unmigrated().await;
+
+    assert_eq!(migrations::current_version(&test_db.connection).await?, 0);
+
+    let applied = migrations::run(&test_db.connection).await?;
+    let versions: Vec<i64> = applied.iter().map(|m| m.version).collect();
+    let expected: Vec<i64> = migrations::all().iter().map(|m| m.version).collect();
+    assert_eq!(versions, expected);
+    assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_legacy_database_is_upgraded() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = TestDatabase::new_unmigrated().await;
+    let db = &test_db.connection;
+
+    // Schema as produced by the old CREATE TABLE IF NOT EXISTS bootstrap, with data
+    for statement in migrations::all()[0].statements {
+        sea_orm::ConnectionTrait::execute_unprepared(db, statement).await?;
+    }
+    // Raw SQL, since the entity may already expect newer columns
+    sea_orm::ConnectionTrait::execute_unprepared(db, r#"INSERT INTO "group" (id, name) VALUES (1, 'Legacy')"#).await?;
+
+    migrations::run(db).await?;
+
+    assert_eq!(migrations::current_version(db).await?, migrations::latest_version());
+    assert!(GroupRepository::find_by_id(db, 1).await?.is_some());
+    assert!(table_columns(db, "group").await.contains(&"name".to_string()));
+
+    Ok(())
+}
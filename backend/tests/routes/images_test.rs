@@ -0,0 +1,363 @@
+use actix_web::{web, App, test, http::StatusCode, http::header};
+use image_labeling_website::imaging::ImageFormat;
+use image_labeling_website::entity::image;
+use image_labeling_website::middleware::auth::{verify_signed_url, ImageAuthMiddleware};
+use image_labeling_website::repository::*;
+use image_labeling_website::routes::images::{get_image_content, get_image_thumbnail, parse_range, ByteRange};
+use image_labeling_website::service::image::ImageService;
+use image_labeling_website::storage::ImageStorage;
+use hmac::{Hmac, Mac};
+use sea_orm::{ActiveModelTrait, Set};
+use sha2::Sha256;
+use super::super::common::test_utils::{setup_test_db, test_png, test_token, TestStorage, TEST_JWT_SECRET};
+use std::sync::Arc;
+
+const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n0123456789";
+
+macro_rules! image_app {
+    ($test_db:expr, $test_storage:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new($test_db.connection.clone()))
+                .app_data(web::Data::from(Arc::new($test_storage.storage.clone()) as Arc<dyn ImageStorage>))
+                .service(
+                    web::scope("/api/v1/images")
+                        .wrap(ImageAuthMiddleware)
+                        .route("/{id}/content", web::get().to(get_image_content))
+                        .route("/{id}/thumbnail", web::get().to(get_image_thumbnail))
+                )
+        ).await
+    };
+}
+
+#[tokio::test]
+async fn test_image_content_served_with_caching_headers() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+    let key = test_storage.storage.put(PNG_BYTES).await?;
+    let image = ImageRepository::create(
+        &test_db.connection,
+        "a.png".to_string(),
+        "png".to_string(),
+        key.clone(),
+        PNG_BYTES.len() as i64,
+        group.id,
+    ).await?;
+
+    let app = image_app!(test_db, test_storage);
+    let token = test_token(1, "admin");
+    let uri = format!("/api/v1/images/{}/content", image.id);
+
+    // Full body
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get(header::CONTENT_TYPE).unwrap(), "image/png");
+    assert_eq!(resp.headers().get(header::ETAG).unwrap().to_str()?, format!("\"{}\"", key));
+    assert_eq!(resp.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+    assert!(resp.headers().get(header::CACHE_CONTROL).is_some());
+    let body = test::read_body(resp).await;
+    assert_eq!(body.as_ref(), PNG_BYTES);
+
+    // Conditional request
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .insert_header((header::IF_NONE_MATCH, format!("\"{}\"", key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+    // Byte range
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .insert_header((header::RANGE, "bytes=8-11"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        resp.headers().get(header::CONTENT_RANGE).unwrap().to_str()?,
+        format!("bytes 8-11/{}", PNG_BYTES.len())
+    );
+    let body = test::read_body(resp).await;
+    assert_eq!(body.as_ref(), b"0123");
+
+    // Range past the end
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .insert_header((header::RANGE, "bytes=500-"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+    // A stale If-Range gets the whole body instead of the range
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .insert_header((header::RANGE, "bytes=8-11"))
+        .insert_header((header::IF_RANGE, "\"outdated\""))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get(header::ETAG).unwrap().to_str()?, format!("\"{}\"", key));
+    let body = test::read_body(resp).await;
+    assert_eq!(body.as_ref(), PNG_BYTES);
+
+    // Images whose content was never stored
+    let mut missing: image::ActiveModel = image.into();
+    missing.storage_key = Set(None);
+    missing.update(&test_db.connection).await?;
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(resp.headers().get(header::ETAG).is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_content_requires_group_membership() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+    let member = LabelerRepository::create(&test_db.connection, "member".to_string(), "hash".to_string()).await?;
+    let outsider = LabelerRepository::create(&test_db.connection, "outsider".to_string(), "hash".to_string()).await?;
+    LabelerRepository::add_to_group(&test_db.connection, member.id, group.id).await?;
+
+    let key = test_storage.storage.put(PNG_BYTES).await?;
+    let image = ImageRepository::create(
+        &test_db.connection,
+        "a.png".to_string(),
+        "png".to_string(),
+        key,
+        PNG_BYTES.len() as i64,
+        group.id,
+    ).await?;
+
+    let app = image_app!(test_db, test_storage);
+    let uri = format!("/api/v1/images/{}/content", image.id);
+
+    // No credentials
+    let req = test::TestRequest::get().uri(&uri).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // Labeler outside the group
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", test_token(outsider.id, "labeler"))))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // Group member
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", test_token(member.id, "labeler"))))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Suspended labelers lose access along with the rest of the labeler API
+    LabelerRepository::set_suspended(&test_db.connection, member.id, true, None).await?;
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", test_token(member.id, "labeler"))))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    LabelerRepository::set_suspended(&test_db.connection, member.id, false, None).await?;
+
+    // Tokens are not accepted in the query string
+    let req = test::TestRequest::get()
+        .uri(&format!("{}?token={}", uri, test_token(member.id, "labeler")))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // Signed URLs, like the ones in image listings, need no token
+    let signed = ImageService::content_url(image.id);
+    assert!(signed.starts_with(&format!("{}?expires=", uri)));
+    let req = test::TestRequest::get().uri(&signed).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let req = test::TestRequest::get().uri(&format!("{}&size=small", ImageService::thumbnail_url(image.id))).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_ne!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // ...but only for the path that was signed, and only until they expire
+    let other_path = signed.replace(&format!("/{}/content", image.id), &format!("/{}/content", image.id + 1));
+    let req = test::TestRequest::get().uri(&other_path).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::UNAUTHORIZED);
+    let (path, query) = signed.split_once('?').unwrap();
+    assert!(verify_signed_url(path, query));
+    assert!(!verify_signed_url(&format!("{}/../thumbnail", path), query));
+    let signature = query.split_once("&signature=").unwrap().1;
+    assert!(!verify_signed_url(path, &format!("expires=1&signature={}", signature)));
+
+    // The signing key is derived from the JWT secret rather than being it
+    let expires = query.split_once("&signature=").unwrap().0.trim_start_matches("expires=");
+    let mut raw = Hmac::<Sha256>::new_from_slice(TEST_JWT_SECRET.as_bytes())?;
+    raw.update(format!("{}:{}", path, expires).as_bytes());
+    let forged = hex::encode(raw.finalize().into_bytes());
+    assert!(!verify_signed_url(path, &format!("expires={}&signature={}", expires, forged)));
+
+    // Unknown image
+    let req = test::TestRequest::get()
+        .uri("/api/v1/images/9999/content")
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", test_token(1, "admin"))))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_thumbnail_generated_once_and_cached() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+    let png = test_png(600, 300);
+    let key = test_storage.storage.put(&png).await?;
+    let image = ImageRepository::create(
+        &test_db.connection,
+        "a.png".to_string(),
+        "png".to_string(),
+        key.clone(),
+        png.len() as i64,
+        group.id,
+    ).await?;
+
+    let app = image_app!(test_db, test_storage);
+    let token = test_token(1, "admin");
+    let uri = format!("/api/v1/images/{}/thumbnail?size=small", image.id);
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get(header::CONTENT_TYPE).unwrap(), "image/jpeg");
+    let etag = resp.headers().get(header::ETAG).unwrap().to_str()?.to_string();
+    assert_ne!(etag, format!("\"{}\"", key));
+    let body = test::read_body(resp).await;
+    let info = image_labeling_website::imaging::inspect(&body)?;
+    assert_eq!((info.width, info.height), (128, 64));
+
+    let cached = ImageThumbnailRepository::find(&test_db.connection, image.id, "small", "image/jpeg").await?.unwrap();
+    assert_eq!(format!("\"{}\"", cached.storage_key), etag);
+    assert_eq!((cached.width, cached.height), (128, 64));
+
+    // Revalidation is answered from the cache
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .insert_header((header::IF_NONE_MATCH, etag.clone()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+    // Defaults to the medium size
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/images/{}/thumbnail", image.id))
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = test::read_body(resp).await;
+    let info = image_labeling_website::imaging::inspect(&body)?;
+    assert_eq!((info.width, info.height), (256, 128));
+
+    // JPEG even for clients that accept WebP, which is only sent when asked for
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .insert_header((header::ACCEPT, "image/avif,image/webp,*/*"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get(header::CONTENT_TYPE).unwrap(), "image/jpeg");
+    let req = test::TestRequest::get()
+        .uri(&format!("{}&format=webp", uri))
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get(header::CONTENT_TYPE).unwrap(), "image/webp");
+    assert_ne!(resp.headers().get(header::ETAG).unwrap().to_str()?, etag);
+    let body = test::read_body(resp).await;
+    let info = image_labeling_website::imaging::inspect(&body)?;
+    assert_eq!((info.format, info.width, info.height), (ImageFormat::WebP, 128, 64));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("{}&format=gif", uri))
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/images/{}/thumbnail?size=huge", image.id))
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_image_thumbnail_fails_for_undecodable_content() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+    let key = test_storage.storage.put(PNG_BYTES).await?;
+    let image = ImageRepository::create(
+        &test_db.connection,
+        "a.png".to_string(),
+        "png".to_string(),
+        key.clone(),
+        PNG_BYTES.len() as i64,
+        group.id,
+    ).await?;
+
+    let app = image_app!(test_db, test_storage);
+    let req = test::TestRequest::get()
+        .uri(&format!("/api/v1/images/{}/thumbnail", image.id))
+        .insert_header((header::AUTHORIZATION, format!("Bearer {}", test_token(1, "admin"))))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    // The full-size original is never sent in place of a thumbnail
+    assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["success"], false);
+    assert!(ImageThumbnailRepository::find(&test_db.connection, image.id, "medium", "image/jpeg").await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_parse_range() {
+    assert_eq!(parse_range("bytes=0-9", 100), ByteRange::Partial(0, 9));
+    assert_eq!(parse_range("bytes=90-", 100), ByteRange::Partial(90, 99));
+    assert_eq!(parse_range("bytes=-10", 100), ByteRange::Partial(90, 99));
+    assert_eq!(parse_range("bytes=50-500", 100), ByteRange::Partial(50, 99));
+    assert_eq!(parse_range("bytes=100-", 100), ByteRange::Unsatisfiable);
+    assert_eq!(parse_range("bytes=-0", 100), ByteRange::Unsatisfiable);
+    assert_eq!(parse_range("bytes=0-1,5-6", 100), ByteRange::Full);
+    assert_eq!(parse_range("bytes=9-3", 100), ByteRange::Full);
+    assert_eq!(parse_range("items=0-9", 100), ByteRange::Full);
+}
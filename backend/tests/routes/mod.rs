@@ -1 +1,3 @@
 pub mod auth_test;
+pub mod images_test;
+pub mod upload_test;
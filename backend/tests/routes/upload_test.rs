@@ -0,0 +1,385 @@
+use actix_web::{web, App, test, http::StatusCode, http::header};
+use actix_multipart::Multipart;
+use actix_web::error::PayloadError;
+use actix_web::web::Bytes;
+use futures_util::TryStreamExt;
+use image_labeling_website::multipart::{read_field, MultipartLimits};
+use image_labeling_website::repository::*;
+use image_labeling_website::routes::admin::duplicates::get_duplicate_report;
+use image_labeling_website::routes::admin::image::upload_images;
+use image_labeling_website::routes::admin::import::import_zip;
+use image_labeling_website::schemas::admin::{CreateGroupRequest, UpdateGroupRequest};
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_bmp, test_jpeg, test_png, test_tiff, TestStorage};
+use std::sync::Arc;
+
+const BOUNDARY: &str = "----test-boundary";
+
+fn multipart_body(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (filename, data) in files {
+        body.extend_from_slice(format!("--{}\r\n", BOUNDARY).as_bytes());
+        body.extend_from_slice(
+            format!("Content-Disposition: form-data; name=\"files\"; filename=\"{}\"\r\n", filename).as_bytes(),
+        );
+        body.extend_from_slice(b"Content-Type: application/octet-stream\r\n\r\n");
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+#[tokio::test]
+async fn test_read_field_handles_split_chunks_and_limits() -> Result<(), Box<dyn std::error::Error>> {
+    let body = multipart_body(&[("a.png", b"first\r\n--not-a-boundary"), ("b.jpg", b"second"), ("c.png", b"third")]);
+
+    // Feed the body a few bytes at a time so boundaries straddle chunks
+    let chunks: Vec<Result<Bytes, PayloadError>> = body
+        .chunks(3)
+        .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+        .collect();
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_str(&format!("multipart/form-data; boundary={}", BOUNDARY))?,
+    );
+    let mut multipart = Multipart::new(&headers, futures_util::stream::iter(chunks));
+
+    let mut first = multipart.try_next().await?.expect("first field");
+    assert_eq!(first.name(), Some("files"));
+    assert_eq!(first.content_disposition().and_then(|cd| cd.get_filename()), Some("a.png"));
+    assert_eq!(read_field(&mut first, 1024).await?.unwrap(), b"first\r\n--not-a-boundary");
+    drop(first);
+
+    // An oversized field is skipped without losing the ones after it
+    let mut second = multipart.try_next().await?.expect("second field");
+    assert!(read_field(&mut second, 3).await?.is_none());
+    drop(second);
+    let mut third = multipart.try_next().await?.expect("third field");
+    assert_eq!(read_field(&mut third, 5).await?.unwrap(), b"third");
+    drop(third);
+
+    assert!(multipart.try_next().await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upload_images_reports_per_file_results() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(test_db.connection.clone()))
+            .app_data(web::Data::from(Arc::new(test_storage.storage.clone()) as Arc<dyn ImageStorage>))
+            .app_data(web::Data::new(MultipartLimits { max_file_size: 1024, max_files: 10, max_archive_size: 4096 }))
+            .route("/groups/{group_id}/images", web::post().to(upload_images))
+    ).await;
+
+    let huge = test_png(64, 64);
+    let body = multipart_body(&[
+        ("cat.png", &test_png(4, 4)),
+        ("notes.txt", b"not an image"),
+        ("huge.png", &huge),
+        ("dog.JPG", &test_jpeg(8, 6, Some(6))),
+    ]);
+    let req = test::TestRequest::post()
+        .uri(&format!("/groups/{}/images", group.id))
+        .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+        .set_payload(body)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    let data = &json["data"];
+    assert_eq!(data["total"], 4);
+    assert_eq!(data["uploaded"], 2);
+    assert_eq!(data["failed"], 2);
+    let results = data["results"].as_array().unwrap();
+    assert_eq!(results[0]["success"], true);
+    assert_eq!(results[1]["success"], false);
+    assert_eq!(results[2]["success"], false);
+    assert!(results[2]["message"].as_str().unwrap().contains("maximum size"));
+    assert_eq!(results[3]["success"], true);
+    assert_eq!(results[3]["image"]["width"], 8);
+    assert_eq!(results[3]["image"]["height"], 6);
+    assert_eq!(results[3]["image"]["color_mode"], "RGB");
+    assert_eq!(results[3]["image"]["orientation"], 6);
+
+    let images = ImageRepository::get_by_group(&test_db.connection, group.id).await?;
+    assert_eq!(images.len(), 2);
+    let key = images[0].storage_key.clone().unwrap();
+    assert!(test_storage.storage.exists(&key).await?);
+
+    // Content that does not match its extension, or does not decode
+    let mut corrupt = test_png(5, 5);
+    corrupt.truncate(30);
+    let req = test::TestRequest::post()
+        .uri(&format!("/groups/{}/images", group.id))
+        .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+        .set_payload(multipart_body(&[("fake.jpg", &test_png(3, 3)), ("broken.png", &corrupt)]))
+        .to_request();
+    let json: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let results = json["data"]["results"].as_array().unwrap();
+    assert!(results[0]["message"].as_str().unwrap().contains("does not match"));
+    assert!(results[1]["message"].as_str().unwrap().starts_with("Invalid image data"));
+
+    // Unknown group
+    let req = test::TestRequest::post()
+        .uri("/groups/999/images")
+        .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+        .set_payload(multipart_body(&[("cat.png", &test_png(4, 4))]))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // Not multipart
+    let req = test::TestRequest::post()
+        .uri(&format!("/groups/{}/images", group.id))
+        .set_payload("{}")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upload_respects_group_allowed_formats() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+
+    let invalid = AdminService::create_group(&test_db.connection, CreateGroupRequest {
+        name: "Scans".to_string(),
+        description: None,
+        allowed_formats: Some(vec!["png".to_string(), "heic".to_string()]),
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?;
+    assert!(!invalid.success);
+    assert!(invalid.message.contains("heic"));
+
+    let created = AdminService::create_group(&test_db.connection, CreateGroupRequest {
+        name: "Scans".to_string(),
+        description: None,
+        allowed_formats: Some(vec!["tif".to_string(), "image/png".to_string()]),
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?;
+    let group = created.data.unwrap();
+    assert_eq!(group.allowed_formats, vec!["image/tiff", "image/png"]);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(test_db.connection.clone()))
+            .app_data(web::Data::from(Arc::new(test_storage.storage.clone()) as Arc<dyn ImageStorage>))
+            .app_data(web::Data::new(MultipartLimits { max_file_size: 4096, max_files: 10, max_archive_size: 4096 }))
+            .route("/groups/{group_id}/images", web::post().to(upload_images))
+    ).await;
+
+    let body = multipart_body(&[
+        ("page.tiff", &test_tiff(6, 4)),
+        ("photo.bmp", &test_bmp(6, 4)),
+        ("icon.png", &test_png(6, 4)),
+    ]);
+    let req = test::TestRequest::post()
+        .uri(&format!("/groups/{}/images", group.id))
+        .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+        .set_payload(body)
+        .to_request();
+    let json: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let results = json["data"]["results"].as_array().unwrap();
+    assert_eq!(results[0]["success"], true);
+    assert_eq!(results[0]["image"]["filetype"], "image/tiff");
+    assert_eq!(results[0]["image"]["width"], 6);
+    assert_eq!(results[1]["success"], false);
+    assert!(results[1]["message"].as_str().unwrap().contains("not allowed in this group"));
+    assert_eq!(results[2]["image"]["filetype"], "image/png");
+
+    // Allowing every format again clears the restriction
+    let updated = AdminService::update_group(&test_db.connection, group.id, UpdateGroupRequest {
+        name: None,
+        description: None,
+        allowed_formats: Some(["png", "jpeg", "webp", "gif", "bmp", "tiff"].map(String::from).to_vec()),
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?;
+    assert_eq!(updated.data.unwrap().allowed_formats.len(), 6);
+    assert_eq!(GroupRepository::find_by_id(&test_db.connection, group.id).await?.unwrap().allowed_formats, None);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/groups/{}/images", group.id))
+        .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+        .set_payload(multipart_body(&[("photo.bmp", &test_bmp(6, 4))]))
+        .to_request();
+    let json: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    assert_eq!(json["data"]["results"][0]["image"]["filetype"], "image/bmp");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_duplicate_uploads_and_report() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(test_db.connection.clone()))
+            .app_data(web::Data::from(Arc::new(test_storage.storage.clone()) as Arc<dyn ImageStorage>))
+            .app_data(web::Data::new(MultipartLimits { max_file_size: 8192, max_files: 10, max_archive_size: 8192 }))
+            .route("/groups/{group_id}/images", web::post().to(upload_images))
+            .route("/groups/{group_id}/duplicates", web::get().to(get_duplicate_report))
+    ).await;
+    let upload = |files: &[(&str, &[u8])]| {
+        test::TestRequest::post()
+            .uri(&format!("/groups/{}/images", group.id))
+            .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+            .set_payload(multipart_body(files))
+            .to_request()
+    };
+
+    // Rejected by default; the same pixels in another format are not an exact copy
+    let png = test_png(32, 24);
+    let jpeg = test_jpeg(8, 8, None);
+    let req = upload(&[("a.png", &png), ("b.png", &png), ("a.bmp", &test_bmp(32, 24)), ("cut.jpg", &jpeg[..jpeg.len() / 2])]);
+    let json: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let results = json["data"]["results"].as_array().unwrap();
+    let first_id = results[0]["image"]["id"].as_i64().unwrap();
+    assert_eq!(results[1]["success"], false);
+    assert_eq!(results[1]["message"], format!("Duplicate of image {} (a.png) already in this group", first_id));
+    assert_eq!(results[2]["success"], true);
+    assert_eq!(results[2]["image"]["duplicate_of"], serde_json::Value::Null);
+    // Truncated content is rejected before it is stored
+    assert_eq!(results[3]["success"], false);
+
+    let image = ImageRepository::find_by_id(&test_db.connection, first_id as i32).await?.unwrap();
+    assert_eq!(image.difference_hash.as_ref().map(String::len), Some(16));
+
+    // Flagging keeps the copy and points it at the original
+    AdminService::update_group(&test_db.connection, group.id, UpdateGroupRequest {
+        name: None,
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: Some("Flag".to_string()),
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?;
+    let json: serde_json::Value = test::read_body_json(test::call_service(&app, upload(&[("c.png", &png)])).await).await;
+    assert_eq!(json["data"]["results"][0]["success"], true);
+    assert_eq!(json["data"]["results"][0]["image"]["duplicate_of"], first_id);
+
+    // Uploaded before hashes were recorded
+    ImageRepository::create(&test_db.connection, "legacy.png".to_string(), "image/png".to_string(), "legacy".to_string(), 1, group.id).await?;
+
+    let req = test::TestRequest::get().uri(&format!("/groups/{}/duplicates?threshold=4", group.id)).to_request();
+    let json: serde_json::Value = test::read_body_json(test::call_service(&app, req).await).await;
+    let report = &json["data"];
+    assert_eq!(report["hash"], "dhash");
+    assert_eq!(report["unhashed_images"], 1);
+    let clusters = report["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0]["exact"], false);
+    assert_eq!(clusters[0]["max_distance"], 0);
+    let filenames: Vec<&str> = clusters[0]["images"].as_array().unwrap().iter().map(|i| i["filename"].as_str().unwrap()).collect();
+    assert_eq!(filenames, vec!["a.png", "a.bmp", "c.png"]);
+
+    let req = test::TestRequest::get().uri(&format!("/groups/{}/duplicates?hash=phash", group.id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::BAD_REQUEST);
+    let req = test::TestRequest::get().uri(&format!("/groups/{}/duplicates?threshold=65", group.id)).to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::BAD_REQUEST);
+    let req = test::TestRequest::get().uri("/groups/999/duplicates").to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), StatusCode::NOT_FOUND);
+
+    let invalid = AdminService::create_group(&test_db.connection, CreateGroupRequest {
+        name: "Other".to_string(),
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: Some("ignore".to_string()),
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?;
+    assert!(!invalid.success);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_zip_limits_each_field() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+    let group = GroupRepository::create(&test_db.connection, "Group".to_string(), None).await?;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(test_db.connection.clone()))
+            .app_data(web::Data::from(Arc::new(test_storage.storage.clone()) as Arc<dyn ImageStorage>))
+            .app_data(web::Data::new(MultipartLimits { max_file_size: 16, max_files: 10, max_archive_size: 64 }))
+            .route("/groups/{group_id}/import", web::post().to(import_zip))
+    ).await;
+
+    let form = |fields: &[(&str, &[u8])]| {
+        let mut body = Vec::new();
+        for (name, data) in fields {
+            body.extend_from_slice(format!("--{}\r\n", BOUNDARY).as_bytes());
+            body.extend_from_slice(
+                format!("Content-Disposition: form-data; name=\"{}\"; filename=\"{}.bin\"\r\n\r\n", name, name).as_bytes(),
+            );
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        test::TestRequest::post()
+            .uri(&format!("/groups/{}/import", group.id))
+            .insert_header((header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY)))
+            .set_payload(body)
+            .to_request()
+    };
+
+    let resp = test::call_service(&app, form(&[("archive", &[0; 65])])).await;
+    assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+    let resp = test::call_service(&app, form(&[("archive", b"not a zip"), ("mapping", &[b'a'; 17])])).await;
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["message"], "Mapping file is too large");
+
+    let resp = test::call_service(&app, form(&[("archive", b"not a zip"), ("seed_final_tags", b"true")])).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert!(json["message"].as_str().unwrap().starts_with("Invalid ZIP archive"));
+
+    let resp = test::call_service(&app, form(&[("mapping", b"a.png,cat")])).await;
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["message"], "Missing \"archive\" file");
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use image_labeling_website::aggregation::{dawid_skene, BinaryLabel, DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE};
+use image_labeling_website::consensus::ConsensusStrategy;
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::ApplyAggregationRequest;
+use image_labeling_website::service::aggregation::AggregationService;
+use image_labeling_website::service::consensus::ConsensusService;
+use super::super::common::test_utils::setup_test_db;
+
+#[test]
+fn test_dawid_skene_discounts_labelers_who_always_say_yes() {
+    // Items 0-4 are positive, 5-9 negative. Labelers 0-2 answer correctly,
+    // 3 and 4 say yes to everything. Item 9 only has labeler 0 against the
+    // two of them.
+    let mut labels = Vec::new();
+    for item in 0..10 {
+        let experts = if item == 9 { 0..1 } else { 0..3 };
+        for labeler in experts {
+            labels.push(BinaryLabel { item, labeler, positive: item < 5 });
+        }
+        for labeler in 3..5 {
+            labels.push(BinaryLabel { item, labeler, positive: true });
+        }
+    }
+
+    let result = dawid_skene(&labels, 10, 5, DEFAULT_MAX_ITERATIONS, DEFAULT_TOLERANCE);
+    assert!(result.converged);
+    assert!(result.posteriors[..5].iter().all(|&p| p > 0.9));
+    assert!(result.posteriors[5..9].iter().all(|&p| p < 0.1));
+    // A plain majority would call item 9 positive
+    assert!(result.posteriors[9] < 0.5);
+
+    for expert in &result.confusion[..3] {
+        assert!(expert.sensitivity > 0.8 && expert.specificity > 0.8);
+    }
+    for spammer in &result.confusion[3..] {
+        assert!(spammer.sensitivity > 0.8 && spammer.specificity < 0.2);
+    }
+    let rows = result.confusion[3].rows();
+    assert!((rows[0][0] + rows[0][1] - 1.0).abs() < 1e-12);
+    assert_eq!(rows[1][1], result.confusion[3].sensitivity);
+
+    let single = dawid_skene(&labels, 10, 5, 1, DEFAULT_TOLERANCE);
+    assert_eq!(single.iterations, 1);
+    assert!(!single.converged);
+}
+
+#[tokio::test]
+async fn test_aggregation_writes_final_tags_with_confidence() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let dog = TagRepository::create(db, "dog".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let spam = LabelerRepository::create(db, "spam".to_string(), "hash".to_string()).await?;
+
+    // Eight cats and eight dogs per alice and bob; spam says cat every time
+    let mut images = Vec::new();
+    for i in 0..16 {
+        let image = ImageRepository::create(db, format!("{}.png", i), "image/png".to_string(), format!("key{}", i), 1, group.id).await?;
+        let tag = if i < 8 { cat.id } else { dog.id };
+        ImageTagsRepository::create(db, image.id, alice.id, tag).await?;
+        ImageTagsRepository::create(db, image.id, bob.id, tag).await?;
+        ImageTagsRepository::create(db, image.id, spam.id, cat.id).await?;
+        images.push(image);
+    }
+    // Only alice and spam saw this dog
+    let contested = ImageRepository::create(db, "contested.png".to_string(), "image/png".to_string(), "contested".to_string(), 1, group.id).await?;
+    ImageTagsRepository::create(db, contested.id, alice.id, dog.id).await?;
+    ImageTagsRepository::create(db, contested.id, spam.id, cat.id).await?;
+    // The admin already decided this one
+    FinalTagsRepository::replace_final_tags(db, images[0].id, vec![dog.id], true).await?;
+
+    let report = AggregationService::report(db, group.id, None).await?.data.unwrap();
+    assert_eq!(report.images_updated, None);
+    let cat_estimate = report.tags.iter().find(|tag| tag.tag_id == cat.id).unwrap();
+    assert_eq!(cat_estimate.labelers.len(), 3);
+    let spam_matrix = cat_estimate.labelers.iter().find(|l| l.labeler_id == spam.id).unwrap();
+    assert_eq!((spam_matrix.username.as_str(), spam_matrix.images), ("spam", 17));
+    assert!(spam_matrix.specificity < 0.2);
+    let contested_cat = cat_estimate.images.iter().find(|i| i.image_id == contested.id).unwrap();
+    assert!(contested_cat.posterior < 0.5);
+
+    let response = AggregationService::apply(db, group.id, ApplyAggregationRequest::default()).await?;
+    assert!(response.success);
+    assert_eq!(response.data.unwrap().images_updated, Some(16));
+
+    let final_tags = FinalTagsRepository::get_by_image(db, contested.id).await?;
+    assert_eq!(final_tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![dog.id]);
+    assert!(final_tags[0].confidence.unwrap() > 0.5);
+    assert_eq!(final_tags[0].consensus_strategy.as_deref(), Some(r#"{"type":"dawid_skene","threshold":0.5}"#));
+
+    let final_tags = FinalTagsRepository::get_by_image(db, images[1].id).await?;
+    assert_eq!(final_tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![cat.id]);
+    assert!(final_tags[0].confidence.unwrap() > 0.9);
+
+    let final_tags = FinalTagsRepository::get_by_image(db, images[0].id).await?;
+    assert!(final_tags[0].is_admin_override);
+    assert_eq!(final_tags[0].confidence, None);
+
+    // The same estimate through the per-image consensus strategy, where a
+    // majority would have kept the cat
+    let strategy = ConsensusStrategy::DawidSkene { threshold: 0.5 };
+    let final_tags = ConsensusService::auto_generate_final_tags(db, contested.id, Some(strategy)).await?.data.unwrap();
+    assert_eq!(final_tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![dog.id]);
+    let majority = ConsensusStrategy::Majority { threshold: 0.5 };
+    let final_tags = ConsensusService::auto_generate_final_tags(db, contested.id, Some(majority)).await?.data.unwrap();
+    assert_eq!(final_tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![cat.id, dog.id]);
+    assert_eq!(final_tags[0].confidence, None);
+
+    let request = ApplyAggregationRequest { threshold: Some(1.5), max_iterations: None };
+    assert!(!AggregationService::apply(db, group.id, request).await?.success);
+    assert!(!AggregationService::report(db, group.id, Some(0)).await?.success);
+    assert_eq!(AggregationService::report(db, 9999, None).await?.message, "Group not found");
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use image_labeling_website::agreement::{cohen_kappa, fleiss_kappa, krippendorff_alpha};
+use image_labeling_website::repository::*;
+use image_labeling_website::service::agreement::AgreementService;
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("statistic should be defined");
+    assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
+}
+
+#[test]
+fn test_agreement_statistics_match_reference_values() {
+    // 20 both yes, 5 only the first, 10 only the second, 15 both no
+    let mut answers = vec![(true, true); 20];
+    answers.extend(vec![(true, false); 5]);
+    answers.extend(vec![(false, true); 10]);
+    answers.extend(vec![(false, false); 15]);
+    assert_close(cohen_kappa(&answers), 0.4);
+    assert_eq!(cohen_kappa(&[(true, true), (true, true)]), None);
+    assert_eq!(cohen_kappa(&[]), None);
+
+    // Fleiss (1971) style example: 10 subjects, 14 raters, 5 categories
+    let subjects = vec![
+        vec![0, 0, 0, 0, 14],
+        vec![0, 2, 6, 4, 2],
+        vec![0, 0, 3, 5, 6],
+        vec![0, 3, 9, 2, 0],
+        vec![2, 2, 8, 1, 1],
+        vec![7, 7, 0, 0, 0],
+        vec![3, 2, 6, 3, 0],
+        vec![2, 5, 3, 2, 2],
+        vec![6, 5, 2, 1, 0],
+        vec![0, 2, 2, 3, 7],
+    ];
+    assert_close(fleiss_kappa(&subjects), 0.210);
+
+    // Krippendorff's reliability data: 4 observers, 12 units with missing
+    // values, values 1-5
+    let units = vec![
+        vec![3, 0, 0, 0, 0],
+        vec![0, 3, 1, 0, 0],
+        vec![0, 0, 4, 0, 0],
+        vec![0, 0, 4, 0, 0],
+        vec![0, 4, 0, 0, 0],
+        vec![1, 1, 1, 1, 0],
+        vec![0, 0, 0, 4, 0],
+        vec![3, 1, 0, 0, 0],
+        vec![0, 4, 0, 0, 0],
+        vec![0, 0, 0, 0, 3],
+        vec![2, 0, 0, 0, 0],
+        vec![0, 0, 1, 0, 0],
+    ];
+    assert_close(krippendorff_alpha(&units), 0.743);
+
+    assert_eq!(fleiss_kappa(&[vec![1, 0]]), None);
+    assert_eq!(krippendorff_alpha(&[vec![0, 3], vec![0, 2]]), None);
+}
+
+#[tokio::test]
+async fn test_group_agreement_and_export() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let dog = TagRepository::create(db, "dog".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let mut images = Vec::new();
+    for i in 0..4 {
+        images.push(ImageRepository::create(db, format!("{}.png", i), "image/png".to_string(), key.clone(), 1, group.id).await?);
+    }
+
+    // alice and bob always agree; carol calls everything a cat; the last
+    // image only has one labeler and does not count
+    for (image, tags) in images.iter().zip([
+        vec![(alice.id, cat.id), (bob.id, cat.id), (carol.id, cat.id)],
+        vec![(alice.id, dog.id), (bob.id, dog.id), (carol.id, cat.id)],
+        vec![(alice.id, cat.id), (bob.id, cat.id)],
+        vec![(alice.id, dog.id)],
+    ]) {
+        for (labeler_id, tag_id) in tags {
+            ImageTagsRepository::create(db, image.id, labeler_id, tag_id).await?;
+        }
+    }
+
+    let agreement = AgreementService::group_agreement(db, group.id).await?.data.unwrap();
+    assert_eq!(agreement.images, 3);
+    assert_eq!(agreement.pairs.len(), 3);
+
+    let alice_bob = &agreement.pairs[0];
+    assert_eq!((alice_bob.username.as_str(), alice_bob.other_username.as_str()), ("alice", "bob"));
+    assert_eq!(alice_bob.shared_images, 3);
+    assert_close(alice_bob.cohen_kappa, 1.0);
+
+    let cat_agreement = agreement.tags.iter().find(|tag| tag.tag_id == cat.id).unwrap();
+    let alice_carol = cat_agreement.pairs.iter().find(|pair| pair.other_labeler_id == carol.id && pair.labeler_id == alice.id).unwrap();
+    assert_eq!(alice_carol.shared_images, 2);
+    assert_close(alice_carol.cohen_kappa, 0.0);
+    assert!(cat_agreement.fleiss_kappa.unwrap() < 1.0);
+    assert!(agreement.krippendorff_alpha.unwrap() < 1.0);
+
+    let response = AgreementService::group_agreement(db, 9999).await?;
+    assert!(!response.success);
+
+    // Gold answers are scored against a key and do not count as agreement
+    ImageRepository::set_gold(db, images[1].id, true).await?;
+    let agreement = AgreementService::group_agreement(db, group.id).await?.data.unwrap();
+    assert_eq!(agreement.images, 2);
+    assert_eq!(agreement.pairs[0].shared_images, 2);
+    ImageRepository::set_gold(db, images[1].id, false).await?;
+
+    // Agreement is only part of the export on request
+    let export = serde_json::to_value(ExportService::bulk_export(db, &storage.storage, false).await?)?;
+    assert!(export[group.id.to_string()].get("agreement").is_none());
+    let export = serde_json::to_value(ExportService::bulk_export(db, &storage.storage, true).await?)?;
+    let exported = &export[group.id.to_string()]["agreement"];
+    assert_eq!(exported["images"], 3);
+    assert_eq!(export[group.id.to_string()][images[0].id.to_string()]["filename"], "0.png");
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::labeler::UpdateImageTagsRequest;
+use image_labeling_website::service::assignment::AssignmentService;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+#[tokio::test]
+async fn test_queue_honors_redundancy_and_leases() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    GroupRepository::set_queue_settings(db, group.id, 2, 900).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let mut labelers = Vec::new();
+    for name in ["alice", "bob", "carol", "dave"] {
+        let labeler = LabelerRepository::create(db, name.to_string(), "hash".to_string()).await?;
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+        labelers.push(labeler.id);
+    }
+    let (alice, bob, carol, dave) = (labelers[0], labelers[1], labelers[2], labelers[3]);
+    let outsider = LabelerRepository::create(db, "eve".to_string(), "hash".to_string()).await?;
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let first = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let second = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+
+    let next = |labeler_id: i32| AssignmentService::next_image(db, labeler_id, group.id);
+
+    // Asking again renews the same lease; leased images go to the others
+    let leased = next(alice).await?.data.unwrap();
+    assert_eq!((leased.image.id, leased.remaining), (first.id, 2));
+    assert_eq!(next(alice).await?.data.unwrap().image.id, first.id);
+    assert_eq!(next(bob).await?.data.unwrap().image.id, second.id);
+    assert_eq!(next(carol).await?.data.unwrap().image.id, first.id);
+    assert_eq!(next(dave).await?.data.unwrap().image.id, second.id);
+
+    // Every image is covered twice, so there is nothing left for anyone else
+    let extra = LabelerRepository::create(db, "frank".to_string(), "hash".to_string()).await?;
+    LabelerRepository::add_to_group(db, extra.id, group.id).await?;
+    assert!(next(extra.id).await?.data.is_none());
+
+    // Labeled images are not handed out again to the same labeler
+    LabelerService::update_image_tags(db, alice, group.id, first.id, UpdateImageTagsRequest { tag_ids: vec![cat.id], text: None }).await?;
+    assert!(next(alice).await?.data.is_none());
+
+    // Expired leases free their slot
+    AssignmentRepository::delete_expired(db, chrono::Utc::now().naive_utc() + chrono::Duration::hours(1)).await?;
+    assert_eq!(next(extra.id).await?.data.unwrap().image.id, second.id);
+
+    assert!(next(outsider.id).await.unwrap_err().contains("not authorized"));
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::{CreateGroupRequest, SelectFinalCaptionRequest};
+use image_labeling_website::schemas::labeler::CaptionRequest;
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::caption::CaptionService;
+use image_labeling_website::service::consensus::ConsensusService;
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::storage::ImageStorage;
+use image_labeling_website::task::TaskType;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+#[tokio::test]
+async fn test_captions_selection_and_jsonl_export() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = AdminService::create_group(db, CreateGroupRequest {
+        name: "Captions".to_string(),
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: Some(TaskType::FreeText),
+    }).await?.data.unwrap();
+    let tagged_group = GroupRepository::create(db, "Tags".to_string(), None).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+    let mallory = LabelerRepository::create(db, "mallory".to_string(), "hash".to_string()).await?;
+    for labeler in [&alice, &bob, &carol] {
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+    }
+    LabelerRepository::add_to_group(db, alice.id, tagged_group.id).await?;
+
+    let key = storage.storage.put(&test_png(10, 10)).await?;
+    let image = ImageRepository::create(db, "car.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let uncaptioned = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let tagged = ImageRepository::create(db, "c.png".to_string(), "image/png".to_string(), key, 1, tagged_group.id).await?;
+
+    let write = |labeler_id: i32, group_id: i32, image_id: i32, text: &str| {
+        CaptionService::save_caption(db, labeler_id, group_id, image_id, CaptionRequest { text: text.to_string() })
+    };
+
+    // Captions are the answer of free-text groups only
+    let not_free_text = write(alice.id, tagged_group.id, tagged.id, "A cat").await?;
+    assert_eq!(not_free_text.message, "This group takes tags, not captions");
+    assert!(write(mallory.id, group.id, image.id, "A car").await.unwrap_err().contains("not authorized"));
+    assert!(!write(alice.id, group.id, image.id, "   ").await?.success);
+    let missing = CaptionService::get_caption(db, alice.id, group.id, image.id).await?;
+    assert_eq!(missing.message, "Caption not found");
+
+    // Writing again replaces the caption, and counts as labeling the image
+    write(alice.id, group.id, image.id, "A car").await?;
+    let saved = write(alice.id, group.id, image.id, " A red car ").await?.data.unwrap();
+    assert_eq!(saved.text, "A red car");
+    assert!(SubmissionRepository::has_submitted(db, image.id, alice.id).await?);
+    write(bob.id, group.id, image.id, "a red  car").await?;
+    let carols = write(carol.id, group.id, image.id, "A blue car").await?.data.unwrap();
+    assert_eq!(CaptionService::get_caption(db, carol.id, group.id, image.id).await?.data.unwrap().id, carols.id);
+
+    // Without an API key there is nothing to draft with, rather than a made-up caption
+    if std::env::var("OPENAI_API_KEY").is_err() {
+        let draft = CaptionService::draft_caption(db, &storage.storage, alice.id, group.id, image.id).await;
+        assert!(draft.unwrap_err().contains("OpenAI is not configured"));
+    }
+    assert!(CaptionService::draft_final_caption(db, &storage.storage, image.id + 100).await.is_ok_and(|r| !r.success));
+
+    ConsensusService::auto_generate_final_tags(db, image.id, None).await?;
+    let review = CaptionService::image_captions(db, image.id).await?.data.unwrap();
+    assert_eq!(review.captions.len(), 3);
+    assert_eq!(review.captions[0].username, "alice");
+    let consensus = review.final_caption.unwrap();
+    assert_eq!((consensus.text.as_str(), consensus.votes, consensus.is_admin_override), ("A red car", 2, false));
+
+    // The admin picks one labeler's caption, or writes their own
+    let select = |caption_id: Option<i32>, text: Option<&str>| {
+        let request = SelectFinalCaptionRequest { caption_id, text: text.map(str::to_string) };
+        CaptionService::select_final_caption(db, image.id, request)
+    };
+    let selected = select(Some(carols.id), None).await?.data.unwrap();
+    assert_eq!((selected.text.as_str(), selected.votes, selected.is_admin_override), ("A blue car", 1, true));
+    let written = select(None, Some("  A small blue car ")).await?.data.unwrap();
+    assert_eq!((written.text.as_str(), written.votes), ("A small blue car", 0));
+    assert!(!select(Some(carols.id), Some("A car")).await?.success);
+    assert!(!select(None, None).await?.success);
+    assert!(!select(None, Some(" ")).await?.success);
+    let other_image = select(Some(carols.id + 100), None).await?;
+    assert!(other_image.message.contains("not found"));
+
+    // One pair per captioned image
+    let pairs = ExportService::caption_export(db, group.id).await?.unwrap();
+    assert_eq!(pairs.len(), 1);
+    assert_eq!((pairs[0].image_id, pairs[0].file_name.as_str()), (image.id, "car.png"));
+    assert_eq!(pairs[0].caption, "A small blue car");
+    assert!(pairs.iter().all(|pair| pair.image_id != uncaptioned.id));
+    assert!(ExportService::caption_export(db, group.id + 100).await?.is_none());
+
+    // Removing a labeler from the group removes their captions
+    AdminService::remove_labeler_from_group(db, group.id, carol.id).await?;
+    assert_eq!(CaptionService::image_captions(db, image.id).await?.data.unwrap().captions.len(), 2);
+
+    Ok(())
+}
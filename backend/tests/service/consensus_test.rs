@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use image_labeling_website::consensus::ConsensusStrategy;
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::{CreateGroupRequest, UpdateGroupRequest};
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::consensus::ConsensusService;
+use super::super::common::test_utils::setup_test_db;
+
+#[test]
+fn test_consensus_strategies_select_tags() {
+    // Labeler 1 chose tags 1 and 2, labeler 2 tag 1, labeler 3 tags 1 and 3
+    let votes = [(1, 1), (1, 2), (2, 1), (3, 1), (3, 3)];
+    let none = HashMap::new();
+
+    assert_eq!(ConsensusStrategy::default().select(&votes, &none), vec![1]);
+    assert_eq!(ConsensusStrategy::Majority { threshold: 0.3 }.select(&votes, &none), vec![1, 2, 3]);
+    assert_eq!(ConsensusStrategy::MinimumVotes { min_votes: 1 }.select(&votes, &none), vec![1, 2, 3]);
+    assert_eq!(ConsensusStrategy::MinimumVotes { min_votes: 4 }.select(&votes, &none), Vec::<i32>::new());
+    assert_eq!(ConsensusStrategy::Unanimous.select(&votes, &none), vec![1]);
+
+    // A trusted labeler carries a tag alone; unknown labelers weigh 0.5
+    let reliability = HashMap::from([(1, 0.9), (2, 0.1), (3, 0.1)]);
+    let weighted = ConsensusStrategy::WeightedReliability { threshold: 0.5 };
+    assert_eq!(weighted.select(&votes, &reliability), vec![1, 2]);
+    assert_eq!(weighted.select(&votes, &HashMap::from([(1, 0.1), (2, 0.1)])), vec![1, 3]);
+
+    assert!(ConsensusStrategy::Majority { threshold: 0.0 }.validate().is_err());
+    assert!(ConsensusStrategy::WeightedReliability { threshold: 1.5 }.validate().is_err());
+    assert!(ConsensusStrategy::MinimumVotes { min_votes: 0 }.validate().is_err());
+    assert!(ConsensusStrategy::Majority { threshold: 1.0 }.validate().is_ok());
+
+    let json = ConsensusStrategy::MinimumVotes { min_votes: 2 }.to_json();
+    assert_eq!(json, r#"{"type":"minimum_votes","min_votes":2}"#);
+    assert_eq!(ConsensusStrategy::from_json(&json), Some(ConsensusStrategy::MinimumVotes { min_votes: 2 }));
+}
+
+#[tokio::test]
+async fn test_auto_generate_uses_group_and_request_strategies() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    let group = AdminService::create_group(db, CreateGroupRequest {
+        name: "Group".to_string(),
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: Some(ConsensusStrategy::MinimumVotes { min_votes: 2 }),
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?.data.unwrap();
+    assert_eq!(group.consensus_strategy, ConsensusStrategy::MinimumVotes { min_votes: 2 });
+
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let dog = TagRepository::create(db, "dog".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let image = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), "a".to_string(), 1, group.id).await?;
+    let reviewed = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), "b".to_string(), 1, group.id).await?;
+
+    ImageTagsRepository::create(db, image.id, alice.id, cat.id).await?;
+    ImageTagsRepository::create(db, image.id, alice.id, dog.id).await?;
+    ImageTagsRepository::create(db, image.id, bob.id, cat.id).await?;
+
+    let tag_ids = |response: image_labeling_website::schemas::admin::ApiResponse<Vec<image_labeling_website::entity::final_tags::Model>>| {
+        response.data.unwrap().iter().map(|tag| tag.tag_id).collect::<Vec<_>>()
+    };
+
+    // The group's strategy applies by default and is recorded on each tag
+    let response = ConsensusService::auto_generate_final_tags(db, image.id, None).await?;
+    assert!(response.success);
+    let final_tags = response.data.unwrap();
+    assert_eq!(final_tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![cat.id]);
+    assert_eq!(final_tags[0].consensus_strategy.as_deref(), Some(r#"{"type":"minimum_votes","min_votes":2}"#));
+    assert!(!final_tags[0].is_admin_override);
+
+    // A per-request strategy overrides it
+    let majority = ConsensusStrategy::Majority { threshold: 0.5 };
+    let response = ConsensusService::auto_generate_final_tags(db, image.id, Some(majority)).await?;
+    assert_eq!(tag_ids(response), vec![cat.id, dog.id]);
+
+    AdminService::update_group(db, group.id, UpdateGroupRequest {
+        name: None,
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: Some(ConsensusStrategy::Unanimous),
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?;
+    let response = ConsensusService::auto_generate_final_tags(db, image.id, None).await?;
+    assert_eq!(tag_ids(response), vec![cat.id]);
+
+    // The admin said only "dog" on the reviewed image: alice agreed on both
+    // tags, bob on neither
+    ImageTagsRepository::create(db, reviewed.id, alice.id, dog.id).await?;
+    ImageTagsRepository::create(db, reviewed.id, bob.id, cat.id).await?;
+    FinalTagsRepository::replace_final_tags(db, reviewed.id, vec![dog.id], true).await?;
+    let reliability = ConsensusService::labeler_reliability(db, group.id).await?;
+    assert_eq!(reliability[&alice.id], 0.75);
+    assert_eq!(reliability[&bob.id], 0.25);
+
+    let weighted = ConsensusStrategy::WeightedReliability { threshold: 0.8 };
+    let response = ConsensusService::auto_generate_final_tags(db, image.id, Some(weighted)).await?;
+    assert_eq!(tag_ids(response), vec![cat.id]);
+    let weighted = ConsensusStrategy::WeightedReliability { threshold: 0.75 };
+    let response = ConsensusService::auto_generate_final_tags(db, image.id, Some(weighted)).await?;
+    assert_eq!(tag_ids(response), vec![cat.id, dog.id]);
+
+    let invalid = ConsensusStrategy::Majority { threshold: 0.0 };
+    let response = ConsensusService::auto_generate_final_tags(db, image.id, Some(invalid)).await?;
+    assert!(!response.success);
+
+    let response = ConsensusService::auto_generate_final_tags(db, 9999, None).await?;
+    assert!(!response.success);
+    assert_eq!(response.message, "Image not found");
+
+    Ok(())
+}
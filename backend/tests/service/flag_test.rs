@@ -0,0 +1,61 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::labeler::{FlagReason, ImageFlagRequest, UpdateImageTagsRequest};
+use image_labeling_website::service::assignment::AssignmentService;
+use image_labeling_website::service::flag::FlagService;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+#[tokio::test]
+async fn test_skips_and_flags() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    LabelerRepository::add_to_group(db, alice.id, group.id).await?;
+    LabelerRepository::add_to_group(db, bob.id, group.id).await?;
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let first = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let second = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+
+    let request = |reason: FlagReason, note: Option<&str>| ImageFlagRequest { reason, note: note.map(str::to_string) };
+
+    // Skipped and flagged images leave the labeler's queue
+    let skipped = FlagService::mark_image(db, alice.id, group.id, first.id, FLAG_KIND_SKIP, request(FlagReason::Ambiguous, None)).await?;
+    assert_eq!(skipped.data.unwrap().reason, FlagReason::Ambiguous);
+    assert_eq!(AssignmentService::next_image(db, alice.id, group.id).await?.data.unwrap().image.id, second.id);
+    FlagService::mark_image(db, alice.id, group.id, second.id, FLAG_KIND_FLAG, request(FlagReason::Unreadable, Some("  too dark "))).await?;
+    assert!(AssignmentService::next_image(db, alice.id, group.id).await?.data.is_none());
+
+    FlagService::mark_image(db, bob.id, group.id, second.id, FLAG_KIND_FLAG, request(FlagReason::Inappropriate, None)).await?;
+    let long_note = "x".repeat(1001);
+    assert!(!FlagService::mark_image(db, bob.id, group.id, first.id, FLAG_KIND_FLAG, request(FlagReason::Other, Some(&long_note))).await?.success);
+
+    // Only flags reach the admins, skips do not
+    let flagged = FlagService::flagged_images(db, Some(group.id)).await?.data.unwrap().images;
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].image.id, second.id);
+    let notes: Vec<(&str, FlagReason, Option<&str>)> = flagged[0]
+        .flags
+        .iter()
+        .map(|flag| (flag.username.as_str(), flag.reason, flag.note.as_deref()))
+        .collect();
+    assert_eq!(notes, vec![("alice", FlagReason::Unreadable, Some("too dark")), ("bob", FlagReason::Inappropriate, None)]);
+    assert!(!FlagService::flagged_images(db, Some(9999)).await?.success);
+
+    // Labeling the image after all withdraws the flag
+    LabelerService::update_image_tags(db, alice.id, group.id, second.id, UpdateImageTagsRequest { tag_ids: vec![cat.id], text: None }).await?;
+    let flagged = FlagService::flagged_images(db, None).await?.data.unwrap().images;
+    assert_eq!(flagged[0].flags.len(), 1);
+
+    let outsider = LabelerRepository::create(db, "eve".to_string(), "hash".to_string()).await?;
+    let denied = FlagService::mark_image(db, outsider.id, group.id, first.id, FLAG_KIND_SKIP, request(FlagReason::Other, None)).await;
+    assert!(denied.unwrap_err().contains("not authorized"));
+
+    Ok(())
+}
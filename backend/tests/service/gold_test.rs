@@ -0,0 +1,122 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::{SetGoldRequest, UpdateGroupRequest, UpdateLabelerRequest};
+use image_labeling_website::schemas::auth::LoginRequest;
+use image_labeling_website::schemas::labeler::UpdateImageTagsRequest;
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::auth::AuthService;
+use image_labeling_website::service::consensus::ConsensusService;
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::service::gold::GoldService;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+use bcrypt::hash;
+use std::env;
+
+#[tokio::test]
+async fn test_gold_answers_suspend_inaccurate_labelers() -> Result<(), Box<dyn std::error::Error>> {
+    unsafe { env::set_var("JWT_SECRET", "test-secret-key-for-testing"); }
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let other_group = GroupRepository::create(db, "Other".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let dog = TagRepository::create(db, "dog".to_string(), None, group.id).await?;
+    let foreign = TagRepository::create(db, "bird".to_string(), None, other_group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), hash("secret", 4)?).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), hash("secret", 4)?).await?;
+    LabelerRepository::add_to_group(db, alice.id, group.id).await?;
+    LabelerRepository::add_to_group(db, bob.id, group.id).await?;
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let mut images = Vec::new();
+    for i in 0..4 {
+        images.push(ImageRepository::create(db, format!("{}.png", i), "image/png".to_string(), key.clone(), 1, group.id).await?);
+    }
+
+    // Gold tags must come from the image's group
+    let response = GoldService::set_gold(db, images[0].id, SetGoldRequest { is_gold: true, tag_ids: Some(vec![foreign.id]) }).await?;
+    assert!(!response.success);
+    let response = GoldService::set_gold(db, images[0].id, SetGoldRequest { is_gold: true, tag_ids: None }).await?;
+    assert!(!response.success);
+    for image in &images[..3] {
+        let response = GoldService::set_gold(db, image.id, SetGoldRequest { is_gold: true, tag_ids: Some(vec![cat.id]) }).await?;
+        assert!(response.success);
+    }
+
+    let updated = AdminService::update_group(db, group.id, UpdateGroupRequest {
+        name: None,
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: Some(2),
+        gold_min_accuracy: Some(0.6),
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: None,
+    }).await?.data.unwrap();
+    assert_eq!((updated.gold_window, updated.gold_min_accuracy), (2, Some(0.6)));
+
+    // alice is right twice; bob misses one of his two answers and is
+    // suspended once his window is full
+    let answer = |labeler_id: i32, image_id: i32, tag_ids: Vec<i32>| {
+        LabelerService::update_image_tags(db, labeler_id, group.id, image_id, UpdateImageTagsRequest { tag_ids, text: None })
+    };
+    answer(alice.id, images[0].id, vec![cat.id]).await?;
+    answer(alice.id, images[1].id, vec![cat.id]).await?;
+    answer(bob.id, images[0].id, vec![cat.id]).await?;
+    assert!(!LabelerRepository::find_by_id(db, bob.id).await?.unwrap().suspended);
+    answer(bob.id, images[1].id, vec![cat.id, dog.id]).await?;
+
+    let bob_now = LabelerRepository::find_by_id(db, bob.id).await?.unwrap();
+    assert!(bob_now.suspended);
+    assert!(bob_now.suspended_reason.unwrap().contains("50%"));
+    assert!(!LabelerRepository::find_by_id(db, alice.id).await?.unwrap().suspended);
+
+    let login = |username: &str| LoginRequest {
+        username: username.to_string(),
+        password: "secret".to_string(),
+        role: "labeler".to_string(),
+    };
+    assert_eq!(AuthService::login(db, login("bob")).await.err().as_deref(), Some("Account suspended"));
+    assert!(AuthService::login(db, login("alice")).await.is_ok());
+
+    let report = GoldService::group_report(db, group.id).await?.data.unwrap();
+    assert_eq!(report.gold_images, 3);
+    let bob_report = report.labelers.iter().find(|labeler| labeler.labeler_id == bob.id).unwrap();
+    assert_eq!((bob_report.answered, bob_report.correct), (2, 1));
+    assert_eq!(bob_report.rolling_accuracy, 0.5);
+    assert!(bob_report.suspended);
+
+    // Answering again does not change the first result
+    answer(bob.id, images[1].id, vec![cat.id]).await?;
+    answer(alice.id, images[0].id, vec![dog.id]).await?;
+    let report = GoldService::group_report(db, group.id).await?.data.unwrap();
+    let bob_report = report.labelers.iter().find(|labeler| labeler.labeler_id == bob.id).unwrap();
+    assert_eq!((bob_report.answered, bob_report.correct), (2, 1));
+    let alice_report = report.labelers.iter().find(|labeler| labeler.labeler_id == alice.id).unwrap();
+    assert_eq!((alice_report.answered, alice_report.correct), (2, 2));
+    assert!(!LabelerRepository::find_by_id(db, alice.id).await?.unwrap().suspended);
+
+    // An admin reinstates bob
+    let reinstated = AdminService::update_labeler(db, bob.id, UpdateLabelerRequest {
+        username: None,
+        password: None,
+        group_ids: None,
+        suspended: Some(false),
+    }).await?.data.unwrap();
+    assert!(!reinstated.suspended);
+    assert_eq!(reinstated.suspended_reason, None);
+
+    // Gold images stay out of consensus and the export
+    assert!(!ConsensusService::auto_generate_final_tags(db, images[0].id, None).await?.success);
+    let export = serde_json::to_value(ExportService::bulk_export(db, &storage.storage, false).await?)?;
+    let exported = &export[group.id.to_string()];
+    assert!(exported.get(images[0].id.to_string()).is_none());
+    assert!(exported.get(images[3].id.to_string()).is_some());
+
+    Ok(())
+}
@@ -0,0 +1,267 @@
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use image_labeling_website::import::{TagMapping, ZipArchive};
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::{ImportJobResponse, ZipImportRequest};
+use image_labeling_website::service::import::ImportService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_jpeg, test_png, TestStorage};
+use sea_orm::DatabaseConnection;
+use std::fs::File;
+use std::io::{Cursor, Seek, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Builds a ZIP archive; entries whose name ends in ".png" are deflated, the rest stored
+fn build_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+
+    for (name, data) in files {
+        let (method, payload): (u16, Vec<u8>) = if name.ends_with(".png") {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).unwrap();
+            (8, encoder.finish().unwrap())
+        } else {
+            (0, data.to_vec())
+        };
+        let crc = crc32fast::hash(data);
+        let offset = out.len() as u32;
+
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&[20, 0, 0, 0]);
+        out.extend_from_slice(&method.to_le_bytes());
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&crc.to_le_bytes());
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&payload);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+        central.extend_from_slice(&method.to_le_bytes());
+        central.extend_from_slice(&[0, 0, 0, 0]);
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        central.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&[0; 12]);
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let directory_offset = out.len() as u32;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0, 0, 0, 0]);
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&directory_offset.to_le_bytes());
+    out.extend_from_slice(&[0, 0]);
+    out
+}
+
+fn archive_file(data: &[u8]) -> File {
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(data).unwrap();
+    file.rewind().unwrap();
+    file
+}
+
+async fn wait_for_job(db: &DatabaseConnection, job_id: i32) -> ImportJobResponse {
+    for _ in 0..200 {
+        let job = ImportService::get_job(db, job_id).await.unwrap().data.unwrap();
+        if job.status == "completed" || job.status == "failed" {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    panic!("import job {} did not finish", job_id);
+}
+
+#[tokio::test]
+async fn test_zip_archive_reads_stored_and_deflated_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let data = build_zip(&[("dir/a.png", b"aaaaaaaaaaaaaaaaaaaa"), ("b.jpg", b"bbb")]);
+    let mut archive = ZipArchive::new(Cursor::new(&data))?;
+
+    assert_eq!(archive.entries().len(), 2);
+    assert_eq!(archive.entries()[0].name, "dir/a.png");
+    let entries = archive.entries().to_vec();
+    assert_eq!(archive.read(&entries[0], 1024)?, b"aaaaaaaaaaaaaaaaaaaa");
+    assert_eq!(archive.read(&entries[1], 1024)?, b"bbb");
+    assert!(archive.read(&entries[0], 4).is_err());
+
+    // Corrupt the stored entry's payload so its checksum no longer matches
+    let mut corrupt = data.clone();
+    let pos = corrupt.windows(3).position(|w| w == b"bbb").unwrap();
+    corrupt[pos] = b'x';
+    let mut archive = ZipArchive::new(Cursor::new(&corrupt))?;
+    assert!(archive.read(&entries[1], 1024).is_err());
+
+    // Directory offsets pointing past the end are rejected, not followed
+    let mut truncated = data.clone();
+    let end = truncated.len();
+    truncated[end - 6..end - 2].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(ZipArchive::new(Cursor::new(&truncated)).is_err());
+    assert!(ZipArchive::new(Cursor::new(&data[..data.len() / 2])).is_err());
+
+    assert!(ZipArchive::new(Cursor::new(b"not a zip")).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tag_mapping_formats() -> Result<(), Box<dyn std::error::Error>> {
+    let csv = TagMapping::parse("labels.csv", b"filename,tags\r\na.png,cat;dog\n\"b, c.png\",\"bird\",fish\n")?;
+    assert_eq!(csv.tags_for("a.png").unwrap(), ["cat", "dog"]);
+    assert_eq!(csv.tags_for("photos/a.png").unwrap(), ["cat", "dog"]);
+    assert_eq!(csv.tags_for("b, c.png").unwrap(), ["bird", "fish"]);
+    assert!(csv.tags_for("missing.png").is_none());
+
+    let json = TagMapping::parse("labels.json", br#"{"a.png": ["cat"], "b.png": "dog"}"#)?;
+    assert_eq!(json.tags_for("b.png").unwrap(), ["dog"]);
+
+    let rows = TagMapping::parse("labels.json", br#"[{"filename": "a.png", "tags": ["cat", "cat"]}]"#)?;
+    assert_eq!(rows.tags_for("a.png").unwrap(), ["cat"]);
+
+    assert!(TagMapping::parse("labels.txt", b"a.png,cat").is_err());
+    assert!(TagMapping::parse("labels.json", b"[1]").is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_zip_import_creates_images_and_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+    let storage: Arc<dyn ImageStorage> = Arc::new(test_storage.storage.clone());
+    let db = &test_db.connection;
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+
+    let cat_png = test_png(4, 4);
+    let dog_jpg = test_jpeg(8, 6, None);
+    let archive = build_zip(&[
+        ("labels.csv", b"filename,tags\ncat.png,cat\ndog.jpg,dog;animal\nbird.png,bird\n"),
+        ("images/cat.png", &cat_png),
+        ("images/dog.jpg", &dog_jpg),
+        ("images/readme.txt", b"not an image"),
+        ("__MACOSX/images/._cat.png", b"resource fork"),
+        ("images/", b""),
+    ]);
+    let request = ZipImportRequest {
+        group_id: group.id,
+        archive: archive_file(&archive),
+        mapping: None,
+        create_missing_tags: true,
+        seed_final_tags: true,
+    };
+
+    let response = ImportService::start_zip_import(db, storage.clone(), request, 1024).await?;
+    assert!(response.success);
+    let job = response.data.unwrap();
+    assert_eq!(job.total_entries, 3);
+
+    let job = wait_for_job(db, job.id).await;
+    assert_eq!(job.status, "completed");
+    assert_eq!(job.processed_entries, 3);
+    assert_eq!(job.created_images, 2);
+    assert_eq!(job.created_tags, 2);
+    assert_eq!(job.errors.len(), 1);
+    assert_eq!(job.errors[0].entry, "images/readme.txt");
+
+    let images = ImageRepository::get_by_group(db, group.id).await?;
+    assert_eq!(images.len(), 2);
+    let cat_image = images.iter().find(|i| i.filename == "cat.png").unwrap();
+    let final_tags = FinalTagsRepository::get_by_image(db, cat_image.id).await?;
+    assert_eq!(final_tags.len(), 1);
+    assert_eq!(final_tags[0].tag_id, cat.id);
+    assert!(!final_tags[0].is_admin_override);
+
+    let dog_image = images.iter().find(|i| i.filename == "dog.jpg").unwrap();
+    assert_eq!(FinalTagsRepository::get_by_image(db, dog_image.id).await?.len(), 2);
+    assert_eq!(TagRepository::get_by_group(db, group.id).await?.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_zip_import_reports_unknown_tags_and_bad_input() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let test_storage = TestStorage::new();
+    let storage: Arc<dyn ImageStorage> = Arc::new(test_storage.storage.clone());
+    let db = &test_db.connection;
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+
+    // Explicit mapping, missing tags are not created
+    let request = ZipImportRequest {
+        group_id: group.id,
+        archive: archive_file(&build_zip(&[("a.png", &test_png(2, 2))])),
+        mapping: Some(("labels.json".to_string(), br#"{"a.png": ["ghost"]}"#.to_vec())),
+        create_missing_tags: false,
+        seed_final_tags: true,
+    };
+    let job = ImportService::start_zip_import(db, storage.clone(), request, 1024).await?.data.unwrap();
+    let job = wait_for_job(db, job.id).await;
+    assert_eq!(job.created_images, 1);
+    assert_eq!(job.created_tags, 0);
+    assert!(job.errors[0].message.contains("ghost"));
+
+    // Invalid archive
+    let request = ZipImportRequest {
+        group_id: group.id,
+        archive: archive_file(b"definitely not a zip"),
+        mapping: None,
+        create_missing_tags: false,
+        seed_final_tags: false,
+    };
+    let response = ImportService::start_zip_import(db, storage.clone(), request, 1024).await?;
+    assert!(!response.success);
+
+    // Unknown group
+    let request = ZipImportRequest {
+        group_id: 999,
+        archive: archive_file(&build_zip(&[("a.png", &test_png(2, 2))])),
+        mapping: None,
+        create_missing_tags: false,
+        seed_final_tags: false,
+    };
+    let response = ImportService::start_zip_import(db, storage.clone(), request, 1024).await?;
+    assert_eq!(response.message, "Group not found");
+
+    let jobs = ImportService::list_jobs(db, group.id).await?.data.unwrap();
+    assert_eq!(jobs.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interrupted_import_jobs_are_failed() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let pending = ImportJobRepository::create(db, group.id, 2).await?;
+    let running = ImportJobRepository::create(db, group.id, 2).await?;
+    ImportJobRepository::update_progress(db, running.id, "running", 1, 1, 0, "[]".to_string()).await?;
+    let completed = ImportJobRepository::create(db, group.id, 2).await?;
+    ImportJobRepository::finish(db, completed.id, "completed", None).await?;
+
+    assert_eq!(ImportService::fail_interrupted_jobs(db).await?, 2);
+    for job_id in [pending.id, running.id] {
+        let job = ImportService::get_job(db, job_id).await?.data.unwrap();
+        assert_eq!(job.status, "failed");
+        assert!(job.finished_at.is_some());
+    }
+    let job = ImportService::get_job(db, completed.id).await?.data.unwrap();
+    assert_eq!(job.status, "completed");
+    assert_eq!(ImportService::fail_interrupted_jobs(db).await?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use image_labeling_website::keypoint::{aggregate, bbox, Keypoint, KeypointSchema, NOT_LABELED, OCCLUDED, VISIBLE};
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::{CreateTagRequest, KeypointAggregationRequest, UpdateTagRequest};
+use image_labeling_website::schemas::labeler::KeypointSetRequest;
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::service::keypoint::KeypointService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+fn schema(names: &[&str], skeleton: Vec<[usize; 2]>) -> KeypointSchema {
+    KeypointSchema {
+        keypoints: names.iter().map(|name| name.to_string()).collect(),
+        skeleton,
+    }
+}
+
+#[test]
+fn test_keypoints_aggregate_by_median() {
+    assert!(schema(&["nose", "tail"], vec![[1, 2]]).validate().is_ok());
+    assert!(schema(&[], vec![]).validate().is_err());
+    assert!(schema(&["nose", "nose"], vec![]).validate().is_err());
+    assert!(schema(&["nose", "tail"], vec![[1, 3]]).validate().is_err());
+    assert!(schema(&["nose", "tail"], vec![[2, 2]]).validate().is_err());
+
+    let sets = vec![
+        (1, vec![Keypoint(10.0, 10.0, VISIBLE), Keypoint(50.0, 50.0, OCCLUDED), Keypoint(5.0, 5.0, VISIBLE)]),
+        (2, vec![Keypoint(12.0, 11.0, VISIBLE), Keypoint(52.0, 50.0, OCCLUDED), Keypoint::NONE]),
+        // Labeler 3 put the nose on the tail
+        (3, vec![Keypoint(60.0, 60.0, VISIBLE), Keypoint(51.0, 49.0, VISIBLE), Keypoint::NONE]),
+    ];
+    let aggregated = aggregate(&sets, 10.0, 2);
+    assert_eq!(aggregated.keypoints[0], Keypoint(11.0, 10.5, VISIBLE));
+    assert_eq!(aggregated.keypoints[1], Keypoint(51.0, 50.0, OCCLUDED));
+    // Only one labeler placed the third point
+    assert_eq!(aggregated.keypoints[2].2, NOT_LABELED);
+    assert_eq!(aggregated.outliers.len(), 1);
+    assert_eq!((aggregated.outliers[0].labeler_id, aggregated.outliers[0].keypoint), (3, 0));
+
+    assert_eq!(aggregate(&sets, 10.0, 1).keypoints[2], Keypoint(5.0, 5.0, VISIBLE));
+    assert_eq!(bbox(&aggregated.keypoints), Some([11.0, 10.5, 40.0, 39.5]));
+    assert_eq!(bbox(&[Keypoint::NONE]), None);
+}
+
+#[tokio::test]
+async fn test_keypoint_sets_aggregation_and_coco_export() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+    for labeler in [&alice, &bob, &carol] {
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+    }
+
+    // The schema is part of the tag's configuration
+    let invalid = CreateTagRequest {
+        name: "dog".to_string(),
+        description: None,
+        group_id: group.id,
+        keypoint_schema: Some(schema(&["nose"], vec![[1, 2]])),
+    };
+    assert!(!AdminService::create_tag(db, invalid).await?.success);
+    let request = CreateTagRequest {
+        name: "person".to_string(),
+        description: None,
+        group_id: group.id,
+        keypoint_schema: Some(schema(&["head", "left_hand", "right_hand"], vec![[1, 2], [1, 3]])),
+    };
+    let person = AdminService::create_tag(db, request).await?.data.unwrap();
+    assert_eq!(person.keypoint_schema.as_ref().unwrap().keypoints.len(), 3);
+
+    let key = storage.storage.put(&test_png(100, 100)).await?;
+    let image = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key, 1, group.id).await?;
+    ImageRepository::set_metadata(db, image.id, 100, 100, "RGB".to_string(), None).await?;
+
+    let place = |labeler_id: i32, tag_id: i32, keypoints: Vec<Keypoint>| {
+        KeypointService::save_keypoint_set(db, labeler_id, group.id, image.id, KeypointSetRequest { tag_id, keypoints })
+    };
+
+    // Keypoints need a keypoint tag, one point per keypoint, within the image
+    assert!(!place(alice.id, cat.id, vec![Keypoint(10.0, 10.0, VISIBLE)]).await?.success);
+    assert!(!place(alice.id, person.id, vec![Keypoint(10.0, 10.0, VISIBLE)]).await?.success);
+    assert!(!place(alice.id, person.id, vec![Keypoint(10.0, 110.0, VISIBLE), Keypoint::NONE, Keypoint::NONE]).await?.success);
+    assert!(!place(alice.id, person.id, vec![Keypoint(10.0, 10.0, 3), Keypoint::NONE, Keypoint::NONE]).await?.success);
+    assert!(!place(alice.id, person.id, vec![Keypoint::NONE, Keypoint::NONE, Keypoint::NONE]).await?.success);
+
+    // Saving again replaces the labeler's set
+    place(alice.id, person.id, vec![Keypoint(90.0, 90.0, VISIBLE), Keypoint::NONE, Keypoint::NONE]).await?;
+    let hand = Keypoint(20.0, 60.0, OCCLUDED);
+    place(alice.id, person.id, vec![Keypoint(50.0, 10.0, VISIBLE), hand, Keypoint::NONE]).await?;
+    place(bob.id, person.id, vec![Keypoint(52.0, 12.0, VISIBLE), Keypoint(22.0, 60.0, VISIBLE), Keypoint::NONE]).await?;
+    place(carol.id, person.id, vec![Keypoint(95.0, 95.0, VISIBLE), Keypoint(21.0, 62.0, OCCLUDED), Keypoint(80.0, 60.0, VISIBLE)]).await?;
+    assert_eq!(KeypointService::list_keypoint_sets(db, alice.id, group.id, image.id).await?.data.unwrap().len(), 1);
+
+    // The number of keypoints is fixed once they are placed
+    let update = |keypoint_schema: KeypointSchema| UpdateTagRequest {
+        name: None,
+        description: None,
+        keypoint_schema: Some(keypoint_schema),
+    };
+    assert!(!AdminService::update_tag(db, person.id, update(schema(&["head"], vec![]))).await?.success);
+    assert!(!AdminService::update_tag(db, person.id, update(schema(&[], vec![]))).await?.success);
+    let renamed = update(schema(&["head", "left", "right"], vec![[1, 2], [1, 3]]));
+    assert!(AdminService::update_tag(db, person.id, renamed).await?.success);
+
+    let aggregated = KeypointService::aggregate_keypoints(db, image.id, KeypointAggregationRequest::default()).await?.data.unwrap();
+    assert_eq!(aggregated.final_keypoint_sets.len(), 1);
+    let final_set = &aggregated.final_keypoint_sets[0];
+    assert_eq!((final_set.tag_name.as_str(), final_set.votes, final_set.num_keypoints), ("person", 3, 2));
+    assert_eq!(final_set.keypoints[0], Keypoint(51.0, 11.0, VISIBLE));
+    assert_eq!(final_set.keypoints[1], Keypoint(21.0, 60.0, OCCLUDED));
+    assert_eq!(aggregated.outliers.len(), 1);
+    assert_eq!((aggregated.outliers[0].labeler_id, aggregated.outliers[0].keypoint.as_str()), (carol.id, "head"));
+
+    let by_cat = KeypointAggregationRequest { tag_id: Some(cat.id), ..Default::default() };
+    assert!(!KeypointService::aggregate_keypoints(db, image.id, by_cat).await?.success);
+
+    assert!(KeypointService::delete_keypoint_set(db, carol.id, group.id, image.id, person.id).await?.success);
+    assert!(!KeypointService::delete_keypoint_set(db, carol.id, group.id, image.id, person.id).await?.success);
+    let review = KeypointService::image_keypoints(db, image.id).await?.data.unwrap();
+    assert_eq!(review.labelers.len(), 2);
+    assert_eq!(review.final_keypoint_sets.len(), 1);
+
+    let coco = serde_json::to_value(ExportService::coco_export(db, group.id).await?.unwrap())?;
+    let category = coco["categories"].as_array().unwrap().iter().find(|c| c["id"] == person.id).unwrap();
+    assert_eq!(category["keypoints"], serde_json::json!(["head", "left", "right"]));
+    assert_eq!(category["skeleton"], serde_json::json!([[1, 2], [1, 3]]));
+    let annotation = &coco["annotations"][0];
+    assert_eq!(annotation["category_id"], person.id);
+    assert_eq!(annotation["num_keypoints"], 2);
+    assert_eq!(annotation["keypoints"], serde_json::json!([51.0, 11.0, 2.0, 21.0, 60.0, 1.0, 0.0, 0.0, 0.0]));
+    assert_eq!(annotation["bbox"], serde_json::json!([21.0, 11.0, 30.0, 49.0]));
+    assert!(annotation.get("segmentation").is_none());
+
+    Ok(())
+}
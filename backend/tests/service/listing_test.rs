@@ -0,0 +1,120 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::pagination::{GroupDetailsQuery, ListQuery};
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::labeler::LabelerService;
+use super::super::common::test_utils::setup_test_db;
+
+#[tokio::test]
+async fn test_image_list_filters_sorts_and_pages_in_sql() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let other = GroupRepository::create(db, "Other".to_string(), None).await?;
+    let tag = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    LabelerRepository::add_to_group(db, alice.id, group.id).await?;
+
+    let mut ids = Vec::new();
+    for (i, filename) in ["d.png", "b.png", "e.png", "a.png", "c.png"].iter().enumerate() {
+        let image = ImageRepository::create(db, filename.to_string(), "image/png".to_string(), format!("key{}", i), 100 * (5 - i as i64), group.id).await?;
+        ids.push(image.id);
+    }
+    ImageRepository::create(db, "z.png".to_string(), "image/png".to_string(), "other".to_string(), 1, other.id).await?;
+
+    // alice tagged d and b, bob tagged e; d has admin-set final tags, b computed ones
+    ImageTagsRepository::create(db, ids[0], alice.id, tag.id).await?;
+    ImageTagsRepository::create(db, ids[1], alice.id, tag.id).await?;
+    ImageTagsRepository::create(db, ids[2], bob.id, tag.id).await?;
+    FinalTagsRepository::create(db, ids[0], tag.id, true).await?;
+    FinalTagsRepository::create(db, ids[1], tag.id, false).await?;
+
+    let filenames = |page: &Page<image_labeling_website::entity::image::Model>| {
+        page.items.iter().map(|image| image.filename.clone()).collect::<Vec<_>>()
+    };
+
+    let by_name = ImageListQuery { sort: ImageSort::Filename, ..Default::default() };
+    let page = ImageRepository::list_by_group(db, group.id, &by_name, PageRequest::new(Some(2), Some(1))?).await?;
+    assert_eq!(page.total, 5);
+    assert_eq!(filenames(&page), vec!["b.png", "c.png"]);
+
+    // Offset without a limit runs to the end
+    let page = ImageRepository::list_by_group(db, group.id, &by_name, PageRequest::new(None, Some(3))?).await?;
+    assert_eq!(filenames(&page), vec!["d.png", "e.png"]);
+
+    let largest_first = ImageListQuery { sort: ImageSort::Size, order: SortOrder::Desc, ..Default::default() };
+    let page = ImageRepository::list_by_group(db, group.id, &largest_first, PageRequest::new(Some(1), None)?).await?;
+    assert_eq!(filenames(&page), vec!["d.png"]);
+
+    let unlabeled = ImageListQuery { labeled: Some(false), ..by_name.clone() };
+    let page = ImageRepository::list_by_group(db, group.id, &unlabeled, PageRequest::default()).await?;
+    assert_eq!(filenames(&page), vec!["a.png", "c.png"]);
+
+    let pending_for_alice = ImageListQuery { labeled: Some(false), labeled_by: Some(alice.id), ..by_name.clone() };
+    let page = ImageRepository::list_by_group(db, group.id, &pending_for_alice, PageRequest::default()).await?;
+    assert_eq!(filenames(&page), vec!["a.png", "c.png", "e.png"]);
+
+    let computed = ImageListQuery { has_final_tags: Some(true), has_admin_override: Some(false), ..by_name.clone() };
+    let page = ImageRepository::list_by_group(db, group.id, &computed, PageRequest::default()).await?;
+    assert_eq!(filenames(&page), vec!["b.png"]);
+
+    assert!(PageRequest::new(Some(0), None).is_err());
+    assert!(PageRequest::new(Some(MAX_PAGE_SIZE + 1), None).is_err());
+
+    // The same filters through the labeler and admin services
+    let query = ListQuery { status: Some("done".to_string()), sort: Some("filename".to_string()), ..Default::default() };
+    let images = LabelerService::get_group_images(db, alice.id, group.id, query).await?.data.unwrap();
+    assert_eq!(images.total, 2);
+    assert_eq!(images.images.iter().map(|i| i.status.as_str()).collect::<Vec<_>>(), vec!["done", "done"]);
+
+    let query = ListQuery { status: Some("labeled".to_string()), ..Default::default() };
+    let response = LabelerService::get_group_images(db, alice.id, group.id, query).await?;
+    assert!(!response.success);
+
+    let query = GroupDetailsQuery { limit: Some(2), status: Some("labeled".to_string()), tag_limit: Some(1), ..Default::default() };
+    let details = AdminService::get_group_details(db, group.id, query).await?.data.unwrap();
+    assert_eq!((details.images.len(), details.image_total), (2, 3));
+    assert_eq!((details.tags.len(), details.tag_total), (1, 1));
+    assert_eq!((details.labelers.len(), details.labeler_total), (1, 1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_lists_are_paged_and_sorted() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    for name in ["carol", "alice", "bob"] {
+        LabelerRepository::create(db, name.to_string(), "hash".to_string()).await?;
+        GroupRepository::create(db, format!("{} group", name), None).await?;
+    }
+
+    let query = ListQuery {
+        limit: Some(2),
+        sort: Some("username".to_string()),
+        order: Some("desc".to_string()),
+        ..Default::default()
+    };
+    let labelers = AdminService::list_labelers(db, query).await?.data.unwrap();
+    assert_eq!(labelers.total, 3);
+    assert_eq!(labelers.labelers.iter().map(|l| l.username.as_str()).collect::<Vec<_>>(), vec!["carol", "bob"]);
+
+    let query = ListQuery { offset: Some(1), sort: Some("name".to_string()), ..Default::default() };
+    let groups = AdminService::list_groups(db, query).await?.data.unwrap();
+    assert_eq!((groups.total, groups.offset, groups.limit), (3, 1, None));
+    assert_eq!(groups.groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(), vec!["bob group", "carol group"]);
+
+    // Everything is returned when no parameters are given
+    let groups = AdminService::list_groups(db, ListQuery::default()).await?.data.unwrap();
+    assert_eq!(groups.groups.len(), 3);
+
+    let response = AdminService::list_groups(db, ListQuery { sort: Some("size".to_string()), ..Default::default() }).await?;
+    assert!(!response.success);
+    assert!(response.message.contains("id, name"));
+    let response = AdminService::list_labelers(db, ListQuery { order: Some("up".to_string()), ..Default::default() }).await?;
+    assert!(!response.success);
+
+    Ok(())
+}
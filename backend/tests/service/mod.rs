@@ -1 +1,18 @@
+pub mod aggregation_test;
+pub mod assignment_test;
+pub mod agreement_test;
+pub mod caption_test;
 pub mod auth_test;
+pub mod consensus_test;
+pub mod flag_test;
+pub mod gold_test;
+pub mod import_test;
+pub mod keypoint_test;
+pub mod listing_test;
+pub mod progress_test;
+pub mod region_test;
+pub mod scoring_test;
+pub mod segmentation_test;
+pub mod submission_test;
+pub mod task_test;
+pub mod timing_test;
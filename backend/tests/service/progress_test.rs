@@ -0,0 +1,73 @@
+use chrono::{Duration, Utc};
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::pagination::GroupDetailsQuery;
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::progress::ProgressService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+#[test]
+fn test_eta_from_recent_throughput() {
+    let now = Utc::now().naive_utc();
+
+    // 10 labels over the last two hours, 5 to go
+    let saved: Vec<_> = (0..10).map(|i| now - Duration::minutes(120 - i * 12)).collect();
+    let (per_hour, eta) = ProgressService::eta(saved, 5, now);
+    assert_eq!(per_hour, Some(5.0));
+    assert_eq!(eta, Some(3600));
+
+    // Labels older than a week do not count
+    assert_eq!(ProgressService::eta(vec![now - Duration::days(8)], 5, now), (None, None));
+    assert_eq!(ProgressService::eta(Vec::new(), 0, now), (None, Some(0)));
+}
+
+#[tokio::test]
+async fn test_group_details_report_progress() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    GroupRepository::set_queue_settings(db, group.id, 2, 900).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let mut labelers = Vec::new();
+    for name in ["alice", "bob", "carol"] {
+        let labeler = LabelerRepository::create(db, name.to_string(), "hash".to_string()).await?;
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+        labelers.push(labeler.id);
+    }
+    let (alice, bob, carol) = (labelers[0], labelers[1], labelers[2]);
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let mut images = Vec::new();
+    for i in 0..4 {
+        images.push(ImageRepository::create(db, format!("{}.png", i), "image/png".to_string(), key.clone(), 1, group.id).await?);
+    }
+    ImageRepository::set_gold(db, images[3].id, true).await?;
+
+    // One image fully labeled, one partially, one untouched; gold images
+    // do not count
+    for (image, labeler_id) in [(0, alice), (0, bob), (1, alice), (3, carol)] {
+        ImageTagsRepository::create(db, images[image].id, labeler_id, cat.id).await?;
+    }
+
+    let details = AdminService::get_group_details(db, group.id, GroupDetailsQuery::default()).await?.data.unwrap();
+    let progress = details.progress;
+    assert_eq!(progress.labels_per_image, 2);
+    assert_eq!(progress.total_images, 3);
+    assert_eq!((progress.fully_labeled, progress.partially_labeled, progress.untouched), (1, 1, 1));
+    assert_eq!((progress.labels_done, progress.labels_needed), (3, 6));
+    assert_eq!(progress.percent_complete, 50.0);
+    assert!(progress.eta_seconds.is_some());
+    assert!(progress.estimated_completion_at.is_some());
+
+    let counts: Vec<(&str, usize)> = progress
+        .labelers
+        .iter()
+        .map(|labeler| (labeler.username.as_str(), labeler.images_labeled))
+        .collect();
+    assert_eq!(counts, vec![("alice", 2), ("bob", 1), ("carol", 0)]);
+    assert!(progress.labelers[2].last_labeled_at.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use image_labeling_website::region::{cluster, BoundingBox, RegionVote};
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::RegionConsensusRequest;
+use image_labeling_website::schemas::labeler::{RegionRequest, UpdateImageTagsRequest};
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::service::region::RegionService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+fn bbox(x: f64, y: f64, width: f64, height: f64) -> BoundingBox {
+    BoundingBox { x, y, width, height }
+}
+
+#[test]
+fn test_boxes_cluster_by_iou() {
+    assert_eq!(bbox(0.0, 0.0, 0.5, 0.5).iou(&bbox(0.0, 0.0, 0.5, 0.5)), 1.0);
+    assert_eq!(bbox(0.0, 0.0, 0.2, 0.2).iou(&bbox(0.5, 0.5, 0.2, 0.2)), 0.0);
+    assert!((bbox(0.0, 0.0, 0.2, 0.2).iou(&bbox(0.1, 0.0, 0.2, 0.2)) - 1.0 / 3.0).abs() < 1e-9);
+    assert!(bbox(0.9, 0.0, 0.2, 0.2).validate().is_err());
+    assert!(bbox(0.1, 0.1, 0.0, 0.2).validate().is_err());
+    assert!(bbox(0.0, 0.0, 1.0, 1.0).validate().is_ok());
+
+    let vote = |labeler_id: i32, tag_id: i32, bbox: BoundingBox| RegionVote { labeler_id, tag_id, bbox };
+    let votes = vec![
+        // Three labelers agree on one cat, one of them draws it twice
+        vote(1, 10, bbox(0.10, 0.10, 0.30, 0.30)),
+        vote(2, 10, bbox(0.12, 0.10, 0.30, 0.32)),
+        vote(3, 10, bbox(0.08, 0.12, 0.30, 0.28)),
+        vote(3, 10, bbox(0.10, 0.10, 0.28, 0.28)),
+        // Only labeler 1 sees a second cat
+        vote(1, 10, bbox(0.60, 0.60, 0.20, 0.20)),
+        // Two labelers see a dog where the cat is
+        vote(1, 20, bbox(0.10, 0.10, 0.30, 0.30)),
+        vote(2, 20, bbox(0.10, 0.10, 0.30, 0.30)),
+    ];
+    let regions = cluster(&votes, 0.5, 2);
+    assert_eq!(regions.len(), 2);
+    assert_eq!((regions[0].tag_id, regions[0].votes), (10, 3));
+    assert!((regions[0].bbox.x - 0.1).abs() < 1e-9);
+    assert_eq!((regions[1].tag_id, regions[1].votes), (20, 2));
+
+    assert_eq!(cluster(&votes, 0.5, 1).len(), 4);
+    assert_eq!(cluster(&votes, 0.5, 4).len(), 0);
+}
+
+#[tokio::test]
+async fn test_region_crud_consensus_and_export() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let other_group = GroupRepository::create(db, "Other".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let bird = TagRepository::create(db, "bird".to_string(), None, other_group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+    let mallory = LabelerRepository::create(db, "mallory".to_string(), "hash".to_string()).await?;
+    for labeler in [&alice, &bob, &carol] {
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+    }
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let image = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key, 1, group.id).await?;
+
+    let request = |tag_id: i32, bbox: BoundingBox| RegionRequest { tag_id, bbox };
+    let draw = |labeler_id: i32, tag_id: i32, bbox: BoundingBox| {
+        RegionService::create_region(db, labeler_id, group.id, image.id, request(tag_id, bbox))
+    };
+
+    // Boxes need a tag of the group and must lie within the image
+    assert!(!draw(alice.id, bird.id, bbox(0.1, 0.1, 0.3, 0.3)).await?.success);
+    assert!(!draw(alice.id, cat.id, bbox(0.8, 0.1, 0.3, 0.3)).await?.success);
+    assert!(draw(mallory.id, cat.id, bbox(0.1, 0.1, 0.3, 0.3)).await.unwrap_err().contains("not authorized"));
+
+    let mistake = draw(alice.id, cat.id, bbox(0.5, 0.5, 0.1, 0.1)).await?.data.unwrap();
+    let moved = RegionService::update_region(db, alice.id, group.id, image.id, mistake.id, request(cat.id, bbox(0.1, 0.1, 0.3, 0.3))).await?;
+    assert_eq!(moved.data.unwrap().bbox, bbox(0.1, 0.1, 0.3, 0.3));
+    assert!(!RegionService::delete_region(db, bob.id, group.id, image.id, mistake.id).await?.success);
+    draw(alice.id, cat.id, bbox(0.7, 0.7, 0.2, 0.2)).await?;
+    let extra = draw(alice.id, cat.id, bbox(0.0, 0.7, 0.2, 0.2)).await?.data.unwrap();
+    assert!(RegionService::delete_region(db, alice.id, group.id, image.id, extra.id).await?.success);
+    assert_eq!(RegionService::list_regions(db, alice.id, group.id, image.id).await?.data.unwrap().len(), 2);
+
+    draw(bob.id, cat.id, bbox(0.12, 0.1, 0.3, 0.3)).await?;
+    // carol saw nothing to draw a box around
+    LabelerService::update_image_tags(db, carol.id, group.id, image.id, UpdateImageTagsRequest { tag_ids: vec![], text: None }).await?;
+
+    // Two of three labelers agree on the first cat
+    let final_regions = RegionService::auto_generate_final_regions(db, image.id, RegionConsensusRequest::default()).await?.data.unwrap();
+    assert_eq!(final_regions.len(), 1);
+    assert_eq!((final_regions[0].tag_name.as_str(), final_regions[0].votes), ("cat", 2));
+    let strict = RegionConsensusRequest { iou_threshold: Some(0.95), min_votes: None };
+    assert!(RegionService::auto_generate_final_regions(db, image.id, strict).await?.data.unwrap().is_empty());
+    let lenient = RegionConsensusRequest { iou_threshold: None, min_votes: Some(1) };
+    assert_eq!(RegionService::auto_generate_final_regions(db, image.id, lenient).await?.data.unwrap().len(), 2);
+
+    let regions = RegionService::image_regions(db, image.id).await?.data.unwrap();
+    assert_eq!(regions.labelers.len(), 2);
+    assert_eq!(regions.final_regions.len(), 2);
+
+    let export = serde_json::to_value(ExportService::bulk_export(db, &storage.storage, false).await?)?;
+    let exported = &export[group.id.to_string()][image.id.to_string()]["regions"];
+    assert_eq!(exported.as_array().unwrap().len(), 2);
+    assert_eq!(exported[0]["tag_name"], "cat");
+    assert!(exported[0]["width"].as_f64().is_some());
+
+    Ok(())
+}
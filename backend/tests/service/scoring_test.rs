@@ -0,0 +1,65 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::service::scoring::ScoringService;
+use super::super::common::test_utils::setup_test_db;
+
+#[tokio::test]
+async fn test_labelers_are_scored_against_admin_overrides() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let dog = TagRepository::create(db, "dog".to_string(), None, group.id).await?;
+    let bird = TagRepository::create(db, "bird".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+
+    let mut images = Vec::new();
+    for i in 0..3 {
+        images.push(ImageRepository::create(db, format!("{}.png", i), "image/png".to_string(), format!("key{}", i), 1, group.id).await?);
+    }
+    // The admin reviewed the first two images; the third only has computed tags
+    FinalTagsRepository::replace_final_tags(db, images[0].id, vec![cat.id], true).await?;
+    FinalTagsRepository::replace_final_tags(db, images[1].id, vec![dog.id, bird.id], true).await?;
+    FinalTagsRepository::replace_final_tags(db, images[2].id, vec![cat.id], false).await?;
+
+    ImageTagsRepository::assign_multiple_tags(db, images[0].id, alice.id, vec![cat.id]).await?;
+    ImageTagsRepository::assign_multiple_tags(db, images[1].id, alice.id, vec![dog.id, bird.id]).await?;
+    ImageTagsRepository::assign_multiple_tags(db, images[0].id, bob.id, vec![cat.id, dog.id]).await?;
+    ImageTagsRepository::assign_multiple_tags(db, images[1].id, bob.id, vec![dog.id]).await?;
+    ImageTagsRepository::assign_multiple_tags(db, images[2].id, carol.id, vec![dog.id]).await?;
+
+    let leaderboard = ScoringService::leaderboard(db, Some(group.id)).await?.data.unwrap();
+    let ranking: Vec<(usize, &str)> = leaderboard.labelers.iter().map(|s| (s.rank, s.username.as_str())).collect();
+    assert_eq!(ranking, vec![(1, "alice"), (2, "bob")]);
+
+    let alice_score = &leaderboard.labelers[0];
+    assert_eq!((alice_score.reviewed_images, alice_score.true_positives), (2, 3));
+    assert_eq!((alice_score.precision, alice_score.recall, alice_score.f1), (Some(1.0), Some(1.0), Some(1.0)));
+
+    let bob_score = &leaderboard.labelers[1];
+    assert_eq!((bob_score.true_positives, bob_score.false_positives, bob_score.false_negatives), (2, 1, 1));
+    assert!((bob_score.f1.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    let errors: Vec<(&str, usize, usize, f64)> = bob_score
+        .tags
+        .iter()
+        .map(|t| (t.tag_name.as_str(), t.false_positives, t.false_negatives, t.error_rate))
+        .collect();
+    assert_eq!(errors, vec![("cat", 0, 0, 0.0), ("dog", 1, 0, 0.5), ("bird", 0, 1, 0.5)]);
+
+    // Across all groups gives the same result here
+    let everywhere = ScoringService::leaderboard(db, None).await?.data.unwrap();
+    assert_eq!(everywhere.group_id, None);
+    assert_eq!(everywhere.labelers.len(), 2);
+
+    let carol_score = ScoringService::labeler_score(db, carol.id, None).await?.data.unwrap();
+    assert_eq!((carol_score.reviewed_images, carol_score.f1), (0, None));
+    let bob_score = ScoringService::labeler_score(db, bob.id, Some(group.id)).await?.data.unwrap();
+    assert_eq!(bob_score.rank, 2);
+
+    assert_eq!(ScoringService::labeler_score(db, 9999, None).await?.message, "Labeler not found");
+    assert_eq!(ScoringService::leaderboard(db, Some(9999)).await?.message, "Group not found");
+
+    Ok(())
+}
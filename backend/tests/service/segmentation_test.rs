@@ -0,0 +1,147 @@
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::MergeSegmentationsRequest;
+use image_labeling_website::schemas::labeler::SegmentationRequest;
+use image_labeling_website::segmentation::{polygon_area, Geometry, Mask};
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::service::segmentation::SegmentationService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+fn square(x: f64, y: f64, size: f64) -> Geometry {
+    Geometry::Polygon {
+        points: vec![[x, y], [x + size, y], [x + size, y + size], [x, y + size]],
+    }
+}
+
+#[test]
+fn test_masks_from_polygons_and_rle() {
+    assert_eq!(polygon_area(&[[0.0, 0.0], [4.0, 0.0], [4.0, 2.0], [0.0, 2.0]]), 8.0);
+
+    let mask = square(1.0, 1.0, 2.0).to_mask(4, 3);
+    assert_eq!(mask.area(), 4);
+    assert!(mask.get(1, 1) && mask.get(2, 2) && !mask.get(0, 0) && !mask.get(3, 1));
+    assert_eq!(mask.bbox(), Some([1, 1, 2, 2]));
+    assert_eq!(Mask::empty(4, 3).bbox(), None);
+
+    // Column-major runs starting with background: column 0 is empty, columns
+    // 1 and 2 have rows 1 and 2 set
+    let counts = mask.to_rle();
+    assert_eq!(counts, vec![4, 2, 1, 2, 3]);
+    let rle = Geometry::Rle { size: [3, 4], counts };
+    assert_eq!(rle.to_mask(4, 3), mask);
+    assert_eq!((square(1.0, 1.0, 2.0).area(4, 3), rle.area(4, 3)), (4, 4));
+    assert!(rle.validate(4, 3).is_ok());
+    assert_eq!(Geometry::from_json(&rle.to_json()), Some(rle.clone()));
+
+    assert!(rle.validate(3, 4).is_err());
+    assert!(Geometry::Rle { size: [3, 4], counts: vec![12] }.validate(4, 3).is_err());
+    assert!(Geometry::Rle { size: [3, 4], counts: vec![4, 2] }.validate(4, 3).is_err());
+    assert!(square(3.0, 1.0, 2.0).validate(4, 3).is_err());
+    assert!(Geometry::Polygon { points: vec![[0.0, 0.0], [1.0, 1.0]] }.validate(4, 3).is_err());
+    assert!(Geometry::Polygon { points: vec![[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]] }.validate(4, 3).is_err());
+
+    let masks = [
+        square(0.0, 0.0, 2.0).to_mask(4, 4),
+        square(1.0, 1.0, 2.0).to_mask(4, 4),
+        square(1.0, 0.0, 2.0).to_mask(4, 4),
+    ];
+    assert_eq!(Mask::merge(&masks, 4, 4, 1).area(), 8);
+    assert_eq!(Mask::merge(&masks, 4, 4, 2).area(), 3);
+    assert_eq!(Mask::merge(&masks, 4, 4, 3).area(), 1);
+}
+
+#[tokio::test]
+async fn test_segmentation_crud_merge_and_coco_export() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let other_group = GroupRepository::create(db, "Other".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let bird = TagRepository::create(db, "bird".to_string(), None, other_group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+    let mallory = LabelerRepository::create(db, "mallory".to_string(), "hash".to_string()).await?;
+    for labeler in [&alice, &bob, &carol] {
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+    }
+
+    let key = storage.storage.put(&test_png(10, 10)).await?;
+    let image = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let unmeasured = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), key, 1, group.id).await?;
+
+    let request = |tag_id: i32, geometry: Geometry| SegmentationRequest { tag_id, geometry };
+    let draw = |labeler_id: i32, image_id: i32, tag_id: i32, geometry: Geometry| {
+        SegmentationService::create_segmentation(db, labeler_id, group.id, image_id, request(tag_id, geometry))
+    };
+
+    // Annotations need known dimensions to be checked against
+    let unknown = draw(alice.id, unmeasured.id, cat.id, square(1.0, 1.0, 4.0)).await?;
+    assert_eq!(unknown.message, "Image dimensions are unknown");
+    ImageRepository::set_metadata(db, image.id, 10, 10, "RGB".to_string(), None).await?;
+
+    assert!(!draw(alice.id, image.id, bird.id, square(1.0, 1.0, 4.0)).await?.success);
+    assert!(!draw(alice.id, image.id, cat.id, square(8.0, 8.0, 4.0)).await?.success);
+    assert!(draw(mallory.id, image.id, cat.id, square(1.0, 1.0, 4.0)).await.unwrap_err().contains("not authorized"));
+    // A sliver encloses an area but no pixel center
+    let sliver = Geometry::Polygon { points: vec![[0.1, 0.1], [0.4, 0.1], [0.4, 0.4]] };
+    assert!(sliver.validate(10, 10).is_ok());
+    let response = draw(alice.id, image.id, cat.id, sliver).await?;
+    assert_eq!((response.success, response.message.as_str()), (false, "The annotation does not cover any pixel"));
+
+    let mistake = draw(alice.id, image.id, cat.id, square(6.0, 6.0, 2.0)).await?.data.unwrap();
+    assert_eq!(mistake.area, 4);
+    let moved = SegmentationService::update_segmentation(db, alice.id, group.id, image.id, mistake.id, request(cat.id, square(0.0, 0.0, 4.0))).await?;
+    assert_eq!(moved.data.unwrap().area, 16);
+    assert!(!SegmentationService::delete_segmentation(db, bob.id, group.id, image.id, mistake.id).await?.success);
+    let extra = draw(alice.id, image.id, cat.id, square(6.0, 6.0, 2.0)).await?.data.unwrap();
+    assert!(SegmentationService::delete_segmentation(db, alice.id, group.id, image.id, extra.id).await?.success);
+    assert_eq!(SegmentationService::list_segmentations(db, alice.id, group.id, image.id).await?.data.unwrap().len(), 1);
+
+    draw(bob.id, image.id, cat.id, square(2.0, 2.0, 4.0)).await?;
+    let rle = Geometry::Rle {
+        size: [10, 10],
+        counts: square(2.0, 0.0, 4.0).to_mask(10, 10).to_rle(),
+    };
+    let carols = draw(carol.id, image.id, cat.id, rle).await?.data.unwrap();
+
+    // Pixels drawn by at least two of the three labelers
+    let merge = |segmentation_ids: Option<Vec<i32>>, min_votes: Option<usize>| MergeSegmentationsRequest {
+        tag_id: cat.id,
+        segmentation_ids,
+        min_votes,
+    };
+    let merged = SegmentationService::merge_segmentations(db, image.id, merge(None, None)).await?.data.unwrap();
+    assert_eq!((merged.tag_name.as_str(), merged.votes), ("cat", 3));
+    assert_eq!(merged.area, 12);
+    assert!(matches!(merged.geometry, Geometry::Rle { size: [10, 10], .. }));
+
+    let only_carol = SegmentationService::merge_segmentations(db, image.id, merge(Some(vec![carols.id]), None)).await?.data.unwrap();
+    assert_eq!((only_carol.votes, only_carol.area), (1, 16));
+    assert!(!SegmentationService::merge_segmentations(db, image.id, merge(Some(vec![carols.id + 100]), None)).await?.success);
+    assert!(!SegmentationService::merge_segmentations(db, image.id, merge(None, Some(4))).await?.success);
+    SegmentationService::merge_segmentations(db, image.id, merge(None, Some(2))).await?;
+
+    let review = SegmentationService::image_segmentations(db, image.id).await?.data.unwrap();
+    assert_eq!(review.labelers.len(), 3);
+    assert_eq!(review.final_segmentations.len(), 1);
+    assert_eq!(review.final_segmentations[0].area, 12);
+
+    // The image without dimensions is left out of the export
+    let coco = serde_json::to_value(ExportService::coco_export(db, group.id).await?.unwrap())?;
+    assert_eq!(coco["images"].as_array().unwrap().len(), 1);
+    assert_eq!(coco["images"][0]["width"], 10);
+    assert_eq!(coco["categories"][0]["name"], "cat");
+    let annotation = &coco["annotations"][0];
+    assert_eq!(annotation["id"], 1);
+    assert_eq!(annotation["category_id"], cat.id);
+    assert_eq!(annotation["area"], 12.0);
+    assert_eq!(annotation["iscrowd"], 1);
+    assert_eq!(annotation["segmentation"]["size"], serde_json::json!([10, 10]));
+    assert_eq!(annotation["bbox"], serde_json::json!([2.0, 0.0, 4.0, 4.0]));
+    assert!(ExportService::coco_export(db, group.id + 100).await?.is_none());
+
+    Ok(())
+}
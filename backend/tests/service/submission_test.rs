@@ -0,0 +1,75 @@
+use image_labeling_website::consensus::ConsensusStrategy;
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::labeler::UpdateImageTagsRequest;
+use image_labeling_website::schemas::pagination::ListQuery;
+use image_labeling_website::service::consensus::ConsensusService;
+use image_labeling_website::service::export::ExportService;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::storage::ImageStorage;
+use sea_orm::ConnectionTrait;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+#[tokio::test]
+async fn test_empty_answers_count_as_labeled() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    LabelerRepository::add_to_group(db, alice.id, group.id).await?;
+    LabelerRepository::add_to_group(db, bob.id, group.id).await?;
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let first = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let second = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+
+    // alice sees no cat on the first image, bob does
+    let answer = |labeler_id: i32, tag_ids: Vec<i32>| {
+        LabelerService::update_image_tags(db, labeler_id, group.id, first.id, UpdateImageTagsRequest { tag_ids, text: None })
+    };
+    answer(alice.id, vec![]).await?;
+    answer(bob.id, vec![cat.id]).await?;
+
+    let images = LabelerService::get_group_images(db, alice.id, group.id, ListQuery::default()).await?.data.unwrap().images;
+    let status = |image_id: i32| images.iter().find(|image| image.id == image_id).unwrap().status.clone();
+    assert_eq!(status(first.id), "done");
+    assert_eq!(status(second.id), "pending");
+
+    let labeled = ImageListQuery { labeled: Some(true), labeled_by: Some(alice.id), ..Default::default() };
+    let page = ImageRepository::list_by_group(db, group.id, &labeled, PageRequest::new(None, None)?).await?;
+    assert_eq!(page.items.iter().map(|image| image.id).collect::<Vec<_>>(), vec![first.id]);
+
+    // alice's empty answer is a vote against cat
+    let response = ConsensusService::auto_generate_final_tags(db, first.id, Some(ConsensusStrategy::Unanimous)).await?;
+    assert!(response.data.unwrap().is_empty());
+    let response = ConsensusService::auto_generate_final_tags(db, second.id, None).await?;
+    assert_eq!(response.message, "No tags found for this image");
+
+    let export = serde_json::to_value(ExportService::bulk_export(db, &storage.storage, false).await?)?;
+    let statistics = &export[group.id.to_string()][first.id.to_string()]["tag_statistics"][0];
+    assert_eq!(statistics["total_labelers"], 2);
+    assert_eq!(statistics["percentage"], 50.0);
+
+    // Labeling again keeps a single submission
+    answer(alice.id, vec![]).await?;
+    assert_eq!(SubmissionRepository::get_by_image(db, first.id).await?.len(), 2);
+
+    // Tags saved without a submission record still count as submitted
+    SubmissionRepository::delete_by_labeler_and_group(db, bob.id, group.id).await?;
+    let submitted = SubmissionRepository::submitted_images(db, bob.id, &[first.id, second.id]).await?;
+    assert_eq!(submitted.into_iter().collect::<Vec<_>>(), vec![first.id]);
+
+    // An answer whose submission cannot be recorded leaves the old tags alone
+    db.execute_unprepared(
+        "CREATE TRIGGER fail_submission BEFORE INSERT ON submission BEGIN SELECT RAISE(ABORT, 'no submissions'); END",
+    )
+    .await?;
+    assert!(answer(bob.id, vec![]).await.is_err());
+    let tags = ImageTagsRepository::get_by_image_and_labeler(db, first.id, bob.id).await?;
+    assert_eq!(tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![cat.id]);
+
+    Ok(())
+}
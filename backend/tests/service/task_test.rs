@@ -0,0 +1,158 @@
+use image_labeling_website::consensus::ConsensusStrategy;
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::admin::{CreateGroupRequest, UpdateGroupRequest};
+use image_labeling_website::schemas::labeler::UpdateImageTagsRequest;
+use image_labeling_website::service::admin::AdminService;
+use image_labeling_website::service::consensus::ConsensusService;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::task::{normalize_text, TaskType, MAX_TEXT_LENGTH};
+use super::super::common::test_utils::setup_test_db;
+
+#[test]
+fn test_task_types_check_answers_and_limit_picks() {
+    let single = TaskType::SingleChoice;
+    assert!(single.check_answer(&[1], None).is_ok());
+    assert!(single.check_answer(&[1, 1], None).is_ok());
+    assert!(single.check_answer(&[], None).is_err());
+    assert!(single.check_answer(&[1, 2], None).is_err());
+    assert!(single.check_answer(&[1], Some("a cat")).is_err());
+
+    let multi = TaskType::MultiChoice { min: 1, max: Some(2) };
+    assert!(multi.check_answer(&[1, 2], Some("  ")).is_ok());
+    assert!(multi.check_answer(&[], None).is_err());
+    assert!(multi.check_answer(&[1, 2, 3], None).is_err());
+    assert!(TaskType::default().check_answer(&[], None).is_ok());
+
+    let text = TaskType::FreeText;
+    assert!(text.check_answer(&[], Some("A cat on a mat")).is_ok());
+    assert!(text.check_answer(&[], Some(" ")).is_err());
+    assert!(text.check_answer(&[1], Some("A cat")).is_err());
+    assert!(text.check_answer(&[], Some(&"a".repeat(MAX_TEXT_LENGTH + 1))).is_err());
+
+    assert!(TaskType::MultiChoice { min: 0, max: Some(0) }.validate().is_err());
+    assert!(TaskType::MultiChoice { min: 3, max: Some(2) }.validate().is_err());
+    let json = multi.to_json();
+    assert_eq!(json, r#"{"type":"multi_choice","min":1,"max":2}"#);
+    assert_eq!(TaskType::from_json(r#"{"type":"multi_choice"}"#), Some(TaskType::default()));
+
+    // Tag 3 has the most support, then tag 1 and tag 2 tied
+    let picked = vec![(1, 2.0, ()), (2, 2.0, ()), (3, 3.0, ())];
+    let tags = |picked: Vec<(i32, f64, ())>| picked.into_iter().map(|(tag_id, _, _)| tag_id).collect::<Vec<_>>();
+    assert_eq!(tags(single.limit(picked.clone())), vec![3]);
+    assert_eq!(tags(single.limit(picked[..2].to_vec())), Vec::<i32>::new());
+    assert_eq!(tags(multi.limit(picked.clone())), vec![1, 3]);
+    assert_eq!(tags(TaskType::default().limit(picked.clone())), vec![1, 2, 3]);
+    assert_eq!(tags(text.limit(picked)), Vec::<i32>::new());
+
+    assert_eq!(normalize_text("  A  Cat\non a mat "), "a cat on a mat");
+}
+
+#[tokio::test]
+async fn test_answers_and_consensus_follow_the_task_type() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+
+    let invalid = AdminService::create_group(db, CreateGroupRequest {
+        name: "Invalid".to_string(),
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: Some(TaskType::MultiChoice { min: 2, max: Some(1) }),
+    }).await?;
+    assert!(!invalid.success);
+
+    let group = AdminService::create_group(db, CreateGroupRequest {
+        name: "Group".to_string(),
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: Some(ConsensusStrategy::Majority { threshold: 0.3 }),
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: Some(TaskType::SingleChoice),
+    }).await?.data.unwrap();
+    assert_eq!(group.task_type, TaskType::SingleChoice);
+
+    let other_group = GroupRepository::create(db, "Other".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let dog = TagRepository::create(db, "dog".to_string(), None, group.id).await?;
+    let bird = TagRepository::create(db, "bird".to_string(), None, other_group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    let bob = LabelerRepository::create(db, "bob".to_string(), "hash".to_string()).await?;
+    let carol = LabelerRepository::create(db, "carol".to_string(), "hash".to_string()).await?;
+    for labeler in [&alice, &bob, &carol] {
+        LabelerRepository::add_to_group(db, labeler.id, group.id).await?;
+    }
+    let image = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), "a".to_string(), 1, group.id).await?;
+    let tied = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), "b".to_string(), 1, group.id).await?;
+
+    let answer_image = |labeler_id: i32, image_id: i32, tag_ids: Vec<i32>, text: Option<&str>| {
+        let request = UpdateImageTagsRequest { tag_ids, text: text.map(str::to_string) };
+        LabelerService::update_image_tags(db, labeler_id, group.id, image_id, request)
+    };
+    let answer = |labeler_id: i32, tag_ids: Vec<i32>, text: Option<&str>| answer_image(labeler_id, image.id, tag_ids, text);
+
+    // Exactly one of the group's tags
+    assert!(!answer(alice.id, vec![cat.id, dog.id], None).await?.success);
+    assert!(!answer(alice.id, vec![], None).await?.success);
+    let foreign = answer(alice.id, vec![bird.id], None).await?;
+    assert_eq!(foreign.message, format!("Tag {} does not belong to this group", bird.id));
+    assert!(!SubmissionRepository::has_submitted(db, image.id, alice.id).await?);
+
+    answer(alice.id, vec![cat.id], None).await?;
+    answer(bob.id, vec![cat.id], None).await?;
+    answer(carol.id, vec![dog.id], None).await?;
+
+    // Both tags pass the 30% majority, but only the most voted one is kept
+    let final_tags = ConsensusService::auto_generate_final_tags(db, image.id, None).await?.data.unwrap();
+    assert_eq!(final_tags.iter().map(|tag| tag.tag_id).collect::<Vec<_>>(), vec![cat.id]);
+
+    // A tie leaves the image without a final tag
+    answer_image(alice.id, tied.id, vec![cat.id], None).await?;
+    answer_image(bob.id, tied.id, vec![dog.id], None).await?;
+    let final_tags = ConsensusService::auto_generate_final_tags(db, tied.id, None).await?.data.unwrap();
+    assert!(final_tags.is_empty());
+
+    let update = AdminService::update_group(db, group.id, UpdateGroupRequest {
+        name: None,
+        description: None,
+        allowed_formats: None,
+        duplicate_policy: None,
+        consensus_strategy: None,
+        gold_window: None,
+        gold_min_accuracy: None,
+        labels_per_image: None,
+        lease_seconds: None,
+        task_type: Some(TaskType::FreeText),
+    }).await?;
+    assert_eq!(update.data.unwrap().task_type, TaskType::FreeText);
+
+    // Free-text groups take a caption and no tags
+    assert!(!answer(alice.id, vec![cat.id], Some("A cat")).await?.success);
+    assert!(!answer(alice.id, vec![], None).await?.success);
+    answer(alice.id, vec![], Some("  A cat on a mat ")).await?;
+    answer(bob.id, vec![], Some("a cat  on a MAT")).await?;
+    answer(carol.id, vec![], Some("A dog")).await?;
+    assert_eq!(CaptionRepository::find(db, image.id, alice.id).await?.unwrap().text, "A cat on a mat");
+    assert!(ImageTagsRepository::get_by_image_and_labeler(db, image.id, alice.id).await?.is_empty());
+
+    // Captions that only differ in case and spacing are the same answer
+    let generated = ConsensusService::auto_generate_final_tags(db, image.id, None).await?;
+    assert_eq!(generated.message, "Final caption auto-generated successfully");
+    let final_caption = CaptionRepository::get_final(db, image.id).await?.unwrap();
+    assert_eq!((final_caption.text.as_str(), final_caption.votes), ("A cat on a mat", 2));
+
+    answer(bob.id, vec![], Some("A bird")).await?;
+    let generated = ConsensusService::auto_generate_final_tags(db, image.id, None).await?;
+    assert_eq!(generated.message, "No caption reached consensus");
+    assert!(CaptionRepository::get_final(db, image.id).await?.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use chrono::{Duration, Utc};
+use image_labeling_website::repository::*;
+use image_labeling_website::schemas::analytics::TimeBucketResponse;
+use image_labeling_website::schemas::labeler::UpdateImageTagsRequest;
+use image_labeling_website::service::labeler::LabelerService;
+use image_labeling_website::service::timing::TimingService;
+use image_labeling_website::storage::ImageStorage;
+use super::super::common::test_utils::{setup_test_db, test_png, TestStorage};
+
+#[test]
+fn test_timing_stats() {
+    let stats = TimingService::stats(&[Some(5), Some(20), Some(40), Some(95), None, Some(7200)]);
+    assert_eq!((stats.images, stats.timed_images), (6, 4));
+    assert_eq!(stats.mean_seconds, Some(40.0));
+    assert_eq!(stats.median_seconds, Some(30.0));
+    assert_eq!(stats.p90_seconds, Some(95.0));
+    assert_eq!(stats.labels_per_hour, Some(90.0));
+    let counts: Vec<usize> = stats.histogram.iter().map(|bucket| bucket.count).collect();
+    assert_eq!(counts, vec![1, 1, 1, 1, 0, 0, 0]);
+    assert_eq!(stats.histogram.last(), Some(&TimeBucketResponse { up_to_seconds: None, count: 0 }));
+
+    let empty = TimingService::stats(&[]);
+    assert_eq!((empty.median_seconds, empty.p90_seconds, empty.labels_per_hour), (None, None, None));
+}
+
+#[tokio::test]
+async fn test_submissions_record_time_spent() -> Result<(), Box<dyn std::error::Error>> {
+    let test_db = setup_test_db().await;
+    let db = &test_db.connection;
+    let storage = TestStorage::new();
+
+    let group = GroupRepository::create(db, "Group".to_string(), None).await?;
+    let cat = TagRepository::create(db, "cat".to_string(), None, group.id).await?;
+    let alice = LabelerRepository::create(db, "alice".to_string(), "hash".to_string()).await?;
+    LabelerRepository::add_to_group(db, alice.id, group.id).await?;
+
+    let key = storage.storage.put(&test_png(2, 2)).await?;
+    let first = ImageRepository::create(db, "a.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+    let second = ImageRepository::create(db, "b.png".to_string(), "image/png".to_string(), key.clone(), 1, group.id).await?;
+
+    // Opening the details starts the clock; opening again does not restart it
+    let opened_at = Utc::now().naive_utc() - Duration::seconds(40);
+    ImageViewRepository::open(db, first.id, alice.id, opened_at).await?;
+    LabelerService::get_image_details(db, alice.id, group.id, first.id).await?;
+    let answer = |image_id: i32, tag_ids: Vec<i32>| {
+        LabelerService::update_image_tags(db, alice.id, group.id, image_id, UpdateImageTagsRequest { tag_ids, text: None })
+    };
+    answer(first.id, vec![cat.id]).await?;
+
+    let submission = SubmissionRepository::find(db, first.id, alice.id).await?.unwrap();
+    assert_eq!(submission.opened_at, Some(opened_at));
+    assert!((40..45).contains(&submission.seconds_spent.unwrap()));
+
+    // Editing the answer moves only the last-modified time
+    answer(first.id, vec![]).await?;
+    let edited = SubmissionRepository::find(db, first.id, alice.id).await?.unwrap();
+    assert_eq!(edited.first_submitted_at, submission.first_submitted_at);
+    assert!(edited.submitted_at >= submission.submitted_at);
+    assert_eq!(edited.seconds_spent, submission.seconds_spent);
+
+    // Images saved without being opened are not timed
+    answer(second.id, vec![cat.id]).await?;
+
+    let report = TimingService::throughput(db, Some(group.id)).await?.data.unwrap();
+    let timing = &report.groups[0].timing;
+    assert_eq!((timing.images, timing.timed_images), (2, 1));
+    assert_eq!(report.groups[0].labelers[0].username, "alice");
+    assert!(!TimingService::throughput(db, Some(group.id + 100)).await?.success);
+
+    Ok(())
+}
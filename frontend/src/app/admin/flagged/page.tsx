@@ -0,0 +1,162 @@
+'use client';
+
+import { useState, useEffect, useCallback, useRef } from 'react';
+import { useRouter } from 'next/navigation';
+import { useApiClient } from '@/utils/api';
+import { LoadingSpinner, ErrorMessage, PageHeader, Card, Table, EmptyState } from '@/components';
+
+interface Group {
+  id: number;
+  name: string;
+}
+
+interface ImageFlag {
+  labeler_id: number;
+  username: string;
+  reason: string;
+  note: string | null;
+  created_at: string;
+}
+
+interface FlaggedImage {
+  group_id: number;
+  image: {
+    id: number;
+    filename: string;
+  };
+  flags: ImageFlag[];
+}
+
+interface FlaggedRow {
+  id: number;
+  group_id: number;
+  filename: string;
+  group_name: string;
+  flag_count: number;
+  reasons: string;
+  notes: string;
+}
+
+interface ApiResponse<T> {
+  success: boolean;
+  message: string;
+  data?: T;
+}
+
+export default function AdminFlaggedImagesPage() {
+  const router = useRouter();
+  const apiClient = useApiClient();
+  const apiClientRef = useRef(apiClient);
+  const [rows, setRows] = useState<FlaggedRow[]>([]);
+  const [groups, setGroups] = useState<Group[]>([]);
+  const [groupId, setGroupId] = useState<string>('');
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  // Update ref when apiClient changes
+  useEffect(() => {
+    apiClientRef.current = apiClient;
+  }, [apiClient]);
+
+  useEffect(() => {
+    const loadGroups = async () => {
+      const response = await apiClientRef.current.get<ApiResponse<{ groups: Group[]; total: number }>>('/admin/groups');
+      if (response.success && response.data?.success && response.data.data) {
+        setGroups(response.data.data.groups);
+      }
+    };
+    loadGroups();
+  }, []);
+
+  const loadFlaggedImages = useCallback(async () => {
+    try {
+      setIsLoading(true);
+      setError(null);
+
+      const query = groupId ? `?group_id=${groupId}` : '';
+      const response = await apiClientRef.current.get<ApiResponse<{ images: FlaggedImage[] }>>(`/admin/flagged-images${query}`);
+
+      if (response.success && response.data?.success && response.data.data) {
+        setRows(response.data.data.images.map((flagged) => ({
+          id: flagged.image.id,
+          group_id: flagged.group_id,
+          filename: flagged.image.filename,
+          group_name: groups.find((group) => group.id === flagged.group_id)?.name ?? `#${flagged.group_id}`,
+          flag_count: flagged.flags.length,
+          reasons: Array.from(new Set(flagged.flags.map((flag) => flag.reason))).join(', '),
+          notes: flagged.flags
+            .filter((flag) => flag.note)
+            .map((flag) => `${flag.username}: ${flag.note}`)
+            .join(' · ') || '—',
+        })));
+      } else {
+        setError(response.data?.message || response.error || 'Failed to load flagged images');
+      }
+    } catch (error) {
+      console.error('Error loading flagged images:', error);
+      setError('Failed to load flagged images');
+    } finally {
+      setIsLoading(false);
+    }
+  }, [groupId, groups]);
+
+  useEffect(() => {
+    loadFlaggedImages();
+  }, [loadFlaggedImages]);
+
+  return (
+    <div className="min-h-screen bg-gray-50">
+      <div className="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 py-8">
+        <PageHeader
+          title="Flagged Images"
+          description="Images labelers reported as unreadable, ambiguous or inappropriate."
+        />
+
+        <Card>
+          <div className="px-4 py-5 sm:p-6">
+            {error && (
+              <ErrorMessage message={error} onClose={() => setError(null)} className="mb-4" />
+            )}
+
+            <div className="flex justify-between items-center mb-6">
+              <h2 className="text-lg font-medium text-gray-900">Most flagged first</h2>
+              <select
+                value={groupId}
+                onChange={(e) => setGroupId(e.target.value)}
+                className="block rounded-md border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 sm:text-sm"
+              >
+                <option value="">All groups</option>
+                {groups.map((group) => (
+                  <option key={group.id} value={group.id}>{group.name}</option>
+                ))}
+              </select>
+            </div>
+
+            {isLoading ? (
+              <LoadingSpinner />
+            ) : rows.length === 0 ? (
+              <EmptyState
+                title="No flagged images"
+                description="Images show up here once a labeler flags them."
+              />
+            ) : (
+              <div className="overflow-x-auto">
+                <Table
+                  data={rows}
+                  onRowClick={(row) => router.push(`/admin/groups/${row.group_id}/image/${row.id}`)}
+                  columns={[
+                    { key: 'filename', label: 'Image', className: 'font-medium text-gray-900' },
+                    { key: 'group_name', label: 'Group', className: 'text-gray-500' },
+                    { key: 'flag_count', label: 'Flags', className: 'text-gray-500' },
+                    { key: 'reasons', label: 'Reasons', className: 'text-gray-500' },
+                    { key: 'notes', label: 'Notes', className: 'text-gray-500' },
+                  ]}
+                />
+              </div>
+            )}
+          </div>
+        </Card>
+      </div>
+    </div>
+  );
+}
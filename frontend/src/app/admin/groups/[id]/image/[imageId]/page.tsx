@@ -3,7 +3,7 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { useParams, useRouter } from 'next/navigation';
 import Image from 'next/image';
-import { useApiClient } from '@/utils/api';
+import { useApiClient, type Caption, type FinalCaption } from '@/utils/api';
 import { LoadingSpinner, PageHeader, BackButton, Card, EmptyState, SuccessMessage, ErrorMessage } from '@/components';
 
 interface ImageData {
@@ -62,6 +62,9 @@ export default function ImageDetailPage() {
   const [isUpdating, setIsUpdating] = useState(false);
   const [updateError, setUpdateError] = useState<string | null>(null);
   const [updateSuccess, setUpdateSuccess] = useState<string | null>(null);
+  const [captions, setCaptions] = useState<Array<{ labeler_id: number; username: string; caption: Caption }>>([]);
+  const [finalCaption, setFinalCaption] = useState<FinalCaption | null>(null);
+  const [captionText, setCaptionText] = useState('');
 
   // Update ref when apiClient changes
   useEffect(() => {
@@ -93,6 +96,13 @@ export default function ImageDetailPage() {
             console.error('Error auto-generating final tags:', error);
           }
         }
+
+        const captionsResponse = await apiClientRef.current.getImageCaptions(imageId);
+        if (captionsResponse.success && captionsResponse.data?.data) {
+          setCaptions(captionsResponse.data.data.captions);
+          setFinalCaption(captionsResponse.data.data.final_caption);
+          setCaptionText(captionsResponse.data.data.final_caption?.text ?? '');
+        }
       } else {
         setError(response.error || 'Failed to load image details');
       }
@@ -148,6 +158,47 @@ export default function ImageDetailPage() {
     handleUpdateFinalTags(newTagIds);
   }, [finalTags, handleUpdateFinalTags]);
 
+  const handleSelectCaption = useCallback(async (selection: { caption_id: number } | { text: string }) => {
+    setIsUpdating(true);
+    setUpdateError(null);
+    setUpdateSuccess(null);
+
+    try {
+      const response = await apiClientRef.current.selectFinalCaption(imageId, selection);
+      if (response.success && response.data?.data) {
+        setFinalCaption(response.data.data);
+        setCaptionText(response.data.data.text);
+        setUpdateSuccess('Final caption updated successfully!');
+      } else {
+        setUpdateError(response.error || 'Failed to update final caption');
+      }
+    } catch (error) {
+      console.error('Error updating final caption:', error);
+      setUpdateError('Failed to update final caption. Please try again.');
+    } finally {
+      setIsUpdating(false);
+    }
+  }, [imageId]);
+
+  const handleDraftCaption = useCallback(async () => {
+    setIsUpdating(true);
+    setUpdateError(null);
+
+    try {
+      const response = await apiClientRef.current.draftFinalCaption(imageId);
+      if (response.success && response.data?.data) {
+        setCaptionText(response.data.data.text);
+      } else {
+        setUpdateError(response.error || 'Failed to draft caption');
+      }
+    } catch (error) {
+      console.error('Error drafting caption:', error);
+      setUpdateError('Failed to draft caption. Please try again.');
+    } finally {
+      setIsUpdating(false);
+    }
+  }, [imageId]);
+
   if (isLoading) {
     return <LoadingSpinner />;
   }
@@ -177,7 +228,7 @@ export default function ImageDetailPage() {
               <h3 className="text-lg font-medium text-gray-900 mb-4">Image</h3>
               <div className="flex justify-center h-96">
                 <Image
-                  src={`data:image/${image.filetype};base64,${image.base64_data}`}
+                  src={`data:${image.filetype};base64,${image.base64_data}`}
                   alt={image.filename}
                   width={400}
                   height={400}
@@ -342,6 +393,63 @@ export default function ImageDetailPage() {
             )}
           </div>
         </Card>
+
+        {/* Captions Section */}
+        {(captions.length > 0 || finalCaption) && (
+          <Card className="mt-6">
+            <div className="px-4 py-5 sm:p-6">
+              <h3 className="text-lg font-medium text-gray-900 mb-4">
+                Captions {finalCaption?.is_admin_override && <span className="text-sm text-orange-600">(Admin Override)</span>}
+              </h3>
+              <div className="space-y-2">
+                {captions.map(({ labeler_id, username, caption }) => (
+                  <div key={labeler_id} className="flex items-start justify-between gap-4 p-3 rounded-lg border border-gray-200">
+                    <div className="min-w-0">
+                      <div className="text-xs text-gray-500">{username}</div>
+                      <div className="text-sm text-gray-900">{caption.text}</div>
+                    </div>
+                    <button
+                      onClick={() => handleSelectCaption({ caption_id: caption.id })}
+                      disabled={isUpdating}
+                      className="text-sm text-blue-600 hover:text-blue-800 disabled:opacity-50"
+                    >
+                      Use
+                    </button>
+                  </div>
+                ))}
+              </div>
+
+              <div className="mt-4">
+                <h4 className="text-sm font-medium text-gray-700 mb-2">
+                  Final Caption{finalCaption && ` (${finalCaption.votes} votes)`}
+                </h4>
+                <textarea
+                  value={captionText}
+                  onChange={(e) => setCaptionText(e.target.value)}
+                  rows={3}
+                  maxLength={2000}
+                  className="w-full rounded-lg border border-gray-300 p-3 text-sm focus:border-blue-500 focus:ring-blue-500"
+                />
+                <div className="mt-2 flex gap-3">
+                  <button
+                    onClick={() => handleSelectCaption({ text: captionText })}
+                    disabled={isUpdating || captionText.trim() === ''}
+                    className="px-3 py-2 rounded-md text-sm font-medium text-white bg-blue-600 hover:bg-blue-700 disabled:opacity-50"
+                  >
+                    Save Final Caption
+                  </button>
+                  <button
+                    onClick={handleDraftCaption}
+                    disabled={isUpdating}
+                    className="px-3 py-2 rounded-md text-sm font-medium text-gray-700 bg-gray-100 hover:bg-gray-200 disabled:opacity-50"
+                  >
+                    Draft with AI
+                  </button>
+                </div>
+              </div>
+            </div>
+          </Card>
+        )}
       </div>
     </div>
   );
@@ -2,13 +2,14 @@
 
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { useParams, useRouter } from 'next/navigation';
-import { useApiClient, ApiClient } from '@/utils/api';
+import { useApiClient, ApiClient, type TaskType } from '@/utils/api';
 import { LoadingSpinner, PageHeader, BackButton, Card, Tabs, Button, EmptyState, ImageUploader, TagModal, LabelerSelectionModal } from '@/components';
 
 interface Group {
   id: number;
   name: string;
   description: string | null;
+  task_type?: TaskType;
 }
 
 interface Labeler {
@@ -29,6 +30,22 @@ interface Image {
   uploaded_at: string;
 }
 
+interface GroupProgress {
+  labels_per_image: number;
+  total_images: number;
+  fully_labeled: number;
+  partially_labeled: number;
+  untouched: number;
+  percent_complete: number;
+  labels_per_hour: number | null;
+  estimated_completion_at: string | null;
+  labelers: Array<{
+    labeler_id: number;
+    username: string;
+    images_labeled: number;
+  }>;
+}
+
 interface GroupDetailResponse {
   success: boolean;
   message: string;
@@ -37,6 +54,7 @@ interface GroupDetailResponse {
     labelers: Labeler[];
     tags: Tag[];
     images: Image[];
+    progress: GroupProgress;
   };
 }
 
@@ -51,6 +69,7 @@ export default function GroupDetailPage() {
   const [labelers, setLabelers] = useState<Labeler[]>([]);
   const [tags, setTags] = useState<Tag[]>([]);
   const [images, setImages] = useState<Image[]>([]);
+  const [progress, setProgress] = useState<GroupProgress | null>(null);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [activeTab, setActiveTab] = useState<'labelers' | 'tags' | 'images'>('labelers');
@@ -71,6 +90,9 @@ export default function GroupDetailPage() {
   const [labelerError, setLabelerError] = useState<string | null>(null);
   const [labelerSuccess, setLabelerSuccess] = useState<string | null>(null);
 
+  const [isExporting, setIsExporting] = useState(false);
+  const [exportError, setExportError] = useState<string | null>(null);
+
   // Update ref when apiClient changes
   useEffect(() => {
     apiClientRef.current = apiClient;
@@ -88,6 +110,7 @@ export default function GroupDetailPage() {
         setLabelers(response.data.data.labelers);
         setTags(response.data.data.tags);
         setImages(response.data.data.images);
+        setProgress(response.data.data.progress);
       } else {
         setError(response.error || 'Failed to load group details');
       }
@@ -259,6 +282,62 @@ export default function GroupDetailPage() {
     }
   }, [groupId, loadGroupDetails]);
 
+  const handleCocoExport = useCallback(async () => {
+    setIsExporting(true);
+    setExportError(null);
+
+    try {
+      const response = await apiClientRef.current.get(`/admin/groups/${groupId}/export/coco`);
+
+      if (response.success && response.data) {
+        const dataBlob = new Blob([JSON.stringify(response.data)], { type: 'application/json' });
+        const url = URL.createObjectURL(dataBlob);
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = `group-${groupId}-coco.json`;
+        document.body.appendChild(link);
+        link.click();
+        document.body.removeChild(link);
+        URL.revokeObjectURL(url);
+      } else {
+        setExportError(response.error || 'Failed to export annotations');
+      }
+    } catch (error) {
+      console.error('COCO export error:', error);
+      setExportError('Failed to export annotations. Please try again.');
+    } finally {
+      setIsExporting(false);
+    }
+  }, [groupId]);
+
+  const handleCaptionExport = useCallback(async () => {
+    setIsExporting(true);
+    setExportError(null);
+
+    try {
+      const response = await apiClientRef.current.exportCaptions(groupId);
+
+      if (response.success && response.data !== undefined) {
+        const dataBlob = new Blob([response.data], { type: 'application/jsonl' });
+        const url = URL.createObjectURL(dataBlob);
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = `group-${groupId}-captions.jsonl`;
+        document.body.appendChild(link);
+        link.click();
+        document.body.removeChild(link);
+        URL.revokeObjectURL(url);
+      } else {
+        setExportError(response.error || 'Failed to export captions');
+      }
+    } catch (error) {
+      console.error('Caption export error:', error);
+      setExportError('Failed to export captions. Please try again.');
+    } finally {
+      setIsExporting(false);
+    }
+  }, [groupId]);
+
   if (isLoading) {
     return <LoadingSpinner />;
   }
@@ -275,12 +354,71 @@ export default function GroupDetailPage() {
           <BackButton onClick={() => router.back()} className="mb-4">
             Back to Groups
           </BackButton>
-          <PageHeader 
-            title={group.name}
-            description={group.description || 'No description'}
-          />
+          <div className="flex flex-col sm:flex-row sm:justify-between sm:items-center">
+            <PageHeader 
+              title={group.name}
+              description={group.description || 'No description'}
+            />
+            <div className="mt-4 sm:mt-0">
+              <Button
+                onClick={handleCocoExport}
+                disabled={isExporting}
+                fullWidth={false}
+                className="w-full sm:w-auto"
+              >
+                {isExporting ? 'Exporting...' : 'Export COCO'}
+              </Button>
+              {group.task_type?.type === 'free_text' && (
+                <Button
+                  onClick={handleCaptionExport}
+                  disabled={isExporting}
+                  fullWidth={false}
+                  className="w-full sm:w-auto mt-2 sm:mt-0 sm:ml-2"
+                >
+                  {isExporting ? 'Exporting...' : 'Export Captions'}
+                </Button>
+              )}
+            </div>
+          </div>
+          {exportError && <p className="mt-2 text-sm text-red-600">{exportError}</p>}
         </div>
 
+        {/* Progress */}
+        {progress && (
+          <Card className="mb-6">
+            <div className="p-4 sm:p-6">
+              <div className="flex flex-col sm:flex-row sm:justify-between sm:items-center gap-2 mb-3">
+                <h2 className="text-lg font-medium text-gray-900">
+                  Progress ({progress.labels_per_image} labels per image)
+                </h2>
+                <span className="text-2xl font-bold text-blue-600">{progress.percent_complete.toFixed(1)}%</span>
+              </div>
+              <div className="w-full bg-gray-200 rounded-full h-2 mb-4">
+                <div
+                  className="bg-blue-600 h-2 rounded-full transition-all duration-300"
+                  style={{ width: `${progress.percent_complete}%` }}
+                />
+              </div>
+              <div className="grid grid-cols-2 sm:grid-cols-4 gap-4 text-sm">
+                <div><div className="text-gray-500">Fully labeled</div><div className="font-medium">{progress.fully_labeled} / {progress.total_images}</div></div>
+                <div><div className="text-gray-500">Partially labeled</div><div className="font-medium">{progress.partially_labeled}</div></div>
+                <div><div className="text-gray-500">Untouched</div><div className="font-medium">{progress.untouched}</div></div>
+                <div>
+                  <div className="text-gray-500">Estimated completion</div>
+                  <div className="font-medium">
+                    {progress.estimated_completion_at ? new Date(progress.estimated_completion_at).toLocaleString() : '—'}
+                  </div>
+                </div>
+              </div>
+              {progress.labelers.length > 0 && (
+                <p className="text-sm text-gray-500 mt-4">
+                  {progress.labelers.map((labeler) => `${labeler.username}: ${labeler.images_labeled}`).join(' · ')}
+                </p>
+              )}
+            </div>
+          </Card>
+        )}
+
         {/* Tabs */}
         <Card>
           <Tabs
@@ -0,0 +1,166 @@
+'use client';
+
+import { useState, useEffect, useCallback, useRef } from 'react';
+import { useApiClient } from '@/utils/api';
+import { LoadingSpinner, ErrorMessage, PageHeader, Card, Table, EmptyState } from '@/components';
+
+interface Group {
+  id: number;
+  name: string;
+}
+
+interface TagError {
+  tag_id: number;
+  tag_name: string;
+  images: number;
+  false_positives: number;
+  false_negatives: number;
+  error_rate: number;
+}
+
+interface LabelerScore {
+  rank: number;
+  labeler_id: number;
+  username: string;
+  reviewed_images: number;
+  true_positives: number;
+  false_positives: number;
+  false_negatives: number;
+  precision: number | null;
+  recall: number | null;
+  f1: number | null;
+  tags: TagError[];
+}
+
+interface LeaderboardRow extends LabelerScore {
+  id: number;
+  worst_tag: string;
+}
+
+interface LeaderboardResponse {
+  group_id: number | null;
+  labelers: LabelerScore[];
+}
+
+interface ApiResponse<T> {
+  success: boolean;
+  message: string;
+  data?: T;
+}
+
+const formatRate = (value: unknown) =>
+  typeof value === 'number' ? `${(value * 100).toFixed(1)}%` : '—';
+
+export default function AdminLeaderboardPage() {
+  const apiClient = useApiClient();
+  const apiClientRef = useRef(apiClient);
+  const [rows, setRows] = useState<LeaderboardRow[]>([]);
+  const [groups, setGroups] = useState<Group[]>([]);
+  const [groupId, setGroupId] = useState<string>('');
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  // Update ref when apiClient changes
+  useEffect(() => {
+    apiClientRef.current = apiClient;
+  }, [apiClient]);
+
+  useEffect(() => {
+    const loadGroups = async () => {
+      const response = await apiClientRef.current.get<ApiResponse<{ groups: Group[]; total: number }>>('/admin/groups');
+      if (response.success && response.data?.success && response.data.data) {
+        setGroups(response.data.data.groups);
+      }
+    };
+    loadGroups();
+  }, []);
+
+  const loadLeaderboard = useCallback(async () => {
+    try {
+      setIsLoading(true);
+      setError(null);
+
+      const query = groupId ? `?group_id=${groupId}` : '';
+      const response = await apiClientRef.current.get<ApiResponse<LeaderboardResponse>>(`/admin/analytics/leaderboard${query}`);
+
+      if (response.success && response.data?.success && response.data.data) {
+        setRows(response.data.data.labelers.map((score) => {
+          const worst = [...score.tags].sort((a, b) => b.error_rate - a.error_rate)[0];
+          return {
+            ...score,
+            id: score.labeler_id,
+            worst_tag: worst && worst.error_rate > 0 ? `${worst.tag_name} (${formatRate(worst.error_rate)})` : '—',
+          };
+        }));
+      } else {
+        setError(response.data?.message || response.error || 'Failed to load leaderboard');
+      }
+    } catch (error) {
+      console.error('Error loading leaderboard:', error);
+      setError('Failed to load leaderboard');
+    } finally {
+      setIsLoading(false);
+    }
+  }, [groupId]);
+
+  useEffect(() => {
+    loadLeaderboard();
+  }, [loadLeaderboard]);
+
+  return (
+    <div className="min-h-screen bg-gray-50">
+      <div className="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 py-8">
+        <PageHeader
+          title="Labeler Leaderboard"
+          description="Labelers scored against final tags approved by an admin."
+        />
+
+        <Card>
+          <div className="px-4 py-5 sm:p-6">
+            {error && (
+              <ErrorMessage message={error} onClose={() => setError(null)} className="mb-4" />
+            )}
+
+            <div className="flex justify-between items-center mb-6">
+              <h2 className="text-lg font-medium text-gray-900">Ranking by F1</h2>
+              <select
+                value={groupId}
+                onChange={(e) => setGroupId(e.target.value)}
+                className="block rounded-md border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 sm:text-sm"
+              >
+                <option value="">All groups</option>
+                {groups.map((group) => (
+                  <option key={group.id} value={group.id}>{group.name}</option>
+                ))}
+              </select>
+            </div>
+
+            {isLoading ? (
+              <LoadingSpinner />
+            ) : rows.length === 0 ? (
+              <EmptyState
+                title="No scores yet"
+                description="Labelers are scored once an admin has set the final tags of images they labeled."
+              />
+            ) : (
+              <div className="overflow-x-auto">
+                <Table
+                  data={rows}
+                  columns={[
+                    { key: 'rank', label: '#', className: 'text-gray-500' },
+                    { key: 'username', label: 'Labeler', className: 'font-medium text-gray-900' },
+                    { key: 'reviewed_images', label: 'Reviewed Images', className: 'text-gray-500' },
+                    { key: 'precision', label: 'Precision', render: formatRate, className: 'text-gray-500' },
+                    { key: 'recall', label: 'Recall', render: formatRate, className: 'text-gray-500' },
+                    { key: 'f1', label: 'F1', render: formatRate, className: 'font-medium text-gray-900' },
+                    { key: 'worst_tag', label: 'Weakest Tag', className: 'text-gray-500' },
+                  ]}
+                />
+              </div>
+            )}
+          </div>
+        </Card>
+      </div>
+    </div>
+  );
+}
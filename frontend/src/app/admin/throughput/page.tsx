@@ -0,0 +1,213 @@
+'use client';
+
+import { useState, useEffect, useCallback, useRef } from 'react';
+import { useApiClient } from '@/utils/api';
+import { LoadingSpinner, ErrorMessage, PageHeader, Card, Table, EmptyState } from '@/components';
+
+interface Group {
+  id: number;
+  name: string;
+}
+
+interface TimeBucket {
+  up_to_seconds: number | null;
+  count: number;
+}
+
+interface TimingStats {
+  images: number;
+  timed_images: number;
+  mean_seconds: number | null;
+  median_seconds: number | null;
+  p90_seconds: number | null;
+  labels_per_hour: number | null;
+  histogram: TimeBucket[];
+}
+
+interface GroupThroughput {
+  group_id: number;
+  name: string;
+  timing: TimingStats;
+  labelers: {
+    labeler_id: number;
+    username: string;
+    timing: TimingStats;
+  }[];
+}
+
+interface ThroughputRow {
+  id: number;
+  username: string;
+  images: number;
+  timed_images: number;
+  median: string;
+  p90: string;
+  labels_per_hour: string;
+}
+
+interface ApiResponse<T> {
+  success: boolean;
+  message: string;
+  data?: T;
+}
+
+const formatSeconds = (seconds: number | null) => (seconds === null ? '—' : `${Math.round(seconds)}s`);
+
+const formatRate = (rate: number | null) => (rate === null ? '—' : rate.toFixed(1));
+
+const bucketLabel = (bucket: TimeBucket, previous?: TimeBucket) =>
+  bucket.up_to_seconds === null
+    ? `> ${previous?.up_to_seconds ?? 0}s`
+    : `≤ ${bucket.up_to_seconds}s`;
+
+export default function AdminThroughputPage() {
+  const apiClient = useApiClient();
+  const apiClientRef = useRef(apiClient);
+  const [report, setReport] = useState<GroupThroughput[]>([]);
+  const [groups, setGroups] = useState<Group[]>([]);
+  const [groupId, setGroupId] = useState<string>('');
+  const [isLoading, setIsLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  // Update ref when apiClient changes
+  useEffect(() => {
+    apiClientRef.current = apiClient;
+  }, [apiClient]);
+
+  useEffect(() => {
+    const loadGroups = async () => {
+      const response = await apiClientRef.current.get<ApiResponse<{ groups: Group[]; total: number }>>('/admin/groups');
+      if (response.success && response.data?.success && response.data.data) {
+        setGroups(response.data.data.groups);
+      }
+    };
+    loadGroups();
+  }, []);
+
+  const loadThroughput = useCallback(async () => {
+    try {
+      setIsLoading(true);
+      setError(null);
+
+      const query = groupId ? `?group_id=${groupId}` : '';
+      const response = await apiClientRef.current.get<ApiResponse<{ groups: GroupThroughput[] }>>(`/admin/analytics/throughput${query}`);
+
+      if (response.success && response.data?.success && response.data.data) {
+        setReport(response.data.data.groups);
+      } else {
+        setError(response.data?.message || response.error || 'Failed to load throughput');
+      }
+    } catch (error) {
+      console.error('Error loading throughput:', error);
+      setError('Failed to load throughput');
+    } finally {
+      setIsLoading(false);
+    }
+  }, [groupId]);
+
+  useEffect(() => {
+    loadThroughput();
+  }, [loadThroughput]);
+
+  const groupsWithWork = report.filter((group) => group.timing.images > 0);
+
+  return (
+    <div className="min-h-screen bg-gray-50">
+      <div className="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 py-8">
+        <PageHeader
+          title="Throughput"
+          description="Time spent per image, from opening it to the first submission, and labels per hour."
+        />
+
+        {error && (
+          <ErrorMessage message={error} onClose={() => setError(null)} className="mb-4" />
+        )}
+
+        <div className="flex justify-end mb-6">
+          <select
+            value={groupId}
+            onChange={(e) => setGroupId(e.target.value)}
+            className="block rounded-md border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 sm:text-sm"
+          >
+            <option value="">All groups</option>
+            {groups.map((group) => (
+              <option key={group.id} value={group.id}>{group.name}</option>
+            ))}
+          </select>
+        </div>
+
+        {isLoading ? (
+          <LoadingSpinner />
+        ) : groupsWithWork.length === 0 ? (
+          <Card>
+            <div className="px-4 py-5 sm:p-6">
+              <EmptyState
+                title="No submissions yet"
+                description="Timing shows up here once labelers submit images."
+              />
+            </div>
+          </Card>
+        ) : (
+          <div className="space-y-6">
+            {groupsWithWork.map((group) => {
+              const maxCount = Math.max(1, ...group.timing.histogram.map((bucket) => bucket.count));
+              const rows: ThroughputRow[] = group.labelers.map((labeler) => ({
+                id: labeler.labeler_id,
+                username: labeler.username,
+                images: labeler.timing.images,
+                timed_images: labeler.timing.timed_images,
+                median: formatSeconds(labeler.timing.median_seconds),
+                p90: formatSeconds(labeler.timing.p90_seconds),
+                labels_per_hour: formatRate(labeler.timing.labels_per_hour),
+              }));
+
+              return (
+                <Card key={group.group_id}>
+                  <div className="px-4 py-5 sm:p-6">
+                    <div className="flex justify-between items-baseline mb-4">
+                      <h2 className="text-lg font-medium text-gray-900">{group.name}</h2>
+                      <p className="text-sm text-gray-500">
+                        {group.timing.timed_images} of {group.timing.images} images timed · median{' '}
+                        {formatSeconds(group.timing.median_seconds)} · p90 {formatSeconds(group.timing.p90_seconds)} ·{' '}
+                        {formatRate(group.timing.labels_per_hour)} labels/hour
+                      </p>
+                    </div>
+
+                    <div className="space-y-1 mb-6">
+                      {group.timing.histogram.map((bucket, index) => (
+                        <div key={index} className="flex items-center text-sm">
+                          <span className="w-16 text-gray-500">{bucketLabel(bucket, group.timing.histogram[index - 1])}</span>
+                          <div className="flex-1 bg-gray-100 rounded h-3 mx-2">
+                            <div
+                              className="bg-blue-500 h-3 rounded"
+                              style={{ width: `${(bucket.count / maxCount) * 100}%` }}
+                            />
+                          </div>
+                          <span className="w-10 text-right text-gray-700">{bucket.count}</span>
+                        </div>
+                      ))}
+                    </div>
+
+                    <div className="overflow-x-auto">
+                      <Table
+                        data={rows}
+                        columns={[
+                          { key: 'username', label: 'Labeler', className: 'font-medium text-gray-900' },
+                          { key: 'images', label: 'Images', className: 'text-gray-500' },
+                          { key: 'timed_images', label: 'Timed', className: 'text-gray-500' },
+                          { key: 'median', label: 'Median', className: 'text-gray-500' },
+                          { key: 'p90', label: 'p90', className: 'text-gray-500' },
+                          { key: 'labels_per_hour', label: 'Labels/hour', className: 'text-gray-500' },
+                        ]}
+                      />
+                    </div>
+                  </div>
+                </Card>
+              );
+            })}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+}
@@ -3,8 +3,9 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
 import { useParams, useRouter } from 'next/navigation';
 import Image from 'next/image';
-import { LoadingSpinner, SmallSpinner, PageHeader, Card, BackButton, Button } from '@/components';
-import { useApiClient } from '@/utils/api';
+import { LoadingSpinner, SmallSpinner, PageHeader, Card, BackButton, Button, RegionEditor } from '@/components';
+import type { BoundingBox, Region } from '@/components/RegionEditor';
+import { resolveApiUrl, useApiClient, type TaskType } from '@/utils/api';
 
 interface Tag {
   id: number;
@@ -17,13 +18,24 @@ interface ImageDetails {
     id: number;
     filename: string;
     status: string;
-    base64_data: string;
     filetype: string;
+    content_url: string;
   };
   group_tags: Tag[];
   current_tags: Tag[];
+  task_type: TaskType;
+  current_text: string | null;
 }
 
+type FlagReason = 'unreadable' | 'ambiguous' | 'inappropriate' | 'other';
+
+const FLAG_REASONS: Array<{ value: FlagReason; label: string }> = [
+  { value: 'unreadable', label: 'Unreadable' },
+  { value: 'ambiguous', label: 'Ambiguous' },
+  { value: 'inappropriate', label: 'Inappropriate' },
+  { value: 'other', label: 'Other' },
+];
+
 export default function ImageLabelingPage() {
   const params = useParams();
   const router = useRouter();
@@ -32,12 +44,19 @@ export default function ImageLabelingPage() {
   
   const [imageDetails, setImageDetails] = useState<ImageDetails | null>(null);
   const [selectedTags, setSelectedTags] = useState<number[]>([]);
+  const [answerText, setAnswerText] = useState('');
   const [suggestedTags, setSuggestedTags] = useState<string[]>([]);
   const [hasTriedSuggesting, setHasTriedSuggesting] = useState(false);
   const [isLoading, setIsLoading] = useState(true);
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [isSuggesting, setIsSuggesting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [flagReason, setFlagReason] = useState<FlagReason>('unreadable');
+  const [flagNote, setFlagNote] = useState('');
+  const [regions, setRegions] = useState<Region[]>([]);
+  const [isDrawing, setIsDrawing] = useState(false);
+  const [boxTagId, setBoxTagId] = useState<number | null>(null);
+  const [regionMessage, setRegionMessage] = useState<string | null>(null);
   
   const apiClient = useApiClient();
   const apiClientRef = useRef(apiClient);
@@ -56,6 +75,12 @@ export default function ImageLabelingPage() {
       if (response.success && response.data) {
         setImageDetails(response.data);
         setSelectedTags(response.data.current_tags.map(tag => tag.id));
+        setAnswerText(response.data.current_text ?? '');
+        setBoxTagId(response.data.group_tags[0]?.id ?? null);
+        const regionsResponse = await apiClientRef.current.listRegions(groupId, imageId);
+        if (regionsResponse.success && regionsResponse.data?.data) {
+          setRegions(regionsResponse.data.data);
+        }
       } else {
         setError(response.error || 'Failed to load image details');
       }
@@ -88,7 +113,30 @@ export default function ImageLabelingPage() {
     }
   };
 
+  const taskType = imageDetails?.task_type.type;
+
+  const handleDraftCaption = async () => {
+    try {
+      setIsSuggesting(true);
+      const response = await apiClientRef.current.draftCaption(groupId, imageId);
+      if (response.success && response.data?.data) {
+        setAnswerText(response.data.data.text);
+      } else {
+        setError(response.error || 'Failed to draft caption');
+      }
+    } catch (error) {
+      console.error('Error drafting caption:', error);
+      setError('Failed to draft caption');
+    } finally {
+      setIsSuggesting(false);
+    }
+  };
+
   const handleTagToggle = (tagId: number) => {
+    if (taskType === 'single_choice') {
+      setSelectedTags([tagId]);
+      return;
+    }
     setSelectedTags(prev => 
       prev.includes(tagId) 
         ? prev.filter(id => id !== tagId)
@@ -96,26 +144,65 @@ export default function ImageLabelingPage() {
     );
   };
 
+  const handleCreateRegion = async (bbox: BoundingBox) => {
+    if (boxTagId === null) return;
+    setRegionMessage(null);
+    const response = await apiClientRef.current.createRegion(groupId, imageId, { tag_id: boxTagId, ...bbox });
+    if (response.success && response.data?.data) {
+      setRegions(prev => [...prev, response.data!.data]);
+    } else {
+      setRegionMessage(response.error || 'Failed to save box');
+    }
+  };
+
+  const handleDeleteRegion = async (regionId: number) => {
+    setRegionMessage(null);
+    const response = await apiClientRef.current.deleteRegion(groupId, imageId, regionId);
+    if (response.success) {
+      setRegions(prev => prev.filter(region => region.id !== regionId));
+    } else {
+      setRegionMessage(response.error || 'Failed to delete box');
+    }
+  };
+
+  // Ask the queue for the next image that needs labels
+  const goToNextImage = async () => {
+    const nextResponse = await apiClientRef.current.getNextImage(groupId);
+    if (nextResponse.success && nextResponse.data) {
+      router.push(`/labeler/groups/${groupId}/images/${nextResponse.data.image.id}`);
+    } else {
+      router.push(`/labeler/groups/${groupId}`);
+    }
+  };
+
+  const handleMark = async (kind: 'skip' | 'flag') => {
+    try {
+      setIsSubmitting(true);
+      const response = await apiClientRef.current.markImage(groupId, imageId, kind, flagReason, flagNote || null);
+      if (response.success) {
+        await goToNextImage();
+      } else {
+        setError(response.error || `Failed to ${kind} image`);
+      }
+    } catch (error) {
+      console.error(`Error trying to ${kind} image:`, error);
+      setError(`Failed to ${kind} image`);
+    } finally {
+      setIsSubmitting(false);
+    }
+  };
+
   const handleSubmit = async (exitAfterSubmit: boolean = false) => {
     try {
       setIsSubmitting(true);
-      const response = await apiClientRef.current.updateImageTags(groupId, imageId, selectedTags);
+      const response = taskType === 'free_text'
+        ? await apiClientRef.current.updateImageTags(groupId, imageId, [], answerText)
+        : await apiClientRef.current.updateImageTags(groupId, imageId, selectedTags);
       if (response.success) {
         if (exitAfterSubmit) {
           router.push('/labeler/groups');
         } else {
-          // Find next pending image in the group
-          const imagesResponse = await apiClientRef.current.getGroupImages(groupId);
-          if (imagesResponse.success && imagesResponse.data) {
-            const pendingImages = imagesResponse.data.filter(img => img.status === 'pending' && img.id !== imageId);
-            if (pendingImages.length > 0) {
-              router.push(`/labeler/groups/${groupId}/images/${pendingImages[0].id}`);
-            } else {
-              router.push(`/labeler/groups/${groupId}`);
-            }
-          } else {
-            router.push(`/labeler/groups/${groupId}`);
-          }
+          await goToNextImage();
         }
       } else {
         setError(response.error || 'Failed to update tags');
@@ -186,16 +273,52 @@ export default function ImageLabelingPage() {
           <div>
             <Card maxHeight="500px">
               <div className="px-4 py-5 sm:p-6">
-                <h2 className="text-lg font-medium text-gray-900 mb-4">Image</h2>
-                <div className="aspect-square bg-gray-100 rounded-lg overflow-hidden">
-                  <Image
-                    src={`data:${imageDetails.image.filetype};base64,${imageDetails.image.base64_data}`}
-                    alt={imageDetails.image.filename}
-                    width={400}
-                    height={400}
-                    className="w-full h-full object-contain"
-                  />
+                <div className="flex items-center justify-between mb-4">
+                  <h2 className="text-lg font-medium text-gray-900">Image</h2>
+                  <div className="flex items-center gap-2">
+                    {isDrawing && (
+                      <select
+                        value={boxTagId ?? ''}
+                        onChange={(e) => setBoxTagId(parseInt(e.target.value))}
+                        className="block rounded-md border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 sm:text-sm"
+                      >
+                        {imageDetails.group_tags.map((tag) => (
+                          <option key={tag.id} value={tag.id}>{tag.name}</option>
+                        ))}
+                      </select>
+                    )}
+                    <Button onClick={() => setIsDrawing(!isDrawing)} variant="secondary">
+                      {isDrawing ? 'Done' : `Draw boxes${regions.length > 0 ? ` (${regions.length})` : ''}`}
+                    </Button>
+                  </div>
                 </div>
+                {isDrawing ? (
+                  <div className="bg-gray-100 rounded-lg overflow-hidden">
+                    <RegionEditor
+                      src={resolveApiUrl(imageDetails.image.content_url)}
+                      alt={imageDetails.image.filename}
+                      regions={regions}
+                      tagNames={Object.fromEntries(imageDetails.group_tags.map((tag) => [tag.id, tag.name]))}
+                      disabled={boxTagId === null}
+                      onCreate={handleCreateRegion}
+                      onDelete={handleDeleteRegion}
+                    />
+                  </div>
+                ) : (
+                  <div className="aspect-square bg-gray-100 rounded-lg overflow-hidden">
+                    <Image
+                      src={resolveApiUrl(imageDetails.image.content_url)}
+                      alt={imageDetails.image.filename}
+                      width={400}
+                      height={400}
+                      unoptimized
+                      className="w-full h-full object-contain"
+                    />
+                  </div>
+                )}
+                {regionMessage && (
+                  <p className="mt-2 text-sm text-red-600">{regionMessage}</p>
+                )}
                 <div className="mt-2 text-center">
                   <p className="text-sm text-gray-600">{imageDetails.image.filename}</p>
                 </div>
@@ -208,9 +331,11 @@ export default function ImageLabelingPage() {
             <Card maxHeight="500px">
               <div className="px-4 py-5 sm:p-6">
                 <div className="flex items-center justify-between mb-4">
-                  <h2 className="text-lg font-medium text-gray-900">Select Tags</h2>
+                  <h2 className="text-lg font-medium text-gray-900">
+                    {taskType === 'free_text' ? 'Describe Image' : 'Select Tags'}
+                  </h2>
                   <Button
-                    onClick={handleSuggestTags}
+                    onClick={taskType === 'free_text' ? handleDraftCaption : handleSuggestTags}
                     disabled={isSuggesting}
                     className="flex items-center space-x-2"
                   >
@@ -221,7 +346,7 @@ export default function ImageLabelingPage() {
                         <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M9.663 17h4.673M12 3v1m6.364 1.636l-.707.707M21 12h-1M4 12H3m3.343-5.657l-.707-.707m2.828 9.9a5 5 0 117.072 0l-.548.547A3.374 3.374 0 0014 18.469V19a2 2 0 11-4 0v-.531c0-.895-.356-1.754-.988-2.386l-.548-.547z" />
                       </svg>
                     )}
-                    <span>Suggest more tags</span>
+                    <span>{taskType === 'free_text' ? 'Draft with AI' : 'Suggest more tags'}</span>
                   </Button>
                 </div>
 
@@ -248,7 +373,20 @@ export default function ImageLabelingPage() {
                   </div>
                 ) : null}
 
-                {/* Available Tags */}
+                {/* Free-text answer, or the available tags */}
+                {taskType === 'free_text' ? (
+                  <div className="space-y-3">
+                    <h3 className="text-sm font-medium text-gray-700">Description</h3>
+                    <textarea
+                      value={answerText}
+                      onChange={(e) => setAnswerText(e.target.value)}
+                      rows={6}
+                      maxLength={2000}
+                      placeholder="Describe the image"
+                      className="w-full rounded-lg border border-gray-300 p-3 text-sm focus:border-blue-500 focus:ring-blue-500"
+                    />
+                  </div>
+                ) : (
                 <div className="space-y-3">
                   <h3 className="text-sm font-medium text-gray-700">Available Tags</h3>
                   <div className="grid grid-cols-1 gap-2 max-h-96 overflow-y-auto">
@@ -258,7 +396,7 @@ export default function ImageLabelingPage() {
                         className="flex items-start space-x-3 p-3 rounded-lg border border-gray-200 hover:bg-gray-50 cursor-pointer"
                       >
                         <input
-                          type="checkbox"
+                          type={taskType === 'single_choice' ? 'radio' : 'checkbox'}
                           checked={selectedTags.includes(tag.id)}
                           onChange={() => handleTagToggle(tag.id)}
                           className="mt-1 h-4 w-4 text-blue-600 focus:ring-blue-500 border-gray-300 rounded"
@@ -273,9 +411,10 @@ export default function ImageLabelingPage() {
                     ))}
                   </div>
                 </div>
+                )}
 
                 {/* Selected Tags Summary */}
-                {selectedTags.length > 0 && (
+                {taskType !== 'free_text' && selectedTags.length > 0 && (
                   <Card maxHeight="200px" className="mt-6">
                     <div className="p-4 bg-blue-50 rounded-lg">
                     <h4 className="text-sm font-medium text-blue-900 mb-2">
@@ -316,6 +455,36 @@ export default function ImageLabelingPage() {
                     {isSubmitting ? <LoadingSpinner /> : 'Submit and Exit'}
                   </Button>
                 </div>
+
+                {/* Skip or flag */}
+                <div className="mt-6 border-t border-gray-200 pt-4">
+                  <div className="text-sm font-medium text-gray-700 mb-2">Can&apos;t label this image?</div>
+                  <div className="flex flex-col sm:flex-row gap-3">
+                    <select
+                      value={flagReason}
+                      onChange={(e) => setFlagReason(e.target.value as FlagReason)}
+                      className="block rounded-md border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 sm:text-sm"
+                    >
+                      {FLAG_REASONS.map((reason) => (
+                        <option key={reason.value} value={reason.value}>{reason.label}</option>
+                      ))}
+                    </select>
+                    <input
+                      type="text"
+                      value={flagNote}
+                      onChange={(e) => setFlagNote(e.target.value)}
+                      placeholder="Note (optional)"
+                      maxLength={1000}
+                      className="flex-1 block rounded-md border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 sm:text-sm"
+                    />
+                    <Button onClick={() => handleMark('skip')} disabled={isSubmitting} variant="secondary">
+                      Skip
+                    </Button>
+                    <Button onClick={() => handleMark('flag')} disabled={isSubmitting} variant="danger">
+                      Flag
+                    </Button>
+                  </div>
+                </div>
               </div>
             </Card>
           </div>
@@ -27,6 +27,7 @@ export default function LabelerGroupDetailPage() {
   const [isLoading, setIsLoading] = useState(true);
   const [isLoadingImages, setIsLoadingImages] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [queueMessage, setQueueMessage] = useState<string | null>(null);
   
   const apiClient = useApiClient();
   const apiClientRef = useRef(apiClient);
@@ -54,6 +55,17 @@ export default function LabelerGroupDetailPage() {
     }
   }, []);
 
+  const startLabeling = async () => {
+    const response = await apiClientRef.current.getNextImage(groupId);
+    if (response.success && response.data) {
+      router.push(`/labeler/groups/${groupId}/images/${response.data.image.id}`);
+    } else if (response.success) {
+      setQueueMessage('No images need labels right now');
+    } else {
+      setQueueMessage(response.error || 'Failed to get the next image');
+    }
+  };
+
   const loadGroupDetails = useCallback(async () => {
     try {
       setIsLoading(true);
@@ -170,6 +182,15 @@ export default function LabelerGroupDetailPage() {
                       style={{ width: `${getProgressStats().percentage}%` }}
                     />
                   </div>
+                  <button
+                    onClick={startLabeling}
+                    className="mt-3 inline-flex items-center px-3 py-2 border border-transparent text-sm font-medium rounded-md text-white bg-blue-600 hover:bg-blue-700"
+                  >
+                    Start Labeling
+                  </button>
+                  {queueMessage && (
+                    <p className="text-xs text-gray-500 mt-2">{queueMessage}</p>
+                  )}
                 </div>
               </div>
 
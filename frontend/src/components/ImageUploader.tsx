@@ -24,11 +24,11 @@ export function ImageUploader({
   const fileInputRef = useRef<HTMLInputElement>(null);
 
   const validateFile = (file: File): boolean => {
-    const allowedTypes = ['image/png', 'image/jpeg', 'image/jpg'];
+    const allowedTypes = ['image/png', 'image/jpeg', 'image/jpg', 'image/webp', 'image/gif', 'image/bmp', 'image/tiff'];
     const maxSize = 10 * 1024 * 1024; // 10MB
 
     if (!allowedTypes.includes(file.type)) {
-      onUploadError(`File "${file.name}" is not a valid image type. Only PNG and JPEG files are allowed.`);
+      onUploadError(`File "${file.name}" is not a valid image type. Only PNG, JPEG, WebP, GIF, BMP and TIFF files are allowed.`);
       return false;
     }
 
@@ -98,7 +98,7 @@ export function ImageUploader({
       <input
         ref={fileInputRef}
         type="file"
-        accept="image/png,image/jpeg,image/jpg"
+        accept="image/png,image/jpeg,image/jpg,image/webp,image/gif,image/bmp,image/tiff"
         multiple
         onChange={handleFileChange}
         className="hidden"
@@ -165,7 +165,7 @@ export function ImageUploader({
               {selectedFiles.length} file{selectedFiles.length !== 1 ? 's' : ''} selected
             </span>
             <div className="text-xs text-gray-500">
-              Only PNG and JPEG files up to 10MB each
+              PNG, JPEG, WebP, GIF, BMP or TIFF files up to 10MB each
             </div>
           </div>
 
@@ -138,6 +138,36 @@ export default function Navbar() {
                 >
                   Manage Groups
                 </Link>
+                <Link
+                  href="/admin/leaderboard"
+                  className={`px-3 py-2 rounded-md text-sm font-medium transition-colors duration-200 ${
+                    isActive('/admin/leaderboard')
+                      ? 'text-blue-600 bg-blue-50'
+                      : 'text-gray-700 hover:text-blue-600 hover:bg-gray-50'
+                  }`}
+                >
+                  Leaderboard
+                </Link>
+                <Link
+                  href="/admin/flagged"
+                  className={`px-3 py-2 rounded-md text-sm font-medium transition-colors duration-200 ${
+                    isActive('/admin/flagged')
+                      ? 'text-blue-600 bg-blue-50'
+                      : 'text-gray-700 hover:text-blue-600 hover:bg-gray-50'
+                  }`}
+                >
+                  Flagged Images
+                </Link>
+                <Link
+                  href="/admin/throughput"
+                  className={`px-3 py-2 rounded-md text-sm font-medium transition-colors duration-200 ${
+                    isActive('/admin/throughput')
+                      ? 'text-blue-600 bg-blue-50'
+                      : 'text-gray-700 hover:text-blue-600 hover:bg-gray-50'
+                  }`}
+                >
+                  Throughput
+                </Link>
                 <button
                   onClick={logout}
                   className="text-gray-700 hover:text-red-600 px-3 py-2 rounded-md text-sm font-medium transition-colors duration-200 hover:bg-red-50"
@@ -250,6 +280,39 @@ export default function Navbar() {
                   >
                     Manage Groups
                   </Link>
+                  <Link
+                    href="/admin/leaderboard"
+                    className={`block px-3 py-2 rounded-md text-base font-medium transition-colors duration-200 ${
+                      isActive('/admin/leaderboard')
+                        ? 'text-blue-600 bg-white'
+                        : 'text-gray-700 hover:text-blue-600 hover:bg-white'
+                    }`}
+                    onClick={closeMobileMenu}
+                  >
+                    Leaderboard
+                  </Link>
+                  <Link
+                    href="/admin/flagged"
+                    className={`block px-3 py-2 rounded-md text-base font-medium transition-colors duration-200 ${
+                      isActive('/admin/flagged')
+                        ? 'text-blue-600 bg-white'
+                        : 'text-gray-700 hover:text-blue-600 hover:bg-white'
+                    }`}
+                    onClick={closeMobileMenu}
+                  >
+                    Flagged Images
+                  </Link>
+                  <Link
+                    href="/admin/throughput"
+                    className={`block px-3 py-2 rounded-md text-base font-medium transition-colors duration-200 ${
+                      isActive('/admin/throughput')
+                        ? 'text-blue-600 bg-white'
+                        : 'text-gray-700 hover:text-blue-600 hover:bg-white'
+                    }`}
+                    onClick={closeMobileMenu}
+                  >
+                    Throughput
+                  </Link>
                   <button
                     onClick={() => {
                       logout();
@@ -0,0 +1,120 @@
+'use client';
+
+import { useRef, useState } from 'react';
+
+export interface BoundingBox {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface Region extends BoundingBox {
+  id: number;
+  tag_id: number;
+}
+
+interface RegionEditorProps {
+  src: string;
+  alt: string;
+  regions: Region[];
+  tagNames: Record<number, string>;
+  disabled?: boolean;
+  onCreate: (bbox: BoundingBox) => void;
+  onDelete: (regionId: number) => void;
+}
+
+// Boxes smaller than this fraction of the image are treated as stray clicks
+const MIN_SIZE = 0.01;
+
+const clamp = (value: number) => Math.min(1, Math.max(0, value));
+
+export default function RegionEditor({ src, alt, regions, tagNames, disabled = false, onCreate, onDelete }: RegionEditorProps) {
+  const containerRef = useRef<HTMLDivElement>(null);
+  const [start, setStart] = useState<{ x: number; y: number } | null>(null);
+  const [draft, setDraft] = useState<BoundingBox | null>(null);
+
+  // Pointer position in coordinates normalized to the image size
+  const position = (event: React.PointerEvent) => {
+    const rect = containerRef.current!.getBoundingClientRect();
+    return {
+      x: clamp((event.clientX - rect.left) / rect.width),
+      y: clamp((event.clientY - rect.top) / rect.height),
+    };
+  };
+
+  const boxBetween = (a: { x: number; y: number }, b: { x: number; y: number }): BoundingBox => ({
+    x: Math.min(a.x, b.x),
+    y: Math.min(a.y, b.y),
+    width: Math.abs(a.x - b.x),
+    height: Math.abs(a.y - b.y),
+  });
+
+  const handlePointerDown = (event: React.PointerEvent) => {
+    if (disabled) return;
+    event.currentTarget.setPointerCapture(event.pointerId);
+    setStart(position(event));
+  };
+
+  const handlePointerMove = (event: React.PointerEvent) => {
+    if (start) {
+      setDraft(boxBetween(start, position(event)));
+    }
+  };
+
+  const handlePointerUp = (event: React.PointerEvent) => {
+    if (start) {
+      const bbox = boxBetween(start, position(event));
+      if (bbox.width >= MIN_SIZE && bbox.height >= MIN_SIZE) {
+        onCreate(bbox);
+      }
+    }
+    setStart(null);
+    setDraft(null);
+  };
+
+  const boxStyle = (bbox: BoundingBox) => ({
+    left: `${bbox.x * 100}%`,
+    top: `${bbox.y * 100}%`,
+    width: `${bbox.width * 100}%`,
+    height: `${bbox.height * 100}%`,
+  });
+
+  return (
+    <div
+      ref={containerRef}
+      className={`relative select-none touch-none ${disabled ? '' : 'cursor-crosshair'}`}
+      onPointerDown={handlePointerDown}
+      onPointerMove={handlePointerMove}
+      onPointerUp={handlePointerUp}
+    >
+      {/* eslint-disable-next-line @next/next/no-img-element */}
+      <img src={src} alt={alt} draggable={false} className="block w-full h-auto" />
+      {regions.map((region) => (
+        <div
+          key={region.id}
+          className="absolute border-2 border-blue-500 bg-blue-500/10"
+          style={boxStyle(region)}
+        >
+          <span className="absolute -top-5 left-0 flex items-center gap-1 px-1 text-xs text-white bg-blue-500 rounded whitespace-nowrap">
+            {tagNames[region.tag_id] ?? `#${region.tag_id}`}
+            {!disabled && (
+              <button
+                type="button"
+                onPointerDown={(event) => event.stopPropagation()}
+                onClick={() => onDelete(region.id)}
+                className="font-bold hover:text-red-200"
+                aria-label="Delete box"
+              >
+                ×
+              </button>
+            )}
+          </span>
+        </div>
+      ))}
+      {draft && (
+        <div className="absolute border-2 border-dashed border-blue-600" style={boxStyle(draft)} />
+      )}
+    </div>
+  );
+}
@@ -16,3 +16,4 @@ export { default as ProtectedRoute } from './ProtectedRoute';
 export { ImageUploader } from './ImageUploader';
 export { TagModal } from './TagModal';
 export { default as LabelerSelectionModal } from './LabelerSelectionModal';
+export { default as RegionEditor } from './RegionEditor';
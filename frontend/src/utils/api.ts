@@ -2,6 +2,10 @@ import { useAuth } from '@/contexts/AuthContext';
 
 const API_BASE_URL = 'http://localhost:8080/api/v1';
 
+// Image URLs in API responses are signed server paths, usable as <img src>
+// once resolved against the API's origin
+export const resolveApiUrl = (path: string) => new URL(path, API_BASE_URL).toString();
+
 export interface ApiResponse<T = unknown> {
   data?: T;
   error?: string;
@@ -10,6 +14,28 @@ export interface ApiResponse<T = unknown> {
   statusText?: string;
 }
 
+// What labelers answer per image of a group
+export type TaskType =
+  | { type: 'single_choice' }
+  | { type: 'multi_choice'; min: number; max: number | null }
+  | { type: 'free_text' };
+
+export interface Caption {
+  id: number;
+  image_id: number;
+  text: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface FinalCaption {
+  id: number;
+  text: string;
+  votes: number;
+  is_admin_override: boolean;
+  created_at: string;
+}
+
 export class ApiClient {
   private baseUrl: string;
   private getToken: () => string | null;
@@ -46,6 +72,15 @@ export class ApiClient {
         };
       }
 
+      // No Content, e.g. an empty labeling queue
+      if (response.status === 204) {
+        return {
+          success: true,
+          status: response.status,
+          statusText: response.statusText,
+        };
+      }
+
       const data = await response.json();
       return {
         success: true,
@@ -171,13 +206,28 @@ export class ApiClient {
     return this.get(`/labeler/groups/${groupId}/images`);
   }
 
+  // Leases the next image of the group that needs labels; no data when
+  // there is none
+  async getNextImage(groupId: number): Promise<ApiResponse<{
+    image: {
+      id: number;
+      filename: string;
+      status: string;
+    };
+    lease_expires_at: string;
+    remaining: number;
+  }>> {
+    return this.get(`/labeler/groups/${groupId}/next`);
+  }
+
   async getImageDetails(groupId: number, imageId: number): Promise<ApiResponse<{
     image: {
       id: number;
       filename: string;
       status: string;
-      base64_data: string;
       filetype: string;
+      content_url: string;
+      thumbnail_url: string;
     };
     group_tags: Array<{
       id: number;
@@ -189,16 +239,205 @@ export class ApiClient {
       name: string;
       description: string | null;
     }>;
+    task_type: TaskType;
+    current_text: string | null;
   }>> {
     return this.get(`/labeler/groups/${groupId}/images/${imageId}`);
   }
 
-  async updateImageTags(groupId: number, imageId: number, tagIds: number[]): Promise<ApiResponse<null>> {
+  // Free-text groups take `text` and no tags
+  async updateImageTags(groupId: number, imageId: number, tagIds: number[], text?: string): Promise<ApiResponse<null>> {
     return this.put(`/labeler/groups/${groupId}/images/${imageId}/tags`, {
-      tag_ids: tagIds
+      tag_ids: tagIds,
+      text: text ?? null
     });
   }
 
+  // Skips an image, or flags it as problematic for the admins
+  async markImage(
+    groupId: number,
+    imageId: number,
+    kind: 'skip' | 'flag',
+    reason: string,
+    note: string | null
+  ): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: {
+      image_id: number;
+      kind: string;
+      reason: string;
+      note: string | null;
+      created_at: string;
+    };
+  }>> {
+    return this.post(`/labeler/groups/${groupId}/images/${imageId}/${kind}`, {
+      reason,
+      note,
+    });
+  }
+
+  async listRegions(groupId: number, imageId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: Array<{
+      id: number;
+      image_id: number;
+      tag_id: number;
+      x: number;
+      y: number;
+      width: number;
+      height: number;
+    }>;
+  }>> {
+    return this.get(`/labeler/groups/${groupId}/images/${imageId}/regions`);
+  }
+
+  async createRegion(
+    groupId: number,
+    imageId: number,
+    region: { tag_id: number; x: number; y: number; width: number; height: number }
+  ): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: {
+      id: number;
+      image_id: number;
+      tag_id: number;
+      x: number;
+      y: number;
+      width: number;
+      height: number;
+    };
+  }>> {
+    return this.post(`/labeler/groups/${groupId}/images/${imageId}/regions`, region);
+  }
+
+  async deleteRegion(groupId: number, imageId: number, regionId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: null;
+  }>> {
+    return this.delete(`/labeler/groups/${groupId}/images/${imageId}/regions/${regionId}`);
+  }
+
+  // Keypoints are [x, y, visibility] in pixels, one per keypoint of the tag's schema
+  async listKeypointSets(groupId: number, imageId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: Array<{
+      id: number;
+      image_id: number;
+      tag_id: number;
+      keypoints: Array<[number, number, number]>;
+    }>;
+  }>> {
+    return this.get(`/labeler/groups/${groupId}/images/${imageId}/keypoints`);
+  }
+
+  async saveKeypointSet(
+    groupId: number,
+    imageId: number,
+    keypointSet: { tag_id: number; keypoints: Array<[number, number, number]> }
+  ): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: {
+      id: number;
+      image_id: number;
+      tag_id: number;
+      keypoints: Array<[number, number, number]>;
+    };
+  }>> {
+    return this.put(`/labeler/groups/${groupId}/images/${imageId}/keypoints`, keypointSet);
+  }
+
+  async deleteKeypointSet(groupId: number, imageId: number, tagId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: null;
+  }>> {
+    return this.delete(`/labeler/groups/${groupId}/images/${imageId}/keypoints/${tagId}`);
+  }
+
+  // Captions are the answer of free-text groups
+  async getCaption(groupId: number, imageId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: Caption;
+  }>> {
+    return this.get(`/labeler/groups/${groupId}/images/${imageId}/caption`);
+  }
+
+  async saveCaption(groupId: number, imageId: number, text: string): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: Caption;
+  }>> {
+    return this.put(`/labeler/groups/${groupId}/images/${imageId}/caption`, { text });
+  }
+
+  // An AI-written caption to edit; it is not saved
+  async draftCaption(groupId: number, imageId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: { text: string };
+  }>> {
+    return this.post(`/labeler/groups/${groupId}/images/${imageId}/caption/draft`);
+  }
+
+  async getImageCaptions(imageId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: {
+      image_id: number;
+      captions: Array<{ labeler_id: number; username: string; caption: Caption }>;
+      final_caption: FinalCaption | null;
+    };
+  }>> {
+    return this.get(`/admin/image/${imageId}/captions`);
+  }
+
+  // Either one of the labelers' captions or the admin's own text
+  async selectFinalCaption(
+    imageId: number,
+    selection: { caption_id: number } | { text: string }
+  ): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: FinalCaption;
+  }>> {
+    return this.put(`/admin/image/${imageId}/final-caption`, selection);
+  }
+
+  async draftFinalCaption(imageId: number): Promise<ApiResponse<{
+    success: boolean;
+    message: string;
+    data: { text: string };
+  }>> {
+    return this.post(`/admin/image/${imageId}/captions/draft`);
+  }
+
+  // JSON Lines, so the body is returned as text
+  async exportCaptions(groupId: number): Promise<ApiResponse<string>> {
+    try {
+      const token = this.getToken();
+      const response = await fetch(`${this.baseUrl}/admin/groups/${groupId}/export/captions`, {
+        headers: token ? { Authorization: `Bearer ${token}` } : {},
+      });
+      const text = await response.text();
+      if (!response.ok) {
+        return { success: false, error: text, status: response.status, statusText: response.statusText };
+      }
+      return { success: true, data: text, status: response.status, statusText: response.statusText };
+    } catch (error) {
+      return {
+        success: false,
+        error: error instanceof Error ? error.message : 'Unknown error occurred',
+      };
+    }
+  }
+
   async suggestTags(imageId: number, ignoredTagIds: number[]): Promise<ApiResponse<{
     success: boolean;
     message: string;